@@ -1,10 +1,12 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::wrong_self_convention)]
 
+use std::collections::BTreeMap;
+
 use meilisearch_types::batches::BatchId;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::keys::Key;
-use meilisearch_types::milli::update::IndexDocumentsMethod;
+use meilisearch_types::milli::update::{IndexDocumentsMethod, OnError};
 use meilisearch_types::settings::Unchecked;
 use meilisearch_types::tasks::{Details, IndexSwap, KindWithContent, Status, Task, TaskId};
 use meilisearch_types::InstanceUid;
@@ -44,6 +46,24 @@ pub struct IndexMetadata {
     pub updated_at: OffsetDateTime,
 }
 
+/// The impressions and engagement of a document, maintained by the searches and the click
+/// and conversion events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentPopularity {
+    pub document_id: String,
+    pub impressions: u64,
+    pub engagement: f64,
+}
+
+/// The version of a document, incremented each time the document is written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentVersion {
+    pub document_id: String,
+    pub version: u64,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Version {
     V1,
@@ -100,6 +120,8 @@ pub enum KindDump {
         method: IndexDocumentsMethod,
         documents_count: u64,
         allow_index_creation: bool,
+        #[serde(default, skip_serializing_if = "OnError::is_fail")]
+        on_error: OnError,
     },
     DocumentDeletion {
         documents_ids: Vec<String>,
@@ -128,6 +150,12 @@ pub enum KindDump {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    IndexClone {
+        new_index_uid: String,
+    },
+    DocumentPopularityUpdate {
+        increments: BTreeMap<String, meilisearch_types::milli::DocumentPopularity>,
+    },
     TaskCancelation {
         query: String,
         tasks: RoaringBitmap,
@@ -172,12 +200,14 @@ impl From<KindWithContent> for KindDump {
                 method,
                 documents_count,
                 allow_index_creation,
+                on_error,
                 ..
             } => KindDump::DocumentImport {
                 primary_key,
                 method,
                 documents_count,
                 allow_index_creation,
+                on_error,
             },
             KindWithContent::DocumentDeletion { documents_ids, .. } => {
                 KindDump::DocumentDeletion { documents_ids }
@@ -203,6 +233,12 @@ impl From<KindWithContent> for KindDump {
                 KindDump::IndexUpdate { primary_key }
             }
             KindWithContent::IndexSwap { swaps } => KindDump::IndexSwap { swaps },
+            KindWithContent::IndexClone { new_index_uid, .. } => {
+                KindDump::IndexClone { new_index_uid }
+            }
+            KindWithContent::DocumentPopularityUpdate { increments, .. } => {
+                KindDump::DocumentPopularityUpdate { increments }
+            }
             KindWithContent::TaskCancelation { query, tasks } => {
                 KindDump::TaskCancelation { query, tasks }
             }
@@ -222,6 +258,7 @@ impl From<KindWithContent> for KindDump {
 
 #[cfg(test)]
 pub(crate) mod test {
+    use std::collections::BTreeMap;
     use std::fs::File;
     use std::io::Seek;
     use std::str::FromStr;
@@ -231,6 +268,7 @@ pub(crate) mod test {
     use meilisearch_types::batches::{Batch, BatchEnqueuedAt, BatchStats};
     use meilisearch_types::facet_values_sort::FacetValuesSort;
     use meilisearch_types::features::{Network, Remote, RuntimeTogglableFeatures};
+    use meilisearch_types::index_alias::IndexAlias;
     use meilisearch_types::index_uid_pattern::IndexUidPattern;
     use meilisearch_types::keys::{Action, Key};
     use meilisearch_types::milli::update::Setting;
@@ -242,7 +280,9 @@ pub(crate) mod test {
     use time::macros::datetime;
     use uuid::Uuid;
 
-    use crate::reader::Document;
+    use crate::{
+        DocumentVersion, DumpReader, DumpWriter, IndexMetadata, KindDump, TaskDump, Version,
+    };
     use crate::{DumpReader, DumpWriter, IndexMetadata, KindDump, TaskDump, Version};
 
     pub fn create_test_instance_uid() -> Uuid {
@@ -291,6 +331,8 @@ pub(crate) mod test {
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
+            ttl_attribute: Setting::NotSet,
+            document_schema: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
             faceting: Setting::Set(FacetingSettings {
@@ -350,11 +392,16 @@ pub(crate) mod test {
                         allow_index_creation: true,
                         primary_key: Some(S("bone")),
                         documents_count: 12,
+                        on_error: OnError::Fail,
                     },
                     canceled_by: None,
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 12,
                         indexed_documents: Some(10),
+                        version_conflicts: Vec::new(),
+                        version_conflicts_count: 0,
+                        skipped_documents: Vec::new(),
+                        skipped_documents_count: 0,
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -374,11 +421,16 @@ pub(crate) mod test {
                         allow_index_creation: true,
                         primary_key: None,
                         documents_count: 2,
+                        on_error: OnError::Fail,
                     },
                     canceled_by: None,
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 2,
                         indexed_documents: None,
+                        version_conflicts: Vec::new(),
+                        version_conflicts_count: 0,
+                        skipped_documents: Vec::new(),
+                        skipped_documents_count: 0,
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -456,6 +508,9 @@ pub(crate) mod test {
         for document in &documents {
             index.push_document(document).unwrap();
         }
+        for version in &create_test_document_versions() {
+            index.push_document_version(version).unwrap();
+        }
         index.flush().unwrap();
         index.settings(&settings).unwrap();
 
@@ -500,6 +555,10 @@ pub(crate) mod test {
         let network = create_test_network();
         dump.create_network(network).unwrap();
 
+        // ========== aliases
+        let aliases = create_test_aliases();
+        dump.create_aliases(aliases).unwrap();
+
         // create the dump
         let mut file = tempfile::tempfile().unwrap();
         dump.persist_to(&mut file).unwrap();
@@ -508,6 +567,13 @@ pub(crate) mod test {
         file
     }
 
+    fn create_test_document_versions() -> Vec<DocumentVersion> {
+        vec![
+            DocumentVersion { document_id: "1".to_string(), version: 3 },
+            DocumentVersion { document_id: "2".to_string(), version: 1 },
+        ]
+    }
+
     fn create_test_features() -> RuntimeTogglableFeatures {
         RuntimeTogglableFeatures::default()
     }
@@ -519,6 +585,15 @@ pub(crate) mod test {
         }
     }
 
+    fn create_test_aliases() -> BTreeMap<String, IndexAlias> {
+        btreemap! {
+            "movies".to_string() => IndexAlias {
+                indexes: vec!["doggos".to_string()],
+                write_target: None,
+            },
+        }
+    }
+
     #[test]
     fn test_creating_and_read_dump() {
         let mut file = create_test_dump();
@@ -539,6 +614,9 @@ pub(crate) mod test {
         }
         assert_eq!(index.settings().unwrap(), create_test_settings());
         assert_eq!(index.metadata(), &create_test_index_metadata());
+        let versions = index.document_versions().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(versions, create_test_document_versions());
+        assert_eq!(index.document_popularity().count(), 0);
 
         drop(index);
         drop(indexes);
@@ -571,5 +649,8 @@ pub(crate) mod test {
         // ==== checking the network
         let expected = create_test_network();
         assert_eq!(&expected, dump.network().unwrap().unwrap());
+
+        // ==== checking the aliases
+        assert_eq!(dump.aliases().unwrap(), create_test_aliases());
     }
 }
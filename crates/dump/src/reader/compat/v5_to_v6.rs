@@ -104,6 +104,7 @@ impl CompatV5ToV6 {
                                 }
                             },
                             allow_index_creation,
+                            on_error: v6::milli::update::OnError::Fail,
                         },
                         v5::tasks::TaskContent::DocumentDeletion { deletion, .. } => match deletion
                         {
@@ -133,6 +134,10 @@ impl CompatV5ToV6 {
                             v6::Details::DocumentAdditionOrUpdate {
                                 received_documents: received_documents as u64,
                                 indexed_documents,
+                                version_conflicts: Vec::new(),
+                                version_conflicts_count: 0,
+                                skipped_documents: Vec::new(),
+                                skipped_documents_count: 0,
                             }
                         }
                         v5::Details::Settings { settings } => {
@@ -359,6 +364,8 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             dictionary: v6::Setting::NotSet,
             synonyms: settings.synonyms.into(),
             distinct_attribute: settings.distinct_attribute.into(),
+            ttl_attribute: v6::Setting::NotSet,
+            document_schema: v6::Setting::NotSet,
             proximity_precision: v6::Setting::NotSet,
             typo_tolerance: match settings.typo_tolerance {
                 v5::Setting::Set(typo) => v6::Setting::Set(v6::TypoTolerance {
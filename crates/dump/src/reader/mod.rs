@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufReader, Read};
 
@@ -129,6 +130,15 @@ impl DumpReader {
             DumpReader::Compat(compat) => compat.network(),
         }
     }
+
+    /// The aliases of the indexes, by name.
+    pub fn aliases(&self) -> Result<BTreeMap<String, v6::IndexAlias>> {
+        match self {
+            DumpReader::Current(current) => Ok(current.aliases().clone()),
+            // Aliases didn't exist before the v6 dumps.
+            DumpReader::Compat(_) => Ok(BTreeMap::new()),
+        }
+    }
 }
 
 impl From<V6Reader> for DumpReader {
@@ -189,6 +199,24 @@ impl DumpIndexReader {
             DumpIndexReader::Compat(compat) => compat.settings(),
         }
     }
+
+    pub fn document_popularity(
+        &mut self,
+    ) -> Box<dyn Iterator<Item = Result<crate::DocumentPopularity>> + '_> {
+        match self {
+            DumpIndexReader::Current(v6) => v6.document_popularity(),
+            DumpIndexReader::Compat(_compat) => Box::new(std::iter::empty()),
+        }
+    }
+
+    pub fn document_versions(
+        &mut self,
+    ) -> Box<dyn Iterator<Item = Result<crate::DocumentVersion>> + '_> {
+        match self {
+            DumpIndexReader::Current(v6) => v6.document_versions(),
+            DumpIndexReader::Compat(_compat) => Box::new(std::iter::empty()),
+        }
+    }
 }
 
 impl From<V6IndexReader> for DumpIndexReader {
@@ -348,6 +376,7 @@ pub(crate) mod test {
 
         assert_eq!(dump.features().unwrap().unwrap(), RuntimeTogglableFeatures::default());
         assert_eq!(dump.network().unwrap(), None);
+        assert!(dump.aliases().unwrap().is_empty());
     }
 
     #[test]
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, ErrorKind};
 use std::path::Path;
@@ -10,7 +11,7 @@ use tracing::debug;
 use uuid::Uuid;
 
 use super::Document;
-use crate::{Error, IndexMetadata, Result, Version};
+use crate::{DocumentPopularity, DocumentVersion, Error, IndexMetadata, Result, Version};
 
 pub type Metadata = crate::Metadata;
 
@@ -23,6 +24,7 @@ pub type Batch = meilisearch_types::batches::Batch;
 pub type Key = meilisearch_types::keys::Key;
 pub type RuntimeTogglableFeatures = meilisearch_types::features::RuntimeTogglableFeatures;
 pub type Network = meilisearch_types::features::Network;
+pub type IndexAlias = meilisearch_types::index_alias::IndexAlias;
 
 // ===== Other types to clarify the code of the compat module
 // everything related to the tasks
@@ -57,6 +59,7 @@ pub struct V6Reader {
     keys: BufReader<File>,
     features: Option<RuntimeTogglableFeatures>,
     network: Option<Network>,
+    aliases: BTreeMap<String, IndexAlias>,
 }
 
 impl V6Reader {
@@ -108,6 +111,13 @@ impl V6Reader {
             None
         };
 
+        let aliases = match fs::read(dump.path().join("aliases.json")) {
+            Ok(aliases_file) => serde_json::from_reader(&*aliases_file)?,
+            // The aliases were introduced after the network, older dumps don't have any.
+            Err(error) if error.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+
         Ok(V6Reader {
             metadata: serde_json::from_reader(&*meta_file)?,
             instance_uid,
@@ -116,6 +126,7 @@ impl V6Reader {
             keys: BufReader::new(File::open(dump.path().join("keys.jsonl"))?),
             features,
             network,
+            aliases,
             dump,
         })
     }
@@ -199,6 +210,10 @@ impl V6Reader {
     pub fn network(&self) -> Option<&Network> {
         self.network.as_ref()
     }
+
+    pub fn aliases(&self) -> &BTreeMap<String, IndexAlias> {
+        &self.aliases
+    }
 }
 
 pub struct UpdateFile {
@@ -229,6 +244,8 @@ pub struct V6IndexReader {
     metadata: IndexMetadata,
     documents: BufReader<File>,
     settings: BufReader<File>,
+    popularity: Option<BufReader<File>>,
+    versions: Option<BufReader<File>>,
 }
 
 impl V6IndexReader {
@@ -239,6 +256,16 @@ impl V6IndexReader {
             metadata: serde_json::from_reader(metadata)?,
             documents: BufReader::new(File::open(path.join("documents.jsonl"))?),
             settings: BufReader::new(File::open(path.join("settings.json"))?),
+            popularity: match File::open(path.join("popularity.jsonl")) {
+                Ok(popularity) => Some(BufReader::new(popularity)),
+                Err(e) if e.kind() == ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            },
+            versions: match File::open(path.join("versions.jsonl")) {
+                Ok(versions) => Some(BufReader::new(versions)),
+                Err(e) if e.kind() == ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            },
         };
 
         Ok(ret)
@@ -259,6 +286,26 @@ impl V6IndexReader {
         patch_embedders(&mut settings);
         Ok(settings.check())
     }
+
+    pub fn document_popularity(
+        &mut self,
+    ) -> Box<dyn Iterator<Item = Result<DocumentPopularity>> + '_> {
+        match self.popularity.as_mut() {
+            Some(popularity) => Box::new(
+                popularity.lines().map(|line| -> Result<_> { Ok(serde_json::from_str(&line?)?) }),
+            ),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    pub fn document_versions(&mut self) -> Box<dyn Iterator<Item = Result<DocumentVersion>> + '_> {
+        match self.versions.as_mut() {
+            Some(versions) => Box::new(
+                versions.lines().map(|line| -> Result<_> { Ok(serde_json::from_str(&line?)?) }),
+            ),
+            None => Box::new(std::iter::empty()),
+        }
+    }
 }
 
 fn patch_embedders(settings: &mut Settings<Unchecked>) {
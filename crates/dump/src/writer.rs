@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
@@ -6,6 +7,7 @@ use flate2::write::GzEncoder;
 use flate2::Compression;
 use meilisearch_types::batches::Batch;
 use meilisearch_types::features::{Network, RuntimeTogglableFeatures};
+use meilisearch_types::index_alias::IndexAlias;
 use meilisearch_types::keys::Key;
 use meilisearch_types::settings::{Checked, Settings};
 use serde_json::{Map, Value};
@@ -14,7 +16,10 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::reader::Document;
-use crate::{IndexMetadata, Metadata, Result, TaskDump, CURRENT_DUMP_VERSION};
+use crate::{
+    DocumentPopularity, DocumentVersion, IndexMetadata, Metadata, Result, TaskDump,
+    CURRENT_DUMP_VERSION,
+};
 
 pub struct DumpWriter {
     dir: TempDir,
@@ -70,6 +75,10 @@ impl DumpWriter {
         Ok(std::fs::write(self.dir.path().join("network.json"), serde_json::to_string(&network)?)?)
     }
 
+    pub fn create_aliases(&self, aliases: BTreeMap<String, IndexAlias>) -> Result<()> {
+        Ok(std::fs::write(self.dir.path().join("aliases.json"), serde_json::to_string(&aliases)?)?)
+    }
+
     pub fn persist_to(self, mut writer: impl Write) -> Result<()> {
         let gz_encoder = GzEncoder::new(&mut writer, Compression::default());
         let mut tar_encoder = tar::Builder::new(gz_encoder);
@@ -190,8 +199,13 @@ impl UpdateFile {
 }
 
 pub struct IndexWriter {
+    path: PathBuf,
     documents: BufWriter<File>,
     settings: File,
+    // only created if at least one document has a popularity score
+    popularity: Option<BufWriter<File>>,
+    // only created if at least one document has a version
+    versions: Option<BufWriter<File>>,
 }
 
 impl IndexWriter {
@@ -204,7 +218,13 @@ impl IndexWriter {
         let documents = File::create(path.join("documents.jsonl"))?;
         let settings = File::create(path.join("settings.json"))?;
 
-        Ok(IndexWriter { documents: BufWriter::new(documents), settings })
+        Ok(IndexWriter {
+            path,
+            documents: BufWriter::new(documents),
+            settings,
+            popularity: None,
+            versions: None,
+        })
     }
 
     pub fn push_document(&mut self, document: &Map<String, Value>) -> Result<()> {
@@ -213,8 +233,38 @@ impl IndexWriter {
         Ok(())
     }
 
+    pub fn push_document_popularity(&mut self, popularity: &DocumentPopularity) -> Result<()> {
+        let writer = match &mut self.popularity {
+            Some(writer) => writer,
+            None => self
+                .popularity
+                .insert(BufWriter::new(File::create(self.path.join("popularity.jsonl"))?)),
+        };
+        serde_json::to_writer(&mut *writer, popularity)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn push_document_version(&mut self, version: &DocumentVersion) -> Result<()> {
+        let writer = match &mut self.versions {
+            Some(writer) => writer,
+            None => self
+                .versions
+                .insert(BufWriter::new(File::create(self.path.join("versions.jsonl"))?)),
+        };
+        serde_json::to_writer(&mut *writer, version)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
     pub fn flush(&mut self) -> Result<()> {
         self.documents.flush()?;
+        if let Some(popularity) = &mut self.popularity {
+            popularity.flush()?;
+        }
+        if let Some(versions) = &mut self.versions {
+            versions.flush()?;
+        }
         Ok(())
     }
 
@@ -327,6 +377,7 @@ pub(crate) mod test {
         │    ├---- update_files/
         │    │    └---- 1.jsonl
         │    └---- queue.jsonl
+        ├---- aliases.json
         ├---- experimental-features.json
         ├---- instance_uid.uuid
         ├---- keys.jsonl
@@ -152,6 +152,7 @@ impl<'a> Dump<'a> {
                     method,
                     documents_count,
                     allow_index_creation,
+                    on_error,
                 } => KindWithContent::DocumentAdditionOrUpdate {
                     index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
                     primary_key,
@@ -159,6 +160,7 @@ impl<'a> Dump<'a> {
                     content_file: content_uuid.ok_or(Error::CorruptedDump)?,
                     documents_count,
                     allow_index_creation,
+                    on_error,
                 },
                 KindDump::DocumentDeletion { documents_ids } => KindWithContent::DocumentDeletion {
                     documents_ids,
@@ -201,6 +203,16 @@ impl<'a> Dump<'a> {
                     primary_key,
                 },
                 KindDump::IndexSwap { swaps } => KindWithContent::IndexSwap { swaps },
+                KindDump::IndexClone { new_index_uid } => KindWithContent::IndexClone {
+                    index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                    new_index_uid,
+                },
+                KindDump::DocumentPopularityUpdate { increments } => {
+                    KindWithContent::DocumentPopularityUpdate {
+                        index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                        increments,
+                    }
+                }
                 KindDump::TaskCancelation { query, tasks } => {
                     KindWithContent::TaskCancelation { query, tasks }
                 }
@@ -55,6 +55,12 @@ pub enum Error {
     IndexNotFound(String),
     #[error("Index `{0}` already exists.")]
     IndexAlreadyExists(String),
+    #[error("Alias `{0}` not found.")]
+    AliasNotFound(String),
+    #[error("`{0}` is already used as an alias.")]
+    AliasAlreadyExists(String),
+    #[error("Alias `{0}` points to several indexes and has no `writeTarget`, it can only be used in federated searches.")]
+    AliasWithoutWriteTarget(String),
     #[error(
         "Indexes must be declared only once during a swap. `{0}` was specified several times."
     )]
@@ -177,6 +183,9 @@ impl Error {
             | Error::WithCustomErrorCode(_, _)
             | Error::BadTaskId { .. }
             | Error::IndexAlreadyExists(_)
+            | Error::AliasNotFound(_)
+            | Error::AliasAlreadyExists(_)
+            | Error::AliasWithoutWriteTarget(_)
             | Error::SwapDuplicateIndexFound(_)
             | Error::SwapDuplicateIndexesFound(_)
             | Error::SwapIndexNotFound(_)
@@ -241,6 +250,9 @@ impl ErrorCode for Error {
             Error::BadTaskId { .. } => Code::BadRequest,
             Error::IndexNotFound(_) => Code::IndexNotFound,
             Error::IndexAlreadyExists(_) => Code::IndexAlreadyExists,
+            Error::AliasNotFound(_) => Code::AliasNotFound,
+            Error::AliasAlreadyExists(_) => Code::IndexAlreadyExists,
+            Error::AliasWithoutWriteTarget(_) => Code::AliasWithoutWriteTarget,
             Error::SwapDuplicateIndexesFound(_) => Code::InvalidSwapDuplicateIndexFound,
             Error::SwapDuplicateIndexFound(_) => Code::InvalidSwapDuplicateIndexFound,
             Error::SwapIndexNotFound(_) => Code::IndexNotFound,
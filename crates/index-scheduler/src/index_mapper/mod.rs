@@ -1,14 +1,17 @@
+use std::borrow::Cow;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use std::{fs, thread};
 
 use meilisearch_types::heed::types::{SerdeJson, Str};
-use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn, WithoutTls};
+use meilisearch_types::heed::{CompactionOption, Database, Env, RoTxn, RwTxn, WithoutTls};
+use meilisearch_types::index_alias::IndexAlias;
 use meilisearch_types::milli;
 use meilisearch_types::milli::database_stats::DatabaseStats;
 use meilisearch_types::milli::update::IndexerConfig;
 use meilisearch_types::milli::{FieldDistribution, Index};
+use meilisearch_types::tasks::KindWithContent;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tracing::error;
@@ -22,11 +25,12 @@ use crate::{Error, IndexBudget, IndexSchedulerOptions, Result};
 mod index_map;
 
 /// The number of database used by index mapper
-const NUMBER_OF_DATABASES: u32 = 2;
+const NUMBER_OF_DATABASES: u32 = 3;
 /// Database const names for the `IndexMapper`.
 mod db_name {
     pub const INDEX_MAPPING: &str = "index-mapping";
     pub const INDEX_STATS: &str = "index-stats";
+    pub const INDEX_ALIASES: &str = "index-aliases";
 }
 
 /// Structure managing meilisearch's indexes.
@@ -66,6 +70,10 @@ pub struct IndexMapper {
     /// Using an UUID forces to use the index_mapping table to recover the index behind a name, ensuring
     /// consistency wrt index swapping.
     pub(crate) index_stats: Database<UuidCodec, SerdeJson<IndexStats>>,
+    /// Map an alias name with the names of the indexes it points to.
+    ///
+    /// Aliases are resolved by name, so they keep pointing to the same names when indexes are swapped.
+    pub(crate) index_aliases: Database<Str, SerdeJson<IndexAlias>>,
 
     /// Path to the folder where the LMDB environments of each index are.
     base_path: PathBuf,
@@ -133,6 +141,9 @@ pub struct IndexStats {
     /// Date of the last update of the index.
     #[serde(with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
+    /// The attribute containing the expiration date of the documents, if any.
+    #[serde(default)]
+    pub ttl_attribute: Option<String>,
 }
 
 impl IndexStats {
@@ -154,6 +165,7 @@ impl IndexStats {
             field_distribution: index.field_distribution(rtxn)?,
             created_at: index.created_at(rtxn)?,
             updated_at: index.updated_at(rtxn)?,
+            ttl_attribute: index.ttl_field(rtxn)?.map(|s| s.to_string()),
         })
     }
 }
@@ -173,6 +185,7 @@ impl IndexMapper {
             index_map: Arc::new(RwLock::new(IndexMap::new(budget.index_count))),
             index_mapping: env.create_database(wtxn, Some(db_name::INDEX_MAPPING))?,
             index_stats: env.create_database(wtxn, Some(db_name::INDEX_STATS))?,
+            index_aliases: env.create_database(wtxn, Some(db_name::INDEX_ALIASES))?,
             base_path: options.indexes_path.clone(),
             index_base_map_size: budget.map_size,
             index_growth_amount: options.index_growth_amount,
@@ -195,6 +208,9 @@ impl IndexMapper {
                 Ok(index)
             }
             Err(Error::IndexNotFound(_)) => {
+                if self.index_aliases.get(&wtxn, name)?.is_some() {
+                    return Err(Error::AliasAlreadyExists(name.to_string()));
+                }
                 let uuid = Uuid::new_v4();
                 self.index_mapping.put(&mut wtxn, name, &uuid)?;
 
@@ -231,6 +247,71 @@ impl IndexMapper {
         }
     }
 
+    /// Creates a new index from a compacted copy of the environment of an existing one.
+    ///
+    /// The documents, settings and embeddings are copied as is, nothing is reindexed.
+    /// The copy is done before opening the write transaction to avoid blocking the task queue
+    /// for the duration of the copy.
+    pub fn clone_index(&self, env: &Env<WithoutTls>, name: &str, new_name: &str) -> Result<Index> {
+        let rtxn = env.read_txn()?;
+        self.ensure_name_is_free(&rtxn, new_name)?;
+        let index = self.index(&rtxn, name)?;
+        drop(rtxn);
+
+        let uuid = Uuid::new_v4();
+        let index_path = self.base_path.join(uuid.to_string());
+        fs::create_dir_all(&index_path)?;
+        if let Err(e) = index.copy_to_path(index_path.join("data.mdb"), CompactionOption::Enabled) {
+            if let Err(e) = fs::remove_dir_all(&index_path) {
+                error!("An error happened when removing the failed copy of the index {name}: {e}");
+            }
+            return Err(Error::from_milli(e, Some(name.to_string())));
+        }
+
+        let mut wtxn = env.write_txn()?;
+        // The name may have been taken by an index or an alias while the index was being copied.
+        if let Err(e) = self.ensure_name_is_free(&wtxn, new_name) {
+            if let Err(e) = fs::remove_dir_all(&index_path) {
+                error!("An error happened when removing the unused copy of the index {name}: {e}");
+            }
+            return Err(e);
+        }
+        self.index_mapping.put(&mut wtxn, new_name, &uuid)?;
+        let new_index = self
+            .index_map
+            .write()
+            .unwrap()
+            .create(
+                &uuid,
+                &index_path,
+                None,
+                self.enable_mdb_writemap,
+                self.index_base_map_size,
+                false,
+            )
+            .map_err(|e| Error::from_milli(e, Some(uuid.to_string())))?;
+        let index_rtxn = new_index.read_txn()?;
+        let stats = IndexStats::new(&new_index, &index_rtxn)
+            .map_err(|e| Error::from_milli(e, Some(new_name.to_string())))?;
+        self.store_stats_of(&mut wtxn, new_name, &stats)?;
+        drop(index_rtxn);
+
+        wtxn.commit()?;
+
+        Ok(new_index)
+    }
+
+    /// Returns an error if an index or an alias is already named `name`.
+    fn ensure_name_is_free(&self, rtxn: &RoTxn, name: &str) -> Result<()> {
+        if self.exists(rtxn, name)? {
+            return Err(Error::IndexAlreadyExists(name.to_string()));
+        }
+        if self.index_aliases.get(rtxn, name)?.is_some() {
+            return Err(Error::AliasAlreadyExists(name.to_string()));
+        }
+        Ok(())
+    }
+
     /// Removes the index from the mapping table and the in-memory index map
     /// but keeps the associated tasks.
     pub fn delete_index(&self, mut wtxn: RwTxn, name: &str) -> Result<()> {
@@ -245,6 +326,8 @@ impl IndexMapper {
         // Once we retrieved the UUID of the index we remove it from the mapping table.
         assert!(self.index_mapping.delete(&mut wtxn, name)?);
 
+        self.remove_index_from_aliases(&mut wtxn, name)?;
+
         wtxn.commit()?;
 
         let mut tries = 0;
@@ -463,6 +546,19 @@ impl IndexMapper {
             .collect()
     }
 
+    /// Return the name of the indexes having a `ttlAttribute` according to their stats, without opening them.
+    pub fn indexes_with_ttl_attribute(&self, rtxn: &RoTxn) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for result in self.index_mapping.iter(rtxn)? {
+            let (name, uuid) = result?;
+            let stats = self.index_stats.get(rtxn, &uuid)?;
+            if stats.is_some_and(|stats| stats.ttl_attribute.is_some()) {
+                names.push(name.to_string());
+            }
+        }
+        Ok(names)
+    }
+
     /// Swap two index names.
     pub fn swap(&self, wtxn: &mut RwTxn, lhs: &str, rhs: &str) -> Result<()> {
         let lhs_uuid = self
@@ -520,6 +616,115 @@ impl IndexMapper {
         Ok(())
     }
 
+    /// Returns the name of the index to use for `name`, following the alias if it's one.
+    ///
+    /// Indexes take precedence over aliases, and unknown names are returned as is
+    /// so that the caller reports them as missing indexes.
+    pub fn resolve_index_name<'a>(&self, rtxn: &RoTxn, name: &'a str) -> Result<Cow<'a, str>> {
+        if self.index_mapping.get(rtxn, name)?.is_some() {
+            return Ok(Cow::Borrowed(name));
+        }
+        match self.index_aliases.get(rtxn, name)? {
+            Some(alias) => match alias.target() {
+                Some(target) => Ok(Cow::Owned(target.to_string())),
+                None => Err(Error::AliasWithoutWriteTarget(name.to_string())),
+            },
+            None => Ok(Cow::Borrowed(name)),
+        }
+    }
+
+    /// Makes the task write into the target of the alias it was sent to, if any.
+    pub fn resolve_task_alias(
+        &self,
+        rtxn: &RoTxn,
+        mut kind: KindWithContent,
+    ) -> Result<KindWithContent> {
+        use KindWithContent as K;
+
+        let index_uid = match &mut kind {
+            K::DocumentAdditionOrUpdate { index_uid, .. }
+            | K::DocumentEdition { index_uid, .. }
+            | K::DocumentDeletion { index_uid, .. }
+            | K::DocumentDeletionByFilter { index_uid, .. }
+            | K::DocumentClear { index_uid }
+            | K::SettingsUpdate { index_uid, .. }
+            | K::IndexUpdate { index_uid, .. }
+            | K::IndexClone { index_uid, .. }
+            | K::DocumentPopularityUpdate { index_uid, .. } => index_uid,
+            // Creating, deleting or swapping an index always refers to the index itself.
+            K::IndexCreation { .. }
+            | K::IndexDeletion { .. }
+            | K::IndexSwap { .. }
+            | K::TaskCancelation { .. }
+            | K::TaskDeletion { .. }
+            | K::DumpCreation { .. }
+            | K::SnapshotCreation
+            | K::UpgradeDatabase { .. } => return Ok(kind),
+        };
+        let target = self.resolve_index_name(rtxn, index_uid)?.into_owned();
+        *index_uid = target;
+        Ok(kind)
+    }
+
+    /// The alias with this name, if any.
+    pub fn alias(&self, rtxn: &RoTxn, name: &str) -> Result<Option<IndexAlias>> {
+        Ok(self.index_aliases.get(rtxn, name)?)
+    }
+
+    /// All the aliases, sorted by name.
+    pub fn aliases(&self, rtxn: &RoTxn) -> Result<Vec<(String, IndexAlias)>> {
+        self.index_aliases
+            .iter(rtxn)?
+            .map(|res| res.map_err(Error::from).map(|(name, alias)| (name.to_string(), alias)))
+            .collect()
+    }
+
+    /// Creates or replaces an alias.
+    ///
+    /// The alias can't use the name of an index and must only point to existing indexes.
+    pub fn put_alias(&self, wtxn: &mut RwTxn, name: &str, alias: &IndexAlias) -> Result<()> {
+        if self.index_mapping.get(wtxn, name)?.is_some() {
+            return Err(Error::IndexAlreadyExists(name.to_string()));
+        }
+        for index in alias.indexes.iter().chain(&alias.write_target) {
+            if self.index_mapping.get(wtxn, index)?.is_none() {
+                return Err(Error::IndexNotFound(index.to_string()));
+            }
+        }
+        self.index_aliases.put(wtxn, name, alias)?;
+        Ok(())
+    }
+
+    /// Deletes an alias, the indexes it points to are left untouched.
+    pub fn delete_alias(&self, wtxn: &mut RwTxn, name: &str) -> Result<()> {
+        if !self.index_aliases.delete(wtxn, name)? {
+            return Err(Error::AliasNotFound(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Makes the aliases stop pointing to a deleted index.
+    ///
+    /// The aliases left without any index are deleted, and those writing into the index
+    /// lose their write target.
+    fn remove_index_from_aliases(&self, wtxn: &mut RwTxn, name: &str) -> Result<()> {
+        for (alias_name, mut alias) in self.aliases(wtxn)? {
+            let len = alias.indexes.len();
+            alias.indexes.retain(|index| index != name);
+            let removed_write_target = alias.write_target.as_deref() == Some(name);
+            if removed_write_target {
+                alias.write_target = None;
+            }
+
+            if alias.indexes.is_empty() && alias.write_target.is_none() {
+                self.index_aliases.delete(wtxn, &alias_name)?;
+            } else if removed_write_target || alias.indexes.len() != len {
+                self.index_aliases.put(wtxn, &alias_name, &alias)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn index_exists(&self, rtxn: &RoTxn, name: &str) -> Result<bool> {
         Ok(self.index_mapping.get(rtxn, name)?.is_some())
     }
@@ -5,7 +5,7 @@ use meilisearch_types::batches::{Batch, BatchEnqueuedAt, BatchStats};
 use meilisearch_types::heed::types::{SerdeBincode, SerdeJson, Str};
 use meilisearch_types::heed::{Database, RoTxn};
 use meilisearch_types::milli::{CboRoaringBitmapCodec, RoaringBitmapCodec, BEU32};
-use meilisearch_types::tasks::{Details, Kind, Status, Task};
+use meilisearch_types::tasks::{Details, Kind, KindWithContent, Status, Task};
 use meilisearch_types::versioning;
 use roaring::RoaringBitmap;
 
@@ -230,19 +230,54 @@ pub fn snapshot_task(task: &Task) -> String {
     if let Some(details) = details {
         snap.push_str(&format!("details: {}, ", &snapshot_details(details)));
     }
-    snap.push_str(&format!("kind: {kind:?}"));
+    snap.push_str(&format!("kind: {}", snapshot_kind_with_content(kind)));
 
     snap.push('}');
     snap
 }
 
+/// The `Debug` of the kind, without the `on_error` of document additions when it is the default.
+fn snapshot_kind_with_content(kind: &KindWithContent) -> String {
+    match kind {
+        KindWithContent::DocumentAdditionOrUpdate {
+            index_uid,
+            primary_key,
+            method,
+            content_file,
+            documents_count,
+            allow_index_creation,
+            on_error,
+        } => {
+            let mut snap = format!("DocumentAdditionOrUpdate {{ index_uid: {index_uid:?}, primary_key: {primary_key:?}, method: {method:?}, content_file: {content_file:?}, documents_count: {documents_count}, allow_index_creation: {allow_index_creation}");
+            if !on_error.is_fail() {
+                snap.push_str(&format!(", on_error: {on_error:?}"));
+            }
+            snap.push_str(" }");
+            snap
+        }
+        kind => format!("{kind:?}"),
+    }
+}
+
 fn snapshot_details(d: &Details) -> String {
     match d {
         Details::DocumentAdditionOrUpdate {
             received_documents,
             indexed_documents,
+            version_conflicts,
+            version_conflicts_count,
+            skipped_documents,
+            skipped_documents_count,
         } => {
-            format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?} }}")
+            let mut details = format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?}");
+            if !version_conflicts.is_empty() {
+                details.push_str(&format!(", version_conflicts: {version_conflicts:?}, version_conflicts_count: {version_conflicts_count}"));
+            }
+            if !skipped_documents.is_empty() {
+                details.push_str(&format!(", skipped_documents: {skipped_documents:?}, skipped_documents_count: {skipped_documents_count}"));
+            }
+            details.push_str(" }");
+            details
         }
         Details::DocumentEdition {
             deleted_documents,
@@ -291,6 +326,12 @@ fn snapshot_details(d: &Details) -> String {
         Details::IndexSwap { swaps } => {
             format!("{{ swaps: {swaps:?} }}")
         }
+        Details::IndexClone { new_index_uid } => {
+            format!("{{ new_index_uid: {new_index_uid:?} }}")
+        }
+        Details::DocumentPopularityUpdate { received_documents, updated_documents } => {
+            format!("{{ received_documents: {received_documents}, updated_documents: {updated_documents:?} }}")
+        }
         Details::UpgradeDatabase { from, to } => {
             format!("{{ from: {from:?}, to: {to:?} }}")
         }
@@ -55,12 +55,15 @@ use meilisearch_types::features::{InstanceTogglableFeatures, Network, RuntimeTog
 use meilisearch_types::heed::byteorder::BE;
 use meilisearch_types::heed::types::I128;
 use meilisearch_types::heed::{self, Env, RoTxn, WithoutTls};
+use meilisearch_types::index_alias::IndexAlias;
 use meilisearch_types::milli::index::IndexEmbeddingConfig;
 use meilisearch_types::milli::update::IndexerConfig;
+use meilisearch_types::milli::vector::persistent_cache::PersistentEmbeddingCache;
+use meilisearch_types::milli::vector::rerank::{Reranker, RerankerOptions};
 use meilisearch_types::milli::vector::{Embedder, EmbedderOptions, EmbeddingConfigs};
 use meilisearch_types::milli::{self, Index};
 use meilisearch_types::task_view::TaskView;
-use meilisearch_types::tasks::{KindWithContent, Task};
+use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
 use processing::ProcessingTasks;
 pub use queue::Query;
 use queue::Queue;
@@ -74,6 +77,10 @@ use crate::utils::clamp_to_page_size;
 
 pub(crate) type BEI128 = I128<BE>;
 
+/// The maximum number of expired documents registered for deletion per index by
+/// [`IndexScheduler::register_expired_documents_deletions`].
+const MAX_EXPIRED_DOCUMENTS_PER_DELETION: usize = 10_000;
+
 #[derive(Debug)]
 pub struct IndexSchedulerOptions {
     /// The path to the version file of Meilisearch.
@@ -129,6 +136,14 @@ pub struct IndexSchedulerOptions {
     ///
     /// 0 disables the cache.
     pub embedding_cache_cap: usize,
+    /// The path to the folder containing the persistent embedding cache.
+    pub embedding_cache_path: PathBuf,
+    /// The maximum size, in bytes, of the persistent embedding cache, shared by all embedders.
+    ///
+    /// 0 disables the persistent cache.
+    pub embedding_cache_size: usize,
+    /// The reranker used to reorder the hits of the searches that request it, if any.
+    pub reranker: Option<RerankerOptions>,
 }
 
 /// Structure which holds meilisearch's indexes and schedules the tasks
@@ -167,6 +182,12 @@ pub struct IndexScheduler {
     /// to the same embeddings for the same input text.
     embedders: Arc<RwLock<HashMap<EmbedderOptions, Arc<Embedder>>>>,
 
+    /// The cache of embeddings persisted across restarts, used by all the embedders, if enabled.
+    persistent_embedding_cache: Option<Arc<PersistentEmbeddingCache>>,
+
+    /// The runtime representation of the reranker configured for this instance.
+    reranker: Option<Arc<Reranker>>,
+
     // ================= test
     // The next entry is dedicated to the tests.
     /// Provide a way to set a breakpoint in multiple part of the scheduler.
@@ -200,6 +221,8 @@ impl IndexScheduler {
             webhook_url: self.webhook_url.clone(),
             webhook_authorization_header: self.webhook_authorization_header.clone(),
             embedders: self.embedders.clone(),
+            persistent_embedding_cache: self.persistent_embedding_cache.clone(),
+            reranker: self.reranker.clone(),
             #[cfg(test)]
             test_breakpoint_sdr: self.test_breakpoint_sdr.clone(),
             #[cfg(test)]
@@ -267,6 +290,13 @@ impl IndexScheduler {
         let index_mapper = IndexMapper::new(&env, &mut wtxn, &options, budget)?;
         wtxn.commit()?;
 
+        let persistent_embedding_cache = match options.embedding_cache_size {
+            0 => None,
+            size => {
+                Some(Arc::new(PersistentEmbeddingCache::new(&options.embedding_cache_path, size)?))
+            }
+        };
+
         // allow unreachable_code to get rids of the warning in the case of a test build.
         let this = Self {
             processing_tasks: Arc::new(RwLock::new(ProcessingTasks::new())),
@@ -280,6 +310,8 @@ impl IndexScheduler {
             webhook_url: options.webhook_url,
             webhook_authorization_header: options.webhook_authorization_header,
             embedders: Default::default(),
+            persistent_embedding_cache,
+            reranker: options.reranker.map(|options| Arc::new(Reranker::new(options))),
 
             #[cfg(test)]
             test_breakpoint_sdr,
@@ -429,6 +461,7 @@ impl IndexScheduler {
     ///
     /// * If the index wasn't opened before, the index will be opened.
     /// * If the index doesn't exist on disk, the `IndexNotFoundError` is thrown.
+    /// * If the name is an alias, the index it writes into is returned.
     ///
     /// ### Note
     ///
@@ -440,7 +473,8 @@ impl IndexScheduler {
     /// see the `try_for_each_index` function.
     pub fn index(&self, name: &str) -> Result<Index> {
         let rtxn = self.env.read_txn()?;
-        self.index_mapper.index(&rtxn, name)
+        let name = self.index_mapper.resolve_index_name(&rtxn, name)?;
+        self.index_mapper.index(&rtxn, &name)
     }
 
     /// Return the boolean referring if index exists.
@@ -633,6 +667,7 @@ impl IndexScheduler {
         }
 
         let mut wtxn = self.env.write_txn()?;
+        let kind = self.index_mapper.resolve_task_alias(&wtxn, kind)?;
         let task = self.queue.register(&mut wtxn, &kind, task_id, dry_run)?;
 
         // If the registered task is a task cancelation
@@ -655,6 +690,64 @@ impl IndexScheduler {
         Ok(task)
     }
 
+    /// Registers a document deletion task for each index containing expired documents.
+    ///
+    /// Only the indexes with a `ttlAttribute` in their stats are opened. At most
+    /// [`MAX_EXPIRED_DOCUMENTS_PER_DELETION`] documents are registered per index and per call,
+    /// the remaining ones are registered on the following calls. Indexes with a document deletion
+    /// that is still enqueued or processing are skipped, so the same documents are never registered twice.
+    /// An index that can't be read is logged and skipped without stopping the others.
+    pub fn register_expired_documents_deletions(&self) -> Result<Vec<Task>> {
+        let now = OffsetDateTime::now_utc().unix_timestamp() as f64;
+        let mut deletions = Vec::new();
+        {
+            let rtxn = self.env.read_txn()?;
+            // the processing tasks stay enqueued in the database until their batch is committed
+            let pending_deletions = self.queue.tasks.get_status(&rtxn, Status::Enqueued)?
+                & self.queue.tasks.get_kind(&rtxn, Kind::DocumentDeletion)?;
+            for index_uid in self.index_mapper.indexes_with_ttl_attribute(&rtxn)? {
+                if !self.queue.tasks.index_tasks(&rtxn, &index_uid)?.is_disjoint(&pending_deletions)
+                {
+                    continue;
+                }
+                match self.expired_documents_of(&rtxn, &index_uid, now) {
+                    Ok(documents_ids) if documents_ids.is_empty() => (),
+                    Ok(documents_ids) => deletions.push((index_uid, documents_ids)),
+                    Err(e) => {
+                        tracing::error!(%index_uid, "Could not list the expired documents: {e}")
+                    }
+                }
+            }
+        }
+
+        deletions
+            .into_iter()
+            .map(|(index_uid, documents_ids)| {
+                self.register(
+                    KindWithContent::DocumentDeletion { index_uid, documents_ids },
+                    None,
+                    false,
+                )
+            })
+            .collect()
+    }
+
+    /// The external ids of the first [`MAX_EXPIRED_DOCUMENTS_PER_DELETION`] expired documents of an index.
+    fn expired_documents_of(&self, rtxn: &RoTxn, index_uid: &str, now: f64) -> Result<Vec<String>> {
+        let index = self.index_mapper.index(rtxn, index_uid)?;
+        let index_rtxn = index.read_txn()?;
+        let expired = index
+            .expired_documents_ids(&index_rtxn, now)
+            .map_err(|e| Error::from_milli(e, Some(index_uid.to_string())))?;
+        index
+            .external_id_of(
+                &index_rtxn,
+                expired.into_iter().take(MAX_EXPIRED_DOCUMENTS_PER_DELETION),
+            )
+            .and_then(|ids| ids.into_iter().collect::<milli::Result<Vec<_>>>())
+            .map_err(|e| Error::from_milli(e, Some(index_uid.to_string())))
+    }
+
     /// Register a new task coming from a dump in the scheduler.
     /// By taking a mutable ref we're pretty sure no one will ever import a dump while actix is running.
     pub fn register_dumped_task(&mut self) -> Result<Dump> {
@@ -768,13 +861,42 @@ impl IndexScheduler {
     }
 
     pub fn index_stats(&self, index_uid: &str) -> Result<IndexStats> {
-        let is_indexing = self.is_index_processing(index_uid)?;
         let rtxn = self.read_txn()?;
-        let index_stats = self.index_mapper.stats_of(&rtxn, index_uid)?;
+        let index_uid = self.index_mapper.resolve_index_name(&rtxn, index_uid)?;
+        let is_indexing = self.is_index_processing(&index_uid)?;
+        let index_stats = self.index_mapper.stats_of(&rtxn, &index_uid)?;
 
         Ok(IndexStats { is_indexing, inner_stats: index_stats })
     }
 
+    /// The alias with this name, if any.
+    pub fn alias(&self, name: &str) -> Result<Option<IndexAlias>> {
+        let rtxn = self.read_txn()?;
+        self.index_mapper.alias(&rtxn, name)
+    }
+
+    /// All the aliases, sorted by name.
+    pub fn aliases(&self) -> Result<Vec<(String, IndexAlias)>> {
+        let rtxn = self.read_txn()?;
+        self.index_mapper.aliases(&rtxn)
+    }
+
+    /// Creates or replaces an alias pointing to existing indexes.
+    pub fn put_alias(&self, name: &str, alias: &IndexAlias) -> Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        self.index_mapper.put_alias(&mut wtxn, name, alias)?;
+        wtxn.commit()?;
+        Ok(())
+    }
+
+    /// Deletes an alias without touching the indexes it points to.
+    pub fn delete_alias(&self, name: &str) -> Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        self.index_mapper.delete_alias(&mut wtxn, name)?;
+        wtxn.commit()?;
+        Ok(())
+    }
+
     pub fn features(&self) -> RoFeatures {
         self.features.features()
     }
@@ -795,6 +917,11 @@ impl IndexScheduler {
         self.features.network()
     }
 
+    /// The reranker configured for this instance, if any.
+    pub fn reranker(&self) -> Option<Arc<Reranker>> {
+        self.reranker.clone()
+    }
+
     pub fn embedders(
         &self,
         index_uid: String,
@@ -802,44 +929,38 @@ impl IndexScheduler {
     ) -> Result<EmbeddingConfigs> {
         let res: Result<_> = embedding_configs
             .into_iter()
-            .map(
-                |IndexEmbeddingConfig {
-                     name,
-                     config: milli::vector::EmbeddingConfig { embedder_options, prompt, quantized },
-                     ..
-                 }| {
-                    let prompt = Arc::new(
-                        prompt
-                            .try_into()
-                            .map_err(meilisearch_types::milli::Error::from)
-                            .map_err(|err| Error::from_milli(err, Some(index_uid.clone())))?,
-                    );
-                    // optimistically return existing embedder
-                    {
-                        let embedders = self.embedders.read().unwrap();
-                        if let Some(embedder) = embedders.get(&embedder_options) {
-                            return Ok((
-                                name,
-                                (embedder.clone(), prompt, quantized.unwrap_or_default()),
-                            ));
-                        }
+            .map(|IndexEmbeddingConfig { name, config, .. }| {
+                let quantized = config.quantized();
+                let milli::vector::EmbeddingConfig { embedder_options, prompt, .. } = config;
+                let prompt = Arc::new(
+                    prompt
+                        .try_into()
+                        .map_err(meilisearch_types::milli::Error::from)
+                        .map_err(|err| Error::from_milli(err, Some(index_uid.clone())))?,
+                );
+                // optimistically return existing embedder
+                {
+                    let embedders = self.embedders.read().unwrap();
+                    if let Some(embedder) = embedders.get(&embedder_options) {
+                        return Ok((name, (embedder.clone(), prompt, quantized)));
                     }
+                }
 
-                    // add missing embedder
-                    let embedder = Arc::new(
-                        Embedder::new(embedder_options.clone(), self.scheduler.embedding_cache_cap)
-                            .map_err(meilisearch_types::milli::vector::Error::from)
-                            .map_err(|err| {
-                                Error::from_milli(err.into(), Some(index_uid.clone()))
-                            })?,
-                    );
-                    {
-                        let mut embedders = self.embedders.write().unwrap();
-                        embedders.insert(embedder_options, embedder.clone());
-                    }
-                    Ok((name, (embedder, prompt, quantized.unwrap_or_default())))
-                },
-            )
+                // add missing embedder
+                let embedder = Arc::new(
+                    Embedder::new(embedder_options.clone(), self.scheduler.embedding_cache_cap)
+                        .map_err(meilisearch_types::milli::vector::Error::from)
+                        .map_err(|err| Error::from_milli(err.into(), Some(index_uid.clone())))?,
+                );
+                if let Some(cache) = &self.persistent_embedding_cache {
+                    embedder.set_persistent_cache(cache, &embedder_options);
+                }
+                {
+                    let mut embedders = self.embedders.write().unwrap();
+                    embedders.insert(embedder_options, embedder.clone());
+                }
+                Ok((name, (embedder, prompt, quantized)))
+            })
             .collect();
         res.map(EmbeddingConfigs::new)
     }
@@ -128,6 +128,18 @@ make_enum_progress! {
     }
 }
 
+make_enum_progress! {
+    pub enum CloneIndexProgress {
+        CopyingTheIndex,
+    }
+}
+
+make_enum_progress! {
+    pub enum DocumentPopularityProgress {
+        UpdatingThePopularity,
+    }
+}
+
 make_enum_progress! {
     pub enum SwappingTheIndexes {
         EnsuringCorrectnessOfTheSwap,
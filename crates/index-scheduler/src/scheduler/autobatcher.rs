@@ -24,6 +24,8 @@ enum AutobatchKind {
     IndexDeletion,
     IndexUpdate,
     IndexSwap,
+    IndexClone,
+    DocumentPopularityUpdate,
 }
 
 impl AutobatchKind {
@@ -67,6 +69,10 @@ impl From<KindWithContent> for AutobatchKind {
             KindWithContent::IndexCreation { .. } => AutobatchKind::IndexCreation,
             KindWithContent::IndexUpdate { .. } => AutobatchKind::IndexUpdate,
             KindWithContent::IndexSwap { .. } => AutobatchKind::IndexSwap,
+            KindWithContent::IndexClone { .. } => AutobatchKind::IndexClone,
+            KindWithContent::DocumentPopularityUpdate { .. } => {
+                AutobatchKind::DocumentPopularityUpdate
+            }
             KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
@@ -116,6 +122,12 @@ pub enum BatchKind {
     IndexSwap {
         id: TaskId,
     },
+    IndexClone {
+        id: TaskId,
+    },
+    DocumentPopularityUpdate {
+        id: TaskId,
+    },
 }
 
 impl BatchKind {
@@ -155,6 +167,10 @@ impl BatchKind {
             K::IndexDeletion => (Break(BatchKind::IndexDeletion { ids: vec![task_id] }), false),
             K::IndexUpdate => (Break(BatchKind::IndexUpdate { id: task_id }), false),
             K::IndexSwap => (Break(BatchKind::IndexSwap { id: task_id }), false),
+            K::IndexClone => (Break(BatchKind::IndexClone { id: task_id }), false),
+            K::DocumentPopularityUpdate => {
+                (Break(BatchKind::DocumentPopularityUpdate { id: task_id }), false)
+            }
             K::DocumentClear => (Continue(BatchKind::DocumentClear { ids: vec![task_id] }), false),
             K::DocumentImport { allow_index_creation, primary_key: pk }
                 if primary_key.is_none() || pk.is_none() || primary_key == pk.as_deref() =>
@@ -202,7 +218,7 @@ impl BatchKind {
 
         match (self, kind) {
             // We don't batch any of these operations
-            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::DocumentEdition) => Break(this),
+            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::IndexClone | K::DocumentPopularityUpdate | K::DocumentEdition) => Break(this),
             // We must not batch tasks that don't have the same index creation rights if the index doesn't already exists.
             (this, kind) if !index_already_exists && this.allow_index_creation() == Some(false) && kind.allow_index_creation() == Some(true) => {
                 Break(this)
@@ -425,6 +441,8 @@ impl BatchKind {
                 | BatchKind::IndexDeletion { .. }
                 | BatchKind::IndexUpdate { .. }
                 | BatchKind::IndexSwap { .. }
+                | BatchKind::IndexClone { .. }
+                | BatchKind::DocumentPopularityUpdate { .. }
                 | BatchKind::DocumentEdition { .. },
                 _,
             ) => {
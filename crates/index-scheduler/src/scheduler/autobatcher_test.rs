@@ -1,6 +1,7 @@
 use meilisearch_types::milli::update::IndexDocumentsMethod::{
     self, ReplaceDocuments, UpdateDocuments,
 };
+use meilisearch_types::milli::update::OnError;
 use meilisearch_types::tasks::{IndexSwap, KindWithContent};
 use uuid::Uuid;
 
@@ -40,6 +41,7 @@ fn doc_imp(
         content_file: Uuid::new_v4(),
         documents_count: 0,
         allow_index_creation,
+        on_error: OnError::Fail,
     }
 }
 
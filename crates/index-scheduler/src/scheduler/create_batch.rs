@@ -1,7 +1,7 @@
 use std::fmt;
 
 use meilisearch_types::heed::RoTxn;
-use meilisearch_types::milli::update::IndexDocumentsMethod;
+use meilisearch_types::milli::update::{IndexDocumentsMethod, OnError};
 use meilisearch_types::settings::{Settings, Unchecked};
 use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
 use roaring::RoaringBitmap;
@@ -47,6 +47,15 @@ pub(crate) enum Batch {
     IndexSwap {
         task: Task,
     },
+    IndexClone {
+        index_uid: String,
+        new_index_uid: String,
+        task: Task,
+    },
+    DocumentPopularityUpdate {
+        index_uid: String,
+        task: Task,
+    },
     UpgradeDatabase {
         tasks: Vec<Task>,
     },
@@ -54,8 +63,8 @@ pub(crate) enum Batch {
 
 #[derive(Debug)]
 pub(crate) enum DocumentOperation {
-    Replace(Uuid),
-    Update(Uuid),
+    Replace(Uuid, OnError),
+    Update(Uuid, OnError),
     Delete(Vec<String>),
 }
 
@@ -103,7 +112,9 @@ impl Batch {
             Batch::TaskCancelation { task, .. }
             | Batch::Dump(task)
             | Batch::IndexCreation { task, .. }
-            | Batch::IndexUpdate { task, .. } => {
+            | Batch::IndexUpdate { task, .. }
+            | Batch::IndexClone { task, .. }
+            | Batch::DocumentPopularityUpdate { task, .. } => {
                 RoaringBitmap::from_sorted_iter(std::iter::once(task.uid)).unwrap()
             }
             Batch::SnapshotCreation(tasks)
@@ -147,6 +158,8 @@ impl Batch {
             IndexOperation { op, .. } => Some(op.index_uid()),
             IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
+            | IndexClone { index_uid, .. }
+            | DocumentPopularityUpdate { index_uid, .. }
             | IndexDeletion { index_uid, .. } => Some(index_uid),
         }
     }
@@ -167,6 +180,8 @@ impl fmt::Display for Batch {
             Batch::IndexUpdate { .. } => f.write_str("IndexUpdate")?,
             Batch::IndexDeletion { .. } => f.write_str("IndexDeletion")?,
             Batch::IndexSwap { .. } => f.write_str("IndexSwap")?,
+            Batch::IndexClone { .. } => f.write_str("IndexClone")?,
+            Batch::DocumentPopularityUpdate { .. } => f.write_str("DocumentPopularityUpdate")?,
             Batch::UpgradeDatabase { .. } => f.write_str("UpgradeDatabase")?,
         };
         match index_uid {
@@ -277,16 +292,19 @@ impl IndexScheduler {
                 for task in tasks.iter() {
                     match task.kind {
                         KindWithContent::DocumentAdditionOrUpdate {
-                            content_file, method, ..
-                        } => match method {
-                            IndexDocumentsMethod::ReplaceDocuments => {
-                                operations.push(DocumentOperation::Replace(content_file))
-                            }
-                            IndexDocumentsMethod::UpdateDocuments => {
-                                operations.push(DocumentOperation::Update(content_file))
+                            content_file,
+                            method,
+                            on_error,
+                            ..
+                        } => {
+                            match method {
+                                IndexDocumentsMethod::ReplaceDocuments => operations
+                                    .push(DocumentOperation::Replace(content_file, on_error)),
+                                IndexDocumentsMethod::UpdateDocuments => operations
+                                    .push(DocumentOperation::Update(content_file, on_error)),
+                                _ => unreachable!("Unknown document merging method"),
                             }
-                            _ => unreachable!("Unknown document merging method"),
-                        },
+                        }
                         KindWithContent::DocumentDeletion { ref documents_ids, .. } => {
                             operations.push(DocumentOperation::Delete(documents_ids.clone()));
                         }
@@ -419,6 +437,22 @@ impl IndexScheduler {
                 current_batch.processing(Some(&mut task));
                 Ok(Some(Batch::IndexSwap { task }))
             }
+            BatchKind::IndexClone { id } => {
+                let mut task =
+                    self.queue.tasks.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                current_batch.processing(Some(&mut task));
+                let new_index_uid = match &task.kind {
+                    KindWithContent::IndexClone { new_index_uid, .. } => new_index_uid.clone(),
+                    _ => unreachable!(),
+                };
+                Ok(Some(Batch::IndexClone { index_uid, new_index_uid, task }))
+            }
+            BatchKind::DocumentPopularityUpdate { id } => {
+                let mut task =
+                    self.queue.tasks.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                current_batch.processing(Some(&mut task));
+                Ok(Some(Batch::DocumentPopularityUpdate { index_uid, task }))
+            }
         }
     }
 
@@ -12,9 +12,9 @@ use roaring::RoaringBitmap;
 
 use super::create_batch::Batch;
 use crate::processing::{
-    AtomicBatchStep, AtomicTaskStep, CreateIndexProgress, DeleteIndexProgress, FinalizingIndexStep,
-    InnerSwappingTwoIndexes, SwappingTheIndexes, TaskCancelationProgress, TaskDeletionProgress,
-    UpdateIndexProgress,
+    AtomicBatchStep, AtomicTaskStep, CloneIndexProgress, CreateIndexProgress, DeleteIndexProgress,
+    DocumentPopularityProgress, FinalizingIndexStep, InnerSwappingTwoIndexes, SwappingTheIndexes,
+    TaskCancelationProgress, TaskDeletionProgress, UpdateIndexProgress,
 };
 use crate::utils::{
     self, remove_n_tasks_datetime_earlier_than, remove_task_datetime, swap_index_uid_in_task,
@@ -224,6 +224,9 @@ impl IndexScheduler {
                         &index,
                         self.index_mapper.indexer_config(),
                     );
+                    if let Some(cache) = &self.persistent_embedding_cache {
+                        builder.set_embedding_cache(cache.clone());
+                    }
                     builder.set_primary_key(primary_key);
                     let must_stop_processing = self.scheduler.must_stop_processing.clone();
                     builder
@@ -349,6 +352,45 @@ impl IndexScheduler {
                 task.status = Status::Succeeded;
                 Ok((vec![task], ProcessBatchInfo::default()))
             }
+            Batch::IndexClone { index_uid, new_index_uid, mut task } => {
+                progress.update_progress(CloneIndexProgress::CopyingTheIndex);
+                self.index_mapper.clone_index(&self.env, &index_uid, &new_index_uid)?;
+
+                task.status = Status::Succeeded;
+                task.details = Some(Details::IndexClone { new_index_uid });
+                Ok((vec![task], ProcessBatchInfo::default()))
+            }
+            Batch::DocumentPopularityUpdate { index_uid, mut task } => {
+                progress.update_progress(DocumentPopularityProgress::UpdatingThePopularity);
+                let KindWithContent::DocumentPopularityUpdate { increments, .. } = &task.kind
+                else {
+                    unreachable!()
+                };
+
+                let rtxn = self.env.read_txn()?;
+                let index = self.index_mapper.index(&rtxn, &index_uid)?;
+                // drop rtxn before starting a new wtxn on the same db
+                rtxn.commit()?;
+
+                let mut index_wtxn = index.write_txn()?;
+                let external_documents_ids = index.external_documents_ids();
+                let mut updated_documents = 0;
+                // The documents deleted since the events were sent are ignored.
+                for (external_id, increment) in increments {
+                    if let Some(docid) = external_documents_ids.get(&index_wtxn, external_id)? {
+                        index.increment_document_popularity(&mut index_wtxn, docid, *increment)?;
+                        updated_documents += 1;
+                    }
+                }
+                index_wtxn.commit()?;
+
+                task.status = Status::Succeeded;
+                task.details = Some(Details::DocumentPopularityUpdate {
+                    received_documents: increments.len() as u64,
+                    updated_documents: Some(updated_documents),
+                });
+                Ok((vec![task], ProcessBatchInfo::default()))
+            }
             Batch::UpgradeDatabase { mut tasks } => {
                 let KindWithContent::UpgradeDatabase { from } = tasks.last().unwrap().kind else {
                     unreachable!();
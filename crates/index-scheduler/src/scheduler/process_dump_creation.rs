@@ -3,7 +3,7 @@ use std::fs::File;
 use std::io::BufWriter;
 use std::sync::atomic::Ordering;
 
-use dump::IndexMetadata;
+use dump::{DocumentPopularity, DocumentVersion, IndexMetadata};
 use meilisearch_types::milli::constants::RESERVED_VECTORS_FIELD_NAME;
 use meilisearch_types::milli::progress::{Progress, VariableNameStep};
 use meilisearch_types::milli::vector::parsed_vectors::{ExplicitVectors, VectorOrArrayOfVectors};
@@ -240,7 +240,44 @@ impl IndexScheduler {
                 atomic.fetch_add(1, Ordering::Relaxed);
             }
 
-            // 4.2. Dump the settings
+            // 4.2. Dump the popularity of the documents
+            for ret in index.document_popularity.iter(&rtxn)? {
+                let (id, popularity) = ret?;
+                let document_id = match index
+                    .external_id_of(&rtxn, std::iter::once(id))
+                    .map_err(|e| Error::from_milli(e, Some(uid.to_string())))?
+                    .into_iter()
+                    .next()
+                {
+                    Some(Ok(document_id)) => document_id,
+                    Some(Err(e)) => return Err(Error::from_milli(e, Some(uid.to_string()))),
+                    None => continue,
+                };
+                index_dumper.push_document_popularity(&DocumentPopularity {
+                    document_id,
+                    impressions: popularity.impressions,
+                    engagement: popularity.engagement,
+                })?;
+            }
+
+            // 4.3. Dump the versions of the documents
+            for ret in index.documents_versions(&rtxn)? {
+                let (id, version) = ret?;
+                let document_id = match index
+                    .external_id_of(&rtxn, std::iter::once(id))
+                    .map_err(|e| Error::from_milli(e, Some(uid.to_string())))?
+                    .into_iter()
+                    .next()
+                {
+                    Some(Ok(document_id)) => document_id,
+                    Some(Err(e)) => return Err(Error::from_milli(e, Some(uid.to_string()))),
+                    None => continue,
+                };
+                index_dumper.push_document_version(&DocumentVersion { document_id, version })?;
+            }
+            index_dumper.flush()?;
+
+            // 4.4. Dump the settings
             let settings = meilisearch_types::settings::settings(
                 index,
                 &rtxn,
@@ -258,6 +295,10 @@ impl IndexScheduler {
         let network = self.network();
         dump.create_network(network)?;
 
+        // 6. Dump the aliases
+        let aliases = self.aliases()?.into_iter().collect();
+        dump.create_aliases(aliases)?;
+
         let dump_uid = started_at.format(format_description!(
                     "[year repr:full][month repr:numerical][day padding:zero]-[hour padding:zero][minute padding:zero][second padding:zero][subsecond digits:3]"
                 )).unwrap();
@@ -7,7 +7,10 @@ use meilisearch_types::milli::update::new::indexer::{self, UpdateByFunction};
 use meilisearch_types::milli::update::DocumentAdditionResult;
 use meilisearch_types::milli::{self, ChannelCongestion, Filter, ThreadPoolNoAbortBuilder};
 use meilisearch_types::settings::apply_settings_to_builder;
-use meilisearch_types::tasks::{Details, KindWithContent, Status, Task};
+use meilisearch_types::tasks::{
+    Details, KindWithContent, Status, Task, MAX_REPORTED_SKIPPED_DOCUMENTS,
+    MAX_REPORTED_VERSION_CONFLICTS,
+};
 use meilisearch_types::Index;
 use roaring::RoaringBitmap;
 
@@ -69,8 +72,8 @@ impl IndexScheduler {
                 let mut content_files = Vec::new();
                 for operation in &operations {
                     match operation {
-                        DocumentOperation::Replace(content_uuid)
-                        | DocumentOperation::Update(content_uuid) => {
+                        DocumentOperation::Replace(content_uuid, _)
+                        | DocumentOperation::Update(content_uuid, _) => {
                             let content_file = self.queue.file_store.get_update(*content_uuid)?;
                             let mmap = unsafe { memmap2::Mmap::map(&content_file)? };
                             content_files.push(mmap);
@@ -91,16 +94,16 @@ impl IndexScheduler {
                 let embedders = self.embedders(index_uid.clone(), embedders)?;
                 for operation in operations {
                     match operation {
-                        DocumentOperation::Replace(_content_uuid) => {
+                        DocumentOperation::Replace(_content_uuid, on_error) => {
                             let mmap = content_files_iter.next().unwrap();
                             indexer
-                                .replace_documents(mmap)
+                                .replace_documents_with_on_error(mmap, on_error)
                                 .map_err(|e| Error::from_milli(e, Some(index_uid.clone())))?;
                         }
-                        DocumentOperation::Update(_content_uuid) => {
+                        DocumentOperation::Update(_content_uuid, on_error) => {
                             let mmap = content_files_iter.next().unwrap();
                             indexer
-                                .update_documents(mmap)
+                                .update_documents_with_on_error(mmap, on_error)
                                 .map_err(|e| Error::from_milli(e, Some(index_uid.clone())))?;
                         }
                         DocumentOperation::Delete(document_ids) => {
@@ -155,6 +158,20 @@ impl IndexScheduler {
                             Some(Details::DocumentAdditionOrUpdate {
                                 received_documents,
                                 indexed_documents: Some(stats.document_count),
+                                version_conflicts_count: stats.version_conflicts.len() as u64,
+                                version_conflicts: stats
+                                    .version_conflicts
+                                    .into_iter()
+                                    .take(MAX_REPORTED_VERSION_CONFLICTS)
+                                    .map(Into::into)
+                                    .collect(),
+                                skipped_documents_count: stats.skipped_documents.len() as u64,
+                                skipped_documents: stats
+                                    .skipped_documents
+                                    .into_iter()
+                                    .take(MAX_REPORTED_SKIPPED_DOCUMENTS)
+                                    .map(Into::into)
+                                    .collect(),
                             })
                         }
                         Some(Details::DocumentDeletion { provided_ids, .. }) => {
@@ -486,6 +503,9 @@ impl IndexScheduler {
                 progress.update_progress(SettingsProgress::RetrievingAndMergingTheSettings);
                 let indexer_config = self.index_mapper.indexer_config();
                 let mut builder = milli::update::Settings::new(index_wtxn, index, indexer_config);
+                if let Some(cache) = &self.persistent_embedding_cache {
+                    builder.set_embedding_cache(cache.clone());
+                }
 
                 for (task, (_, settings)) in tasks.iter_mut().zip(settings) {
                     let checked_settings = settings.clone().check();
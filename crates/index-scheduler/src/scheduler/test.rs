@@ -3,9 +3,11 @@ use std::collections::BTreeMap;
 use big_s::S;
 use meili_snap::{json_string, snapshot};
 use meilisearch_auth::AuthFilter;
+use meilisearch_types::index_alias::IndexAlias;
 use meilisearch_types::milli::index::IndexEmbeddingConfig;
 use meilisearch_types::milli::update::IndexDocumentsMethod::*;
-use meilisearch_types::milli::{self};
+use meilisearch_types::milli::update::OnError;
+use meilisearch_types::milli::{self, DocumentPopularity};
 use meilisearch_types::settings::SettingEmbeddingSettings;
 use meilisearch_types::tasks::{IndexSwap, KindWithContent};
 use roaring::RoaringBitmap;
@@ -294,6 +296,7 @@ fn document_addition_and_index_deletion() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -460,6 +463,353 @@ fn swap_indexes_errors() {
     snapshot!(snapshot_index_scheduler(&index_scheduler), name: "first_swap_failed");
 }
 
+#[test]
+fn clone_index() {
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    let content = r#"[
+            { "id": 1, "doggo": "jean bob" },
+            { "id": 2, "doggo": "bork" }
+        ]"#;
+    let (uuid, mut file) = index_scheduler.queue.create_update_file_with_uuid(0).unwrap();
+    let documents_count = read_json(content.as_bytes(), &mut file).unwrap();
+    file.persist().unwrap();
+    index_scheduler
+        .register(
+            KindWithContent::DocumentAdditionOrUpdate {
+                index_uid: S("doggos"),
+                primary_key: Some(S("id")),
+                method: ReplaceDocuments,
+                content_file: uuid,
+                documents_count,
+                allow_index_creation: true,
+                on_error: OnError::Fail,
+            },
+            None,
+            false,
+        )
+        .unwrap();
+    index_scheduler
+        .register(
+            KindWithContent::IndexClone { index_uid: S("doggos"), new_index_uid: S("puppies") },
+            None,
+            false,
+        )
+        .unwrap();
+    // Cloning into an index that already exists must fail
+    index_scheduler
+        .register(
+            KindWithContent::IndexClone { index_uid: S("puppies"), new_index_uid: S("doggos") },
+            None,
+            false,
+        )
+        .unwrap();
+    index_scheduler.assert_internally_consistent();
+
+    handle.advance_n_successful_batches(2);
+    handle.advance_one_failed_batch();
+    index_scheduler.assert_internally_consistent();
+
+    let index = index_scheduler.index("puppies").unwrap();
+    let rtxn = index.read_txn().unwrap();
+    snapshot!(format!("{:?}", index.primary_key(&rtxn).unwrap()), @r###"Some("id")"###);
+    let field_ids_map = index.fields_ids_map(&rtxn).unwrap();
+    let field_ids = field_ids_map.ids().collect::<Vec<_>>();
+    let documents = index
+        .all_documents(&rtxn)
+        .unwrap()
+        .map(|ret| milli::obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+        .collect::<Vec<_>>();
+    snapshot!(serde_json::to_string(&documents).unwrap(), @r###"[{"id":1,"doggo":"jean bob"},{"id":2,"doggo":"bork"}]"###);
+
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let task = index_scheduler.queue.tasks.get_task(&rtxn, 2).unwrap().unwrap();
+    snapshot!(task.error.unwrap().message, @"Index `doggos` already exists.");
+}
+
+#[test]
+fn tasks_registered_on_an_alias_target_its_index() {
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    index_scheduler
+        .register(
+            KindWithContent::IndexCreation { index_uid: S("doggos-v1"), primary_key: None },
+            None,
+            false,
+        )
+        .unwrap();
+    handle.advance_one_successful_batch();
+
+    let alias = IndexAlias { indexes: vec![S("doggos-v1")], write_target: None };
+    index_scheduler.put_alias("doggos", &alias).unwrap();
+    // An alias can't shadow an index or point to a missing one
+    let err = index_scheduler.put_alias("doggos-v1", &alias).unwrap_err();
+    snapshot!(err, @"Index `doggos-v1` already exists.");
+    let missing = IndexAlias { indexes: vec![S("cats")], write_target: None };
+    let err = index_scheduler.put_alias("kitties", &missing).unwrap_err();
+    snapshot!(err, @"Index `cats` not found.");
+
+    let task = index_scheduler
+        .register(KindWithContent::DocumentClear { index_uid: S("doggos") }, None, false)
+        .unwrap();
+    snapshot!(task.index_uid().unwrap(), @"doggos-v1");
+    // Creating an index named after an alias must fail
+    index_scheduler
+        .register(
+            KindWithContent::IndexCreation { index_uid: S("doggos"), primary_key: None },
+            None,
+            false,
+        )
+        .unwrap();
+    handle.advance_one_successful_batch();
+    handle.advance_one_failed_batch();
+
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let task = index_scheduler.queue.tasks.get_task(&rtxn, 2).unwrap().unwrap();
+    snapshot!(task.error.unwrap().message, @"`doggos` is already used as an alias.");
+    drop(rtxn);
+
+    let several = IndexAlias { indexes: vec![S("doggos-v1"), S("doggos-v2")], write_target: None };
+    index_scheduler
+        .register(
+            KindWithContent::IndexCreation { index_uid: S("doggos-v2"), primary_key: None },
+            None,
+            false,
+        )
+        .unwrap();
+    handle.advance_one_successful_batch();
+    index_scheduler.put_alias("doggos", &several).unwrap();
+    let err = index_scheduler
+        .register(KindWithContent::DocumentClear { index_uid: S("doggos") }, None, false)
+        .unwrap_err();
+    snapshot!(err, @"Alias `doggos` points to several indexes and has no `writeTarget`, it can only be used in federated searches.");
+
+    index_scheduler.delete_alias("doggos").unwrap();
+    let err = index_scheduler.delete_alias("doggos").unwrap_err();
+    snapshot!(err, @"Alias `doggos` not found.");
+    index_scheduler.assert_internally_consistent();
+}
+
+#[test]
+fn deleting_an_index_removes_it_from_the_aliases() {
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    for index_uid in ["a", "b"] {
+        index_scheduler
+            .register(
+                KindWithContent::IndexCreation { index_uid: S(index_uid), primary_key: None },
+                None,
+                false,
+            )
+            .unwrap();
+        handle.advance_one_successful_batch();
+    }
+    let both = IndexAlias { indexes: vec![S("a"), S("b")], write_target: Some(S("a")) };
+    index_scheduler.put_alias("both", &both).unwrap();
+    let only_a = IndexAlias { indexes: vec![S("a")], write_target: None };
+    index_scheduler.put_alias("only-a", &only_a).unwrap();
+
+    index_scheduler
+        .register(KindWithContent::IndexDeletion { index_uid: S("a") }, None, false)
+        .unwrap();
+    handle.advance_one_successful_batch();
+    snapshot!(format!("{:?}", index_scheduler.aliases().unwrap()), @r###"[("both", IndexAlias { indexes: ["b"], write_target: None })]"###);
+
+    index_scheduler
+        .register(KindWithContent::IndexDeletion { index_uid: S("b") }, None, false)
+        .unwrap();
+    handle.advance_one_successful_batch();
+    snapshot!(format!("{:?}", index_scheduler.aliases().unwrap()), @"[]");
+    index_scheduler.assert_internally_consistent();
+}
+
+#[test]
+fn document_popularity_update() {
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    let content = r#"[
+            { "id": 1, "doggo": "jean bob" },
+            { "id": 2, "doggo": "bork" }
+        ]"#;
+    let (uuid, mut file) = index_scheduler.queue.create_update_file_with_uuid(0).unwrap();
+    let documents_count = read_json(content.as_bytes(), &mut file).unwrap();
+    file.persist().unwrap();
+    index_scheduler
+        .register(
+            KindWithContent::DocumentAdditionOrUpdate {
+                index_uid: S("doggos"),
+                primary_key: Some(S("id")),
+                method: ReplaceDocuments,
+                content_file: uuid,
+                documents_count,
+                allow_index_creation: true,
+                on_error: OnError::Fail,
+            },
+            None,
+            false,
+        )
+        .unwrap();
+    // The unknown document is ignored
+    index_scheduler
+        .register(
+            KindWithContent::DocumentPopularityUpdate {
+                index_uid: S("doggos"),
+                increments: BTreeMap::from([
+                    (S("2"), DocumentPopularity { impressions: 3, engagement: 5.0 }),
+                    (S("3"), DocumentPopularity { impressions: 1, engagement: 1.0 }),
+                ]),
+            },
+            None,
+            false,
+        )
+        .unwrap();
+    index_scheduler
+        .register(
+            KindWithContent::DocumentPopularityUpdate {
+                index_uid: S("doggos"),
+                increments: BTreeMap::from([
+                    (S("1"), DocumentPopularity { impressions: 2, engagement: 0.0 }),
+                    (S("2"), DocumentPopularity { impressions: 1, engagement: 1.0 }),
+                ]),
+            },
+            None,
+            false,
+        )
+        .unwrap();
+    // The popularity of the documents of an index that does not exist cannot be updated
+    index_scheduler
+        .register(
+            KindWithContent::DocumentPopularityUpdate {
+                index_uid: S("cattos"),
+                increments: BTreeMap::from([(S("1"), DocumentPopularity::default())]),
+            },
+            None,
+            false,
+        )
+        .unwrap();
+    index_scheduler.assert_internally_consistent();
+
+    // The popularity updates are never batched together
+    handle.advance_n_successful_batches(3);
+    handle.advance_one_failed_batch();
+    index_scheduler.assert_internally_consistent();
+
+    let index = index_scheduler.index("doggos").unwrap();
+    let rtxn = index.read_txn().unwrap();
+    let scores: Vec<_> =
+        index.document_popularity.iter(&rtxn).unwrap().map(|ret| ret.unwrap()).collect();
+    snapshot!(format!("{scores:?}"), @"[(0, DocumentPopularity { impressions: 2, engagement: 0.0 }), (1, DocumentPopularity { impressions: 4, engagement: 6.0 })]");
+
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let task = index_scheduler.queue.tasks.get_task(&rtxn, 1).unwrap().unwrap();
+    snapshot!(format!("{:?}", task.details.unwrap()), @"DocumentPopularityUpdate { received_documents: 2, updated_documents: Some(1) }");
+    let task = index_scheduler.queue.tasks.get_task(&rtxn, 3).unwrap().unwrap();
+    snapshot!(task.error.unwrap().message, @"Index `cattos` not found.");
+}
+
+#[test]
+fn expired_documents_deletions() {
+    use meilisearch_types::settings::{Settings, Unchecked};
+    use milli::update::Setting;
+
+    let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+    let add_documents = |file_uuid: u128, content: &str| {
+        let (uuid, mut file) =
+            index_scheduler.queue.create_update_file_with_uuid(file_uuid).unwrap();
+        let documents_count = read_json(content.as_bytes(), &mut file).unwrap();
+        file.persist().unwrap();
+        index_scheduler
+            .register(
+                KindWithContent::DocumentAdditionOrUpdate {
+                    index_uid: S("doggos"),
+                    primary_key: Some(S("id")),
+                    method: ReplaceDocuments,
+                    content_file: uuid,
+                    documents_count,
+                    allow_index_creation: true,
+                    on_error: OnError::Fail,
+                },
+                None,
+                false,
+            )
+            .unwrap();
+    };
+
+    add_documents(
+        0,
+        r#"[
+            { "id": 1, "expires_at": 0 },
+            { "id": 2, "expires_at": 4102444800 },
+            { "id": 3 }
+        ]"#,
+    );
+    handle.advance_one_successful_batch();
+
+    let mut new_settings: Box<Settings<Unchecked>> = Box::default();
+    new_settings.ttl_attribute = Setting::Set(S("expires_at"));
+    index_scheduler
+        .register(
+            KindWithContent::SettingsUpdate {
+                index_uid: S("doggos"),
+                new_settings,
+                is_deletion: false,
+                allow_index_creation: true,
+            },
+            None,
+            false,
+        )
+        .unwrap();
+    handle.advance_one_successful_batch();
+
+    // Only the indexes with a ttlAttribute are visited
+    index_scheduler
+        .register(
+            KindWithContent::IndexCreation { index_uid: S("catto"), primary_key: None },
+            None,
+            false,
+        )
+        .unwrap();
+    handle.advance_one_successful_batch();
+    let rtxn = index_scheduler.read_txn().unwrap();
+    let indexes = index_scheduler.index_mapper.indexes_with_ttl_attribute(&rtxn).unwrap();
+    snapshot!(format!("{indexes:?}"), @r###"["doggos"]"###);
+    drop(rtxn);
+
+    let tasks = index_scheduler.register_expired_documents_deletions().unwrap();
+    let kinds: Vec<_> = tasks.into_iter().map(|task| task.kind).collect();
+    snapshot!(format!("{kinds:?}"), @r###"[DocumentDeletion { index_uid: "doggos", documents_ids: ["1"] }]"###);
+
+    // The deletion of the expired documents is still enqueued, the same documents must not be registered twice
+    let tasks = index_scheduler.register_expired_documents_deletions().unwrap();
+    snapshot!(tasks.len(), @"0");
+
+    handle.advance_one_successful_batch();
+    index_scheduler.assert_internally_consistent();
+
+    add_documents(1, r#"[{ "id": 4, "expires_at": 1 }]"#);
+    handle.advance_one_successful_batch();
+
+    // An enqueued document addition doesn't prevent the expired documents from being deleted
+    add_documents(2, r#"[{ "id": 5, "expires_at": 4102444800 }]"#);
+    let tasks = index_scheduler.register_expired_documents_deletions().unwrap();
+    let kinds: Vec<_> = tasks.into_iter().map(|task| task.kind).collect();
+    snapshot!(format!("{kinds:?}"), @r###"[DocumentDeletion { index_uid: "doggos", documents_ids: ["4"] }]"###);
+
+    handle.advance_n_successful_batches(2);
+    index_scheduler.assert_internally_consistent();
+
+    let index = index_scheduler.index("doggos").unwrap();
+    let rtxn = index.read_txn().unwrap();
+    let documents_ids: Vec<_> = index
+        .external_id_of(&rtxn, index.documents_ids(&rtxn).unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|id| id.unwrap())
+        .collect();
+    snapshot!(format!("{documents_ids:?}"), @r###"["2", "3", "5"]"###);
+}
+
 #[test]
 fn document_addition_and_index_deletion_on_unexisting_index() {
     let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
@@ -482,6 +832,7 @@ fn document_addition_and_index_deletion_on_unexisting_index() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
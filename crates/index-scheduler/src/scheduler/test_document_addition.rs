@@ -2,6 +2,7 @@ use big_s::S;
 use meili_snap::snapshot;
 use meilisearch_types::milli::obkv_to_json;
 use meilisearch_types::milli::update::IndexDocumentsMethod::*;
+use meilisearch_types::milli::update::OnError;
 use meilisearch_types::tasks::KindWithContent;
 
 use crate::insta_snapshot::snapshot_index_scheduler;
@@ -31,6 +32,7 @@ fn document_addition() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -67,6 +69,7 @@ fn document_addition_and_document_deletion() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -133,6 +136,7 @@ fn document_deletion_and_document_addition() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -185,6 +189,7 @@ fn test_document_replace() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -236,6 +241,7 @@ fn test_document_update() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -289,6 +295,7 @@ fn test_mixed_document_addition() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -340,6 +347,7 @@ fn test_document_replace_without_autobatching() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -395,6 +403,7 @@ fn test_document_update_without_autobatching() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -454,6 +463,7 @@ fn test_document_addition_cant_create_index_without_index() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -506,6 +516,7 @@ fn test_document_addition_cant_create_index_without_index_without_autobatching()
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -568,6 +579,7 @@ fn test_document_addition_cant_create_index_with_index() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -635,6 +647,7 @@ fn test_document_addition_cant_create_index_with_index_without_autobatching() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -707,6 +720,7 @@ fn test_document_addition_mixed_rights_with_index() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -764,6 +778,7 @@ fn test_document_addition_mixed_right_without_index_starts_with_cant_create() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -820,6 +835,7 @@ fn test_document_addition_with_multiple_primary_key() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -883,6 +899,7 @@ fn test_document_addition_with_multiple_primary_key_batch_wrong_key() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -943,6 +960,7 @@ fn test_document_addition_with_bad_primary_key() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -1029,6 +1047,7 @@ fn test_document_addition_with_set_and_null_primary_key() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -1115,6 +1134,7 @@ fn test_document_addition_with_set_and_null_primary_key_inference_works() {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    on_error: OnError::Fail,
                 },
                 None,
                 false,
@@ -10,6 +10,7 @@ use meilisearch_types::milli::{self, obkv_to_json};
 use meilisearch_types::settings::{SettingEmbeddingSettings, Settings, Unchecked};
 use meilisearch_types::tasks::KindWithContent;
 use milli::update::IndexDocumentsMethod::*;
+use milli::update::OnError;
 
 use crate::insta_snapshot::snapshot_index_scheduler;
 use crate::test_utils::read_json;
@@ -148,6 +149,7 @@ fn import_vectors() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -222,6 +224,7 @@ fn import_vectors() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -342,6 +345,7 @@ fn import_vectors_first_and_embedder_later() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -423,8 +427,10 @@ fn import_vectors_first_and_embedder_later() {
                     max_bytes: Some(
                         400,
                     ),
+                    chunking: None,
                 },
                 quantized: None,
+                int8_rescoring: None,
             },
             user_provided: RoaringBitmap<[1, 2]>,
         },
@@ -473,6 +479,7 @@ fn import_vectors_first_and_embedder_later() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -574,6 +581,7 @@ fn delete_document_containing_vector() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: false,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -620,8 +628,10 @@ fn delete_document_containing_vector() {
                         max_bytes: Some(
                             400,
                         ),
+                        chunking: None,
                     },
                     quantized: None,
+                    int8_rescoring: None,
                 },
                 user_provided: RoaringBitmap<[0]>,
             },
@@ -664,8 +674,10 @@ fn delete_document_containing_vector() {
                         max_bytes: Some(
                             400,
                         ),
+                        chunking: None,
                     },
                     quantized: None,
+                    int8_rescoring: None,
                 },
                 user_provided: RoaringBitmap<[]>,
             },
@@ -748,6 +760,7 @@ fn delete_embedder_with_user_provided_vectors() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: false,
+                on_error: OnError::Fail,
             },
             None,
             false,
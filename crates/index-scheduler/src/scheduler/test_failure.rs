@@ -4,6 +4,7 @@ use big_s::S;
 use meili_snap::snapshot;
 use meilisearch_types::milli::obkv_to_json;
 use meilisearch_types::milli::update::IndexDocumentsMethod::*;
+use meilisearch_types::milli::update::OnError;
 use meilisearch_types::milli::update::Setting;
 use meilisearch_types::milli::FilterableAttributesRule;
 use meilisearch_types::tasks::{Kind, KindWithContent};
@@ -53,6 +54,7 @@ fn fail_in_process_batch_for_document_addition() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -95,6 +97,7 @@ fn fail_in_update_task_after_process_batch_success_for_document_addition() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -161,6 +164,7 @@ fn fail_in_process_batch_for_document_deletion() {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                on_error: OnError::Fail,
             },
             None,
             false,
@@ -9,6 +9,7 @@ use meilisearch_auth::open_auth_store_env;
 use meilisearch_types::document_formats::DocumentFormatError;
 use meilisearch_types::milli::update::IndexDocumentsMethod::ReplaceDocuments;
 use meilisearch_types::milli::update::IndexerConfig;
+use meilisearch_types::milli::update::OnError;
 use meilisearch_types::tasks::KindWithContent;
 use meilisearch_types::{versioning, VERSION_FILE_NAME};
 use tempfile::{NamedTempFile, TempDir};
@@ -113,6 +114,9 @@ impl IndexScheduler {
             instance_features: Default::default(),
             auto_upgrade: true, // Don't cost much and will ensure the happy path works
             embedding_cache_cap: 10,
+            embedding_cache_path: tempdir.path().join("embedding_cache"),
+            embedding_cache_size: 0,
+            reranker: None,
         };
         let version = configuration(&mut options).unwrap_or_else(|| {
             (
@@ -192,6 +196,7 @@ pub(crate) fn replace_document_import_task(
         content_file: Uuid::from_u128(content_file_uuid),
         documents_count,
         allow_index_creation: true,
+        on_error: OnError::Fail,
     }
 }
 
@@ -251,6 +251,11 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
         K::IndexDeletion { index_uid } => index_uids.push(index_uid),
         K::IndexCreation { index_uid, .. } => index_uids.push(index_uid),
         K::IndexUpdate { index_uid, .. } => index_uids.push(index_uid),
+        K::DocumentPopularityUpdate { index_uid, .. } => index_uids.push(index_uid),
+        K::IndexClone { index_uid, new_index_uid } => {
+            index_uids.push(index_uid);
+            index_uids.push(new_index_uid);
+        }
         K::IndexSwap { swaps } => {
             for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
                 if lhs == swap.0 || lhs == swap.1 {
@@ -267,15 +272,19 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
         | K::UpgradeDatabase { .. }
         | K::SnapshotCreation => (),
     };
-    if let Some(Details::IndexSwap { swaps }) = &mut task.details {
-        for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
-            if lhs == swap.0 || lhs == swap.1 {
-                index_uids.push(lhs);
-            }
-            if rhs == swap.0 || rhs == swap.1 {
-                index_uids.push(rhs);
+    match &mut task.details {
+        Some(Details::IndexSwap { swaps }) => {
+            for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
+                if lhs == swap.0 || lhs == swap.1 {
+                    index_uids.push(lhs);
+                }
+                if rhs == swap.0 || rhs == swap.1 {
+                    index_uids.push(rhs);
+                }
             }
         }
+        Some(Details::IndexClone { new_index_uid }) => index_uids.push(new_index_uid),
+        _ => (),
     }
     for index_uid in index_uids {
         if index_uid == swap.0 {
@@ -440,7 +449,22 @@ impl crate::IndexScheduler {
                             assert_eq!(&sw1, sw2);
                         }
                     }
-                    Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
+                    Details::IndexClone { new_index_uid: uid1 } => {
+                        if let KindWithContent::IndexClone { new_index_uid: uid2, .. } = &kind {
+                            assert_eq!(&uid1, uid2);
+                        }
+                    }
+                    Details::DocumentPopularityUpdate { received_documents, updated_documents } => {
+                        assert_eq!(kind.as_kind(), Kind::DocumentPopularityUpdate);
+                        if let Some(updated_documents) = updated_documents {
+                            assert!(updated_documents <= received_documents);
+                        }
+                    }
+                    Details::DocumentAdditionOrUpdate {
+                        received_documents,
+                        indexed_documents,
+                        ..
+                    } => {
                         assert_eq!(kind.as_kind(), Kind::DocumentAdditionOrUpdate);
                         match indexed_documents {
                             Some(indexed_documents) => {
@@ -146,6 +146,8 @@ macro_rules! make_missing_field_convenience_builder {
     };
 }
 make_missing_field_convenience_builder!(MissingIndexUid, missing_index_uid);
+make_missing_field_convenience_builder!(MissingAliasUid, missing_alias_uid);
+make_missing_field_convenience_builder!(MissingAliasIndexes, missing_alias_indexes);
 make_missing_field_convenience_builder!(MissingApiKeyActions, missing_api_key_actions);
 make_missing_field_convenience_builder!(MissingApiKeyExpiresAt, missing_api_key_expires_at);
 make_missing_field_convenience_builder!(MissingApiKeyIndexes, missing_api_key_indexes);
@@ -196,6 +198,9 @@ merge_with_error_impl_take_error_message!(InvalidMultiSearchWeight);
 merge_with_error_impl_take_error_message!(InvalidNetworkUrl);
 merge_with_error_impl_take_error_message!(InvalidNetworkSearchApiKey);
 merge_with_error_impl_take_error_message!(InvalidSearchSemanticRatio);
+merge_with_error_impl_take_error_message!(InvalidSearchRerank);
+merge_with_error_impl_take_error_message!(InvalidSearchSnippetsPerAttribute);
+merge_with_error_impl_take_error_message!(InvalidSearchSnippetLength);
 merge_with_error_impl_take_error_message!(InvalidSearchRankingScoreThreshold);
 merge_with_error_impl_take_error_message!(InvalidSimilarRankingScoreThreshold);
 merge_with_error_impl_take_error_message!(InvalidSimilarId);
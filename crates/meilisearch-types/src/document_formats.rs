@@ -1,13 +1,26 @@
 use std::fmt::{self, Debug, Display};
 use std::fs::File;
-use std::io::{self, BufWriter};
+use std::io::{self, BufReader, BufWriter};
 use std::marker::PhantomData;
-
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Date32Type, Date64Type, DurationMicrosecondType, DurationMillisecondType,
+    DurationNanosecondType, DurationSecondType, Float64Type, Int64Type, Time32MillisecondType,
+    Time32SecondType, Time64MicrosecondType, Time64NanosecondType, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+};
+use arrow_array::{ArrayRef, ArrowPrimitiveType, RecordBatch};
+use arrow_ipc::reader::StreamReader;
+use arrow_json::writer::LineDelimited;
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
 use bumpalo::Bump;
 use bumparaw_collections::RawMap;
 use memmap2::Mmap;
 use milli::documents::Error;
 use milli::Object;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use rustc_hash::FxBuildHasher;
 use serde::de::{SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer};
@@ -24,6 +37,8 @@ pub enum PayloadType {
     Ndjson,
     Json,
     Csv { delimiter: u8 },
+    Parquet,
+    ArrowStream,
 }
 
 impl fmt::Display for PayloadType {
@@ -32,6 +47,8 @@ impl fmt::Display for PayloadType {
             PayloadType::Ndjson => f.write_str("ndjson"),
             PayloadType::Json => f.write_str("json"),
             PayloadType::Csv { .. } => f.write_str("csv"),
+            PayloadType::Parquet => f.write_str("parquet"),
+            PayloadType::ArrowStream => f.write_str("arrow"),
         }
     }
 }
@@ -40,6 +57,7 @@ impl fmt::Display for PayloadType {
 pub enum DocumentFormatError {
     Io(io::Error),
     MalformedPayload(Error, PayloadType),
+    MalformedArrowPayload(ArrowError, PayloadType),
 }
 
 impl Display for DocumentFormatError {
@@ -78,6 +96,9 @@ impl Display for DocumentFormatError {
                 }
                 _ => write!(f, "The `{}` payload provided is malformed: `{}`.", b, me),
             },
+            Self::MalformedArrowPayload(ae, b) => {
+                write!(f, "The `{}` payload provided is malformed: `{}`.", b, ae)
+            }
         }
     }
 }
@@ -113,6 +134,15 @@ impl From<(PayloadType, csv::Error)> for DocumentFormatError {
     }
 }
 
+impl From<(PayloadType, ArrowError)> for DocumentFormatError {
+    fn from((ty, error): (PayloadType, ArrowError)) -> Self {
+        match error {
+            ArrowError::IoError(_, e) => Self::Io(e),
+            e => Self::MalformedArrowPayload(e, ty),
+        }
+    }
+}
+
 impl From<io::Error> for DocumentFormatError {
     fn from(error: io::Error) -> Self {
         Self::Io(error)
@@ -123,7 +153,8 @@ impl ErrorCode for DocumentFormatError {
     fn error_code(&self) -> Code {
         match self {
             DocumentFormatError::Io(e) => e.error_code(),
-            DocumentFormatError::MalformedPayload(_, _) => Code::MalformedPayload,
+            DocumentFormatError::MalformedPayload(_, _)
+            | DocumentFormatError::MalformedArrowPayload(_, _) => Code::MalformedPayload,
         }
     }
 }
@@ -275,6 +306,156 @@ pub fn read_ndjson(input: &File) -> Result<u64> {
     Ok(count)
 }
 
+/// Reads Parquet from file and write it in NDJSON in a file, keeping the types of the columns.
+///
+/// The columns can be uncompressed or compressed with Snappy, Gzip or Zstandard.
+///
+/// The timestamps, dates, times and durations are written as numbers of seconds.
+pub fn read_parquet(input: &File, output: impl io::Write) -> Result<u64> {
+    let ptype = PayloadType::Parquet;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(input.try_clone()?)
+        .and_then(|builder| builder.build())
+        .map_err(|e| DocumentFormatError::from((ptype, ArrowError::from(e))))?;
+
+    write_record_batches(reader, output, ptype)
+}
+
+/// Reads an Arrow IPC stream from file and write it in NDJSON in a file, keeping the types of the columns.
+///
+/// The timestamps, dates, times and durations are written as numbers of seconds.
+pub fn read_arrow_stream(input: &File, output: impl io::Write) -> Result<u64> {
+    let ptype = PayloadType::ArrowStream;
+    let reader = StreamReader::try_new(BufReader::new(input), None)
+        .map_err(|e| DocumentFormatError::from((ptype, e)))?;
+
+    write_record_batches(reader, output, ptype)
+}
+
+/// Writes every row of the record batches as a JSON object, one per line.
+///
+/// Structs are written as objects and lists as arrays, and the null values are kept.
+fn write_record_batches(
+    batches: impl Iterator<Item = std::result::Result<RecordBatch, ArrowError>>,
+    output: impl io::Write,
+    ptype: PayloadType,
+) -> Result<u64> {
+    let mut writer = arrow_json::WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, LineDelimited>(BufWriter::new(output));
+
+    let mut count = 0;
+    for batch in batches {
+        let batch = batch
+            .and_then(temporal_columns_to_numbers)
+            .map_err(|e| DocumentFormatError::from((ptype, e)))?;
+        writer.write(&batch).map_err(|e| DocumentFormatError::from((ptype, e)))?;
+        count += batch.num_rows() as u64;
+    }
+    writer.finish().map_err(|e| DocumentFormatError::from((ptype, e)))?;
+
+    match writer.into_inner().into_inner() {
+        Ok(_) => Ok(count),
+        Err(ie) => Err(DocumentFormatError::Io(ie.into_error())),
+    }
+}
+
+/// Converts the top-level temporal columns to numbers of seconds so that they can be
+/// used in filters and sorts, the JSON writer would otherwise turn them into strings.
+///
+/// - timestamps and dates become seconds since the Unix epoch,
+/// - times become seconds since midnight,
+/// - durations become seconds.
+///
+/// Values with a second or day precision are written as integers and finer precisions
+/// as floats. Intervals and temporal values nested in structs or lists are kept as strings.
+fn temporal_columns_to_numbers(batch: RecordBatch) -> std::result::Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        match temporal_column_to_numbers(column) {
+            Some(column) => {
+                let field =
+                    Field::new(field.name(), column.data_type().clone(), field.is_nullable())
+                        .with_metadata(field.metadata().clone());
+                fields.push(Arc::new(field));
+                columns.push(column);
+            }
+            None => {
+                fields.push(field.clone());
+                columns.push(column.clone());
+            }
+        }
+    }
+
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+/// Returns the column converted in seconds, or `None` if it is not a temporal column.
+fn temporal_column_to_numbers(column: &ArrayRef) -> Option<ArrayRef> {
+    fn seconds<T>(column: &ArrayRef, per_second: i64) -> ArrayRef
+    where
+        T: ArrowPrimitiveType,
+        T::Native: Into<i64>,
+    {
+        let column = column.as_primitive::<T>();
+        Arc::new(column.unary::<_, Int64Type>(|value| value.into() * per_second))
+    }
+
+    fn fractional_seconds<T>(column: &ArrayRef, per_second: f64) -> ArrayRef
+    where
+        T: ArrowPrimitiveType,
+        T::Native: Into<i64>,
+    {
+        let column = column.as_primitive::<T>();
+        Arc::new(column.unary::<_, Float64Type>(|value| value.into() as f64 / per_second))
+    }
+
+    let column = match column.data_type() {
+        DataType::Timestamp(TimeUnit::Second, _) => seconds::<TimestampSecondType>(column, 1),
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            fractional_seconds::<TimestampMillisecondType>(column, 1e3)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            fractional_seconds::<TimestampMicrosecondType>(column, 1e6)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            fractional_seconds::<TimestampNanosecondType>(column, 1e9)
+        }
+        DataType::Date32 => seconds::<Date32Type>(column, 86_400),
+        // Date64 values are days expressed in milliseconds
+        DataType::Date64 => {
+            let column = column.as_primitive::<Date64Type>();
+            Arc::new(column.unary::<_, Int64Type>(|millis| millis.div_euclid(1_000)))
+        }
+        DataType::Time32(TimeUnit::Second) => seconds::<Time32SecondType>(column, 1),
+        DataType::Time32(TimeUnit::Millisecond) => {
+            fractional_seconds::<Time32MillisecondType>(column, 1e3)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            fractional_seconds::<Time64MicrosecondType>(column, 1e6)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            fractional_seconds::<Time64NanosecondType>(column, 1e9)
+        }
+        DataType::Duration(TimeUnit::Second) => seconds::<DurationSecondType>(column, 1),
+        DataType::Duration(TimeUnit::Millisecond) => {
+            fractional_seconds::<DurationMillisecondType>(column, 1e3)
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            fractional_seconds::<DurationMicrosecondType>(column, 1e6)
+        }
+        DataType::Duration(TimeUnit::Nanosecond) => {
+            fractional_seconds::<DurationNanosecondType>(column, 1e9)
+        }
+        _ => return None,
+    };
+
+    Some(column)
+}
+
 /// The actual handling of the deserialization process in serde
 /// avoids storing the deserialized object in memory.
 ///
@@ -323,3 +504,119 @@ where
     let visitor = SeqVisitor(f, PhantomData);
     deserializer.deserialize_seq(visitor)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Seek;
+
+    use arrow_array::types::Int32Type;
+    use arrow_array::{
+        Date32Array, Float64Array, Int64Array, ListArray, StringArray, StructArray,
+        TimestampMillisecondArray, TimestampSecondArray,
+    };
+    use arrow_ipc::writer::StreamWriter;
+    use parquet::arrow::ArrowWriter;
+
+    use super::*;
+
+    const EXPECTED_NDJSON: &str = r#"{"id":1,"title":"Carol","tags":[1,2],"price":{"amount":12.5,"currency":"EUR"}}
+{"id":2,"title":null,"tags":[],"price":{"amount":3.5,"currency":"USD"}}
+"#;
+
+    fn record_batch() -> RecordBatch {
+        let id: ArrayRef = Arc::new(Int64Array::from(vec![1, 2]));
+        let title: ArrayRef = Arc::new(StringArray::from(vec![Some("Carol"), None]));
+        let tags: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+            Some(vec![]),
+        ]));
+        let price: ArrayRef = Arc::new(StructArray::from(vec![
+            (
+                Arc::new(Field::new("amount", DataType::Float64, false)),
+                Arc::new(Float64Array::from(vec![12.5, 3.5])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("currency", DataType::Utf8, false)),
+                Arc::new(StringArray::from(vec!["EUR", "USD"])) as ArrayRef,
+            ),
+        ]));
+        RecordBatch::try_from_iter(vec![
+            ("id", id),
+            ("title", title),
+            ("tags", tags),
+            ("price", price),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn parquet_payload() {
+        let batch = record_batch();
+        let input = tempfile::tempfile().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(input.try_clone().unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut output = Vec::new();
+        let count = read_parquet(&input, &mut output).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(String::from_utf8(output).unwrap(), EXPECTED_NDJSON);
+    }
+
+    #[test]
+    fn arrow_stream_payload() {
+        let batch = record_batch();
+        let mut input = tempfile::tempfile().unwrap();
+        let mut writer =
+            StreamWriter::try_new(input.try_clone().unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        input.rewind().unwrap();
+
+        let mut output = Vec::new();
+        let count = read_arrow_stream(&input, &mut output).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(String::from_utf8(output).unwrap(), EXPECTED_NDJSON);
+    }
+
+    #[test]
+    fn temporal_columns_are_written_as_seconds() {
+        let released: ArrayRef =
+            Arc::new(TimestampSecondArray::from(vec![Some(1_700_000_000), None]));
+        let updated: ArrayRef = Arc::new(
+            TimestampMillisecondArray::from(vec![1_700_000_000_500, 0]).with_timezone("UTC"),
+        );
+        let day: ArrayRef = Arc::new(Date32Array::from(vec![19_675, -1]));
+        let batch = RecordBatch::try_from_iter(vec![
+            ("released", released),
+            ("updated", updated),
+            ("day", day),
+        ])
+        .unwrap();
+
+        let input = tempfile::tempfile().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(input.try_clone().unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut output = Vec::new();
+        read_parquet(&input, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"released":1700000000,"updated":1700000000.5,"day":1699920000}
+{"released":null,"updated":0.0,"day":-86400}
+"#
+        );
+    }
+
+    #[test]
+    fn malformed_parquet_payload() {
+        let mut input = tempfile::tempfile().unwrap();
+        io::Write::write_all(&mut input, br#"{ "id": 1 }"#).unwrap();
+
+        let error = read_parquet(&input, Vec::new()).unwrap_err();
+        assert_eq!(error.error_code(), Code::MalformedPayload);
+    }
+}
@@ -194,6 +194,8 @@ macro_rules! make_error_codes {
 
 // An exhaustive list of all the error codes used by meilisearch.
 make_error_codes! {
+AliasNotFound                         , InvalidRequest       , NOT_FOUND ;
+AliasWithoutWriteTarget               , InvalidRequest       , BAD_REQUEST ;
 ApiKeyAlreadyExists                   , InvalidRequest       , CONFLICT ;
 ApiKeyNotFound                        , InvalidRequest       , NOT_FOUND ;
 BadParameter                          , InvalidRequest       , BAD_REQUEST;
@@ -203,7 +205,9 @@ DocumentNotFound                      , InvalidRequest       , NOT_FOUND;
 DumpAlreadyProcessing                 , InvalidRequest       , CONFLICT;
 DumpNotFound                          , InvalidRequest       , NOT_FOUND;
 DumpProcessFailed                     , Internal             , INTERNAL_SERVER_ERROR;
+EmbedderNotFound                      , InvalidRequest       , NOT_FOUND;
 DuplicateIndexFound                   , InvalidRequest       , BAD_REQUEST;
+EventQueryNotFound                    , InvalidRequest       , NOT_FOUND;
 ImmutableApiKeyActions                , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyCreatedAt              , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyExpiresAt              , InvalidRequest       , BAD_REQUEST;
@@ -221,6 +225,12 @@ IndexPrimaryKeyAlreadyExists          , InvalidRequest       , BAD_REQUEST ;
 IndexPrimaryKeyMultipleCandidatesFound, InvalidRequest       , BAD_REQUEST;
 IndexPrimaryKeyNoCandidateFound       , InvalidRequest       , BAD_REQUEST ;
 Internal                              , Internal             , INTERNAL_SERVER_ERROR ;
+InvalidAliasIndexes                   , InvalidRequest       , BAD_REQUEST ;
+InvalidAliasUid                       , InvalidRequest       , BAD_REQUEST ;
+InvalidAliasWriteTarget               , InvalidRequest       , BAD_REQUEST ;
+InvalidAnalyticsFrom                  , InvalidRequest       , BAD_REQUEST ;
+InvalidAnalyticsLimit                 , InvalidRequest       , BAD_REQUEST ;
+InvalidAnalyticsTo                    , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKey                         , Auth                 , FORBIDDEN ;
 InvalidApiKeyActions                  , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyDescription              , InvalidRequest       , BAD_REQUEST ;
@@ -232,18 +242,28 @@ InvalidApiKeyOffset                   , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyUid                      , InvalidRequest       , BAD_REQUEST ;
 InvalidContentType                    , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
 InvalidDocumentCsvDelimiter           , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentExportFormat           , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentFields                 , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentOnError                , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentRetrieveVectors        , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentRetrieveVersion        , InvalidRequest       , BAD_REQUEST ;
 MissingDocumentFilter                 , InvalidRequest       , BAD_REQUEST ;
 MissingDocumentEditionFunction        , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentFilter                 , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentGeoField               , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentOperator               , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentIfVersion              , InvalidRequest       , BAD_REQUEST ;
+DocumentSchemaMismatch                , InvalidRequest       , BAD_REQUEST ;
 InvalidVectorDimensions               , InvalidRequest       , BAD_REQUEST ;
 InvalidVectorsType                    , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentId                     , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentIds                    , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentLimit                  , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentOffset                 , InvalidRequest       , BAD_REQUEST ;
+InvalidEventDocumentId                , InvalidRequest       , BAD_REQUEST ;
+InvalidEventPosition                  , InvalidRequest       , BAD_REQUEST ;
+InvalidEventQueryId                   , InvalidRequest       , BAD_REQUEST ;
+InvalidEventType                      , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchEmbedder                 , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarEmbedder                , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHybridQuery              , InvalidRequest       , BAD_REQUEST ;
@@ -260,6 +280,9 @@ InvalidMultiSearchMaxValuesPerFacet   , InvalidRequest       , BAD_REQUEST ;
 InvalidMultiSearchMergeFacets         , InvalidRequest       , BAD_REQUEST ;
 InvalidMultiSearchQueryFacets         , InvalidRequest       , BAD_REQUEST ;
 InvalidMultiSearchQueryPagination     , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchQueryRerank         , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchFusion              , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchRrfK                , InvalidRequest       , BAD_REQUEST ;
 InvalidMultiSearchQueryRankingRules   , InvalidRequest       , BAD_REQUEST ;
 InvalidMultiSearchQueryPosition       , InvalidRequest       , BAD_REQUEST ;
 InvalidMultiSearchRemote              , InvalidRequest       , BAD_REQUEST ;
@@ -279,12 +302,30 @@ InvalidSimilarRankingScoreThreshold   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchRetrieveVectors          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropLength               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropMarker               , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchCropMode                 , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSnippetLength            , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSnippetsPerAttribute     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFacets                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchSemanticRatio            , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchLocales                  , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRerank                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFusion                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchExhaustive               , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRrfK                     , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchExhaustiveFacetCount, InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarId                      , InvalidRequest       , BAD_REQUEST ;
+InvalidSimilarPositive                , InvalidRequest       , BAD_REQUEST ;
+InvalidEmbedTexts                     , InvalidRequest       , BAD_REQUEST ;
+InvalidEmbedDocuments                 , InvalidRequest       , BAD_REQUEST ;
+InvalidEmbedInput                     , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderTemplate                 , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderDocumentTemplateMaxBytes , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderEmbedder                 , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderId                       , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderDocument                 , InvalidRequest       , BAD_REQUEST ;
+InvalidRenderInput                    , InvalidRequest       , BAD_REQUEST ;
+InvalidSimilarNegative                , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFilter                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSimilarFilter                  , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHighlightPostTag         , InvalidRequest       , BAD_REQUEST ;
@@ -310,6 +351,8 @@ InvalidSearchSort                     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchDistinct                 , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDisplayedAttributes    , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDistinctAttribute      , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsTtlAttribute           , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsDocumentSchema         , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsProximityPrecision     , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsFacetSearch            , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsPrefixSearch           , InvalidRequest       , BAD_REQUEST ;
@@ -350,6 +393,8 @@ IoError                               , System               , UNPROCESSABLE_ENT
 FeatureNotEnabled                     , InvalidRequest       , BAD_REQUEST ;
 MalformedPayload                      , InvalidRequest       , BAD_REQUEST ;
 MaxFieldsLimitExceeded                , InvalidRequest       , BAD_REQUEST ;
+MissingAliasIndexes                   , InvalidRequest       , BAD_REQUEST ;
+MissingAliasUid                       , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyActions                  , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyExpiresAt                , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyIndexes                  , InvalidRequest       , BAD_REQUEST ;
@@ -372,6 +417,7 @@ RemoteCouldNotSendRequest             , System               , BAD_GATEWAY ;
 RemoteInvalidApiKey                   , Auth                 , FORBIDDEN ;
 RemoteRemoteError                     , System               , BAD_GATEWAY ;
 RemoteTimeout                         , System               , BAD_GATEWAY ;
+TooManyDocumentExports                , System               , SERVICE_UNAVAILABLE ;
 TooManySearchRequests                 , System               , SERVICE_UNAVAILABLE ;
 TaskNotFound                          , InvalidRequest       , NOT_FOUND ;
 TaskFileNotFound                      , InvalidRequest       , NOT_FOUND ;
@@ -430,8 +476,12 @@ impl ErrorCode for milli::Error {
                     | UserError::InvalidSettingsDimensions { .. }
                     | UserError::InvalidUrl { .. }
                     | UserError::InvalidSettingsDocumentTemplateMaxBytes { .. }
+                    | UserError::InvalidSettingsChunking { .. }
+                    | UserError::InvalidSettingsRequestPolicy { .. }
                     | UserError::InvalidPrompt(_)
                     | UserError::InvalidDisableBinaryQuantization { .. }
+                    | UserError::InvalidDisableInt8Rescoring { .. }
+                    | UserError::InvalidInt8RescoringOfBinaryQuantized { .. }
                     | UserError::InvalidSourceForNested { .. }
                     | UserError::MissingSourceForNested { .. }
                     | UserError::InvalidSettingsEmbedder { .. } => Code::InvalidSettingsEmbedders,
@@ -454,10 +504,15 @@ impl ErrorCode for milli::Error {
                     }
                     UserError::CriterionError(_) => Code::InvalidSettingsRankingRules,
                     UserError::InvalidGeoField { .. } => Code::InvalidDocumentGeoField,
+                    UserError::InvalidDocumentOperator { .. } => Code::InvalidDocumentOperator,
+                    UserError::InvalidDocumentIfVersion { .. } => Code::InvalidDocumentIfVersion,
+                    UserError::InvalidDocumentSchema(_) => Code::InvalidSettingsDocumentSchema,
+                    UserError::DocumentSchemaMismatch(_) => Code::DocumentSchemaMismatch,
                     UserError::InvalidVectorDimensions { .. }
                     | UserError::InvalidIndexingVectorDimensions { .. } => {
                         Code::InvalidVectorDimensions
                     }
+                    UserError::InvalidVectorForSparseEmbedder(_) => Code::InvalidSearchVector,
                     UserError::InvalidVectorsMapType { .. }
                     | UserError::InvalidVectorsEmbedderConf { .. } => Code::InvalidVectorsType,
                     UserError::TooManyVectors(_, _) => Code::TooManyVectors,
@@ -467,6 +522,9 @@ impl ErrorCode for milli::Error {
                     }
                     UserError::InvalidSearchEmbedder(_) => Code::InvalidSearchEmbedder,
                     UserError::InvalidSimilarEmbedder(_) => Code::InvalidSimilarEmbedder,
+                    UserError::TooManyCandidatesForExhaustiveSearch { .. } => {
+                        Code::InvalidSearchExhaustive
+                    }
                     UserError::VectorEmbeddingError(_) | UserError::DocumentEmbeddingError(_) => {
                         Code::VectorEmbeddingError
                     }
@@ -570,6 +628,30 @@ impl fmt::Display for deserr_codes::InvalidSearchSemanticRatio {
     }
 }
 
+impl fmt::Display for deserr_codes::InvalidSearchRerank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the value of `topK` is invalid, expected an integer between `1` and `100`.")
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidSearchSnippetsPerAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `snippetsPerAttribute` is invalid, expected an integer between `0` and `20`."
+        )
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidSearchSnippetLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `snippetLength` is invalid, expected an integer between `0` and `1000`."
+        )
+    }
+}
+
 impl fmt::Display for deserr_codes::InvalidMultiSearchWeight {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "the value of `weight` is invalid, expected a positive float (>= 0.0).")
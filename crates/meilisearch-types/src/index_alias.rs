@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// An alternative name for one or several indexes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexAlias {
+    /// The indexes the alias points to, a federated search on the alias searches all of them.
+    pub indexes: Vec<String>,
+    /// The index receiving the writes and the single-index reads made through the alias.
+    #[serde(default)]
+    pub write_target: Option<String>,
+}
+
+impl IndexAlias {
+    /// Returns the index the alias resolves to outside of federated searches.
+    ///
+    /// It's the write target, or the only index of the alias when it doesn't have one.
+    pub fn target(&self) -> Option<&str> {
+        match (&self.write_target, self.indexes.as_slice()) {
+            (Some(target), _) => Some(target),
+            (None, [index]) => Some(index),
+            (None, _) => None,
+        }
+    }
+}
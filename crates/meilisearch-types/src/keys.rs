@@ -308,6 +308,12 @@ pub enum Action {
     #[serde(rename = "network.update")]
     #[deserr(rename = "network.update")]
     NetworkUpdate,
+    #[serde(rename = "events.send")]
+    #[deserr(rename = "events.send")]
+    EventsSend,
+    #[serde(rename = "embedders.embed")]
+    #[deserr(rename = "embedders.embed")]
+    EmbeddersEmbed,
 }
 
 impl Action {
@@ -349,6 +355,8 @@ impl Action {
             EXPERIMENTAL_FEATURES_UPDATE => Some(Self::ExperimentalFeaturesUpdate),
             NETWORK_GET => Some(Self::NetworkGet),
             NETWORK_UPDATE => Some(Self::NetworkUpdate),
+            EVENTS_SEND => Some(Self::EventsSend),
+            EMBEDDERS_EMBED => Some(Self::EmbeddersEmbed),
             _otherwise => None,
         }
     }
@@ -397,4 +405,8 @@ pub mod actions {
 
     pub const NETWORK_GET: u8 = NetworkGet.repr();
     pub const NETWORK_UPDATE: u8 = NetworkUpdate.repr();
+
+    pub const EVENTS_SEND: u8 = EventsSend.repr();
+
+    pub const EMBEDDERS_EMBED: u8 = EmbeddersEmbed.repr();
 }
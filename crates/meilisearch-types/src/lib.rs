@@ -6,6 +6,7 @@ pub mod document_formats;
 pub mod error;
 pub mod facet_values_sort;
 pub mod features;
+pub mod index_alias;
 pub mod index_uid;
 pub mod index_uid_pattern;
 pub mod keys;
@@ -245,6 +245,16 @@ pub struct Settings<T> {
     #[deserr(default, error = DeserrJsonError<InvalidSettingsDistinctAttribute>)]
     #[schema(value_type = Option<String>, example = json!("sku"))]
     pub distinct_attribute: Setting<String>,
+    /// Field containing the Unix timestamp, in seconds, after which a document expires.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsTtlAttribute>)]
+    #[schema(value_type = Option<String>, example = json!("expiresAt"))]
+    pub ttl_attribute: Setting<String>,
+    /// JSON schema the documents sent to the index must match.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsDocumentSchema>)]
+    #[schema(value_type = Option<Object>, example = json!({ "type": "object", "required": ["title"], "properties": { "title": { "type": "string" } } }))]
+    pub document_schema: Setting<serde_json::Value>,
     /// Precision level when calculating the proximity ranking rule.
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsProximityPrecision>)]
@@ -345,6 +355,8 @@ impl Settings<Checked> {
             separator_tokens: Setting::Reset,
             dictionary: Setting::Reset,
             distinct_attribute: Setting::Reset,
+            ttl_attribute: Setting::Reset,
+            document_schema: Setting::Reset,
             proximity_precision: Setting::Reset,
             typo_tolerance: Setting::Reset,
             faceting: Setting::Reset,
@@ -371,6 +383,8 @@ impl Settings<Checked> {
             dictionary,
             synonyms,
             distinct_attribute,
+            ttl_attribute,
+            document_schema,
             proximity_precision,
             typo_tolerance,
             faceting,
@@ -395,6 +409,8 @@ impl Settings<Checked> {
             dictionary,
             synonyms,
             distinct_attribute,
+            ttl_attribute,
+            document_schema,
             proximity_precision,
             typo_tolerance,
             faceting,
@@ -445,6 +461,8 @@ impl Settings<Unchecked> {
             separator_tokens: self.separator_tokens,
             dictionary: self.dictionary,
             distinct_attribute: self.distinct_attribute,
+            ttl_attribute: self.ttl_attribute,
+            document_schema: self.document_schema,
             proximity_precision: self.proximity_precision,
             typo_tolerance: self.typo_tolerance,
             faceting: self.faceting,
@@ -506,6 +524,8 @@ impl Settings<Unchecked> {
                 .distinct_attribute
                 .clone()
                 .or(self.distinct_attribute.clone()),
+            ttl_attribute: other.ttl_attribute.clone().or(self.ttl_attribute.clone()),
+            document_schema: other.document_schema.clone().or(self.document_schema.clone()),
             proximity_precision: other.proximity_precision.or(self.proximity_precision),
             typo_tolerance: other.typo_tolerance.clone().or(self.typo_tolerance.clone()),
             faceting: other.faceting.clone().or(self.faceting.clone()),
@@ -559,6 +579,8 @@ pub fn apply_settings_to_builder(
         dictionary,
         synonyms,
         distinct_attribute,
+        ttl_attribute,
+        document_schema,
         proximity_precision,
         typo_tolerance,
         faceting,
@@ -645,6 +667,18 @@ pub fn apply_settings_to_builder(
         Setting::NotSet => (),
     }
 
+    match ttl_attribute {
+        Setting::Set(ref attr) => builder.set_ttl_field(attr.clone()),
+        Setting::Reset => builder.reset_ttl_field(),
+        Setting::NotSet => (),
+    }
+
+    match document_schema {
+        Setting::Set(ref schema) => builder.set_document_schema(schema.clone()),
+        Setting::Reset => builder.reset_document_schema(),
+        Setting::NotSet => (),
+    }
+
     match proximity_precision {
         Setting::Set(ref precision) => builder.set_proximity_precision((*precision).into()),
         Setting::Reset => builder.reset_proximity_precision(),
@@ -811,6 +845,10 @@ pub fn settings(
 
     let distinct_field = index.distinct_field(rtxn)?.map(String::from);
 
+    let ttl_field = index.ttl_field(rtxn)?.map(String::from);
+
+    let document_schema = index.document_schema(rtxn)?;
+
     let proximity_precision = index.proximity_precision(rtxn)?.map(ProximityPrecisionView::from);
 
     let synonyms = index.user_defined_synonyms(rtxn)?;
@@ -862,6 +900,7 @@ pub fn settings(
     let embedders: BTreeMap<_, _> = index
         .embedding_configs(rtxn)?
         .into_iter()
+        .chain(index.sparse_embedding_configs(rtxn)?)
         .map(|IndexEmbeddingConfig { name, config, .. }| {
             (name, SettingEmbeddingSettings { inner: Setting::Set(config.into()) })
         })
@@ -898,6 +937,14 @@ pub fn settings(
             Some(field) => Setting::Set(field),
             None => Setting::Reset,
         },
+        ttl_attribute: match ttl_field {
+            Some(field) => Setting::Set(field),
+            None => Setting::Reset,
+        },
+        document_schema: match document_schema {
+            Some(schema) => Setting::Set(schema),
+            None => Setting::Reset,
+        },
         proximity_precision: Setting::Set(proximity_precision.unwrap_or_default()),
         synonyms: Setting::Set(synonyms),
         typo_tolerance: Setting::Set(typo_tolerance),
@@ -941,6 +988,9 @@ pub enum RankingRuleView {
     Sort,
     /// Sorted by the similarity of the matched words with the query words.
     Exactness,
+    /// Sorted by the decreasing click-through rate of the documents across all queries,
+    /// maintained from the searches and the click and conversion events sent to the `/events` route.
+    Popularity,
     /// Sorted by the increasing value of the field specified.
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
@@ -999,6 +1049,7 @@ impl From<Criterion> for RankingRuleView {
             Criterion::Attribute => RankingRuleView::Attribute,
             Criterion::Sort => RankingRuleView::Sort,
             Criterion::Exactness => RankingRuleView::Exactness,
+            Criterion::Popularity => RankingRuleView::Popularity,
             Criterion::Asc(x) => RankingRuleView::Asc(x),
             Criterion::Desc(x) => RankingRuleView::Desc(x),
         }
@@ -1013,6 +1064,7 @@ impl From<RankingRuleView> for Criterion {
             RankingRuleView::Attribute => Criterion::Attribute,
             RankingRuleView::Sort => Criterion::Sort,
             RankingRuleView::Exactness => Criterion::Exactness,
+            RankingRuleView::Popularity => Criterion::Popularity,
             RankingRuleView::Asc(x) => Criterion::Asc(x),
             RankingRuleView::Desc(x) => Criterion::Desc(x),
         }
@@ -1132,6 +1184,8 @@ pub(crate) mod test {
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
+            ttl_attribute: Setting::NotSet,
+            document_schema: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
             faceting: Setting::NotSet,
@@ -1163,6 +1217,8 @@ pub(crate) mod test {
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
+            ttl_attribute: Setting::NotSet,
+            document_schema: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
             faceting: Setting::NotSet,
@@ -6,7 +6,10 @@ use utoipa::ToSchema;
 use crate::batches::BatchId;
 use crate::error::ResponseError;
 use crate::settings::{Settings, Unchecked};
-use crate::tasks::{serialize_duration, Details, IndexSwap, Kind, Status, Task, TaskId};
+use crate::tasks::{
+    serialize_duration, Details, DocumentVersionConflict, IndexSwap, Kind, SkippedDocument, Status,
+    Task, TaskId, MAX_REPORTED_SKIPPED_DOCUMENTS, MAX_REPORTED_VERSION_CONFLICTS,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -77,9 +80,24 @@ pub struct DetailsView {
     /// Number of documents finally indexed for documentAdditionOrUpdate task or a documentAdditionOrUpdate batch of tasks.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub indexed_documents: Option<Option<u64>>,
+    /// Documents that were not written because their `_ifVersion` didn't match their current version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_conflicts: Option<Vec<DocumentVersionConflict>>,
+    /// Number of documents that were not written because of a version conflict, only the first 100 are listed in `versionConflicts`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_conflicts_count: Option<u64>,
+    /// Documents that were not written because they are invalid, when the invalid documents are skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped_documents: Option<Vec<SkippedDocument>>,
+    /// Number of documents that were not written because they are invalid, only the first 100 are listed in `skippedDocuments`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped_documents_count: Option<u64>,
     /// Number of documents edited for editDocumentByFunction task.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub edited_documents: Option<Option<u64>>,
+    /// Number of documents whose popularity was updated for documentPopularityUpdate task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_documents: Option<Option<u64>>,
     /// Value for the primaryKey field encountered if any for indexCreation or indexUpdate task.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub primary_key: Option<Option<String>>,
@@ -114,6 +132,9 @@ pub struct DetailsView {
     pub settings: Option<Box<Settings<Unchecked>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub swaps: Option<Vec<IndexSwap>>,
+    /// The uid of the index created by an indexClone task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_index_uid: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub upgrade_from: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -136,6 +157,46 @@ impl DetailsView {
                 }
                 (Some(Some(left)), Some(Some(right))) => Some(Some(left + right)),
             },
+            version_conflicts: match (
+                self.version_conflicts.clone(),
+                other.version_conflicts.clone(),
+            ) {
+                (None, None) => None,
+                (None, Some(conflicts)) | (Some(conflicts), None) => Some(conflicts),
+                (Some(mut left), Some(mut right)) => {
+                    left.append(&mut right);
+                    left.truncate(MAX_REPORTED_VERSION_CONFLICTS);
+                    Some(left)
+                }
+            },
+            version_conflicts_count: match (
+                self.version_conflicts_count,
+                other.version_conflicts_count,
+            ) {
+                (None, None) => None,
+                (None, Some(count)) | (Some(count), None) => Some(count),
+                (Some(left), Some(right)) => Some(left + right),
+            },
+            skipped_documents: match (
+                self.skipped_documents.clone(),
+                other.skipped_documents.clone(),
+            ) {
+                (None, None) => None,
+                (None, Some(skipped)) | (Some(skipped), None) => Some(skipped),
+                (Some(mut left), Some(mut right)) => {
+                    left.append(&mut right);
+                    left.truncate(MAX_REPORTED_SKIPPED_DOCUMENTS);
+                    Some(left)
+                }
+            },
+            skipped_documents_count: match (
+                self.skipped_documents_count,
+                other.skipped_documents_count,
+            ) {
+                (None, None) => None,
+                (None, Some(count)) | (Some(count), None) => Some(count),
+                (Some(left), Some(right)) => Some(left + right),
+            },
             edited_documents: match (self.edited_documents, other.edited_documents) {
                 (None, None) => None,
                 (None, Some(None)) | (Some(None), None) | (Some(None), Some(None)) => Some(None),
@@ -144,6 +205,14 @@ impl DetailsView {
                 }
                 (Some(Some(left)), Some(Some(right))) => Some(Some(left + right)),
             },
+            updated_documents: match (self.updated_documents, other.updated_documents) {
+                (None, None) => None,
+                (None, Some(None)) | (Some(None), None) | (Some(None), Some(None)) => Some(None),
+                (None | Some(None), Some(Some(doc))) | (Some(Some(doc)), None | Some(None)) => {
+                    Some(Some(doc))
+                }
+                (Some(Some(left)), Some(Some(right))) => Some(Some(left + right)),
+            },
             primary_key: match (&self.primary_key, &other.primary_key) {
                 (None, None) => None,
                 (None, Some(None)) | (Some(None), None) | (Some(None), Some(None)) => Some(None),
@@ -238,6 +307,7 @@ impl DetailsView {
                     Some(left)
                 }
             },
+            new_index_uid: other.new_index_uid.clone().or(self.new_index_uid.clone()),
             // We want the earliest version
             upgrade_from: match (self.upgrade_from.clone(), other.upgrade_from.clone()) {
                 (None, None) => None,
@@ -257,13 +327,24 @@ impl DetailsView {
 impl From<Details> for DetailsView {
     fn from(details: Details) -> Self {
         match details {
-            Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
-                DetailsView {
-                    received_documents: Some(received_documents),
-                    indexed_documents: Some(indexed_documents),
-                    ..DetailsView::default()
-                }
-            }
+            Details::DocumentAdditionOrUpdate {
+                received_documents,
+                indexed_documents,
+                version_conflicts,
+                version_conflicts_count,
+                skipped_documents,
+                skipped_documents_count,
+            } => DetailsView {
+                received_documents: Some(received_documents),
+                indexed_documents: Some(indexed_documents),
+                version_conflicts: (!version_conflicts.is_empty()).then_some(version_conflicts),
+                version_conflicts_count: (version_conflicts_count != 0)
+                    .then_some(version_conflicts_count),
+                skipped_documents: (!skipped_documents.is_empty()).then_some(skipped_documents),
+                skipped_documents_count: (skipped_documents_count != 0)
+                    .then_some(skipped_documents_count),
+                ..DetailsView::default()
+            },
             Details::DocumentEdition {
                 deleted_documents,
                 edited_documents,
@@ -327,6 +408,16 @@ impl From<Details> for DetailsView {
             Details::IndexSwap { swaps } => {
                 DetailsView { swaps: Some(swaps), ..Default::default() }
             }
+            Details::IndexClone { new_index_uid } => {
+                DetailsView { new_index_uid: Some(new_index_uid), ..Default::default() }
+            }
+            Details::DocumentPopularityUpdate { received_documents, updated_documents } => {
+                DetailsView {
+                    received_documents: Some(received_documents),
+                    updated_documents: Some(updated_documents),
+                    ..Default::default()
+                }
+            }
             Details::UpgradeDatabase { from, to } => DetailsView {
                 upgrade_from: Some(format!("v{}.{}.{}", from.0, from.1, from.2)),
                 upgrade_to: Some(format!("v{}.{}.{}", to.0, to.1, to.2)),
@@ -1,11 +1,11 @@
 use core::fmt;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Write};
 use std::str::FromStr;
 
 use enum_iterator::Sequence;
-use milli::update::IndexDocumentsMethod;
-use milli::Object;
+use milli::update::{IndexDocumentsMethod, OnError};
+use milli::{DocumentPopularity, Object};
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize, Serializer};
 use time::{Duration, OffsetDateTime};
@@ -60,6 +60,8 @@ impl Task {
             | SettingsUpdate { index_uid, .. }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
+            | IndexClone { index_uid, .. }
+            | DocumentPopularityUpdate { index_uid, .. }
             | IndexDeletion { index_uid } => Some(index_uid),
         }
     }
@@ -82,6 +84,8 @@ impl Task {
             | KindWithContent::IndexCreation { .. }
             | KindWithContent::IndexUpdate { .. }
             | KindWithContent::IndexSwap { .. }
+            | KindWithContent::IndexClone { .. }
+            | KindWithContent::DocumentPopularityUpdate { .. }
             | KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
@@ -101,6 +105,8 @@ pub enum KindWithContent {
         content_file: Uuid,
         documents_count: u64,
         allow_index_creation: bool,
+        #[serde(default)]
+        on_error: OnError,
     },
     DocumentDeletion {
         index_uid: String,
@@ -139,6 +145,16 @@ pub enum KindWithContent {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    IndexClone {
+        index_uid: String,
+        new_index_uid: String,
+    },
+    DocumentPopularityUpdate {
+        index_uid: String,
+        /// The impressions and engagement each document received since the last update,
+        /// by external document id.
+        increments: BTreeMap<String, DocumentPopularity>,
+    },
     TaskCancelation {
         query: String,
         tasks: RoaringBitmap,
@@ -163,6 +179,50 @@ pub struct IndexSwap {
     pub indexes: (String, String),
 }
 
+/// The maximum number of version conflicts listed in the details of a task,
+/// the others are only counted.
+pub const MAX_REPORTED_VERSION_CONFLICTS: usize = 100;
+
+/// A document that was not written because its `_ifVersion` didn't match its current version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentVersionConflict {
+    pub document_id: String,
+    pub expected_version: u64,
+    pub current_version: u64,
+}
+
+impl From<milli::update::new::indexer::VersionConflict> for DocumentVersionConflict {
+    fn from(conflict: milli::update::new::indexer::VersionConflict) -> Self {
+        let milli::update::new::indexer::VersionConflict {
+            document_id,
+            expected_version,
+            current_version,
+        } = conflict;
+        Self { document_id, expected_version, current_version }
+    }
+}
+
+/// The maximum number of skipped documents listed in the details of a task,
+/// the others are only counted.
+pub const MAX_REPORTED_SKIPPED_DOCUMENTS: usize = 100;
+
+/// A document that was not written because it is invalid, when the invalid documents are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedDocument {
+    /// The id of the document, `null` if it couldn't be extracted.
+    pub document_id: Option<String>,
+    pub reason: String,
+}
+
+impl From<milli::update::new::indexer::SkippedDocument> for SkippedDocument {
+    fn from(skipped: milli::update::new::indexer::SkippedDocument) -> Self {
+        let milli::update::new::indexer::SkippedDocument { document_id, reason } = skipped;
+        Self { document_id, reason }
+    }
+}
+
 impl KindWithContent {
     pub fn as_kind(&self) -> Kind {
         match self {
@@ -176,6 +236,8 @@ impl KindWithContent {
             KindWithContent::IndexDeletion { .. } => Kind::IndexDeletion,
             KindWithContent::IndexUpdate { .. } => Kind::IndexUpdate,
             KindWithContent::IndexSwap { .. } => Kind::IndexSwap,
+            KindWithContent::IndexClone { .. } => Kind::IndexClone,
+            KindWithContent::DocumentPopularityUpdate { .. } => Kind::DocumentPopularityUpdate,
             KindWithContent::TaskCancelation { .. } => Kind::TaskCancelation,
             KindWithContent::TaskDeletion { .. } => Kind::TaskDeletion,
             KindWithContent::DumpCreation { .. } => Kind::DumpCreation,
@@ -201,7 +263,9 @@ impl KindWithContent {
             | SettingsUpdate { index_uid, .. }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
+            | DocumentPopularityUpdate { index_uid, .. }
             | IndexDeletion { index_uid } => vec![index_uid],
+            IndexClone { index_uid, new_index_uid } => vec![index_uid, new_index_uid],
             IndexSwap { swaps } => {
                 let mut indexes = HashSet::<&str>::default();
                 for swap in swaps {
@@ -221,6 +285,10 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    version_conflicts: Vec::new(),
+                    version_conflicts_count: 0,
+                    skipped_documents: Vec::new(),
+                    skipped_documents_count: 0,
                 })
             }
             KindWithContent::DocumentEdition { index_uid: _, filter_expr, context, function } => {
@@ -257,6 +325,15 @@ impl KindWithContent {
             KindWithContent::IndexSwap { swaps } => {
                 Some(Details::IndexSwap { swaps: swaps.clone() })
             }
+            KindWithContent::IndexClone { new_index_uid, .. } => {
+                Some(Details::IndexClone { new_index_uid: new_index_uid.clone() })
+            }
+            KindWithContent::DocumentPopularityUpdate { increments, .. } => {
+                Some(Details::DocumentPopularityUpdate {
+                    received_documents: increments.len() as u64,
+                    updated_documents: None,
+                })
+            }
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -286,6 +363,10 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: Some(0),
+                    version_conflicts: Vec::new(),
+                    version_conflicts_count: 0,
+                    skipped_documents: Vec::new(),
+                    skipped_documents_count: 0,
                 })
             }
             KindWithContent::DocumentEdition { index_uid: _, filter_expr, context, function } => {
@@ -323,6 +404,15 @@ impl KindWithContent {
             KindWithContent::IndexSwap { .. } => {
                 todo!()
             }
+            KindWithContent::IndexClone { new_index_uid, .. } => {
+                Some(Details::IndexClone { new_index_uid: new_index_uid.clone() })
+            }
+            KindWithContent::DocumentPopularityUpdate { increments, .. } => {
+                Some(Details::DocumentPopularityUpdate {
+                    received_documents: increments.len() as u64,
+                    updated_documents: Some(0),
+                })
+            }
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: Some(0),
@@ -354,6 +444,10 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    version_conflicts: Vec::new(),
+                    version_conflicts_count: 0,
+                    skipped_documents: Vec::new(),
+                    skipped_documents_count: 0,
                 })
             }
             KindWithContent::DocumentEdition { .. } => None,
@@ -371,6 +465,15 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
             }
             KindWithContent::IndexSwap { .. } => None,
+            KindWithContent::IndexClone { new_index_uid, .. } => {
+                Some(Details::IndexClone { new_index_uid: new_index_uid.clone() })
+            }
+            KindWithContent::DocumentPopularityUpdate { increments, .. } => {
+                Some(Details::DocumentPopularityUpdate {
+                    received_documents: increments.len() as u64,
+                    updated_documents: None,
+                })
+            }
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -495,6 +598,8 @@ pub enum Kind {
     IndexDeletion,
     IndexUpdate,
     IndexSwap,
+    IndexClone,
+    DocumentPopularityUpdate,
     TaskCancelation,
     TaskDeletion,
     DumpCreation,
@@ -511,8 +616,10 @@ impl Kind {
             | Kind::SettingsUpdate
             | Kind::IndexCreation
             | Kind::IndexDeletion
-            | Kind::IndexUpdate => true,
+            | Kind::IndexUpdate
+            | Kind::DocumentPopularityUpdate => true,
             Kind::IndexSwap
+            | Kind::IndexClone
             | Kind::TaskCancelation
             | Kind::TaskDeletion
             | Kind::DumpCreation
@@ -532,6 +639,8 @@ impl Display for Kind {
             Kind::IndexDeletion => write!(f, "indexDeletion"),
             Kind::IndexUpdate => write!(f, "indexUpdate"),
             Kind::IndexSwap => write!(f, "indexSwap"),
+            Kind::IndexClone => write!(f, "indexClone"),
+            Kind::DocumentPopularityUpdate => write!(f, "documentPopularityUpdate"),
             Kind::TaskCancelation => write!(f, "taskCancelation"),
             Kind::TaskDeletion => write!(f, "taskDeletion"),
             Kind::DumpCreation => write!(f, "dumpCreation"),
@@ -550,6 +659,8 @@ impl FromStr for Kind {
             Ok(Kind::IndexUpdate)
         } else if kind.eq_ignore_ascii_case("indexSwap") {
             Ok(Kind::IndexSwap)
+        } else if kind.eq_ignore_ascii_case("indexClone") {
+            Ok(Kind::IndexClone)
         } else if kind.eq_ignore_ascii_case("indexDeletion") {
             Ok(Kind::IndexDeletion)
         } else if kind.eq_ignore_ascii_case("documentAdditionOrUpdate") {
@@ -558,6 +669,8 @@ impl FromStr for Kind {
             Ok(Kind::DocumentEdition)
         } else if kind.eq_ignore_ascii_case("documentDeletion") {
             Ok(Kind::DocumentDeletion)
+        } else if kind.eq_ignore_ascii_case("documentPopularityUpdate") {
+            Ok(Kind::DocumentPopularityUpdate)
         } else if kind.eq_ignore_ascii_case("settingsUpdate") {
             Ok(Kind::SettingsUpdate)
         } else if kind.eq_ignore_ascii_case("taskCancelation") {
@@ -602,6 +715,14 @@ pub enum Details {
     DocumentAdditionOrUpdate {
         received_documents: u64,
         indexed_documents: Option<u64>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        version_conflicts: Vec<DocumentVersionConflict>,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        version_conflicts_count: u64,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        skipped_documents: Vec<SkippedDocument>,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        skipped_documents_count: u64,
     },
     SettingsUpdate {
         settings: Box<Settings<Unchecked>>,
@@ -643,6 +764,13 @@ pub enum Details {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    IndexClone {
+        new_index_uid: String,
+    },
+    DocumentPopularityUpdate {
+        received_documents: u64,
+        updated_documents: Option<u64>,
+    },
     UpgradeDatabase {
         from: (u32, u32, u32),
         to: (u32, u32, u32),
@@ -662,19 +790,27 @@ impl Details {
                 *deleted_documents = Some(0)
             }
             Self::ClearAll { deleted_documents } => *deleted_documents = Some(0),
+            Self::DocumentPopularityUpdate { updated_documents, .. } => {
+                *updated_documents = Some(0)
+            }
             Self::TaskCancelation { canceled_tasks, .. } => *canceled_tasks = Some(0),
             Self::TaskDeletion { deleted_tasks, .. } => *deleted_tasks = Some(0),
             Self::SettingsUpdate { .. }
             | Self::IndexInfo { .. }
             | Self::Dump { .. }
             | Self::UpgradeDatabase { .. }
-            | Self::IndexSwap { .. } => (),
+            | Self::IndexSwap { .. }
+            | Self::IndexClone { .. } => (),
         }
 
         details
     }
 }
 
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
 /// Serialize a `time::Duration` as a best effort ISO 8601 while waiting for
 /// https://github.com/time-rs/time/issues/378.
 /// This code is a port of the old code of time that was removed in 0.2.
@@ -0,0 +1,701 @@
+//! A local, opt-in store of the searches made on each index.
+//!
+//! Unlike the segment analytics, nothing recorded here ever leaves the instance:
+//! every search is stored in a dedicated LMDB environment under the database path,
+//! and is only exposed through the `/indexes/{indexUid}/analytics` routes.
+//!
+//! Each search is identified by a `queryId` returned in the search response, that the
+//! click and conversion events sent to the `/events` route refer to.
+//!
+//! The searches and events are buffered in memory and written by a background thread,
+//! which also deletes the records past their retention period and those of deleted indexes.
+//! The documents returned by each search and the events received on them are accumulated
+//! by index and regularly registered as `documentPopularityUpdate` tasks, so that the
+//! click-through rate of every document is maintained by the scheduler.
+//! An event is only counted once per `queryId`, document and kind.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::heed::types::{Bytes, DecodeIgnore, SerdeJson, Unit};
+use meilisearch_types::heed::{self, Database, Env, EnvOpenOptions, RwTxn, WithoutTls};
+use meilisearch_types::milli::{DocumentId, DocumentPopularity};
+use meilisearch_types::tasks::KindWithContent;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tracing::{error, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::search::{FederatedSearchResult, HitsInfo, SearchResult};
+
+const LOCAL_ANALYTICS_DIR: &str = "search-analytics";
+const LOCAL_ANALYTICS_STORE_SIZE: usize = 1_073_741_824; // 1GiB
+const SEARCHES_DB_NAME: &str = "searches";
+const QUERIES_DB_NAME: &str = "queries";
+const EVENTS_DB_NAME: &str = "events";
+const RECEIVED_EVENTS_DB_NAME: &str = "received-events";
+
+/// How often the pending records are written to the store.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Past this number of records waiting to be written, new records are dropped.
+const MAX_PENDING_RECORDS: usize = 100_000;
+/// How often the old records and the records of deleted indexes are deleted.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// How long the searches and events are kept.
+const SEARCHES_RETENTION: time::Duration = time::Duration::days(90);
+/// How long the `queryId`s can be referred to by events.
+const QUERIES_RETENTION: time::Duration = time::Duration::days(7);
+/// The retention is shortened when the store uses more than this part of its map size...
+const MAX_STORE_USAGE_PERCENT: u64 = 80;
+/// ...but never below this duration.
+const MIN_RETENTION: time::Duration = time::Duration::days(1);
+
+/// The minimum delay between two registrations of the accumulated popularity increments as tasks.
+const POPULARITY_UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+/// The maximum number of documents whose popularity is updated by a single task.
+const MAX_POPULARITY_UPDATES_PER_TASK: usize = 10_000;
+
+/// How much a click increases the engagement of a document.
+const CLICK_POPULARITY_WEIGHT: f64 = 1.0;
+/// A conversion is a much stronger signal than a click, so it weighs more in the engagement.
+const CONVERSION_POPULARITY_WEIGHT: f64 = 5.0;
+
+/// A single search as it is persisted in the local analytics store.
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchEvent {
+    query: String,
+    hits: u64,
+    processing_time_ms: u64,
+    no_results: bool,
+}
+
+/// The search a `queryId` refers to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedQuery {
+    pub index_uid: String,
+    /// The normalized query, empty for placeholder searches.
+    pub query: String,
+    /// The internal ids of the returned hits, in order.
+    #[serde(default)]
+    pub documents_ids: Vec<DocumentId>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize, ToSchema)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum EventKind {
+    /// The user clicked on a document returned by the search.
+    Click,
+    /// The user converted on a document returned by the search, e.g. bought or added it to a cart.
+    Conversion,
+}
+
+impl EventKind {
+    /// How much the event increases the engagement of the document it refers to.
+    pub fn popularity_weight(&self) -> f64 {
+        match self {
+            EventKind::Click => CLICK_POPULARITY_WEIGHT,
+            EventKind::Conversion => CONVERSION_POPULARITY_WEIGHT,
+        }
+    }
+}
+
+/// A click or conversion event as it is persisted in the local analytics store.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEvent {
+    kind: EventKind,
+    query_id: Uuid,
+    query: String,
+    document_id: String,
+    position: usize,
+}
+
+/// The statistics of a normalized query over a time window.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStats {
+    /// The normalized query.
+    pub query: String,
+    /// Number of times the query was searched.
+    pub count: u64,
+    /// Number of times the query returned no results.
+    pub no_results_count: u64,
+    /// Average number of hits returned for the query.
+    pub average_hits: f64,
+    /// Average processing time of the query, in milliseconds.
+    pub average_processing_time_ms: f64,
+    /// Number of clicks on the documents returned for the query.
+    pub click_count: u64,
+    /// Number of conversions on the documents returned for the query.
+    pub conversion_count: u64,
+    /// Number of clicks per search of the query.
+    pub click_through_rate: f64,
+    /// Last time the query was searched.
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_searched_at: OffsetDateTime,
+}
+
+pub struct LocalAnalytics {
+    store: Arc<Store>,
+}
+
+struct Store {
+    env: Env<WithoutTls>,
+    /// Maps `{indexUid}\0{timestamp}{sequence}` to the search made at that time.
+    searches: Database<Bytes, SerdeJson<SearchEvent>>,
+    /// Maps the `queryId` of every search, including placeholder searches, to the search.
+    ///
+    /// The `queryId`s are UUIDv7, thus the keys are ordered by time too.
+    queries: Database<Bytes, SerdeJson<RecordedQuery>>,
+    /// Maps `{indexUid}\0{timestamp}{sequence}` to the event received at that time.
+    events: Database<Bytes, SerdeJson<StoredEvent>>,
+    /// The `{queryId}{kind}{documentId}` of the events already received, see [`event_key`].
+    ///
+    /// The keys start with the `queryId`, so they are ordered by time and pruned like the queries.
+    received_events: Database<Bytes, Unit>,
+    /// Disambiguates searches and events recorded during the same nanosecond.
+    sequence: AtomicU32,
+    /// The records that have not been written yet, they are flushed in a single transaction
+    /// by a background thread so that searches never wait on a commit.
+    pending: Mutex<PendingRecords>,
+    /// Held for the whole duration of a flush so that the records taken from `pending`
+    /// can still be found until they are committed.
+    flushing: Mutex<PendingRecords>,
+    /// The impressions and engagement received since the last registered tasks,
+    /// by index and internal document id.
+    popularity: Mutex<HashMap<String, BTreeMap<DocumentId, DocumentPopularity>>>,
+}
+
+#[derive(Default)]
+struct PendingRecords {
+    queries: HashMap<Uuid, RecordedQuery>,
+    searches: Vec<(Vec<u8>, SearchEvent)>,
+    events: Vec<(Vec<u8>, StoredEvent)>,
+    /// The keys of the pending events, in the format of [`Store::received_events`].
+    received_events: HashSet<Vec<u8>>,
+}
+
+impl PendingRecords {
+    fn len(&self) -> usize {
+        self.queries.len() + self.searches.len() + self.events.len()
+    }
+}
+
+impl LocalAnalytics {
+    pub fn new(db_path: &Path, index_scheduler: Arc<IndexScheduler>) -> anyhow::Result<Self> {
+        let path = db_path.join(LOCAL_ANALYTICS_DIR);
+        std::fs::create_dir_all(&path)?;
+
+        let options = EnvOpenOptions::new();
+        let mut options = options.read_txn_without_tls();
+        options.map_size(LOCAL_ANALYTICS_STORE_SIZE);
+        options.max_dbs(4);
+        let env = unsafe { options.open(&path) }?;
+
+        let mut wtxn = env.write_txn()?;
+        let searches = env.create_database(&mut wtxn, Some(SEARCHES_DB_NAME))?;
+        let queries = env.create_database(&mut wtxn, Some(QUERIES_DB_NAME))?;
+        let events = env.create_database(&mut wtxn, Some(EVENTS_DB_NAME))?;
+        let received_events = env.create_database(&mut wtxn, Some(RECEIVED_EVENTS_DB_NAME))?;
+        wtxn.commit()?;
+
+        let store = Arc::new(Store {
+            env,
+            searches,
+            queries,
+            events,
+            received_events,
+            sequence: AtomicU32::new(0),
+            pending: Mutex::default(),
+            flushing: Mutex::default(),
+            popularity: Mutex::default(),
+        });
+
+        let weak_store = Arc::downgrade(&store);
+        let weak_scheduler = Arc::downgrade(&index_scheduler);
+        std::thread::Builder::new()
+            .name(String::from("local-analytics-flusher"))
+            .spawn(move || flush_loop(weak_store, weak_scheduler))?;
+
+        Ok(Self { store })
+    }
+
+    /// Records a successful search made on the given index and returns its `queryId`.
+    ///
+    /// Placeholder searches are not part of the query statistics since they do not tell anything
+    /// about what users look for, but they still get a `queryId` so that events can refer to them.
+    /// The returned hits of all the searches count as impressions in the popularity of the documents.
+    pub fn record(&self, index_uid: &str, result: &SearchResult) -> Uuid {
+        let hits = match result.hits_info {
+            HitsInfo::Pagination { total_hits, .. } => total_hits,
+            HitsInfo::OffsetLimit { estimated_total_hits, .. } => estimated_total_hits,
+        } as u64;
+        let query_id = Uuid::now_v7();
+        let query = normalize_query(&result.query);
+        let recorded = RecordedQuery {
+            index_uid: index_uid.to_string(),
+            query: query.clone(),
+            documents_ids: result.documents_ids.clone(),
+        };
+
+        let mut pending = self.store.pending.lock().unwrap();
+        if pending.len() >= MAX_PENDING_RECORDS {
+            warn!("Too many local search analytics are waiting to be written, dropping a search");
+            return query_id;
+        }
+        pending.queries.insert(query_id, recorded);
+        if !query.is_empty() {
+            let event = SearchEvent {
+                query,
+                hits,
+                processing_time_ms: result.processing_time_ms as u64,
+                no_results: hits == 0,
+            };
+            pending.searches.push((self.store.new_key(index_uid), event));
+        }
+        drop(pending);
+
+        for &docid in &result.documents_ids {
+            let impression = DocumentPopularity { impressions: 1, engagement: 0.0 };
+            self.store.increment_popularity(index_uid, docid, impression);
+        }
+
+        query_id
+    }
+
+    /// Records a query of a federated search made on the given index.
+    ///
+    /// The hits of a federated search are merged across all its queries, so only the
+    /// query statistics are recorded and events cannot refer to it.
+    pub fn record_federated(&self, index_uid: &str, query: &str, result: &FederatedSearchResult) {
+        let query = normalize_query(query);
+        if query.is_empty() {
+            return;
+        }
+        let hits = match result.hits_info {
+            HitsInfo::Pagination { total_hits, .. } => total_hits,
+            HitsInfo::OffsetLimit { estimated_total_hits, .. } => estimated_total_hits,
+        } as u64;
+        let event = SearchEvent {
+            query,
+            hits,
+            processing_time_ms: result.processing_time_ms as u64,
+            no_results: hits == 0,
+        };
+
+        let mut pending = self.store.pending.lock().unwrap();
+        if pending.len() >= MAX_PENDING_RECORDS {
+            warn!("Too many local search analytics are waiting to be written, dropping a search");
+            return;
+        }
+        pending.searches.push((self.store.new_key(index_uid), event));
+    }
+
+    /// Returns the search the given `queryId` refers to, if any.
+    pub fn query(&self, query_id: Uuid) -> heed::Result<Option<RecordedQuery>> {
+        if let Some(recorded) = self.store.pending.lock().unwrap().queries.get(&query_id) {
+            return Ok(Some(recorded.clone()));
+        }
+        // waits for the flush in progress, if any, to be committed
+        if let Some(recorded) = self.store.flushing.lock().unwrap().queries.get(&query_id) {
+            return Ok(Some(recorded.clone()));
+        }
+        let rtxn = self.store.env.read_txn()?;
+        self.store.queries.get(&rtxn, query_id.as_bytes())
+    }
+
+    /// Records a click or conversion event on a document returned by the given search
+    /// and schedules the increment of the engagement of the document.
+    ///
+    /// The event is ignored if an event of the same kind was already received for this
+    /// document and `queryId`, so that retried or replayed events are only counted once.
+    pub fn record_event(
+        &self,
+        query_id: Uuid,
+        recorded: &RecordedQuery,
+        kind: EventKind,
+        document_id: &str,
+        docid: DocumentId,
+        position: usize,
+    ) -> heed::Result<()> {
+        let key = event_key(query_id, kind, document_id);
+        // Holding the flushing lock ensures that no pending event is committed
+        // between the lookup in the store and the one in the pending records.
+        let _flushing = self.store.flushing.lock().unwrap();
+        let rtxn = self.store.env.read_txn()?;
+        if self.store.received_events.get(&rtxn, &key)?.is_some() {
+            return Ok(());
+        }
+        drop(rtxn);
+
+        let mut pending = self.store.pending.lock().unwrap();
+        if pending.received_events.contains(&key) {
+            return Ok(());
+        }
+        if pending.len() >= MAX_PENDING_RECORDS {
+            warn!("Too many local search analytics are waiting to be written, dropping an event");
+            return Ok(());
+        }
+        let event = StoredEvent {
+            kind,
+            query_id,
+            query: recorded.query.clone(),
+            document_id: document_id.to_string(),
+            position,
+        };
+        pending.events.push((self.store.new_key(&recorded.index_uid), event));
+        pending.received_events.insert(key);
+        drop(pending);
+
+        let engagement =
+            DocumentPopularity { impressions: 0, engagement: kind.popularity_weight() };
+        self.store.increment_popularity(&recorded.index_uid, docid, engagement);
+        Ok(())
+    }
+
+    /// Aggregates the searches made on the given index between `from` (included) and `to` (excluded)
+    /// by normalized query.
+    pub fn query_stats(
+        &self,
+        index_uid: &str,
+        from: Option<OffsetDateTime>,
+        to: Option<OffsetDateTime>,
+    ) -> heed::Result<Vec<QueryStats>> {
+        let prefix = index_prefix(index_uid);
+        let start = match from {
+            Some(from) => [prefix.as_slice(), &timestamp_bytes(from)].concat(),
+            None => prefix.clone(),
+        };
+        let end = match to {
+            Some(to) => [prefix.as_slice(), &timestamp_bytes(to)].concat(),
+            None => {
+                // The separator is the smallest byte, bumping it gives the first key after this index.
+                let mut end = prefix.clone();
+                *end.last_mut().unwrap() += 1;
+                end
+            }
+        };
+
+        // the statistics must include the searches made right before
+        self.store.flush()?;
+
+        let mut stats: HashMap<String, QueryStats> = HashMap::new();
+        let rtxn = self.store.env.read_txn()?;
+        let range = (Bound::Included(start.as_slice()), Bound::Excluded(end.as_slice()));
+        for result in self.store.searches.range(&rtxn, &range)? {
+            let (key, event) = result?;
+            let searched_at = timestamp_from_key(&key[prefix.len()..]);
+            let stat = stats.entry(event.query.clone()).or_insert_with(|| QueryStats {
+                query: event.query,
+                count: 0,
+                no_results_count: 0,
+                average_hits: 0.0,
+                average_processing_time_ms: 0.0,
+                click_count: 0,
+                conversion_count: 0,
+                click_through_rate: 0.0,
+                last_searched_at: searched_at,
+            });
+            // we store the sums in the averages and divide once everything has been aggregated
+            stat.count += 1;
+            stat.no_results_count += event.no_results as u64;
+            stat.average_hits += event.hits as f64;
+            stat.average_processing_time_ms += event.processing_time_ms as f64;
+            stat.last_searched_at = stat.last_searched_at.max(searched_at);
+        }
+
+        // the events of searches that are not part of the window are ignored
+        for result in self.store.events.range(&rtxn, &range)? {
+            let (_key, event) = result?;
+            if let Some(stat) = stats.get_mut(&event.query) {
+                match event.kind {
+                    EventKind::Click => stat.click_count += 1,
+                    EventKind::Conversion => stat.conversion_count += 1,
+                }
+            }
+        }
+
+        Ok(stats
+            .into_values()
+            .map(|mut stat| {
+                stat.average_hits /= stat.count as f64;
+                stat.average_processing_time_ms /= stat.count as f64;
+                stat.click_through_rate = stat.click_count as f64 / stat.count as f64;
+                stat
+            })
+            .collect())
+    }
+}
+
+impl Store {
+    /// Generates a key ordered by time for a search or an event made on the given index.
+    fn new_key(&self, index_uid: &str) -> Vec<u8> {
+        let mut key = index_prefix(index_uid);
+        key.extend_from_slice(&timestamp_bytes(OffsetDateTime::now_utc()));
+        key.extend_from_slice(&self.sequence.fetch_add(1, Ordering::Relaxed).to_be_bytes());
+        key
+    }
+
+    /// Writes all the pending records in a single transaction.
+    fn flush(&self) -> heed::Result<()> {
+        let mut flushing = self.flushing.lock().unwrap();
+        *flushing = std::mem::take(&mut *self.pending.lock().unwrap());
+        if flushing.len() == 0 {
+            return Ok(());
+        }
+
+        let mut wtxn = self.env.write_txn()?;
+        for (query_id, recorded) in &flushing.queries {
+            self.queries.put(&mut wtxn, query_id.as_bytes(), recorded)?;
+        }
+        for (key, search) in &flushing.searches {
+            self.searches.put(&mut wtxn, key, search)?;
+        }
+        for (key, event) in &flushing.events {
+            self.events.put(&mut wtxn, key, event)?;
+        }
+        for key in &flushing.received_events {
+            self.received_events.put(&mut wtxn, key, &())?;
+        }
+        wtxn.commit()?;
+
+        *flushing = PendingRecords::default();
+        Ok(())
+    }
+
+    /// Adds `increment` to the impressions and engagement of the given document
+    /// that will be registered by the next `documentPopularityUpdate` task.
+    fn increment_popularity(
+        &self,
+        index_uid: &str,
+        docid: DocumentId,
+        increment: DocumentPopularity,
+    ) {
+        let mut popularity = self.popularity.lock().unwrap();
+        let pending_documents: usize = popularity.values().map(BTreeMap::len).sum();
+        let increments = popularity.entry(index_uid.to_string()).or_default();
+        match increments.get_mut(&docid) {
+            Some(current) => {
+                current.impressions += increment.impressions;
+                current.engagement += increment.engagement;
+            }
+            None if pending_documents < MAX_PENDING_RECORDS => {
+                increments.insert(docid, increment);
+            }
+            None => warn!("Too many popularity updates are waiting to be registered, dropping one"),
+        }
+    }
+
+    /// Registers a `documentPopularityUpdate` task per index with the impressions and engagement
+    /// accumulated since the last call, and returns whether there was anything to register.
+    ///
+    /// The internal ids of the documents are converted to their external ids right before
+    /// registering the tasks, the documents deleted in the meantime are ignored.
+    fn register_popularity_updates(&self, index_scheduler: &IndexScheduler) -> bool {
+        let popularity = std::mem::take(&mut *self.popularity.lock().unwrap());
+        let registered = !popularity.is_empty();
+        for (index_uid, increments) in popularity {
+            let mut increments = match external_increments(index_scheduler, &index_uid, increments)
+            {
+                Ok(increments) => increments,
+                Err(e) => {
+                    error!("Could not register the popularity updates of `{index_uid}`: {e}");
+                    continue;
+                }
+            };
+            while !increments.is_empty() {
+                let rest = match increments.keys().nth(MAX_POPULARITY_UPDATES_PER_TASK).cloned() {
+                    Some(key) => increments.split_off(&key),
+                    None => BTreeMap::new(),
+                };
+                let task = KindWithContent::DocumentPopularityUpdate {
+                    index_uid: index_uid.clone(),
+                    increments,
+                };
+                if let Err(e) = index_scheduler.register(task, None, false) {
+                    error!("Could not register the popularity updates of `{index_uid}`: {e}");
+                }
+                increments = rest;
+            }
+        }
+        registered
+    }
+
+    /// Deletes the searches and events older than the retention period, the queries older than
+    /// their own retention period, and everything recorded on the indexes that no longer exist.
+    ///
+    /// If the store is still too large afterward, the retention period is halved until it fits.
+    fn prune(&self, index_exists: impl Fn(&str) -> bool) -> heed::Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let mut retention = SEARCHES_RETENTION;
+        loop {
+            let mut wtxn = self.env.write_txn()?;
+            let searches = self.searches.remap_data_type::<DecodeIgnore>();
+            let events = self.events.remap_data_type::<DecodeIgnore>();
+            for database in [searches, events] {
+                prune_indexes(&mut wtxn, database, now - retention, &index_exists)?;
+            }
+            let queries_end = query_id_lower_bound(now - QUERIES_RETENTION.min(retention));
+            let queries = self.queries.remap_data_type::<DecodeIgnore>();
+            queries.delete_range(&mut wtxn, &(..queries_end.as_slice()))?;
+            let received_events = self.received_events.remap_data_type::<DecodeIgnore>();
+            received_events.delete_range(&mut wtxn, &(..queries_end.as_slice()))?;
+            wtxn.commit()?;
+
+            let usage = self.env.non_free_pages_size()? * 100 / LOCAL_ANALYTICS_STORE_SIZE as u64;
+            if usage < MAX_STORE_USAGE_PERCENT || retention <= MIN_RETENTION {
+                return Ok(());
+            }
+            retention /= 2;
+            warn!(
+                "The local search analytics store is {usage}% full, only keeping the last {retention}"
+            );
+        }
+    }
+}
+
+/// Flushes the pending records every [`FLUSH_INTERVAL`], registers the popularity updates
+/// at most every [`POPULARITY_UPDATE_INTERVAL`] and prunes the store every [`PRUNE_INTERVAL`],
+/// until the store or the index scheduler is dropped.
+fn flush_loop(store: Weak<Store>, index_scheduler: Weak<IndexScheduler>) {
+    let mut last_prune: Option<Instant> = None;
+    let mut last_popularity_update: Option<Instant> = None;
+    loop {
+        std::thread::sleep(FLUSH_INTERVAL);
+        let (Some(store), Some(index_scheduler)) = (store.upgrade(), index_scheduler.upgrade())
+        else {
+            return;
+        };
+
+        let index_exists = |uid: &str| {
+            // in doubt, the records are kept
+            index_scheduler.index_exists(uid).unwrap_or(true)
+                || index_scheduler.alias(uid).map_or(true, |alias| alias.is_some())
+        };
+
+        if let Err(e) = store.flush() {
+            error!("Could not write the local search analytics: {e}");
+            if matches!(e, heed::Error::Mdb(heed::MdbError::MapFull)) {
+                last_prune = None;
+            }
+        }
+
+        // the first events received after a quiet period are registered right away
+        if last_popularity_update.is_none_or(|last| last.elapsed() >= POPULARITY_UPDATE_INTERVAL)
+            && store.register_popularity_updates(&index_scheduler)
+        {
+            last_popularity_update = Some(Instant::now());
+        }
+
+        if last_prune.is_none_or(|last| last.elapsed() >= PRUNE_INTERVAL) {
+            match store.prune(index_exists) {
+                Ok(()) => last_prune = Some(Instant::now()),
+                Err(e) => error!("Could not prune the local search analytics: {e}"),
+            }
+        }
+    }
+}
+
+/// Deletes, for every index of the database, the entries older than `cutoff`,
+/// or all its entries if the index no longer exists.
+fn prune_indexes(
+    wtxn: &mut RwTxn,
+    database: Database<Bytes, DecodeIgnore>,
+    cutoff: OffsetDateTime,
+    index_exists: impl Fn(&str) -> bool,
+) -> heed::Result<()> {
+    let mut start = Vec::new();
+    loop {
+        let range = (Bound::Included(start.as_slice()), Bound::Unbounded);
+        let Some(key) = database.range(wtxn, &range)?.next().transpose()?.map(|(k, ())| k.to_vec())
+        else {
+            return Ok(());
+        };
+        let Some(separator) = key.iter().position(|&b| b == 0) else {
+            return Ok(());
+        };
+        let prefix = &key[..=separator];
+        let mut end = prefix.to_vec();
+        *end.last_mut().unwrap() += 1;
+
+        let index_uid = std::str::from_utf8(&key[..separator]).unwrap_or_default();
+        let range_end = if index_exists(index_uid) {
+            [prefix, &timestamp_bytes(cutoff)].concat()
+        } else {
+            end.clone()
+        };
+        let range = (Bound::Included(prefix), Bound::Excluded(range_end.as_slice()));
+        database.delete_range(wtxn, &range)?;
+
+        start = end;
+    }
+}
+
+/// Converts the increments of the documents of the given index to their external ids.
+fn external_increments(
+    index_scheduler: &IndexScheduler,
+    index_uid: &str,
+    increments: BTreeMap<DocumentId, DocumentPopularity>,
+) -> anyhow::Result<BTreeMap<String, DocumentPopularity>> {
+    let index = index_scheduler.index(index_uid)?;
+    let rtxn = index.read_txn()?;
+    let external_ids = index.external_id_of(&rtxn, increments.keys().copied())?;
+    let mut external_increments = BTreeMap::new();
+    for (external_id, increment) in external_ids.into_iter().zip(increments.into_values()) {
+        // the documents deleted since the search are ignored
+        if let Ok(external_id) = external_id {
+            external_increments.insert(external_id, increment);
+        }
+    }
+    Ok(external_increments)
+}
+
+/// The key under which an event is deduplicated: `{queryId}{kind}{documentId}`.
+fn event_key(query_id: Uuid, kind: EventKind, document_id: &str) -> Vec<u8> {
+    let mut key = query_id.as_bytes().to_vec();
+    key.push(match kind {
+        EventKind::Click => 0,
+        EventKind::Conversion => 1,
+    });
+    key.extend_from_slice(document_id.as_bytes());
+    key
+}
+
+/// The smallest UUIDv7 generated at the given time.
+fn query_id_lower_bound(datetime: OffsetDateTime) -> [u8; 16] {
+    let millis = u64::try_from(datetime.unix_timestamp_nanos() / 1_000_000).unwrap_or(0);
+    let mut bytes = [0; 16];
+    // the first 48 bits of a UUIDv7 are its unix timestamp in milliseconds
+    bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    bytes
+}
+
+/// Lowercases the query and collapses its whitespaces so that trivial variations
+/// of a query are counted together.
+pub fn normalize_query(query: &str) -> String {
+    query.split_whitespace().map(|word| word.to_lowercase()).collect::<Vec<_>>().join(" ")
+}
+
+fn index_prefix(index_uid: &str) -> Vec<u8> {
+    // index uids cannot contain a nul byte, so it unambiguously ends the prefix
+    let mut prefix = index_uid.as_bytes().to_vec();
+    prefix.push(0);
+    prefix
+}
+
+fn timestamp_bytes(datetime: OffsetDateTime) -> [u8; 8] {
+    let nanos = u64::try_from(datetime.unix_timestamp_nanos()).unwrap_or(0);
+    nanos.to_be_bytes()
+}
+
+fn timestamp_from_key(bytes: &[u8]) -> OffsetDateTime {
+    let nanos = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+    OffsetDateTime::from_unix_timestamp_nanos(nanos as i128).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+}
@@ -1,3 +1,4 @@
+pub mod local_analytics;
 pub mod segment_analytics;
 
 use std::fs;
@@ -12,9 +13,11 @@ use meilisearch_types::InstanceUid;
 use mopa::mopafy;
 use once_cell::sync::Lazy;
 use platform_dirs::AppDirs;
+use tracing::error;
 
 // if the feature analytics is enabled we use the real analytics
 pub type SegmentAnalytics = segment_analytics::SegmentAnalytics;
+pub use local_analytics::{EventKind, LocalAnalytics, QueryStats, RecordedQuery};
 
 use crate::Opt;
 
@@ -134,6 +137,7 @@ macro_rules! aggregate_methods {
 #[derive(Clone)]
 pub struct Analytics {
     segment: Option<Arc<SegmentAnalytics>>,
+    local: Option<Arc<LocalAnalytics>>,
 }
 
 impl Analytics {
@@ -142,15 +146,31 @@ impl Analytics {
         index_scheduler: Arc<IndexScheduler>,
         auth_controller: Arc<AuthController>,
     ) -> Self {
+        // The local analytics never leave the instance, thus they do not depend on `--no-analytics`.
+        let local = if opt.experimental_local_search_analytics {
+            match LocalAnalytics::new(&opt.db_path, index_scheduler.clone()) {
+                Ok(local) => Some(Arc::new(local)),
+                Err(e) => {
+                    error!("Could not open the local search analytics store: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         if opt.no_analytics {
-            Self { segment: None }
+            Self { segment: None, local }
         } else {
-            Self { segment: SegmentAnalytics::new(opt, index_scheduler, auth_controller).await }
+            Self {
+                segment: SegmentAnalytics::new(opt, index_scheduler, auth_controller).await,
+                local,
+            }
         }
     }
 
     pub fn no_analytics() -> Self {
-        Self { segment: None }
+        Self { segment: None, local: None }
     }
 
     pub fn instance_uid(&self) -> Option<&InstanceUid> {
@@ -163,4 +183,9 @@ impl Analytics {
             let _ = segment.sender.try_send(segment_analytics::Message::new(event, request));
         }
     }
+
+    /// The local search analytics store, if it has been enabled with `--experimental-local-search-analytics`.
+    pub fn local(&self) -> Option<&LocalAnalytics> {
+        self.local.as_deref()
+    }
 }
@@ -200,6 +200,10 @@ struct Infos {
     experimental_get_task_documents_route: bool,
     experimental_composite_embedders: bool,
     experimental_embedding_cache_entries: usize,
+    experimental_persistent_embedding_cache_size: u64,
+    experimental_local_search_analytics: bool,
+    experimental_reranker: bool,
+    experimental_local_models: bool,
     gpu_enabled: bool,
     db_path: bool,
     import_dump: bool,
@@ -248,6 +252,12 @@ impl Infos {
             experimental_max_number_of_batched_tasks,
             experimental_limit_batched_tasks_total_size,
             experimental_embedding_cache_entries,
+            experimental_persistent_embedding_cache_size,
+            experimental_local_search_analytics,
+            experimental_reranker_url,
+            experimental_reranker_api_key: _,
+            experimental_reranker_model: _,
+            experimental_local_models_directory,
             http_addr,
             master_key: _,
             env,
@@ -315,6 +325,11 @@ impl Infos {
             experimental_get_task_documents_route: get_task_documents_route,
             experimental_composite_embedders: composite_embedders,
             experimental_embedding_cache_entries,
+            experimental_persistent_embedding_cache_size:
+                experimental_persistent_embedding_cache_size.into(),
+            experimental_local_search_analytics,
+            experimental_reranker: experimental_reranker_url.is_some(),
+            experimental_local_models: experimental_local_models_directory.is_some(),
             gpu_enabled: meilisearch_types::milli::vector::is_cuda_enabled(),
             db_path: db_path != PathBuf::from("./data.ms"),
             import_dump: import_dump.is_some(),
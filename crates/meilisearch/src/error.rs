@@ -8,6 +8,7 @@ use meilisearch_types::milli;
 use meilisearch_types::milli::OrderBy;
 use serde_json::Value;
 use tokio::task::JoinError;
+use uuid::Uuid;
 
 #[derive(Debug, thiserror::Error)]
 pub enum MeilisearchHttpError {
@@ -18,6 +19,8 @@ pub enum MeilisearchHttpError {
     AlreadyUsedLogRoute,
     #[error("The Content-Type `{0}` does not support the use of a csv delimiter. The csv delimiter can only be used with the Content-Type `text/csv`.")]
     CsvDelimiterWithWrongContentType(String),
+    #[error("The Content-Type `{0}` is a binary columnar format and does not support the use of a csv delimiter. The csv delimiter can only be used with the Content-Type `text/csv`.")]
+    CsvDelimiterWithColumnarContentType(String),
     #[error(
         "The Content-Type `{}` is invalid. Accepted values for the Content-Type header are: {}",
         .0, .1.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", ")
@@ -35,6 +38,8 @@ pub enum MeilisearchHttpError {
     PaginationInFederatedQuery(usize, &'static str),
     #[error("Inside `.queries[{0}]`: Using facet options is not allowed in federated queries.\n - Hint: remove `facets` from query #{0} or remove `federation` from the request\n - Hint: pass `federation.facetsByIndex.{1}: {2:?}` for facets in federated search")]
     FacetsInFederatedQuery(usize, String, Vec<String>),
+    #[error("Inside `.queries[{0}]`: Using `rerank` is not allowed in federated queries.\n - Hint: remove `rerank` from query #{0} or remove `federation` from the request")]
+    RerankInFederatedQuery(usize),
     #[error("Inconsistent order for values in facet `{facet}`: index `{previous_uid}` orders {previous_facet_order}, but index `{current_uid}` orders {index_facet_order}.\n - Hint: Remove `federation.mergeFacets` or change `faceting.sortFacetValuesBy` to be consistent in settings.")]
     InconsistentFacetOrder {
         facet: String,
@@ -47,6 +52,8 @@ pub enum MeilisearchHttpError {
     MissingPayload(PayloadType),
     #[error("Too many search requests running at the same time: {0}. Retry after 10s.")]
     TooManySearchRequests(usize),
+    #[error("Too many document exports running at the same time: {0}. Retry after 10s.")]
+    TooManyDocumentExports(usize),
     #[error("Internal error: Search limiter is down.")]
     SearchLimiterIsDown,
     #[error("The provided payload reached the size limit. The maximum accepted payload size is {}.",  Byte::from_u64(*.0 as u64).get_appropriate_unit(UnitType::Binary))]
@@ -55,6 +62,10 @@ pub enum MeilisearchHttpError {
         .0.iter().map(|uid| format!("\"{uid}\"")).collect::<Vec<_>>().join(", "), .0.len()
     )]
     SwapIndexPayloadWrongLength(Vec<IndexUid>),
+    #[error("An alias must point to at least one index.")]
+    EmptyAliasIndexes,
+    #[error("The write target `{0}` must be one of the indexes of the alias.")]
+    AliasWriteTargetNotInIndexes(IndexUid),
     #[error(transparent)]
     IndexUid(#[from] IndexUidFormatError),
     #[error(transparent)]
@@ -78,6 +89,14 @@ pub enum MeilisearchHttpError {
     Join(#[from] JoinError),
     #[error("Invalid request: missing `hybrid` parameter when `vector` is present.")]
     MissingSearchHybrid,
+    #[error("{0} requires enabling the `local search analytics` experimental feature. Relaunch Meilisearch with `--experimental-local-search-analytics`.")]
+    LocalSearchAnalyticsNotEnabled(&'static str),
+    #[error("Query `{0}` not found. Events must refer to the `queryId` of a search made with the local search analytics enabled.")]
+    EventQueryNotFound(Uuid),
+    #[error("Document `{document_id}` was not returned at position {position} by query `{query_id}`. The position must be the index of the document in the `hits` of the search response.")]
+    EventDocumentNotInQuery { query_id: Uuid, document_id: String, position: usize },
+    #[error("Reranking search results requires configuring a reranker. Relaunch Meilisearch with `--experimental-reranker-url`.")]
+    RerankerNotConfigured,
 }
 
 impl MeilisearchHttpError {
@@ -91,7 +110,10 @@ impl ErrorCode for MeilisearchHttpError {
         match self {
             MeilisearchHttpError::MissingContentType(_) => Code::MissingContentType,
             MeilisearchHttpError::AlreadyUsedLogRoute => Code::BadRequest,
-            MeilisearchHttpError::CsvDelimiterWithWrongContentType(_) => Code::InvalidContentType,
+            MeilisearchHttpError::CsvDelimiterWithWrongContentType(_)
+            | MeilisearchHttpError::CsvDelimiterWithColumnarContentType(_) => {
+                Code::InvalidContentType
+            }
             MeilisearchHttpError::MissingPayload(_) => Code::MissingPayload,
             MeilisearchHttpError::InvalidContentType(_, _) => Code::InvalidContentType,
             MeilisearchHttpError::DocumentNotFound(_) => Code::DocumentNotFound,
@@ -99,8 +121,11 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::InvalidExpression(_, _) => Code::InvalidSearchFilter,
             MeilisearchHttpError::PayloadTooLarge(_) => Code::PayloadTooLarge,
             MeilisearchHttpError::TooManySearchRequests(_) => Code::TooManySearchRequests,
+            MeilisearchHttpError::TooManyDocumentExports(_) => Code::TooManyDocumentExports,
             MeilisearchHttpError::SearchLimiterIsDown => Code::Internal,
             MeilisearchHttpError::SwapIndexPayloadWrongLength(_) => Code::InvalidSwapIndexes,
+            MeilisearchHttpError::EmptyAliasIndexes => Code::InvalidAliasIndexes,
+            MeilisearchHttpError::AliasWriteTargetNotInIndexes(_) => Code::InvalidAliasWriteTarget,
             MeilisearchHttpError::IndexUid(e) => e.error_code(),
             MeilisearchHttpError::SerdeJson(_) => Code::Internal,
             MeilisearchHttpError::HeedError(_) => Code::Internal,
@@ -111,6 +136,10 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::DocumentFormat(e) => e.error_code(),
             MeilisearchHttpError::Join(_) => Code::Internal,
             MeilisearchHttpError::MissingSearchHybrid => Code::MissingSearchHybrid,
+            MeilisearchHttpError::LocalSearchAnalyticsNotEnabled(_) => Code::FeatureNotEnabled,
+            MeilisearchHttpError::EventQueryNotFound(_) => Code::EventQueryNotFound,
+            MeilisearchHttpError::EventDocumentNotInQuery { .. } => Code::InvalidEventDocumentId,
+            MeilisearchHttpError::RerankerNotConfigured => Code::FeatureNotEnabled,
             MeilisearchHttpError::FederationOptionsInNonFederatedRequest(_) => {
                 Code::InvalidMultiSearchFederationOptions
             }
@@ -118,6 +147,7 @@ impl ErrorCode for MeilisearchHttpError {
                 Code::InvalidMultiSearchQueryPagination
             }
             MeilisearchHttpError::FacetsInFederatedQuery(..) => Code::InvalidMultiSearchQueryFacets,
+            MeilisearchHttpError::RerankInFederatedQuery(_) => Code::InvalidMultiSearchQueryRerank,
             MeilisearchHttpError::InconsistentFacetOrder { .. } => {
                 Code::InvalidMultiSearchFacetOrder
             }
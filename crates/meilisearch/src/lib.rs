@@ -38,6 +38,7 @@ use meilisearch_auth::{open_auth_store_env, AuthController};
 use meilisearch_types::milli::constants::VERSION_MAJOR;
 use meilisearch_types::milli::documents::{DocumentsBatchBuilder, DocumentsBatchReader};
 use meilisearch_types::milli::update::{IndexDocumentsConfig, IndexDocumentsMethod};
+use meilisearch_types::milli::vector::rerank::RerankerOptions;
 use meilisearch_types::settings::apply_settings_to_builder;
 use meilisearch_types::tasks::KindWithContent;
 use meilisearch_types::versioning::{
@@ -80,6 +81,9 @@ const DEFAULT_INDEX_COUNT: usize = 4;
 #[cfg(not(windows))]
 const DEFAULT_INDEX_COUNT: usize = 20;
 
+/// Interval at which the expired documents of the indexes defining a `ttlAttribute` are registered for deletion.
+const EXPIRED_DOCUMENTS_DELETION_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Check if a db is empty. It does not provide any information on the
 /// validity of the data in it.
 /// We consider a database as non empty when it's a non empty directory.
@@ -234,6 +238,13 @@ pub fn setup_meilisearch(opt: &Opt) -> anyhow::Result<(Arc<IndexScheduler>, Arc<
         instance_features: opt.to_instance_features(),
         auto_upgrade: opt.experimental_dumpless_upgrade,
         embedding_cache_cap: opt.experimental_embedding_cache_entries,
+        embedding_cache_path: opt.db_path.join("embedding_cache"),
+        embedding_cache_size: opt.experimental_persistent_embedding_cache_size.as_u64() as usize,
+        reranker: opt.experimental_reranker_url.clone().map(|url| RerankerOptions {
+            url,
+            api_key: opt.experimental_reranker_api_key.clone(),
+            model: opt.experimental_reranker_model.clone(),
+        }),
     };
     let bin_major: u32 = VERSION_MAJOR.parse().unwrap();
     let bin_minor: u32 = VERSION_MINOR.parse().unwrap();
@@ -324,6 +335,20 @@ pub fn setup_meilisearch(opt: &Opt) -> anyhow::Result<(Arc<IndexScheduler>, Arc<
             .unwrap();
     }
 
+    // We create a loop in a thread that registers the deletion of the expired documents
+    {
+        let index_scheduler = index_scheduler.clone();
+        thread::Builder::new()
+            .name(String::from("register-expired-documents-deletions"))
+            .spawn(move || loop {
+                thread::sleep(EXPIRED_DOCUMENTS_DELETION_INTERVAL);
+                if let Err(e) = index_scheduler.register_expired_documents_deletions() {
+                    error!("Error while registering expired documents deletions: {}", e);
+                }
+            })
+            .unwrap();
+    }
+
     Ok((index_scheduler, auth_controller))
 }
 
@@ -570,21 +595,45 @@ fn import_dump(
         let user_result = user_result?;
         tracing::info!(documents_found = user_result, "{} documents found.", user_result);
         builder.execute()?;
+
+        // 4.4 Import the popularity of the documents.
+        let external_documents_ids = index.external_documents_ids();
+        for popularity in index_reader.document_popularity() {
+            let dump::DocumentPopularity { document_id, impressions, engagement } = popularity?;
+            if let Some(docid) = external_documents_ids.get(&wtxn, &document_id)? {
+                let popularity = milli::DocumentPopularity { impressions, engagement };
+                index.increment_document_popularity(&mut wtxn, docid, popularity)?;
+            }
+        }
+
+        // 4.5 Import the versions of the documents.
+        for version in index_reader.document_versions() {
+            let dump::DocumentVersion { document_id, version } = version?;
+            if let Some(docid) = external_documents_ids.get(&wtxn, &document_id)? {
+                index.put_document_version(&mut wtxn, docid, version)?;
+            }
+        }
+
         wtxn.commit()?;
         tracing::info!("All documents successfully imported.");
 
         index_scheduler.refresh_index_stats(&uid)?;
     }
 
-    // 5. Import the queue
+    // 5. Import the aliases, once the indexes they point to exist.
+    for (name, alias) in dump_reader.aliases()? {
+        index_scheduler.put_alias(&name, &alias)?;
+    }
+
+    // 6. Import the queue
     let mut index_scheduler_dump = index_scheduler.register_dumped_task()?;
-    // 5.1. Import the batches
+    // 6.1. Import the batches
     for ret in dump_reader.batches()? {
         let batch = ret?;
         index_scheduler_dump.register_dumped_batch(batch)?;
     }
 
-    // 5.2. Import the tasks
+    // 6.2. Import the tasks
     for ret in dump_reader.tasks()? {
         let (task, file) = ret?;
         index_scheduler_dump.register_dumped_task(task, file)?;
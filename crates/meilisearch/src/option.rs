@@ -65,6 +65,13 @@ const MEILI_EXPERIMENTAL_LIMIT_BATCHED_TASKS_TOTAL_SIZE: &str =
     "MEILI_EXPERIMENTAL_LIMIT_BATCHED_TASKS_SIZE";
 const MEILI_EXPERIMENTAL_EMBEDDING_CACHE_ENTRIES: &str =
     "MEILI_EXPERIMENTAL_EMBEDDING_CACHE_ENTRIES";
+const MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE: &str =
+    "MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE";
+const MEILI_EXPERIMENTAL_LOCAL_SEARCH_ANALYTICS: &str = "MEILI_EXPERIMENTAL_LOCAL_SEARCH_ANALYTICS";
+const MEILI_EXPERIMENTAL_RERANKER_URL: &str = "MEILI_EXPERIMENTAL_RERANKER_URL";
+const MEILI_EXPERIMENTAL_RERANKER_API_KEY: &str = "MEILI_EXPERIMENTAL_RERANKER_API_KEY";
+const MEILI_EXPERIMENTAL_RERANKER_MODEL: &str = "MEILI_EXPERIMENTAL_RERANKER_MODEL";
+const MEILI_EXPERIMENTAL_LOCAL_MODELS_DIRECTORY: &str = "MEILI_EXPERIMENTAL_LOCAL_MODELS_DIRECTORY";
 const DEFAULT_CONFIG_FILE_PATH: &str = "./config.toml";
 const DEFAULT_DB_PATH: &str = "./data.ms";
 const DEFAULT_HTTP_ADDR: &str = "localhost:7700";
@@ -455,6 +462,51 @@ pub struct Opt {
     #[serde(default = "default_embedding_cache_entries")]
     pub experimental_embedding_cache_entries: usize,
 
+    /// Enables an experimental persistent cache of embeddings, kept on disk across restarts. The value is the
+    /// maximal size of the cache, shared by all the embedders of all the indexes.
+    ///
+    /// The embeddings of both documents and search queries are cached, by embedder configuration and embedded text,
+    /// so that reindexing documents after a restart doesn't compute their embeddings again.
+    /// When the cache is full, the oldest embeddings are evicted.
+    #[clap(long, env = MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE, default_value_t = default_persistent_embedding_cache_size())]
+    #[serde(default = "default_persistent_embedding_cache_size")]
+    pub experimental_persistent_embedding_cache_size: Byte,
+
+    /// Experimental local search analytics.
+    ///
+    /// Records the searches made on each index in a store local to the instance, and exposes the most
+    /// searched queries and the queries without results on the `GET /indexes/{indexUid}/analytics/top-queries`
+    /// and `GET /indexes/{indexUid}/analytics/no-results` routes. Nothing is sent to a third party.
+    #[clap(long, env = MEILI_EXPERIMENTAL_LOCAL_SEARCH_ANALYTICS)]
+    #[serde(default)]
+    pub experimental_local_search_analytics: bool,
+
+    /// Experimental reranking of the search results.
+    ///
+    /// The URL of a rerank API (Cohere, Jina, Voyage or text-embeddings-inference compatible).
+    /// When set, searches can request to reorder their first hits with the `rerank` search parameter.
+    #[clap(long, env = MEILI_EXPERIMENTAL_RERANKER_URL)]
+    #[serde(default)]
+    pub experimental_reranker_url: Option<String>,
+
+    /// The API key sent as a bearer token to the reranker.
+    #[clap(long, env = MEILI_EXPERIMENTAL_RERANKER_API_KEY)]
+    #[serde(default)]
+    pub experimental_reranker_api_key: Option<String>,
+
+    /// The model requested from the reranker.
+    #[clap(long, env = MEILI_EXPERIMENTAL_RERANKER_MODEL)]
+    #[serde(default)]
+    pub experimental_reranker_model: Option<String>,
+
+    /// Allows the `huggingFace` embedders to load their model from a local directory.
+    ///
+    /// Only the models located inside this directory can be used, by setting the `model` of the embedder to their path.
+    /// When not set, loading models from the local filesystem is refused.
+    #[clap(long, env = MEILI_EXPERIMENTAL_LOCAL_MODELS_DIRECTORY)]
+    #[serde(default)]
+    pub experimental_local_models_directory: Option<PathBuf>,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub indexer_options: IndexerOpts,
@@ -559,6 +611,12 @@ impl Opt {
             experimental_max_number_of_batched_tasks,
             experimental_limit_batched_tasks_total_size,
             experimental_embedding_cache_entries,
+            experimental_persistent_embedding_cache_size,
+            experimental_local_search_analytics,
+            experimental_reranker_url,
+            experimental_reranker_api_key,
+            experimental_reranker_model,
+            experimental_local_models_directory,
         } = self;
         export_to_env_if_not_present(MEILI_DB_PATH, db_path);
         export_to_env_if_not_present(MEILI_HTTP_ADDR, http_addr);
@@ -655,6 +713,38 @@ impl Opt {
             MEILI_EXPERIMENTAL_EMBEDDING_CACHE_ENTRIES,
             experimental_embedding_cache_entries.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_PERSISTENT_EMBEDDING_CACHE_SIZE,
+            experimental_persistent_embedding_cache_size.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_LOCAL_SEARCH_ANALYTICS,
+            experimental_local_search_analytics.to_string(),
+        );
+        if let Some(experimental_reranker_url) = experimental_reranker_url {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_RERANKER_URL,
+                experimental_reranker_url,
+            );
+        }
+        if let Some(experimental_reranker_api_key) = experimental_reranker_api_key {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_RERANKER_API_KEY,
+                experimental_reranker_api_key,
+            );
+        }
+        if let Some(experimental_reranker_model) = experimental_reranker_model {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_RERANKER_MODEL,
+                experimental_reranker_model,
+            );
+        }
+        if let Some(experimental_local_models_directory) = experimental_local_models_directory {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_LOCAL_MODELS_DIRECTORY,
+                experimental_local_models_directory,
+            );
+        }
         indexer_options.export_to_env();
     }
 
@@ -976,6 +1066,10 @@ fn default_embedding_cache_entries() -> usize {
     0
 }
 
+fn default_persistent_embedding_cache_size() -> Byte {
+    Byte::from_u64(0)
+}
+
 fn default_snapshot_dir() -> PathBuf {
     PathBuf::from(DEFAULT_SNAPSHOT_DIR)
 }
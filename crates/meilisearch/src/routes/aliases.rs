@@ -0,0 +1,310 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::{
+    InvalidAliasIndexes, InvalidAliasUid, InvalidAliasWriteTarget,
+};
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_alias::IndexAlias;
+use meilisearch_types::index_uid::IndexUid;
+use serde::Serialize;
+use tracing::debug;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::analytics::{Aggregate, Analytics};
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::{AuthenticationError, GuardedData};
+use crate::extractors::sequential_extractor::SeqHandler;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_aliases, get_alias, create_alias, delete_alias),
+    tags((
+        name = "Aliases",
+        description = "Aliases are alternative names for one or several indexes. They can be used wherever an index uid is accepted for reads, writes go to their write target.",
+    )),
+)]
+pub struct AliasesApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(SeqHandler(list_aliases)))
+            .route(web::post().to(SeqHandler(create_alias))),
+    )
+    .service(
+        web::resource("/{alias}")
+            .route(web::get().to(SeqHandler(get_alias)))
+            .route(web::delete().to(SeqHandler(delete_alias))),
+    );
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct AliasView {
+    /// The name of the alias.
+    pub uid: String,
+    /// The indexes the alias points to.
+    pub indexes: Vec<String>,
+    /// The index receiving the writes made through the alias.
+    pub write_target: Option<String>,
+}
+
+impl AliasView {
+    fn new(uid: String, alias: IndexAlias) -> Self {
+        let write_target = alias.target().map(ToString::to_string);
+        Self { uid, indexes: alias.indexes, write_target }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AliasesView {
+    results: Vec<AliasView>,
+}
+
+/// List aliases
+///
+/// List all the aliases the API key can access.
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "Aliases",
+    security(("Bearer" = ["indexes.get", "indexes.*", "*"])),
+    responses(
+        (status = 200, description = "The aliases are returned", body = AliasesView, content_type = "application/json", example = json!(
+            {
+                "results": [
+                    {
+                        "uid": "movies",
+                        "indexes": ["movies-v2"],
+                        "writeTarget": "movies-v2"
+                    }
+                ]
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn list_aliases(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_GET }>, Data<IndexScheduler>>,
+) -> Result<HttpResponse, ResponseError> {
+    let filters = index_scheduler.filters();
+    let results = index_scheduler
+        .aliases()?
+        .into_iter()
+        .filter(|(uid, _)| filters.is_index_authorized(uid))
+        .map(|(uid, alias)| AliasView::new(uid, alias))
+        .collect();
+    let aliases = AliasesView { results };
+
+    debug!(returns = ?aliases, "List aliases");
+    Ok(HttpResponse::Ok().json(aliases))
+}
+
+/// Get alias
+///
+/// Get the indexes an alias points to.
+#[utoipa::path(
+    get,
+    path = "/{alias}",
+    tag = "Aliases",
+    security(("Bearer" = ["indexes.get", "indexes.*", "*"])),
+    params(("alias", example = "movies", description = "Name of the alias", nullable = false)),
+    responses(
+        (status = 200, description = "The alias is returned", body = AliasView, content_type = "application/json", example = json!(
+            {
+                "uid": "movies",
+                "indexes": ["movies-v2"],
+                "writeTarget": "movies-v2"
+            }
+        )),
+        (status = 404, description = "The alias doesn't exist", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Alias `movies` not found.",
+                "code": "alias_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#alias_not_found"
+            }
+        )),
+    )
+)]
+pub async fn get_alias(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_GET }>, Data<IndexScheduler>>,
+    alias_uid: web::Path<String>,
+) -> Result<HttpResponse, ResponseError> {
+    let alias_uid = alias_uid.into_inner();
+    if !index_scheduler.filters().is_index_authorized(&alias_uid) {
+        return Err(AuthenticationError::InvalidToken.into());
+    }
+
+    let alias = index_scheduler
+        .alias(&alias_uid)?
+        .ok_or_else(|| index_scheduler::Error::AliasNotFound(alias_uid.clone()))?;
+    let alias = AliasView::new(alias_uid, alias);
+
+    debug!(returns = ?alias, "Get alias");
+    Ok(HttpResponse::Ok().json(alias))
+}
+
+#[derive(Deserr, Debug, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+#[schema(rename_all = "camelCase")]
+pub struct CreateAliasPayload {
+    /// The name of the alias, it can't be the name of an existing index.
+    #[schema(value_type = String, example = "movies")]
+    #[deserr(error = DeserrJsonError<InvalidAliasUid>, missing_field_error = DeserrJsonError::missing_alias_uid)]
+    uid: IndexUid,
+    /// The indexes the alias points to, federated searches on the alias search all of them.
+    #[schema(value_type = Vec<String>, example = json!(["movies-v2"]))]
+    #[deserr(error = DeserrJsonError<InvalidAliasIndexes>, missing_field_error = DeserrJsonError::missing_alias_indexes)]
+    indexes: Vec<IndexUid>,
+    /// The index receiving the writes made through the alias. Required when the alias points to several indexes.
+    #[schema(value_type = Option<String>, example = "movies-v2")]
+    #[deserr(default, error = DeserrJsonError<InvalidAliasWriteTarget>)]
+    write_target: Option<IndexUid>,
+}
+
+#[derive(Serialize)]
+struct AliasCreatedAggregate {
+    max_indexes_per_alias: usize,
+    with_write_target: bool,
+}
+
+impl Aggregate for AliasCreatedAggregate {
+    fn event_name(&self) -> &'static str {
+        "Alias Created"
+    }
+
+    fn aggregate(self: Box<Self>, new: Box<Self>) -> Box<Self> {
+        Box::new(Self {
+            max_indexes_per_alias: self.max_indexes_per_alias.max(new.max_indexes_per_alias),
+            with_write_target: self.with_write_target | new.with_write_target,
+        })
+    }
+
+    fn into_event(self: Box<Self>) -> serde_json::Value {
+        serde_json::to_value(*self).unwrap_or_default()
+    }
+}
+
+/// Create or update an alias
+///
+/// Point an alias to one or several indexes. If the alias already exists, it is switched to the new indexes atomically.
+/// Requests made on the alias are then resolved to its write target, or to its only index, while federated searches on an alias search all its indexes.
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "Aliases",
+    security(("Bearer" = ["indexes.swap", "indexes.*", "*"])),
+    request_body = CreateAliasPayload,
+    responses(
+        (status = 200, description = "The alias is created or updated", body = AliasView, content_type = "application/json", example = json!(
+            {
+                "uid": "movies",
+                "indexes": ["movies-v2"],
+                "writeTarget": "movies-v2"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn create_alias(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_SWAP }>, Data<IndexScheduler>>,
+    body: AwebJson<CreateAliasPayload, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let CreateAliasPayload { uid, indexes, write_target } = body.into_inner();
+
+    if indexes.is_empty() {
+        return Err(MeilisearchHttpError::EmptyAliasIndexes.into());
+    }
+    if let Some(write_target) = &write_target {
+        if !indexes.contains(write_target) {
+            return Err(
+                MeilisearchHttpError::AliasWriteTargetNotInIndexes(write_target.clone()).into()
+            );
+        }
+    }
+    let filters = index_scheduler.filters();
+    if !filters.is_index_authorized(&uid) || !indexes.iter().all(|i| filters.is_index_authorized(i))
+    {
+        return Err(AuthenticationError::InvalidToken.into());
+    }
+
+    analytics.publish(
+        AliasCreatedAggregate {
+            max_indexes_per_alias: indexes.len(),
+            with_write_target: write_target.is_some(),
+        },
+        &req,
+    );
+
+    let alias = IndexAlias {
+        indexes: indexes.into_iter().map(IndexUid::into_inner).collect(),
+        write_target: write_target.map(IndexUid::into_inner),
+    };
+    let uid = uid.into_inner();
+    let alias = tokio::task::spawn_blocking(move || {
+        index_scheduler.put_alias(&uid, &alias).map(|()| AliasView::new(uid, alias))
+    })
+    .await??;
+
+    debug!(returns = ?alias, "Create alias");
+    Ok(HttpResponse::Ok().json(alias))
+}
+
+/// Delete alias
+///
+/// Delete an alias, the indexes it points to are left untouched.
+#[utoipa::path(
+    delete,
+    path = "/{alias}",
+    tag = "Aliases",
+    security(("Bearer" = ["indexes.swap", "indexes.*", "*"])),
+    params(("alias", example = "movies", description = "Name of the alias", nullable = false)),
+    responses(
+        (status = NO_CONTENT, description = "The alias is deleted"),
+        (status = 404, description = "The alias doesn't exist", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Alias `movies` not found.",
+                "code": "alias_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#alias_not_found"
+            }
+        )),
+    )
+)]
+pub async fn delete_alias(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_SWAP }>, Data<IndexScheduler>>,
+    alias_uid: web::Path<String>,
+) -> Result<HttpResponse, ResponseError> {
+    let alias_uid = alias_uid.into_inner();
+    if !index_scheduler.filters().is_index_authorized(&alias_uid) {
+        return Err(AuthenticationError::InvalidToken.into());
+    }
+
+    tokio::task::spawn_blocking(move || index_scheduler.delete_alias(&alias_uid)).await??;
+
+    debug!("Delete alias");
+    Ok(HttpResponse::NoContent().finish())
+}
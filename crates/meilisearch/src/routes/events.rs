@@ -0,0 +1,148 @@
+use actix_web::web::{self, Data};
+use actix_web::HttpResponse;
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::keys::actions;
+use tracing::debug;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::analytics::{Analytics, EventKind};
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::{AuthenticationError, GuardedData};
+use crate::extractors::sequential_extractor::SeqHandler;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(send_event),
+    tags(
+        (
+            name = "Events",
+            description = "The `/events` route receives the clicks and conversions made on the documents returned by a search. They are aggregated in the search analytics and maintain the click-through rate of the documents used by the `popularity` ranking rule.",
+        ),
+    ),
+)]
+pub struct EventsApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(send_event))));
+}
+
+#[derive(Debug, Clone, Deserr, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+#[schema(rename_all = "camelCase")]
+pub struct Event {
+    /// The kind of event.
+    #[deserr(rename = "type", error = DeserrJsonError<InvalidEventType>)]
+    #[schema(rename = "type")]
+    pub kind: EventKind,
+    /// The `queryId` returned by the search the event comes from.
+    #[deserr(error = DeserrJsonError<InvalidEventQueryId>, try_from(&String) = Uuid::parse_str -> uuid::Error)]
+    #[schema(value_type = String, example = "4c8fd4d2-cd9c-4ac4-8a5d-7e7f3a0e2a2b")]
+    pub query_id: Uuid,
+    /// The primary key of the document the event is about.
+    #[deserr(error = DeserrJsonError<InvalidEventDocumentId>)]
+    #[schema(example = "2001")]
+    pub document_id: String,
+    /// The position of the document in the `hits` of the search response, starting at 0.
+    #[deserr(error = DeserrJsonError<InvalidEventPosition>)]
+    pub position: usize,
+}
+
+/// Send an event
+///
+/// Send a click or conversion event made on a document returned by a search.
+/// The event increases the engagement of the document in the index that was searched, the
+/// `popularity` ranking rule sorts the documents by engagement per time they were returned by
+/// a search, across all queries. The popularity is updated by `documentPopularityUpdate` tasks
+/// registered in the background.
+///
+/// Only the first event of each type sent for a document and `queryId` is counted,
+/// the following ones are accepted but ignored.
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "Events",
+    security(("Bearer" = ["events.send", "*"])),
+    request_body = Event,
+    responses(
+        (status = 204, description = "The event has been recorded"),
+        (status = 400, description = "The local search analytics are not enabled", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Sending search events requires enabling the `local search analytics` experimental feature. Relaunch Meilisearch with `--experimental-local-search-analytics`.",
+                "code": "feature_not_enabled",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+            }
+        )),
+        (status = 400, description = "The document was not returned at this position by the search", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Document `2001` was not returned at position 3 by query `4c8fd4d2-cd9c-4ac4-8a5d-7e7f3a0e2a2b`. The position must be the index of the document in the `hits` of the search response.",
+                "code": "invalid_event_document_id",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#invalid_event_document_id"
+            }
+        )),
+        (status = 404, description = "The query was not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Query `4c8fd4d2-cd9c-4ac4-8a5d-7e7f3a0e2a2b` not found. Events must refer to the `queryId` of a search made with the local search analytics enabled.",
+                "code": "event_query_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#event_query_not_found"
+            }
+        )),
+    )
+)]
+pub async fn send_event(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::EVENTS_SEND }>, Data<IndexScheduler>>,
+    analytics: web::Data<Analytics>,
+    params: AwebJson<Event, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let event = params.into_inner();
+    debug!(parameters = ?event, "Send event");
+
+    let local = analytics
+        .local()
+        .ok_or(MeilisearchHttpError::LocalSearchAnalyticsNotEnabled("Sending search events"))?;
+    let recorded = local
+        .query(event.query_id)
+        .map_err(MeilisearchHttpError::from)?
+        .ok_or(MeilisearchHttpError::EventQueryNotFound(event.query_id))?;
+    if !index_scheduler.filters().is_index_authorized(&recorded.index_uid) {
+        return Err(AuthenticationError::InvalidToken.into());
+    }
+    let index = index_scheduler.index(&recorded.index_uid)?;
+
+    let rtxn = index.read_txn()?;
+    let docid = index.external_documents_ids().get(&rtxn, &event.document_id)?;
+    drop(rtxn);
+    let docid = match docid {
+        Some(docid) if recorded.documents_ids.get(event.position) == Some(&docid) => docid,
+        _ => {
+            return Err(MeilisearchHttpError::EventDocumentNotInQuery {
+                query_id: event.query_id,
+                document_id: event.document_id,
+                position: event.position,
+            }
+            .into())
+        }
+    };
+
+    local
+        .record_event(
+            event.query_id,
+            &recorded,
+            event.kind,
+            &event.document_id,
+            docid,
+            event.position,
+        )
+        .map_err(MeilisearchHttpError::from)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
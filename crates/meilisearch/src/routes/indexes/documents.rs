@@ -1,9 +1,12 @@
+use std::borrow::Cow;
 use std::collections::HashSet;
-use std::io::{ErrorKind, Seek as _};
+use std::io::{ErrorKind, Seek as _, Write as _};
 use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
 
 use actix_web::http::header::CONTENT_TYPE;
-use actix_web::web::Data;
+use actix_web::web::{Bytes, Data};
 use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
 use bstr::ByteSlice as _;
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
@@ -12,12 +15,17 @@ use futures::StreamExt;
 use index_scheduler::{IndexScheduler, RoFeatures, TaskId};
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
-use meilisearch_types::document_formats::{read_csv, read_json, read_ndjson, PayloadType};
+use meilisearch_types::document_formats::{
+    read_arrow_stream, read_csv, read_json, read_ndjson, read_parquet, PayloadType,
+};
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
-use meilisearch_types::milli::update::IndexDocumentsMethod;
+use meilisearch_types::milli::constants::{
+    RESERVED_VECTORS_FIELD_NAME, RESERVED_VERSION_FIELD_NAME,
+};
+use meilisearch_types::milli::update::{IndexDocumentsMethod, OnError};
 use meilisearch_types::milli::vector::parsed_vectors::ExplicitVectors;
 use meilisearch_types::milli::DocumentId;
 use meilisearch_types::serde_cs::vec::CS;
@@ -32,6 +40,8 @@ use serde_json::Value;
 use tempfile::tempfile;
 use tokio::fs::File;
 use tokio::io::{AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc::error::SendTimeoutError;
+use tokio::sync::{mpsc, oneshot, Semaphore};
 use tracing::debug;
 use utoipa::{IntoParams, OpenApi, ToSchema};
 
@@ -49,7 +59,13 @@ use crate::search::{parse_filter, ExternalDocumentId, RetrieveVectors};
 use crate::{aggregate_methods, Opt};
 
 static ACCEPTED_CONTENT_TYPE: Lazy<Vec<String>> = Lazy::new(|| {
-    vec!["application/json".to_string(), "application/x-ndjson".to_string(), "text/csv".to_string()]
+    vec![
+        "application/json".to_string(),
+        "application/x-ndjson".to_string(),
+        "text/csv".to_string(),
+        "application/vnd.apache.parquet".to_string(),
+        "application/vnd.apache.arrow.stream".to_string(),
+    ]
 });
 
 /// Extracts the mime type from the content type and return
@@ -76,7 +92,7 @@ pub struct DocumentParam {
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(get_document, get_documents, delete_document, replace_documents, update_documents, clear_all_documents, delete_documents_batch, delete_documents_by_filter, edit_documents_by_function, documents_by_query_post),
+    paths(get_document, get_documents, export_documents, delete_document, replace_documents, update_documents, clear_all_documents, delete_documents_batch, delete_documents_by_filter, edit_documents_by_function, documents_by_query_post),
     tags(
         (
             name = "Documents",
@@ -102,6 +118,7 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     .service(web::resource("/delete").route(web::post().to(SeqHandler(delete_documents_by_filter))))
     .service(web::resource("/edit").route(web::post().to(SeqHandler(edit_documents_by_function))))
     .service(web::resource("/fetch").route(web::post().to(SeqHandler(documents_by_query_post))))
+    .service(web::resource("/export").route(web::get().to(SeqHandler(export_documents))))
     .service(
         web::resource("/{document_id}")
             .route(web::get().to(SeqHandler(get_document)))
@@ -122,11 +139,17 @@ pub struct GetDocument {
     #[param(value_type = Option<bool>)]
     #[schema(value_type = Option<bool>)]
     retrieve_vectors: Param<bool>,
+    /// Return the `_version` of the document.
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentRetrieveVersion>)]
+    #[param(value_type = Option<bool>)]
+    #[schema(value_type = Option<bool>)]
+    retrieve_version: Param<bool>,
 }
 
 aggregate_methods!(
     DocumentsGET => "Documents Fetched GET",
     DocumentsPOST => "Documents Fetched POST",
+    DocumentsExport => "Documents Exported",
 );
 
 #[derive(Serialize)]
@@ -266,7 +289,8 @@ pub async fn get_document(
     debug!(parameters = ?params, "Get document");
     let index_uid = IndexUid::try_from(index_uid)?;
 
-    let GetDocument { fields, retrieve_vectors: param_retrieve_vectors } = params.into_inner();
+    let GetDocument { fields, retrieve_vectors: param_retrieve_vectors, retrieve_version } =
+        params.into_inner();
     let attributes_to_retrieve = fields.merge_star_and_none();
 
     let retrieve_vectors = RetrieveVectors::new(param_retrieve_vectors.0);
@@ -285,8 +309,13 @@ pub async fn get_document(
     );
 
     let index = index_scheduler.index(&index_uid)?;
-    let document =
-        retrieve_document(&index, &document_id, attributes_to_retrieve, retrieve_vectors)?;
+    let document = retrieve_document(
+        &index,
+        &document_id,
+        attributes_to_retrieve,
+        retrieve_vectors,
+        retrieve_version.0,
+    )?;
     debug!(returns = ?document, "Get document");
     Ok(HttpResponse::Ok().json(document))
 }
@@ -400,6 +429,9 @@ pub struct BrowseQueryGet {
     #[param(default, value_type = Option<bool>)]
     #[deserr(default, error = DeserrQueryParamError<InvalidDocumentRetrieveVectors>)]
     retrieve_vectors: Param<bool>,
+    #[param(default, value_type = Option<bool>)]
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentRetrieveVersion>)]
+    retrieve_version: Param<bool>,
     #[param(default, value_type = Option<Vec<String>>)]
     #[deserr(default, error = DeserrQueryParamError<InvalidDocumentIds>)]
     ids: Option<CS<String>>,
@@ -424,6 +456,9 @@ pub struct BrowseQuery {
     #[schema(default, example = true)]
     #[deserr(default, error = DeserrJsonError<InvalidDocumentRetrieveVectors>)]
     retrieve_vectors: bool,
+    #[schema(default, example = true)]
+    #[deserr(default, error = DeserrJsonError<InvalidDocumentRetrieveVersion>)]
+    retrieve_version: bool,
     #[schema(value_type = Option<Vec<String>>, example = json!(["cody", "finn", "brandy", "gambit"]))]
     #[deserr(default, error = DeserrJsonError<InvalidDocumentIds>)]
     ids: Option<Vec<serde_json::Value>>,
@@ -571,7 +606,7 @@ pub async fn get_documents(
 ) -> Result<HttpResponse, ResponseError> {
     debug!(parameters = ?params, "Get documents GET");
 
-    let BrowseQueryGet { limit, offset, fields, retrieve_vectors, filter, ids } =
+    let BrowseQueryGet { limit, offset, fields, retrieve_vectors, retrieve_version, filter, ids } =
         params.into_inner();
 
     let filter = match filter {
@@ -589,6 +624,7 @@ pub async fn get_documents(
         limit: limit.0,
         fields: fields.merge_star_and_none(),
         retrieve_vectors: retrieve_vectors.0,
+        retrieve_version: retrieve_version.0,
         filter,
         ids,
     };
@@ -615,7 +651,8 @@ fn documents_by_query(
     query: BrowseQuery,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
-    let BrowseQuery { offset, limit, fields, retrieve_vectors, filter, ids } = query;
+    let BrowseQuery { offset, limit, fields, retrieve_vectors, retrieve_version, filter, ids } =
+        query;
 
     let retrieve_vectors = RetrieveVectors::new(retrieve_vectors);
 
@@ -642,6 +679,7 @@ fn documents_by_query(
         filter,
         fields,
         retrieve_vectors,
+        retrieve_version,
         index_scheduler.features(),
     )?;
 
@@ -651,6 +689,337 @@ fn documents_by_query(
     Ok(HttpResponse::Ok().json(ret))
 }
 
+/// The size of the chunks of documents sent by the export route.
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+/// The number of chunks that can be waiting to be sent to the client.
+const EXPORT_CHANNEL_CAPACITY: usize = 16;
+/// The number of exports that can run at the same time, each of them keeps a read transaction open.
+const MAX_CONCURRENT_EXPORTS: usize = 4;
+/// How long an export waits for the client to read a chunk before giving up.
+const EXPORT_SEND_TIMEOUT: Duration = Duration::from_secs(60);
+
+static EXPORT_PERMITS: Lazy<Arc<Semaphore>> =
+    Lazy::new(|| Arc::new(Semaphore::new(MAX_CONCURRENT_EXPORTS)));
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserr, ToSchema)]
+#[deserr(rename_all = camelCase)]
+#[schema(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// One JSON document per line.
+    #[default]
+    Ndjson,
+    /// One document per row, the objects and arrays are written as JSON.
+    Csv,
+}
+
+impl ExportFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv",
+        }
+    }
+}
+
+#[derive(Debug, Deserr, IntoParams)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+#[into_params(rename_all = "camelCase", parameter_in = Query)]
+pub struct ExportDocumentsQuery {
+    /// The format of the exported documents, `ndjson` or `csv`.
+    #[param(default, value_type = Option<ExportFormat>, example = "csv")]
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentExportFormat>)]
+    format: ExportFormat,
+    #[param(default, value_type = Option<Vec<String>>)]
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentFields>)]
+    fields: OptionStarOrList<String>,
+    #[param(default, value_type = Option<bool>)]
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentRetrieveVectors>)]
+    retrieve_vectors: Param<bool>,
+    #[param(default, value_type = Option<String>, example = "popularity > 1000")]
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentFilter>)]
+    filter: Option<String>,
+}
+
+/// Export documents
+///
+/// Stream all the documents of an index, or those matching a filter, as NDJSON or CSV.
+/// The documents are read from a single read transaction, so the export is consistent
+/// even if the index is updated in the meantime. A limited number of exports can run at
+/// the same time and an export is aborted if the client stops reading it.
+#[utoipa::path(
+    get,
+    path = "{indexUid}/documents/export",
+    tag = "Documents",
+    security(("Bearer" = ["documents.get", "documents.*", "*"])),
+    params(
+        ("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false),
+        ExportDocumentsQuery
+    ),
+    responses(
+        (status = 200, description = "The documents are streamed", body = String, content_type = "application/x-ndjson", example = json!(
+            "{\"id\":25684,\"title\":\"American Ninja 5\"}\n{\"id\":45881,\"title\":\"The Bridge of San Luis Rey\"}\n"
+        )),
+        (status = 404, description = "Index not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Index `movies` not found.",
+                "code": "index_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#index_not_found"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn export_documents(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<ExportDocumentsQuery, DeserrQueryParamError>,
+    req: HttpRequest,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    debug!(parameters = ?params, "Export documents");
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    let ExportDocumentsQuery { format, fields, retrieve_vectors, filter } = params.into_inner();
+
+    let filter = match filter {
+        Some(f) => match serde_json::from_str(&f) {
+            Ok(v) => Some(v),
+            _ => Some(Value::String(f)),
+        },
+        None => None,
+    };
+
+    analytics.publish(
+        DocumentsFetchAggregator::<DocumentsExport> {
+            per_filter: filter.is_some(),
+            retrieve_vectors: retrieve_vectors.0,
+            max_limit: 0,
+            max_offset: 0,
+            max_document_ids: 0,
+            per_document_id: false,
+            marker: PhantomData,
+        },
+        &req,
+    );
+
+    let permit = EXPORT_PERMITS
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| MeilisearchHttpError::TooManyDocumentExports(MAX_CONCURRENT_EXPORTS))?;
+    let index = index_scheduler.index(&index_uid)?;
+    let features = index_scheduler.features();
+    let fields = fields.merge_star_and_none();
+    let retrieve_vectors = RetrieveVectors::new(retrieve_vectors.0);
+
+    let runtime = tokio::runtime::Handle::current();
+    let (ready_sender, ready_receiver) = oneshot::channel::<Result<(), ResponseError>>();
+    let (sender, mut receiver) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || {
+        // released once the export is over
+        let _permit = permit;
+        let rtxn = match index.read_txn() {
+            Ok(rtxn) => rtxn,
+            Err(e) => {
+                let _ = ready_sender.send(Err(e.into()));
+                return;
+            }
+        };
+        let candidates = match export_candidates(&index, &rtxn, filter.as_ref(), features) {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                let _ = ready_sender.send(Err(e));
+                return;
+            }
+        };
+        if ready_sender.send(Ok(())).is_err() {
+            return;
+        }
+
+        let mut output = ChunkWriter { buffer: Vec::new(), sender: sender.clone(), runtime };
+        let result = match format {
+            ExportFormat::Ndjson => write_ndjson_export(
+                &index,
+                &rtxn,
+                candidates,
+                fields.as_deref(),
+                retrieve_vectors,
+                &mut output,
+            ),
+            ExportFormat::Csv => write_csv_export(
+                &index,
+                &rtxn,
+                candidates,
+                fields.as_deref(),
+                retrieve_vectors,
+                &mut output,
+            ),
+        };
+        let result = result.and_then(|()| output.flush().map_err(export_error));
+        if let Err(e) = result {
+            tracing::error!(index_uid = %index_uid, "Could not export the documents: {e}");
+            // the client may not be reading anymore, we must not wait for it
+            let _ = sender.try_send(Err(e));
+        }
+    });
+
+    match ready_receiver.await {
+        Ok(Ok(())) => (),
+        Ok(Err(e)) => return Err(e),
+        Err(e) => return Err(ResponseError::from_msg(e.to_string(), Code::Internal)),
+    }
+
+    let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    });
+    Ok(HttpResponse::Ok().content_type(format.content_type()).streaming(stream))
+}
+
+fn export_candidates(
+    index: &Index,
+    rtxn: &RoTxn,
+    filter: Option<&Value>,
+    features: RoFeatures,
+) -> Result<RoaringBitmap, ResponseError> {
+    let mut candidates = index.documents_ids(rtxn)?;
+    if let Some(filter) = filter {
+        if let Some(filter) = parse_filter(filter, Code::InvalidDocumentFilter, features)? {
+            candidates &= evaluate_filter(index, rtxn, &filter)?;
+        }
+    }
+    Ok(candidates)
+}
+
+fn write_ndjson_export(
+    index: &Index,
+    rtxn: &RoTxn,
+    candidates: RoaringBitmap,
+    fields: Option<&[String]>,
+    retrieve_vectors: RetrieveVectors,
+    output: &mut impl std::io::Write,
+) -> Result<(), ResponseError> {
+    for document in some_documents(index, rtxn, candidates, retrieve_vectors, false)? {
+        let document = select_exported_fields(document?, fields, retrieve_vectors);
+        serde_json::to_writer(&mut *output, &document).map_err(MeilisearchHttpError::from)?;
+        output.write_all(b"\n").map_err(export_error)?;
+    }
+    Ok(())
+}
+
+fn write_csv_export(
+    index: &Index,
+    rtxn: &RoTxn,
+    candidates: RoaringBitmap,
+    fields: Option<&[String]>,
+    retrieve_vectors: RetrieveVectors,
+    output: &mut impl std::io::Write,
+) -> Result<(), ResponseError> {
+    let mut columns: Vec<String> = match fields {
+        Some(fields) => fields.to_vec(),
+        // We must know all the columns before writing the first row, so we use
+        // the top-level fields of the index, in the order they were first seen.
+        None => {
+            let fields_ids_map = index.fields_ids_map(rtxn)?;
+            let mut columns: Vec<_> = index
+                .field_distribution(rtxn)?
+                .into_keys()
+                .filter(|name| name != RESERVED_VECTORS_FIELD_NAME)
+                .collect();
+            columns.sort_by_key(|name| fields_ids_map.id(name));
+            columns
+        }
+    };
+    if retrieve_vectors == RetrieveVectors::Retrieve
+        && !columns.iter().any(|column| column == RESERVED_VECTORS_FIELD_NAME)
+    {
+        columns.push(RESERVED_VECTORS_FIELD_NAME.to_string());
+    }
+
+    let mut writer = csv::Writer::from_writer(output);
+    writer.write_record(&columns).map_err(export_error)?;
+    for document in some_documents(index, rtxn, candidates, retrieve_vectors, false)? {
+        let document = document?;
+        let record = columns.iter().map(|column| match field_value(&document, column) {
+            None | Some(Value::Null) => Cow::Borrowed(&b""[..]),
+            Some(Value::String(string)) => Cow::Borrowed(string.as_bytes()),
+            Some(value) => Cow::Owned(value.to_string().into_bytes()),
+        });
+        writer.write_record(record).map_err(export_error)?;
+    }
+    writer.flush().map_err(export_error)?;
+    Ok(())
+}
+
+fn select_exported_fields(
+    document: Document,
+    fields: Option<&[String]>,
+    retrieve_vectors: RetrieveVectors,
+) -> Document {
+    match fields {
+        Some(fields) => permissive_json_pointer::select_values(
+            &document,
+            fields
+                .iter()
+                .map(|s| s.as_str())
+                .chain((retrieve_vectors == RetrieveVectors::Retrieve).then_some("_vectors")),
+        ),
+        None => document,
+    }
+}
+
+/// Returns the value of a field of the document, following the dots of nested fields.
+fn field_value<'a>(document: &'a Document, field: &str) -> Option<&'a Value> {
+    if let Some(value) = document.get(field) {
+        return Some(value);
+    }
+    field.match_indices('.').find_map(|(i, _)| match document.get(&field[..i]) {
+        Some(Value::Object(object)) => field_value(object, &field[i + 1..]),
+        _ => None,
+    })
+}
+
+fn export_error(error: impl std::fmt::Display) -> ResponseError {
+    ResponseError::from_msg(error.to_string(), Code::Internal)
+}
+
+/// Buffers the exported documents to send them to the client by chunks.
+struct ChunkWriter {
+    buffer: Vec<u8>,
+    sender: mpsc::Sender<Result<Bytes, ResponseError>>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl std::io::Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= EXPORT_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            let chunk = Bytes::from(std::mem::take(&mut self.buffer));
+            let send = self.sender.send_timeout(Ok(chunk), EXPORT_SEND_TIMEOUT);
+            self.runtime.block_on(send).map_err(|e| match e {
+                // the client is too slow to read the export
+                SendTimeoutError::Timeout(_) => ErrorKind::TimedOut,
+                // the client stopped reading the export
+                SendTimeoutError::Closed(_) => ErrorKind::BrokenPipe,
+            })?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Deserialize, Debug, Deserr, IntoParams)]
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
 #[into_params(parameter_in = Query, rename_all = "camelCase")]
@@ -664,6 +1033,10 @@ pub struct UpdateDocumentsQuery {
     #[param(value_type = char, default = ",", example = ";")]
     #[deserr(default, try_from(char) = from_char_csv_delimiter -> DeserrQueryParamError<InvalidDocumentCsvDelimiter>, error = DeserrQueryParamError<InvalidDocumentCsvDelimiter>)]
     pub csv_delimiter: Option<u8>,
+    /// What to do with the invalid documents of the payload. `fail` fails the whole task, `skip` indexes the valid documents and lists the invalid ones in the `skippedDocuments` of the task details.
+    #[param(value_type = OnError, default = "fail", example = "skip")]
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentOnError>)]
+    pub on_error: OnError,
 }
 
 fn from_char_csv_delimiter(
@@ -689,6 +1062,7 @@ pub struct DocumentsAggregator<T: AggregateMethod> {
     payload_types: HashSet<String>,
     primary_key: HashSet<String>,
     index_creation: bool,
+    skip_invalid_documents: bool,
     #[serde(skip)]
     method: PhantomData<T>,
 }
@@ -703,6 +1077,7 @@ impl<Method: AggregateMethod> Aggregate for DocumentsAggregator<Method> {
             payload_types: self.payload_types.union(&new.payload_types).cloned().collect(),
             primary_key: self.primary_key.union(&new.primary_key).cloned().collect(),
             index_creation: self.index_creation | new.index_creation,
+            skip_invalid_documents: self.skip_invalid_documents | new.skip_invalid_documents,
             method: PhantomData,
         })
     }
@@ -722,6 +1097,12 @@ impl<Method: AggregateMethod> Aggregate for DocumentsAggregator<Method> {
 /// > info
 /// > If the provided index does not exist, it will be created.
 /// > info
+/// > A document is only written if its reserved `_ifVersion` field matches its current `_version`, or `0` if it doesn't exist yet.
+/// > The documents that don't match are skipped and listed in the `versionConflicts` of the task details.
+/// > info
+/// > With `onError=skip`, the invalid documents, e.g. with a bad `_geo`, `_vectors` or primary key, are skipped
+/// > and listed with the reason in the `skippedDocuments` of the task details instead of failing the whole task.
+/// > info
 /// > Use the reserved `_geo` object to add geo coordinates to a document. `_geo` is an object made of `lat` and `lng` field.
 /// >
 /// > When the vectorStore feature is enabled you can use the reserved `_vectors` field in your documents.
@@ -789,6 +1170,7 @@ pub async fn replace_documents(
             payload_types: content_types,
             primary_key: primary_keys,
             index_creation: index_scheduler.index_exists(&index_uid).map_or(true, |x| !x),
+            skip_invalid_documents: params.on_error == OnError::Skip,
             method: PhantomData,
         },
         &req,
@@ -805,6 +1187,7 @@ pub async fn replace_documents(
         params.csv_delimiter,
         body,
         IndexDocumentsMethod::ReplaceDocuments,
+        params.on_error,
         uid,
         dry_run,
         allow_index_creation,
@@ -823,6 +1206,15 @@ pub async fn replace_documents(
 /// > info
 /// > If the provided index does not exist, it will be created.
 /// > info
+/// > Fields can be updated relatively to their current value with the `$inc`, `$push`, `$pull` and `$unset` operators,
+/// > e.g. `{"id": 1, "$inc": {"views": 1}, "$push": {"tags": "new"}, "$unset": ["draft"]}`.
+/// > info
+/// > A document is only written if its reserved `_ifVersion` field matches its current `_version`, or `0` if it doesn't exist yet.
+/// > The documents that don't match are skipped and listed in the `versionConflicts` of the task details.
+/// > info
+/// > With `onError=skip`, the invalid documents, e.g. with a bad `_geo`, `_vectors` or primary key, are skipped
+/// > and listed with the reason in the `skippedDocuments` of the task details instead of failing the whole task.
+/// > info
 /// > Use the reserved `_geo` object to add geo coordinates to a document. `_geo` is an object made of `lat` and `lng` field.
 /// >
 /// > When the vectorStore feature is enabled you can use the reserved `_vectors` field in your documents.
@@ -890,6 +1282,7 @@ pub async fn update_documents(
             payload_types: content_types,
             primary_key: primary_keys,
             index_creation: index_scheduler.index_exists(&index_uid).map_or(true, |x| !x),
+            skip_invalid_documents: params.on_error == OnError::Skip,
             method: PhantomData,
         },
         &req,
@@ -906,6 +1299,7 @@ pub async fn update_documents(
         params.csv_delimiter,
         body,
         IndexDocumentsMethod::UpdateDocuments,
+        params.on_error,
         uid,
         dry_run,
         allow_index_creation,
@@ -925,6 +1319,7 @@ async fn document_addition(
     csv_delimiter: Option<u8>,
     body: Payload,
     method: IndexDocumentsMethod,
+    on_error: OnError,
     task_id: Option<TaskId>,
     dry_run: bool,
     allow_index_creation: bool,
@@ -937,6 +1332,8 @@ async fn document_addition(
         (Some(("application", "x-ndjson")), None) => PayloadType::Ndjson,
         (Some(("text", "csv")), None) => PayloadType::Csv { delimiter: b',' },
         (Some(("text", "csv")), Some(delimiter)) => PayloadType::Csv { delimiter },
+        (Some(("application", "vnd.apache.parquet")), None) => PayloadType::Parquet,
+        (Some(("application", "vnd.apache.arrow.stream")), None) => PayloadType::ArrowStream,
 
         (Some(("application", "json")), Some(_)) => {
             return Err(MeilisearchHttpError::CsvDelimiterWithWrongContentType(String::from(
@@ -948,6 +1345,16 @@ async fn document_addition(
                 "application/x-ndjson",
             )))
         }
+        (Some(("application", "vnd.apache.parquet")), Some(_)) => {
+            return Err(MeilisearchHttpError::CsvDelimiterWithColumnarContentType(String::from(
+                "application/vnd.apache.parquet",
+            )))
+        }
+        (Some(("application", "vnd.apache.arrow.stream")), Some(_)) => {
+            return Err(MeilisearchHttpError::CsvDelimiterWithColumnarContentType(String::from(
+                "application/vnd.apache.arrow.stream",
+            )))
+        }
         (Some((type_, subtype)), _) => {
             return Err(MeilisearchHttpError::InvalidContentType(
                 format!("{}/{}", type_, subtype),
@@ -989,7 +1396,10 @@ async fn document_addition(
 
             Ok(documents_count)
         }
-        PayloadType::Json | PayloadType::Csv { delimiter: _ } => {
+        PayloadType::Json
+        | PayloadType::Csv { delimiter: _ }
+        | PayloadType::Parquet
+        | PayloadType::ArrowStream => {
             let temp_file = match tempfile() {
                 Ok(file) => file,
                 Err(e) => return Err(MeilisearchHttpError::Payload(ReceivePayload(Box::new(e)))),
@@ -1002,6 +1412,8 @@ async fn document_addition(
                     PayloadType::Csv { delimiter } => {
                         read_csv(&read_file, &mut update_file, delimiter)?
                     }
+                    PayloadType::Parquet => read_parquet(&read_file, &mut update_file)?,
+                    PayloadType::ArrowStream => read_arrow_stream(&read_file, &mut update_file)?,
                     PayloadType::Ndjson => {
                         unreachable!("We already wrote the user content into the update file")
                     }
@@ -1043,6 +1455,7 @@ async fn document_addition(
         documents_count,
         primary_key,
         allow_index_creation,
+        on_error,
         index_uid: index_uid.to_string(),
     };
 
@@ -1449,6 +1862,7 @@ fn some_documents<'a, 't: 'a>(
     rtxn: &'t RoTxn,
     doc_ids: impl IntoIterator<Item = DocumentId> + 'a,
     retrieve_vectors: RetrieveVectors,
+    retrieve_version: bool,
 ) -> Result<impl Iterator<Item = Result<Document, ResponseError>> + 'a, ResponseError> {
     let fields_ids_map = index.fields_ids_map(rtxn)?;
     let all_fields: Vec<_> = fields_ids_map.iter().map(|(id, _)| id).collect();
@@ -1457,6 +1871,10 @@ fn some_documents<'a, 't: 'a>(
     Ok(index.iter_documents(rtxn, doc_ids)?.map(move |ret| {
         ret.map_err(ResponseError::from).and_then(|(key, document)| -> Result<_, ResponseError> {
             let mut document = milli::obkv_to_json(&all_fields, &fields_ids_map, document)?;
+            if retrieve_version {
+                let version = index.document_version(rtxn, key)?;
+                document.insert(RESERVED_VERSION_FIELD_NAME.to_string(), version.into());
+            }
             match retrieve_vectors {
                 RetrieveVectors::Hide => {
                     document.remove("_vectors");
@@ -1500,6 +1918,7 @@ fn retrieve_documents<S: AsRef<str>>(
     filter: Option<Value>,
     attributes_to_retrieve: Option<Vec<S>>,
     retrieve_vectors: RetrieveVectors,
+    retrieve_version: bool,
     features: RoFeatures,
 ) -> Result<(u64, Vec<Document>), ResponseError> {
     let rtxn = index.read_txn()?;
@@ -1525,12 +1944,7 @@ fn retrieve_documents<S: AsRef<str>>(
     };
 
     if let Some(filter) = filter {
-        candidates &= filter.evaluate(&rtxn, index).map_err(|err| match err {
-            milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
-                ResponseError::from_msg(err.to_string(), Code::InvalidDocumentFilter)
-            }
-            e => e.into(),
-        })?
+        candidates &= evaluate_filter(index, &rtxn, &filter)?;
     }
 
     let (it, number_of_documents) = {
@@ -1541,6 +1955,7 @@ fn retrieve_documents<S: AsRef<str>>(
                 &rtxn,
                 candidates.into_iter().skip(offset).take(limit),
                 retrieve_vectors,
+                retrieve_version,
             )?,
             number_of_documents,
         )
@@ -1551,9 +1966,13 @@ fn retrieve_documents<S: AsRef<str>>(
             Ok(match &attributes_to_retrieve {
                 Some(attributes_to_retrieve) => permissive_json_pointer::select_values(
                     &document?,
-                    attributes_to_retrieve.iter().map(|s| s.as_ref()).chain(
-                        (retrieve_vectors == RetrieveVectors::Retrieve).then_some("_vectors"),
-                    ),
+                    attributes_to_retrieve
+                        .iter()
+                        .map(|s| s.as_ref())
+                        .chain(
+                            (retrieve_vectors == RetrieveVectors::Retrieve).then_some("_vectors"),
+                        )
+                        .chain(retrieve_version.then_some(RESERVED_VERSION_FIELD_NAME)),
                 ),
                 None => document?,
             })
@@ -1563,11 +1982,25 @@ fn retrieve_documents<S: AsRef<str>>(
     Ok((number_of_documents, documents))
 }
 
-fn retrieve_document<S: AsRef<str>>(
+fn evaluate_filter(
+    index: &Index,
+    rtxn: &RoTxn,
+    filter: &milli::Filter,
+) -> Result<RoaringBitmap, ResponseError> {
+    filter.evaluate(rtxn, index).map_err(|err| match err {
+        milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
+            ResponseError::from_msg(err.to_string(), Code::InvalidDocumentFilter)
+        }
+        e => e.into(),
+    })
+}
+
+pub(crate) fn retrieve_document<S: AsRef<str>>(
     index: &Index,
     doc_id: &str,
     attributes_to_retrieve: Option<Vec<S>>,
     retrieve_vectors: RetrieveVectors,
+    retrieve_version: bool,
 ) -> Result<Document, ResponseError> {
     let txn = index.read_txn()?;
 
@@ -1576,9 +2009,10 @@ fn retrieve_document<S: AsRef<str>>(
         .get(&txn, doc_id)?
         .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(doc_id.to_string()))?;
 
-    let document = some_documents(index, &txn, Some(internal_id), retrieve_vectors)?
-        .next()
-        .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(doc_id.to_string()))??;
+    let document =
+        some_documents(index, &txn, Some(internal_id), retrieve_vectors, retrieve_version)?
+            .next()
+            .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(doc_id.to_string()))??;
 
     let document = match &attributes_to_retrieve {
         Some(attributes_to_retrieve) => permissive_json_pointer::select_values(
@@ -1586,7 +2020,8 @@ fn retrieve_document<S: AsRef<str>>(
             attributes_to_retrieve
                 .iter()
                 .map(|s| s.as_ref())
-                .chain((retrieve_vectors == RetrieveVectors::Retrieve).then_some("_vectors")),
+                .chain((retrieve_vectors == RetrieveVectors::Retrieve).then_some("_vectors"))
+                .chain(retrieve_version.then_some(RESERVED_VERSION_FIELD_NAME)),
         ),
         None => document,
     };
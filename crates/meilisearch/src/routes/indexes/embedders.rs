@@ -0,0 +1,341 @@
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::keys::actions;
+use meilisearch_types::milli::vector::Embedder;
+use meilisearch_types::milli::{self, Object, ThreadPoolNoAbort, ThreadPoolNoAbortBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::debug;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::analytics::{Aggregate, Analytics};
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(embed),
+    tags(
+        (
+            name = "Embedders",
+            description = "The `/embedders` routes give access to the embedders configured in the settings of an index, for example to compute the same query vectors as Meilisearch.",
+        ),
+    ),
+)]
+pub struct EmbeddersApi;
+
+/// Maximum number of texts or documents embedded by a single request.
+pub const MAX_EMBED_INPUTS: usize = 100;
+
+/// Time after which the requests sent to the embedder are given up.
+const EMBED_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/{embedder_name}/embed").route(web::post().to(SeqHandler(embed))));
+}
+
+#[derive(Deserialize)]
+pub struct EmbedderParam {
+    index_uid: String,
+    embedder_name: String,
+}
+
+#[derive(Debug, Clone, Deserr, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+#[schema(rename_all = "camelCase")]
+pub struct EmbedQuery {
+    /// Texts to embed, as search queries would be.
+    ///
+    /// At most 100 texts can be embedded at once.
+    #[deserr(default, error = DeserrJsonError<InvalidEmbedTexts>)]
+    #[schema(value_type = Option<Vec<String>>, example = json!(["a kefir recipe"]))]
+    pub texts: Option<Vec<String>>,
+    /// Documents to render through the `documentTemplate` of the embedder, then embed as they would be during indexing.
+    ///
+    /// The chunking of the embedder is not applied. At most 100 documents can be embedded at once.
+    #[deserr(default, error = DeserrJsonError<InvalidEmbedDocuments>)]
+    #[schema(value_type = Option<Vec<Object>>, example = json!([{ "id": 1, "title": "Kefir" }]))]
+    pub documents: Option<Vec<Object>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedResult {
+    /// One embedding per text or document, in the order of the request.
+    ///
+    /// The embeddings of sparse embedders are objects mapping the token ids to their weight.
+    #[schema(value_type = Vec<Vec<f32>>)]
+    pub embeddings: Vec<Value>,
+    pub processing_time_ms: u128,
+}
+
+#[derive(Default)]
+pub struct EmbedAggregator {
+    total_received: usize,
+    total_succeeded: usize,
+    time_spent: BinaryHeap<usize>,
+
+    // inputs
+    texts: bool,
+    documents: bool,
+    max_inputs: usize,
+}
+
+impl EmbedAggregator {
+    fn from_query(query: &EmbedQuery) -> Self {
+        let EmbedQuery { texts, documents } = query;
+        let inputs = texts.as_ref().map_or(0, Vec::len) + documents.as_ref().map_or(0, Vec::len);
+
+        Self {
+            total_received: 1,
+            texts: texts.is_some(),
+            documents: documents.is_some(),
+            max_inputs: inputs,
+            ..Default::default()
+        }
+    }
+
+    fn succeed(&mut self, result: &EmbedResult) {
+        self.total_succeeded = 1;
+        self.time_spent.push(result.processing_time_ms as usize);
+    }
+}
+
+impl Aggregate for EmbedAggregator {
+    fn event_name(&self) -> &'static str {
+        "Embed POST"
+    }
+
+    fn aggregate(mut self: Box<Self>, new: Box<Self>) -> Box<Self> {
+        self.total_received = self.total_received.saturating_add(new.total_received);
+        self.total_succeeded = self.total_succeeded.saturating_add(new.total_succeeded);
+        self.time_spent.extend(new.time_spent);
+        self.texts |= new.texts;
+        self.documents |= new.documents;
+        self.max_inputs = self.max_inputs.max(new.max_inputs);
+        self
+    }
+
+    fn into_event(self: Box<Self>) -> serde_json::Value {
+        let Self { total_received, total_succeeded, time_spent, texts, documents, max_inputs } =
+            *self;
+
+        // the index of the 99th percentage of value
+        let time_spent = time_spent.into_sorted_vec();
+        let percentile_99th = time_spent.len() * 99 / 100;
+        let time_spent = time_spent.get(percentile_99th);
+
+        json!({
+            "requests": {
+                "99th_response_time": time_spent.map(|t| format!("{:.2}", t)),
+                "total_succeeded": total_succeeded,
+                "total_failed": total_received.saturating_sub(total_succeeded),
+                "total_received": total_received,
+            },
+            "texts": texts,
+            "documents": documents,
+            "max_inputs": max_inputs,
+        })
+    }
+}
+
+/// Embed texts or documents
+///
+/// Compute embeddings with an embedder configured on the index, exactly as Meilisearch does when searching or indexing.
+#[utoipa::path(
+    post,
+    path = "{indexUid}/embedders/{embedderName}/embed",
+    tag = "Embedders",
+    security(("Bearer" = ["embedders.embed", "*"])),
+    params(
+        ("indexUid" = String, Path, example = "movies", description = "Index Unique Identifier", nullable = false),
+        ("embedderName" = String, Path, example = "default", description = "Name of the embedder", nullable = false),
+    ),
+    request_body = EmbedQuery,
+    responses(
+        (status = 200, description = "The embeddings are returned", body = EmbedResult, content_type = "application/json", example = json!(
+            {
+                "embeddings": [[0.1, 0.2, 0.3]],
+                "processingTimeMs": 12
+            }
+        )),
+        (status = 404, description = "Embedder not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Embedder `default` not found in index `movies`.",
+                "code": "embedder_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#embedder_not_found"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn embed(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::EMBEDDERS_EMBED }>, Data<IndexScheduler>>,
+    path: web::Path<EmbedderParam>,
+    params: AwebJson<EmbedQuery, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let EmbedderParam { index_uid, embedder_name } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+
+    let query = params.into_inner();
+    debug!(parameters = ?query, "Embed");
+
+    let mut aggregate = EmbedAggregator::from_query(&query);
+
+    let result = tokio::task::spawn_blocking(move || {
+        perform_embed(&index_scheduler, &index_uid, &embedder_name, query)
+    })
+    .await?;
+
+    if let Ok(result) = &result {
+        aggregate.succeed(result);
+    }
+    analytics.publish(aggregate, &req);
+
+    let result = result?;
+
+    debug!(returns = ?result, "Embed");
+    Ok(HttpResponse::Ok().json(result))
+}
+
+fn perform_embed(
+    index_scheduler: &IndexScheduler,
+    index_uid: &IndexUid,
+    embedder_name: &str,
+    query: EmbedQuery,
+) -> Result<EmbedResult, ResponseError> {
+    let before_embed = Instant::now();
+    let deadline = before_embed + EMBED_TIMEOUT;
+
+    let (texts, search) = match query {
+        EmbedQuery { texts: Some(texts), documents: None } => {
+            check_input_count(texts.len(), "texts", Code::InvalidEmbedTexts)?;
+            (Inputs::Texts(texts), true)
+        }
+        EmbedQuery { texts: None, documents: Some(documents) } => {
+            check_input_count(documents.len(), "documents", Code::InvalidEmbedDocuments)?;
+            (Inputs::Documents(documents), false)
+        }
+        EmbedQuery { texts: Some(_), documents: Some(_) } => {
+            return Err(ResponseError::from_msg(
+                "`texts` and `documents` cannot be used together".to_string(),
+                Code::InvalidEmbedInput,
+            ))
+        }
+        EmbedQuery { texts: None, documents: None } => {
+            return Err(ResponseError::from_msg(
+                "One of `texts` or `documents` must be provided".to_string(),
+                Code::InvalidEmbedInput,
+            ))
+        }
+    };
+
+    let index = index_scheduler.index(index_uid)?;
+    let rtxn = index.read_txn()?;
+    let mut embedder_configs = index.embedding_configs(&rtxn)?;
+    embedder_configs.extend(index.sparse_embedding_configs(&rtxn)?);
+    let embedders = index_scheduler.embedders(index_uid.to_string(), embedder_configs)?;
+
+    let Some((embedder, prompt, _quantized)) = embedders.get(embedder_name) else {
+        return Err(ResponseError::from_msg(
+            format!("Embedder `{embedder_name}` not found in index `{index_uid}`."),
+            Code::EmbedderNotFound,
+        ));
+    };
+
+    let texts = match texts {
+        Inputs::Texts(texts) => texts,
+        Inputs::Documents(documents) => {
+            let fields_ids_map = index.fields_ids_map_with_metadata(&rtxn)?;
+            documents
+                .iter()
+                .enumerate()
+                .map(|(i, document)| {
+                    prompt.render_json_document(
+                        &format!("documents[{i}]"),
+                        document,
+                        fields_ids_map.clone(),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+    drop(rtxn);
+
+    let local_pool;
+    let threads = match &index_scheduler.indexer_config().thread_pool {
+        Some(pool) => pool,
+        None => {
+            local_pool = ThreadPoolNoAbortBuilder::new()
+                .thread_name(|i| format!("embedding-thread-{i}"))
+                .build()
+                .map_err(milli::Error::from)?;
+            &local_pool
+        }
+    };
+
+    let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+    let embeddings = embed_texts(&embedder, &texts, search, threads, deadline)?;
+
+    Ok(EmbedResult { embeddings, processing_time_ms: before_embed.elapsed().as_millis() })
+}
+
+enum Inputs {
+    Texts(Vec<String>),
+    Documents(Vec<Object>),
+}
+
+fn check_input_count(count: usize, name: &str, code: Code) -> Result<(), ResponseError> {
+    if count > MAX_EMBED_INPUTS {
+        return Err(ResponseError::from_msg(
+            format!(
+                "`{name}` cannot contain more than {MAX_EMBED_INPUTS} elements, but it contains {count} elements"
+            ),
+            code,
+        ));
+    }
+    Ok(())
+}
+
+/// Embeds search queries, through the embedding cache, or rendered documents, by chunks sent in parallel.
+fn embed_texts(
+    embedder: &Embedder,
+    texts: &[&str],
+    search: bool,
+    threads: &ThreadPoolNoAbort,
+    deadline: Instant,
+) -> Result<Vec<Value>, milli::Error> {
+    let embeddings = match embedder {
+        Embedder::Sparse(embedder) => embedder
+            .embed_index_ref(texts, threads, Some(deadline))
+            .map(|vectors| vectors.into_iter().map(|vector| json!(vector)).collect()),
+        embedder if search => embedder
+            .embed_search_ref(texts, threads, Some(deadline))
+            .map(|embeddings| embeddings.into_iter().map(|embedding| json!(embedding)).collect()),
+        embedder => embedder
+            .embed_index_ref(texts, threads, Some(deadline))
+            .map(|embeddings| embeddings.into_iter().map(|embedding| json!(embedding)).collect()),
+    };
+    Ok(embeddings.map_err(milli::vector::Error::from)?)
+}
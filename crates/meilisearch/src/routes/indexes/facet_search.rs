@@ -18,10 +18,10 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::routes::indexes::search::search_kind;
 use crate::search::{
-    add_search_rules, perform_facet_search, FacetSearchResult, HybridQuery, MatchingStrategy,
-    RankingScoreThreshold, SearchQuery, SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
-    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
-    DEFAULT_SEARCH_OFFSET,
+    add_index_search_rules, perform_facet_search, CropMode, FacetSearchResult, HybridQuery,
+    MatchingStrategy, RankingScoreThreshold, SearchQuery, SearchResult, DEFAULT_CROP_LENGTH,
+    DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG,
+    DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET,
 };
 use crate::search_queue::SearchQueue;
 
@@ -251,9 +251,12 @@ pub async fn search(
     let mut search_query = SearchQuery::from(query);
 
     // Tenant token search_rules.
-    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut search_query.filter, search_rules);
-    }
+    add_index_search_rules(
+        &index_scheduler,
+        index_scheduler.filters(),
+        &index_uid,
+        &mut search_query.filter,
+    )?;
 
     let index = index_scheduler.index(&index_uid)?;
     let search_kind = search_kind(&search_query, &index_scheduler, index_uid.to_string(), &index)?;
@@ -331,12 +334,16 @@ impl From<FacetSearchQuery> for SearchQuery {
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
+            crop_mode: CropMode::default(),
+            snippets_per_attribute: None,
+            snippet_length: None,
             matching_strategy,
             vector,
             attributes_to_search_on,
             hybrid,
             ranking_score_threshold,
             locales,
+            rerank: None,
         }
     }
 }
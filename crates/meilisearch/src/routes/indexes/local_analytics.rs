@@ -0,0 +1,266 @@
+use actix_web::web::{self, Data};
+use actix_web::HttpResponse;
+use deserr::actix_web::AwebQueryParameter;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::DeserrQueryParamError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{InvalidTaskDateError, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::keys::actions;
+use meilisearch_types::star_or::OptionStarOr;
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::debug;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::analytics::{Analytics, QueryStats};
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::routes::tasks::{deserialize_date, DeserializeDateOption};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_top_queries, get_no_results_queries),
+    tags(
+        (
+            name = "Search analytics",
+            description = "The `/analytics` routes expose the searches recorded locally by Meilisearch when it is launched with `--experimental-local-search-analytics`.",
+        ),
+    ),
+)]
+pub struct LocalAnalyticsApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/top-queries").route(web::get().to(SeqHandler(get_top_queries))))
+        .service(
+            web::resource("/no-results").route(web::get().to(SeqHandler(get_no_results_queries))),
+        );
+}
+
+const DEFAULT_ANALYTICS_LIMIT: usize = 20;
+
+#[derive(Debug, Deserr, IntoParams)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+#[into_params(rename_all = "camelCase", parameter_in = Query)]
+pub struct AnalyticsQuery {
+    /// Only aggregates the searches made after the given date. Supports RFC 3339 date format.
+    #[deserr(default, error = DeserrQueryParamError<InvalidAnalyticsFrom>, try_from(OptionStarOr<String>) = deserialize_window_start -> InvalidTaskDateError)]
+    #[param(required = false, value_type = Option<String>, example = json!("2024-08-08T16:37:09.971Z"))]
+    pub from: OptionStarOr<OffsetDateTime>,
+    /// Only aggregates the searches made before the given date. Supports RFC 3339 date format.
+    #[deserr(default, error = DeserrQueryParamError<InvalidAnalyticsTo>, try_from(OptionStarOr<String>) = deserialize_window_end -> InvalidTaskDateError)]
+    #[param(required = false, value_type = Option<String>, example = json!("2024-08-15"))]
+    pub to: OptionStarOr<OffsetDateTime>,
+    /// Maximum number of queries to return.
+    #[deserr(default = Param(DEFAULT_ANALYTICS_LIMIT), error = DeserrQueryParamError<InvalidAnalyticsLimit>)]
+    #[param(required = false, value_type = usize, default = 20)]
+    pub limit: Param<usize>,
+}
+
+/// A date without a time starts the window at the beginning of that day.
+fn deserialize_window_start(
+    value: OptionStarOr<String>,
+) -> Result<OptionStarOr<OffsetDateTime>, InvalidTaskDateError> {
+    value.try_map(|x| deserialize_date(&x, DeserializeDateOption::Before))
+}
+
+/// A date without a time ends the window at the end of that day.
+fn deserialize_window_end(
+    value: OptionStarOr<String>,
+) -> Result<OptionStarOr<OffsetDateTime>, InvalidTaskDateError> {
+    value.try_map(|x| deserialize_date(&x, DeserializeDateOption::After))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStatsView {
+    /// The aggregated queries, in decreasing order of relevance for the route.
+    pub results: Vec<QueryStats>,
+    /// Start of the aggregation window, if any.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub from: Option<OffsetDateTime>,
+    /// End of the aggregation window, if any.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub to: Option<OffsetDateTime>,
+    pub limit: usize,
+    /// Total number of distinct queries in the window.
+    pub total: usize,
+}
+
+/// Get the most searched queries
+///
+/// Get the queries searched the most on an index over a time window.
+#[utoipa::path(
+    get,
+    path = "/{indexUid}/analytics/top-queries",
+    tag = "Search analytics",
+    security(("Bearer" = ["stats.get", "stats.*", "*"])),
+    params(
+        ("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false),
+        AnalyticsQuery,
+    ),
+    responses(
+        (status = OK, description = "The most searched queries", body = QueryStatsView, content_type = "application/json", example = json!(
+            {
+                "results": [
+                    {
+                        "query": "star wars",
+                        "count": 12,
+                        "noResultsCount": 0,
+                        "averageHits": 8.0,
+                        "averageProcessingTimeMs": 1.5,
+                        "clickCount": 3,
+                        "conversionCount": 1,
+                        "clickThroughRate": 0.25,
+                        "lastSearchedAt": "2024-08-08T16:37:09.971Z"
+                    }
+                ],
+                "from": null,
+                "to": null,
+                "limit": 20,
+                "total": 1
+            }
+        )),
+        (status = 400, description = "The local search analytics are not enabled", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Getting search analytics requires enabling the `local search analytics` experimental feature. Relaunch Meilisearch with `--experimental-local-search-analytics`.",
+                "code": "feature_not_enabled",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+            }
+        )),
+        (status = 404, description = "Index not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Index `movies` not found.",
+                "code": "index_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#index_not_found"
+            }
+        )),
+    )
+)]
+pub async fn get_top_queries(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
+    analytics: web::Data<Analytics>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<AnalyticsQuery, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let params = params.into_inner();
+    debug!(parameters = ?params, "Get top queries");
+
+    let mut stats = query_stats(&index_scheduler, &analytics, &index_uid, &params)?;
+    stats.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.query.cmp(&b.query)));
+    let view = QueryStatsView::new(stats, params);
+
+    debug!(returns = ?view, "Get top queries");
+    Ok(HttpResponse::Ok().json(view))
+}
+
+/// Get the queries without results
+///
+/// Get the queries that returned no results the most on an index over a time window.
+#[utoipa::path(
+    get,
+    path = "/{indexUid}/analytics/no-results",
+    tag = "Search analytics",
+    security(("Bearer" = ["stats.get", "stats.*", "*"])),
+    params(
+        ("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false),
+        AnalyticsQuery,
+    ),
+    responses(
+        (status = OK, description = "The queries without results", body = QueryStatsView, content_type = "application/json", example = json!(
+            {
+                "results": [
+                    {
+                        "query": "star warz",
+                        "count": 3,
+                        "noResultsCount": 3,
+                        "averageHits": 0.0,
+                        "averageProcessingTimeMs": 0.3,
+                        "clickCount": 0,
+                        "conversionCount": 0,
+                        "clickThroughRate": 0.0,
+                        "lastSearchedAt": "2024-08-08T16:37:09.971Z"
+                    }
+                ],
+                "from": null,
+                "to": null,
+                "limit": 20,
+                "total": 1
+            }
+        )),
+        (status = 400, description = "The local search analytics are not enabled", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Getting search analytics requires enabling the `local search analytics` experimental feature. Relaunch Meilisearch with `--experimental-local-search-analytics`.",
+                "code": "feature_not_enabled",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+            }
+        )),
+        (status = 404, description = "Index not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Index `movies` not found.",
+                "code": "index_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#index_not_found"
+            }
+        )),
+    )
+)]
+pub async fn get_no_results_queries(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
+    analytics: web::Data<Analytics>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<AnalyticsQuery, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let params = params.into_inner();
+    debug!(parameters = ?params, "Get no results queries");
+
+    let mut stats = query_stats(&index_scheduler, &analytics, &index_uid, &params)?;
+    stats.retain(|stat| stat.no_results_count != 0);
+    stats.sort_unstable_by(|a, b| {
+        b.no_results_count.cmp(&a.no_results_count).then_with(|| a.query.cmp(&b.query))
+    });
+    let view = QueryStatsView::new(stats, params);
+
+    debug!(returns = ?view, "Get no results queries");
+    Ok(HttpResponse::Ok().json(view))
+}
+
+fn query_stats(
+    index_scheduler: &IndexScheduler,
+    analytics: &Analytics,
+    index_uid: &IndexUid,
+    params: &AnalyticsQuery,
+) -> Result<Vec<QueryStats>, ResponseError> {
+    let local = analytics
+        .local()
+        .ok_or(MeilisearchHttpError::LocalSearchAnalyticsNotEnabled("Getting search analytics"))?;
+    // ensures the index exists
+    index_scheduler.index(index_uid)?;
+
+    let from = params.from.merge_star_and_none();
+    let to = params.to.merge_star_and_none();
+    local.query_stats(index_uid, from, to).map_err(|e| MeilisearchHttpError::from(e).into())
+}
+
+impl QueryStatsView {
+    fn new(stats: Vec<QueryStats>, params: AnalyticsQuery) -> Self {
+        let total = stats.len();
+        let limit = params.limit.0;
+        Self {
+            results: stats.into_iter().take(limit).collect(),
+            from: params.from.merge_star_and_none(),
+            to: params.to.merge_star_and_none(),
+            limit,
+            total,
+        }
+    }
+}
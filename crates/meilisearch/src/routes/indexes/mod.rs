@@ -29,7 +29,10 @@ use crate::routes::is_dry_run;
 use crate::Opt;
 
 pub mod documents;
+pub mod embedders;
 pub mod facet_search;
+pub mod local_analytics;
+pub mod render;
 pub mod search;
 mod search_analytics;
 #[cfg(test)]
@@ -43,11 +46,14 @@ mod similar_analytics;
 #[openapi(
     nest(
         (path = "/", api = documents::DocumentsApi),
+        (path = "/", api = embedders::EmbeddersApi),
         (path = "/", api = facet_search::FacetSearchApi),
         (path = "/", api = similar::SimilarApi),
         (path = "/", api = settings::SettingsApi),
+        (path = "/", api = local_analytics::LocalAnalyticsApi),
+        (path = "/", api = render::RenderApi),
     ),
-    paths(list_indexes, create_index, get_index, update_index, delete_index, get_index_stats),
+    paths(list_indexes, create_index, get_index, update_index, clone_index, delete_index, get_index_stats),
     tags(
         (
             name = "Indexes",
@@ -73,11 +79,15 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                     .route(web::delete().to(SeqHandler(delete_index))),
             )
             .service(web::resource("/stats").route(web::get().to(SeqHandler(get_index_stats))))
+            .service(web::resource("/clone").route(web::post().to(SeqHandler(clone_index))))
             .service(web::scope("/documents").configure(documents::configure))
             .service(web::scope("/search").configure(search::configure))
             .service(web::scope("/facet-search").configure(facet_search::configure))
             .service(web::scope("/similar").configure(similar::configure))
-            .service(web::scope("/settings").configure(settings::configure)),
+            .service(web::scope("/embedders").configure(embedders::configure))
+            .service(web::scope("/render").configure(render::configure))
+            .service(web::scope("/settings").configure(settings::configure))
+            .service(web::scope("/analytics").configure(local_analytics::configure)),
     );
 }
 
@@ -440,6 +450,108 @@ pub async fn update_index(
     Ok(HttpResponse::Accepted().json(task))
 }
 
+#[derive(Deserr, Debug, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+#[schema(rename_all = "camelCase")]
+pub struct IndexCloneRequest {
+    /// The name of the index to create from the copy
+    #[schema(example = "movies_copy")]
+    #[deserr(error = DeserrJsonError<InvalidIndexUid>, missing_field_error = DeserrJsonError::missing_index_uid)]
+    uid: IndexUid,
+}
+
+#[derive(Serialize, Default)]
+struct IndexClonedAggregate {
+    total_received: usize,
+}
+
+impl Aggregate for IndexClonedAggregate {
+    fn event_name(&self) -> &'static str {
+        "Index Cloned"
+    }
+
+    fn aggregate(self: Box<Self>, new: Box<Self>) -> Box<Self> {
+        Box::new(Self { total_received: self.total_received.saturating_add(new.total_received) })
+    }
+
+    fn into_event(self: Box<Self>) -> serde_json::Value {
+        serde_json::to_value(*self).unwrap_or_default()
+    }
+}
+
+/// Clone index
+///
+/// Create a new index with a copy of the documents, settings and embeddings of an index.
+/// The data is copied as is, nothing is reindexed nor embedded again.
+/// The API key must also be allowed to get the documents of the cloned index.
+#[utoipa::path(
+    post,
+    path = "/{indexUid}/clone",
+    tag = "Indexes",
+    security(("Bearer" = ["indexes.create", "indexes.*", "*"])),
+    params(("indexUid", example = "movies", description = "Index Unique Identifier", nullable = false)),
+    request_body = IndexCloneRequest,
+    responses(
+        (status = ACCEPTED, description = "Task successfully enqueued", body = SummarizedTaskView, content_type = "application/json", example = json!(
+            {
+                "taskUid": 0,
+                "indexUid": "movies",
+                "status": "enqueued",
+                "type": "indexClone",
+                "enqueuedAt": "2021-01-01T09:39:00.000000Z"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn clone_index(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_CREATE }>, Data<IndexScheduler>>,
+    // The copy can be read by other keys, so the documents of the source must be readable.
+    _documents_reader: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    body: AwebJson<IndexCloneRequest, DeserrJsonError>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    debug!(parameters = ?body, "Clone index");
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let IndexCloneRequest { uid: new_index_uid } = body.into_inner();
+
+    if !index_scheduler.filters().allow_index_creation(&new_index_uid) {
+        return Err(AuthenticationError::InvalidToken.into());
+    }
+    if index_uid == new_index_uid {
+        return Err(ResponseError::from_msg(
+            format!("Index `{new_index_uid}` already exists."),
+            Code::IndexAlreadyExists,
+        ));
+    }
+
+    analytics.publish(IndexClonedAggregate { total_received: 1 }, &req);
+
+    let task = KindWithContent::IndexClone {
+        index_uid: index_uid.into_inner(),
+        new_index_uid: new_index_uid.into_inner(),
+    };
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Clone index");
+    Ok(HttpResponse::Accepted().json(task))
+}
+
 /// Delete index
 ///
 /// Delete an index.
@@ -0,0 +1,337 @@
+use std::collections::BinaryHeap;
+use std::num::NonZeroUsize;
+use std::time::Instant;
+
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::keys::actions;
+use meilisearch_types::milli::prompt::{default_max_bytes, Prompt};
+use meilisearch_types::milli::{self, Object};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::debug;
+use utoipa::{OpenApi, ToSchema};
+
+use super::documents::retrieve_document;
+use crate::analytics::{Aggregate, Analytics};
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::search::{ExternalDocumentId, RetrieveVectors};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(render),
+    tags(
+        (
+            name = "Render",
+            description = "The `/render` route renders a document through a `documentTemplate`, to check the text that would be embedded for it.",
+        ),
+    ),
+)]
+pub struct RenderApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(render))));
+}
+
+#[derive(Debug, Clone, Deserr, ToSchema)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+#[schema(rename_all = "camelCase")]
+pub struct RenderQuery {
+    /// A Liquid template to render the document with, as the `documentTemplate` of an embedder.
+    #[deserr(default, error = DeserrJsonError<InvalidRenderTemplate>)]
+    #[schema(value_type = Option<String>, example = "A movie titled {{doc.title}}")]
+    pub template: Option<String>,
+    /// Maximum size of the rendered text, as the `documentTemplateMaxBytes` of an embedder.
+    ///
+    /// Can only be used with `template`.
+    #[deserr(default, error = DeserrJsonError<InvalidRenderDocumentTemplateMaxBytes>)]
+    #[schema(value_type = Option<usize>, example = 400)]
+    pub document_template_max_bytes: Option<usize>,
+    /// Name of an embedder whose `documentTemplate` and `documentTemplateMaxBytes` are used.
+    #[deserr(default, error = DeserrJsonError<InvalidRenderEmbedder>)]
+    #[schema(value_type = Option<String>, example = "default")]
+    pub embedder: Option<String>,
+    /// Identifier of a document of the index to render.
+    #[deserr(default, error = DeserrJsonError<InvalidRenderId>)]
+    #[schema(value_type = Option<String>, example = "23")]
+    pub id: Option<Value>,
+    /// A document to render, that doesn't need to be in the index.
+    #[deserr(default, error = DeserrJsonError<InvalidRenderDocument>)]
+    #[schema(value_type = Option<Object>, example = json!({ "id": 1, "title": "Kefir" }))]
+    pub document: Option<Object>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderResult {
+    /// The template used to render the document.
+    pub template: String,
+    /// The whole rendered text, before truncation.
+    pub rendered: String,
+    /// Byte offset at which the rendered text is truncated before being embedded, or `null` if it isn't.
+    pub truncated_at: Option<usize>,
+    pub processing_time_ms: u128,
+}
+
+#[derive(Default)]
+pub struct RenderAggregator {
+    total_received: usize,
+    total_succeeded: usize,
+    time_spent: BinaryHeap<usize>,
+
+    // inputs
+    template: bool,
+    embedder: bool,
+    id: bool,
+    document: bool,
+
+    // result
+    truncated: usize,
+}
+
+impl RenderAggregator {
+    fn from_query(query: &RenderQuery) -> Self {
+        let RenderQuery { template, document_template_max_bytes: _, embedder, id, document } =
+            query;
+
+        Self {
+            total_received: 1,
+            template: template.is_some(),
+            embedder: embedder.is_some(),
+            id: id.is_some(),
+            document: document.is_some(),
+            ..Default::default()
+        }
+    }
+
+    fn succeed(&mut self, result: &RenderResult) {
+        self.total_succeeded = 1;
+        self.time_spent.push(result.processing_time_ms as usize);
+        self.truncated = result.truncated_at.is_some() as usize;
+    }
+}
+
+impl Aggregate for RenderAggregator {
+    fn event_name(&self) -> &'static str {
+        "Render POST"
+    }
+
+    fn aggregate(mut self: Box<Self>, new: Box<Self>) -> Box<Self> {
+        self.total_received = self.total_received.saturating_add(new.total_received);
+        self.total_succeeded = self.total_succeeded.saturating_add(new.total_succeeded);
+        self.time_spent.extend(new.time_spent);
+        self.template |= new.template;
+        self.embedder |= new.embedder;
+        self.id |= new.id;
+        self.document |= new.document;
+        self.truncated = self.truncated.saturating_add(new.truncated);
+        self
+    }
+
+    fn into_event(self: Box<Self>) -> serde_json::Value {
+        let Self {
+            total_received,
+            total_succeeded,
+            time_spent,
+            template,
+            embedder,
+            id,
+            document,
+            truncated,
+        } = *self;
+
+        // the index of the 99th percentage of value
+        let time_spent = time_spent.into_sorted_vec();
+        let percentile_99th = time_spent.len() * 99 / 100;
+        let time_spent = time_spent.get(percentile_99th);
+
+        json!({
+            "requests": {
+                "99th_response_time": time_spent.map(|t| format!("{:.2}", t)),
+                "total_succeeded": total_succeeded,
+                "total_failed": total_received.saturating_sub(total_succeeded),
+                "total_received": total_received,
+            },
+            "template": template,
+            "embedder": embedder,
+            "id": id,
+            "document": document,
+            "total_truncated": truncated,
+        })
+    }
+}
+
+/// Render a document template
+///
+/// Render a document of the index, or a document sent in the request, through a `documentTemplate`.
+/// The text is returned exactly as it is rendered during indexing, with the point where it is truncated by `documentTemplateMaxBytes`.
+#[utoipa::path(
+    post,
+    path = "{indexUid}/render",
+    tag = "Render",
+    security(("Bearer" = ["documents.get", "*"])),
+    params(("indexUid" = String, Path, example = "movies", description = "Index Unique Identifier", nullable = false)),
+    request_body = RenderQuery,
+    responses(
+        (status = 200, description = "The document is rendered", body = RenderResult, content_type = "application/json", example = json!(
+            {
+                "template": "A movie titled {{doc.title}}",
+                "rendered": "A movie titled Kefir",
+                "truncatedAt": null,
+                "processingTimeMs": 0
+            }
+        )),
+        (status = 404, description = "Document not found", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "Document `23` not found.",
+                "code": "document_not_found",
+                "type": "invalid_request",
+                "link": "https://docs.meilisearch.com/errors#document_not_found"
+            }
+        )),
+        (status = 401, description = "The authorization header is missing", body = ResponseError, content_type = "application/json", example = json!(
+            {
+                "message": "The Authorization header is missing. It must use the bearer authorization method.",
+                "code": "missing_authorization_header",
+                "type": "auth",
+                "link": "https://docs.meilisearch.com/errors#missing_authorization_header"
+            }
+        )),
+    )
+)]
+pub async fn render(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebJson<RenderQuery, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    let query = params.into_inner();
+    debug!(parameters = ?query, "Render");
+
+    let mut aggregate = RenderAggregator::from_query(&query);
+
+    let result =
+        tokio::task::spawn_blocking(move || perform_render(&index_scheduler, &index_uid, query))
+            .await?;
+
+    if let Ok(result) = &result {
+        aggregate.succeed(result);
+    }
+    analytics.publish(aggregate, &req);
+
+    let result = result?;
+
+    debug!(returns = ?result, "Render");
+    Ok(HttpResponse::Ok().json(result))
+}
+
+fn perform_render(
+    index_scheduler: &IndexScheduler,
+    index_uid: &IndexUid,
+    query: RenderQuery,
+) -> Result<RenderResult, ResponseError> {
+    let before_render = Instant::now();
+
+    let RenderQuery { template, document_template_max_bytes, embedder, id, document } = query;
+
+    let index = index_scheduler.index(index_uid)?;
+    let rtxn = index.read_txn()?;
+
+    let prompt = match (template, embedder) {
+        (Some(template), None) => {
+            let max_bytes = document_template_max_bytes
+                .and_then(NonZeroUsize::new)
+                .unwrap_or(default_max_bytes());
+            Prompt::new(template, Some(max_bytes)).map_err(|error| {
+                ResponseError::from_msg(
+                    format!("Invalid value at `.template`: {}", error.kind),
+                    Code::InvalidRenderTemplate,
+                )
+            })?
+        }
+        (None, Some(embedder)) => {
+            if document_template_max_bytes.is_some() {
+                return Err(ResponseError::from_msg(
+                    "`documentTemplateMaxBytes` can only be used with `template`".to_string(),
+                    Code::InvalidRenderInput,
+                ));
+            }
+            let mut configs = index.embedding_configs(&rtxn)?;
+            configs.extend(index.sparse_embedding_configs(&rtxn)?);
+            let Some(config) = configs.into_iter().find(|config| config.name == embedder) else {
+                return Err(ResponseError::from_msg(
+                    format!("Embedder `{embedder}` not found in index `{index_uid}`."),
+                    Code::EmbedderNotFound,
+                ));
+            };
+            // the prompt was validated when the settings were applied
+            Prompt::try_from(config.config.prompt).map_err(milli::Error::from)?
+        }
+        (Some(_), Some(_)) => {
+            return Err(ResponseError::from_msg(
+                "`template` and `embedder` cannot be used together".to_string(),
+                Code::InvalidRenderInput,
+            ))
+        }
+        (None, None) => {
+            return Err(ResponseError::from_msg(
+                "One of `template` or `embedder` must be provided".to_string(),
+                Code::InvalidRenderInput,
+            ))
+        }
+    };
+
+    let (external_docid, document) = match (id, document) {
+        (Some(id), None) => {
+            let id: ExternalDocumentId = id.try_into().map_err(|error| {
+                let msg = format!("Invalid value at `.id`: {error}");
+                ResponseError::from_msg(msg, Code::InvalidRenderId)
+            })?;
+            let document = retrieve_document(
+                &index,
+                id.as_ref(),
+                None::<Vec<String>>,
+                RetrieveVectors::Hide,
+                false,
+            )?;
+            (id.into_inner(), document)
+        }
+        (None, Some(document)) => ("document".to_string(), document),
+        (Some(_), Some(_)) => {
+            return Err(ResponseError::from_msg(
+                "`id` and `document` cannot be used together".to_string(),
+                Code::InvalidRenderInput,
+            ))
+        }
+        (None, None) => {
+            return Err(ResponseError::from_msg(
+                "One of `id` or `document` must be provided".to_string(),
+                Code::InvalidRenderInput,
+            ))
+        }
+    };
+
+    let fields_ids_map = index.fields_ids_map_with_metadata(&rtxn)?;
+    let rendered =
+        prompt.render_json_document_untruncated(&external_docid, &document, fields_ids_map)?;
+    let truncated_at = prompt.truncation_point(&rendered);
+
+    Ok(RenderResult {
+        template: prompt.template().to_string(),
+        rendered,
+        truncated_at,
+        processing_time_ms: before_render.elapsed().as_millis(),
+    })
+}
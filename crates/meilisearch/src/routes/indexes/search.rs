@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
@@ -9,6 +11,7 @@ use meilisearch_types::error::ResponseError;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::locales::Locale;
 use meilisearch_types::milli;
+use meilisearch_types::milli::vector::rerank::Reranker;
 use meilisearch_types::serde_cs::vec::CS;
 use serde_json::Value;
 use tracing::debug;
@@ -22,12 +25,13 @@ use crate::extractors::sequential_extractor::SeqHandler;
 use crate::metrics::MEILISEARCH_DEGRADED_SEARCH_REQUESTS;
 use crate::routes::indexes::search_analytics::{SearchAggregator, SearchGET, SearchPOST};
 use crate::search::{
-    add_search_rules, perform_search, HybridQuery, MatchingStrategy, RankingScoreThreshold,
-    RetrieveVectors, SearchKind, SearchQuery, SearchResult, SemanticRatio, DEFAULT_CROP_LENGTH,
-    DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG,
-    DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
+    add_index_search_rules, perform_search, CropMode, Fusion, HybridQuery, MatchingStrategy,
+    RankingScoreThreshold, RetrieveVectors, SearchKind, SearchQuery, SearchResult, SemanticRatio,
+    SnippetLength, SnippetsPerAttribute, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_RRF_K, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
 };
-use crate::search_queue::SearchQueue;
+use crate::search_queue::{SearchQueue, SharedPermit};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -116,6 +120,14 @@ pub struct SearchQueryGet {
     #[deserr(default = DEFAULT_CROP_MARKER(), error = DeserrQueryParamError<InvalidSearchCropMarker>)]
     #[param(default = DEFAULT_CROP_MARKER)]
     crop_marker: String,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchCropMode>)]
+    crop_mode: CropMode,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSnippetsPerAttribute>)]
+    #[param(value_type = Option<usize>)]
+    snippets_per_attribute: Option<SnippetsPerAttributeGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSnippetLength>)]
+    #[param(value_type = Option<usize>)]
+    snippet_length: Option<SnippetLengthGet>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchMatchingStrategy>)]
     matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchAttributesToSearchOn>)]
@@ -126,6 +138,15 @@ pub struct SearchQueryGet {
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchSemanticRatio>)]
     #[param(value_type = f32)]
     pub hybrid_semantic_ratio: Option<SemanticRatioGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFusion>)]
+    #[param(value_type = Option<Fusion>)]
+    pub hybrid_fusion: Option<Fusion>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRrfK>)]
+    #[param(value_type = Option<u32>)]
+    pub hybrid_rrf_k: Option<Param<u32>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchExhaustive>)]
+    #[param(value_type = Option<bool>)]
+    pub hybrid_exhaustive: Option<Param<bool>>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchRankingScoreThreshold>)]
     #[param(value_type = f32)]
     pub ranking_score_threshold: Option<RankingScoreThresholdGet>,
@@ -168,6 +189,30 @@ impl std::ops::Deref for SemanticRatioGet {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, deserr::Deserr)]
+#[deserr(try_from(Param<usize>) = TryFrom::try_from -> InvalidSearchSnippetsPerAttribute)]
+pub struct SnippetsPerAttributeGet(SnippetsPerAttribute);
+
+impl std::convert::TryFrom<Param<usize>> for SnippetsPerAttributeGet {
+    type Error = InvalidSearchSnippetsPerAttribute;
+
+    fn try_from(count: Param<usize>) -> Result<Self, Self::Error> {
+        Ok(SnippetsPerAttributeGet(SnippetsPerAttribute::try_from(count.0)?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, deserr::Deserr)]
+#[deserr(try_from(Param<usize>) = TryFrom::try_from -> InvalidSearchSnippetLength)]
+pub struct SnippetLengthGet(SnippetLength);
+
+impl std::convert::TryFrom<Param<usize>> for SnippetLengthGet {
+    type Error = InvalidSearchSnippetLength;
+
+    fn try_from(length: Param<usize>) -> Result<Self, Self::Error> {
+        Ok(SnippetLengthGet(SnippetLength::try_from(length.0)?))
+    }
+}
+
 impl TryFrom<SearchQueryGet> for SearchQuery {
     type Error = ResponseError;
 
@@ -188,14 +233,29 @@ impl TryFrom<SearchQueryGet> for SearchQuery {
                     meilisearch_types::error::Code::InvalidSearchHybridQuery,
                 ));
             }
-            (Some(embedder), None) => {
-                Some(HybridQuery { semantic_ratio: DEFAULT_SEMANTIC_RATIO(), embedder })
-            }
-            (Some(embedder), Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder })
-            }
+            (Some(embedder), semantic_ratio) => Some(HybridQuery {
+                semantic_ratio: semantic_ratio.map_or_else(DEFAULT_SEMANTIC_RATIO, |ratio| *ratio),
+                embedder,
+                fusion: other.hybrid_fusion.unwrap_or_default(),
+                rrf_k: other.hybrid_rrf_k.map_or_else(DEFAULT_RRF_K, |k| k.0),
+                exhaustive: other.hybrid_exhaustive.is_some_and(|exhaustive| exhaustive.0),
+            }),
         };
 
+        if hybrid.is_none() {
+            if let Some(parameter) = other
+                .hybrid_fusion
+                .map(|_| "hybridFusion")
+                .or(other.hybrid_rrf_k.map(|_| "hybridRrfK"))
+                .or(other.hybrid_exhaustive.map(|_| "hybridExhaustive"))
+            {
+                return Err(ResponseError::from_msg(
+                    format!("`hybridEmbedder` is mandatory when `{parameter}` is present"),
+                    meilisearch_types::error::Code::InvalidSearchHybridQuery,
+                ));
+            }
+        }
+
         if other.vector.is_some() && hybrid.is_none() {
             return Err(ResponseError::from_msg(
                 "`hybridEmbedder` is mandatory when `vector` is present".into(),
@@ -225,11 +285,15 @@ impl TryFrom<SearchQueryGet> for SearchQuery {
             highlight_pre_tag: other.highlight_pre_tag,
             highlight_post_tag: other.highlight_post_tag,
             crop_marker: other.crop_marker,
+            crop_mode: other.crop_mode,
+            snippets_per_attribute: other.snippets_per_attribute.map(|o| o.0),
+            snippet_length: other.snippet_length.map(|o| o.0),
             matching_strategy: other.matching_strategy,
             attributes_to_search_on: other.attributes_to_search_on.map(|o| o.into_iter().collect()),
             hybrid,
             ranking_score_threshold: other.ranking_score_threshold.map(|o| o.0),
             locales: other.locales.map(|o| o.into_iter().collect()),
+            rerank: None,
         })
     }
 }
@@ -329,9 +393,12 @@ pub async fn search_with_url_query(
     let mut query: SearchQuery = params.into_inner().try_into()?;
 
     // Tenant token search_rules.
-    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut query.filter, search_rules);
-    }
+    add_index_search_rules(
+        &index_scheduler,
+        index_scheduler.filters(),
+        &index_uid,
+        &mut query.filter,
+    )?;
 
     let mut aggregate = SearchAggregator::<SearchGET>::from_query(&query);
 
@@ -339,17 +406,26 @@ pub async fn search_with_url_query(
 
     let search_kind =
         search_kind(&query, index_scheduler.get_ref(), index_uid.to_string(), &index)?;
+    let reranker = search_reranker(&query, index_scheduler.get_ref())?;
     let retrieve_vector = RetrieveVectors::new(query.retrieve_vectors);
-    let permit = search_queue.try_get_search_permit().await?;
+    let permit = SharedPermit::new(search_queue.try_get_search_permit().await?);
+    let search_permit = permit.clone();
+    let local_analytics = analytics.clone().into_inner();
     let search_result = tokio::task::spawn_blocking(move || {
-        perform_search(
+        let mut search_result = perform_search(
             index_uid.to_string(),
             &index,
             query,
             search_kind,
+            reranker,
+            &search_permit,
             retrieve_vector,
             index_scheduler.features(),
-        )
+        );
+        if let (Some(local), Ok(search_result)) = (local_analytics.local(), &mut search_result) {
+            search_result.query_id = Some(local.record(&index_uid, search_result));
+        }
+        search_result
     })
     .await;
     permit.drop().await;
@@ -435,9 +511,12 @@ pub async fn search_with_post(
     debug!(parameters = ?query, "Search post");
 
     // Tenant token search_rules.
-    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut query.filter, search_rules);
-    }
+    add_index_search_rules(
+        &index_scheduler,
+        index_scheduler.filters(),
+        &index_uid,
+        &mut query.filter,
+    )?;
 
     let mut aggregate = SearchAggregator::<SearchPOST>::from_query(&query);
 
@@ -445,18 +524,27 @@ pub async fn search_with_post(
 
     let search_kind =
         search_kind(&query, index_scheduler.get_ref(), index_uid.to_string(), &index)?;
+    let reranker = search_reranker(&query, index_scheduler.get_ref())?;
     let retrieve_vectors = RetrieveVectors::new(query.retrieve_vectors);
 
-    let permit = search_queue.try_get_search_permit().await?;
+    let permit = SharedPermit::new(search_queue.try_get_search_permit().await?);
+    let search_permit = permit.clone();
+    let local_analytics = analytics.clone().into_inner();
     let search_result = tokio::task::spawn_blocking(move || {
-        perform_search(
+        let mut search_result = perform_search(
             index_uid.to_string(),
             &index,
             query,
             search_kind,
+            reranker,
+            &search_permit,
             retrieve_vectors,
             index_scheduler.features(),
-        )
+        );
+        if let (Some(local), Ok(search_result)) = (local_analytics.local(), &mut search_result) {
+            search_result.query_id = Some(local.record(&index_uid, search_result));
+        }
+        search_result
     })
     .await;
     permit.drop().await;
@@ -475,6 +563,20 @@ pub async fn search_with_post(
     Ok(HttpResponse::Ok().json(search_result))
 }
 
+/// Returns the reranker of the instance if the query asks for reranking.
+pub fn search_reranker(
+    query: &SearchQuery,
+    index_scheduler: &IndexScheduler,
+) -> Result<Option<Arc<Reranker>>, ResponseError> {
+    if query.rerank.is_none() {
+        return Ok(None);
+    }
+    match index_scheduler.reranker() {
+        Some(reranker) => Ok(Some(reranker)),
+        None => Err(MeilisearchHttpError::RerankerNotConfigured.into()),
+    }
+}
+
 pub fn search_kind(
     query: &SearchQuery,
     index_scheduler: &IndexScheduler,
@@ -488,28 +590,31 @@ pub fn search_kind(
         // no query, no vector => placeholder search
         (None, _, None) => Ok(SearchKind::KeywordOnly),
         // hybrid.semantic_ratio == 1.0 => vector
-        (_, Some(HybridQuery { semantic_ratio, embedder }), v) if **semantic_ratio == 1.0 => {
+        (_, Some(HybridQuery { semantic_ratio, embedder, .. }), v) if **semantic_ratio == 1.0 => {
             SearchKind::semantic(index_scheduler, index_uid, index, embedder, v.map(|v| v.len()))
         }
         // hybrid.semantic_ratio == 0.0 => keyword
-        (_, Some(HybridQuery { semantic_ratio, embedder: _ }), _) if **semantic_ratio == 0.0 => {
+        (_, Some(HybridQuery { semantic_ratio, .. }), _) if **semantic_ratio == 0.0 => {
             Ok(SearchKind::KeywordOnly)
         }
         // no query, hybrid, vector => semantic
-        (None, Some(HybridQuery { semantic_ratio: _, embedder }), Some(v)) => {
+        (None, Some(HybridQuery { embedder, .. }), Some(v)) => {
             SearchKind::semantic(index_scheduler, index_uid, index, embedder, Some(v.len()))
         }
         // query, no hybrid, no vector => keyword
         (Some(_), None, None) => Ok(SearchKind::KeywordOnly),
         // query, hybrid, maybe vector => hybrid
-        (Some(_), Some(HybridQuery { semantic_ratio, embedder }), v) => SearchKind::hybrid(
-            index_scheduler,
-            index_uid,
-            index,
-            embedder,
-            **semantic_ratio,
-            v.map(|v| v.len()),
-        ),
+        (Some(_), Some(hybrid @ HybridQuery { semantic_ratio, embedder, .. }), v) => {
+            SearchKind::hybrid(
+                index_scheduler,
+                index_uid,
+                index,
+                embedder,
+                **semantic_ratio,
+                hybrid.milli_fusion(),
+                v.map(|v| v.len()),
+            )
+        }
 
         (_, None, Some(_)) => Err(MeilisearchHttpError::MissingSearchHybrid.into()),
     }
@@ -8,7 +8,7 @@ use serde_json::{json, Value};
 use crate::aggregate_methods;
 use crate::analytics::{Aggregate, AggregateMethod};
 use crate::search::{
-    SearchQuery, SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    CropMode, Fusion, SearchQuery, SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
     DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
     DEFAULT_SEMANTIC_RATIO,
 };
@@ -60,6 +60,10 @@ pub struct SearchAggregator<Method: AggregateMethod> {
     // Whether the semantic ratio passed to a hybrid search equals the default ratio.
     semantic_ratio: bool,
     hybrid: bool,
+    // Whether a hybrid search used Reciprocal Rank Fusion instead of the score comparison.
+    rrf_fusion: bool,
+    // Whether a semantic search forced an exact scan of the candidates.
+    exhaustive_vector_search: bool,
     retrieve_vectors: bool,
 
     // every time a search is done, we increment the counter linked to the used settings
@@ -80,8 +84,11 @@ pub struct SearchAggregator<Method: AggregateMethod> {
     highlight_post_tag: bool,
     max_attributes_to_crop: usize,
     crop_marker: bool,
+    crop_mode: bool,
     show_matches_position: bool,
     crop_length: bool,
+    max_snippets_per_attribute: usize,
+    snippet_length: bool,
 
     // facets
     facets_sum_of_terms: usize,
@@ -92,6 +99,10 @@ pub struct SearchAggregator<Method: AggregateMethod> {
     show_ranking_score_details: bool,
     ranking_score_threshold: bool,
 
+    // reranking
+    rerank: bool,
+    max_rerank_top_k: usize,
+
     marker: std::marker::PhantomData<Method>,
 }
 
@@ -120,11 +131,15 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
+            crop_mode,
+            snippets_per_attribute,
+            snippet_length,
             matching_strategy,
             attributes_to_search_on,
             hybrid,
             ranking_score_threshold,
             locales,
+            rerank,
         } = query;
 
         let mut ret = Self::default();
@@ -197,16 +212,26 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
         ret.highlight_pre_tag = *highlight_pre_tag != DEFAULT_HIGHLIGHT_PRE_TAG();
         ret.highlight_post_tag = *highlight_post_tag != DEFAULT_HIGHLIGHT_POST_TAG();
         ret.crop_marker = *crop_marker != DEFAULT_CROP_MARKER();
+        ret.crop_mode = *crop_mode != CropMode::default();
         ret.crop_length = *crop_length != DEFAULT_CROP_LENGTH();
         ret.show_matches_position = *show_matches_position;
+        ret.max_snippets_per_attribute = snippets_per_attribute.map_or(0, |count| *count);
+        ret.snippet_length = snippet_length.is_some();
 
         ret.show_ranking_score = *show_ranking_score;
         ret.show_ranking_score_details = *show_ranking_score_details;
         ret.ranking_score_threshold = ranking_score_threshold.is_some();
 
+        if let Some(rerank) = rerank {
+            ret.rerank = true;
+            ret.max_rerank_top_k = *rerank.top_k;
+        }
+
         if let Some(hybrid) = hybrid {
             ret.semantic_ratio = hybrid.semantic_ratio != DEFAULT_SEMANTIC_RATIO();
             ret.hybrid = true;
+            ret.rrf_fusion = hybrid.fusion == Fusion::Rrf;
+            ret.exhaustive_vector_search = hybrid.exhaustive;
         }
 
         ret
@@ -221,6 +246,8 @@ impl<Method: AggregateMethod> SearchAggregator<Method> {
             semantic_hit_count: _,
             facet_distribution: _,
             facet_stats: _,
+            query_id: _,
+            documents_ids: _,
             degraded,
             used_negative_operator,
         } = result;
@@ -269,18 +296,25 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             highlight_post_tag,
             max_attributes_to_crop,
             crop_marker,
+            crop_mode,
             show_matches_position,
             crop_length,
+            max_snippets_per_attribute,
+            snippet_length,
             facets_sum_of_terms,
             facets_total_number_of_facets,
             show_ranking_score,
             show_ranking_score_details,
             semantic_ratio,
             hybrid,
+            rrf_fusion,
+            exhaustive_vector_search,
             total_degraded,
             total_used_negative_operator,
             ranking_score_threshold,
             mut locales,
+            rerank,
+            max_rerank_top_k,
             marker: _,
         } = *new;
 
@@ -326,6 +360,8 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
         self.max_vector_size = self.max_vector_size.max(max_vector_size);
         self.retrieve_vectors |= retrieve_vectors;
         self.semantic_ratio |= semantic_ratio;
+        self.rrf_fusion |= rrf_fusion;
+        self.exhaustive_vector_search |= exhaustive_vector_search;
         self.hybrid |= hybrid;
 
         // pagination
@@ -342,8 +378,12 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
         self.highlight_post_tag |= highlight_post_tag;
         self.max_attributes_to_crop = self.max_attributes_to_crop.max(max_attributes_to_crop);
         self.crop_marker |= crop_marker;
+        self.crop_mode |= crop_mode;
         self.show_matches_position |= show_matches_position;
         self.crop_length |= crop_length;
+        self.max_snippets_per_attribute =
+            self.max_snippets_per_attribute.max(max_snippets_per_attribute);
+        self.snippet_length |= snippet_length;
 
         // facets
         self.facets_sum_of_terms = self.facets_sum_of_terms.saturating_add(facets_sum_of_terms);
@@ -364,6 +404,10 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
         // locales
         self.locales.append(&mut locales);
 
+        // reranking
+        self.rerank |= rerank;
+        self.max_rerank_top_k = self.max_rerank_top_k.max(max_rerank_top_k);
+
         self
     }
 
@@ -395,18 +439,25 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             highlight_post_tag,
             max_attributes_to_crop,
             crop_marker,
+            crop_mode,
             show_matches_position,
             crop_length,
+            max_snippets_per_attribute,
+            snippet_length,
             facets_sum_of_terms,
             facets_total_number_of_facets,
             show_ranking_score,
             show_ranking_score_details,
             semantic_ratio,
             hybrid,
+            rrf_fusion,
+            exhaustive_vector_search,
             total_degraded,
             total_used_negative_operator,
             ranking_score_threshold,
             locales,
+            rerank,
+            max_rerank_top_k,
             marker: _,
         } = *self;
 
@@ -450,6 +501,8 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
             "hybrid": {
                 "enabled": hybrid,
                 "semantic_ratio": semantic_ratio,
+                "rrf_fusion": rrf_fusion,
+                "exhaustive": exhaustive_vector_search,
             },
             "pagination": {
                "max_limit": max_limit,
@@ -463,8 +516,11 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
                 "highlight_post_tag": highlight_post_tag,
                 "max_attributes_to_crop": max_attributes_to_crop,
                 "crop_marker": crop_marker,
+                "crop_mode": crop_mode,
                 "show_matches_position": show_matches_position,
                 "crop_length": crop_length,
+                "max_snippets_per_attribute": max_snippets_per_attribute,
+                "snippet_length": snippet_length,
             },
             "facets": {
                 "avg_facets_number": format!("{:.2}", facets_sum_of_terms as f64 / facets_total_number_of_facets as f64),
@@ -478,6 +534,10 @@ impl<Method: AggregateMethod> Aggregate for SearchAggregator<Method> {
                 "show_ranking_score_details": show_ranking_score_details,
                 "ranking_score_threshold": ranking_score_threshold,
             },
+            "rerank": {
+                "enabled": rerank,
+                "max_top_k": max_rerank_top_k,
+            },
         })
     }
 }
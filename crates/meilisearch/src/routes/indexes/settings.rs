@@ -213,6 +213,7 @@ macro_rules! make_setting_route {
                 let new_settings = $crate::routes::indexes::settings::validate_settings(
                     new_settings,
                     &index_scheduler,
+                    &opt,
                 )?;
 
                 let allow_index_creation =
@@ -409,6 +410,28 @@ make_setting_routes!(
         camelcase_attr: "distinctAttribute",
         analytics: DistinctAttributeAnalytics
     },
+    {
+        route: "/ttl-attribute",
+        update_verb: put,
+        value_type: String,
+        err_type: meilisearch_types::deserr::DeserrJsonError<
+            meilisearch_types::error::deserr_codes::InvalidSettingsTtlAttribute,
+        >,
+        attr: ttl_attribute,
+        camelcase_attr: "ttlAttribute",
+        analytics: TtlAttributeAnalytics
+    },
+    {
+        route: "/document-schema",
+        update_verb: put,
+        value_type: serde_json::Value,
+        err_type: meilisearch_types::deserr::DeserrJsonError<
+            meilisearch_types::error::deserr_codes::InvalidSettingsDocumentSchema,
+        >,
+        attr: document_schema,
+        camelcase_attr: "documentSchema",
+        analytics: DocumentSchemaAnalytics
+    },
     {
         route: "/proximity-precision",
         update_verb: put,
@@ -555,7 +578,7 @@ pub async fn update_all(
 
     let new_settings = body.into_inner();
     debug!(parameters = ?new_settings, "Update all settings");
-    let new_settings = validate_settings(new_settings, &index_scheduler)?;
+    let new_settings = validate_settings(new_settings, &index_scheduler, &opt)?;
 
     analytics.publish(
         SettingsAnalytics {
@@ -575,6 +598,10 @@ pub async fn update_all(
             distinct_attribute: DistinctAttributeAnalytics::new(
                 new_settings.distinct_attribute.as_ref().set(),
             ),
+            ttl_attribute: TtlAttributeAnalytics::new(new_settings.ttl_attribute.as_ref().set()),
+            document_schema: DocumentSchemaAnalytics::new(
+                new_settings.document_schema.as_ref().set(),
+            ),
             proximity_precision: ProximityPrecisionAnalytics::new(
                 new_settings.proximity_precision.as_ref().set(),
             ),
@@ -717,16 +744,35 @@ pub async fn delete_all(
 fn validate_settings(
     settings: Settings<Unchecked>,
     index_scheduler: &IndexScheduler,
+    opt: &Opt,
 ) -> Result<Settings<Unchecked>, ResponseError> {
     use meilisearch_types::milli::update::Setting;
     use meilisearch_types::milli::vector::settings::EmbedderSource;
 
     let features = index_scheduler.features();
     if let Setting::Set(embedders) = &settings.embedders {
-        for SettingEmbeddingSettings { inner: embedder } in embedders.values() {
+        for (name, SettingEmbeddingSettings { inner: embedder }) in embedders {
             let Setting::Set(embedder) = embedder else {
                 continue;
             };
+
+            let models = [
+                ("model", embedder.model.as_ref().set()),
+                (
+                    "searchEmbedder.model",
+                    embedder.search_embedder.as_ref().set().and_then(|e| e.model.as_ref().set()),
+                ),
+                (
+                    "indexingEmbedder.model",
+                    embedder.indexing_embedder.as_ref().set().and_then(|e| e.model.as_ref().set()),
+                ),
+            ];
+            for (field, model) in models {
+                if let Some(model) = model {
+                    validate_local_model(name, field, model, opt)?;
+                }
+            }
+
             if matches!(embedder.source, Setting::Set(EmbedderSource::Composite)) {
                 features.check_composite_embedders("using `\"composite\"` as source")?;
             }
@@ -743,3 +789,24 @@ fn validate_settings(
 
     Ok(settings.validate()?)
 }
+
+/// Refuses the models loaded from the local filesystem outside of the directory allowed by the instance options.
+fn validate_local_model(
+    embedder_name: &str,
+    field: &str,
+    model: &str,
+    opt: &Opt,
+) -> Result<(), ResponseError> {
+    use meilisearch_types::error::Code;
+    use meilisearch_types::milli::vector::hf;
+
+    if !hf::is_local_model(model) {
+        return Ok(());
+    }
+    let message = match &opt.experimental_local_models_directory {
+        Some(directory) if hf::is_local_model_inside(model, directory) => return Ok(()),
+        Some(_) => format!("`.embedders.{embedder_name}.{field}`: the local model `{model}` is outside of the directory allowed for local models.\n  - Hint: move the model inside the directory passed to `--experimental-local-models-directory`"),
+        None => format!("`.embedders.{embedder_name}.{field}`: loading models from the local filesystem is disabled.\n  - Hint: start Meilisearch with `--experimental-local-models-directory` pointing to the directory containing the models"),
+    };
+    Err(ResponseError::from_msg(message, Code::InvalidSettingsEmbedders))
+}
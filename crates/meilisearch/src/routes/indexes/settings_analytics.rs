@@ -25,6 +25,8 @@ pub struct SettingsAnalytics {
     pub sortable_attributes: SortableAttributesAnalytics,
     pub filterable_attributes: FilterableAttributesAnalytics,
     pub distinct_attribute: DistinctAttributeAnalytics,
+    pub ttl_attribute: TtlAttributeAnalytics,
+    pub document_schema: DocumentSchemaAnalytics,
     pub proximity_precision: ProximityPrecisionAnalytics,
     pub typo_tolerance: TypoToleranceAnalytics,
     pub faceting: FacetingAnalytics,
@@ -67,6 +69,10 @@ impl Aggregate for SettingsAnalytics {
                     .ranking_rules
                     .exactness_position
                     .or(self.ranking_rules.exactness_position),
+                popularity_position: new
+                    .ranking_rules
+                    .popularity_position
+                    .or(self.ranking_rules.popularity_position),
                 values: new.ranking_rules.values.or(self.ranking_rules.values),
             },
             searchable_attributes: SearchableAttributesAnalytics {
@@ -98,6 +104,12 @@ impl Aggregate for SettingsAnalytics {
             distinct_attribute: DistinctAttributeAnalytics {
                 set: self.distinct_attribute.set | new.distinct_attribute.set,
             },
+            ttl_attribute: TtlAttributeAnalytics {
+                set: self.ttl_attribute.set | new.ttl_attribute.set,
+            },
+            document_schema: DocumentSchemaAnalytics {
+                set: self.document_schema.set | new.document_schema.set,
+            },
             proximity_precision: ProximityPrecisionAnalytics {
                 set: self.proximity_precision.set | new.proximity_precision.set,
                 value: new.proximity_precision.value.or(self.proximity_precision.value),
@@ -173,6 +185,27 @@ impl Aggregate for SettingsAnalytics {
                     (Some(bq), None) | (None, Some(bq)) => Some(bq),
                     (Some(this), Some(other)) => Some(this | other),
                 },
+                int8_rescoring_used: match (
+                    self.embedders.int8_rescoring_used,
+                    new.embedders.int8_rescoring_used,
+                ) {
+                    (None, None) => None,
+                    (Some(sq), None) | (None, Some(sq)) => Some(sq),
+                    (Some(this), Some(other)) => Some(this | other),
+                },
+                chunking_used: match (self.embedders.chunking_used, new.embedders.chunking_used) {
+                    (None, None) => None,
+                    (Some(chunking), None) | (None, Some(chunking)) => Some(chunking),
+                    (Some(this), Some(other)) => Some(this | other),
+                },
+                request_policy_used: match (
+                    self.embedders.request_policy_used,
+                    new.embedders.request_policy_used,
+                ) {
+                    (None, None) => None,
+                    (Some(policy), None) | (None, Some(policy)) => Some(policy),
+                    (Some(this), Some(other)) => Some(this | other),
+                },
             },
             search_cutoff_ms: SearchCutoffMsAnalytics {
                 search_cutoff_ms: new
@@ -214,6 +247,7 @@ pub struct RankingRulesAnalytics {
     pub attribute_position: Option<usize>,
     pub sort_position: Option<usize>,
     pub exactness_position: Option<usize>,
+    pub popularity_position: Option<usize>,
     pub values: Option<String>,
 }
 
@@ -247,6 +281,11 @@ impl RankingRulesAnalytics {
                     matches!(s, meilisearch_types::settings::RankingRuleView::Exactness)
                 })
             }),
+            popularity_position: rr.as_ref().and_then(|rr| {
+                rr.iter().position(|s| {
+                    matches!(s, meilisearch_types::settings::RankingRuleView::Popularity)
+                })
+            }),
             values: rr.as_ref().map(|rr| {
                 rr.iter()
                     .filter(|s| {
@@ -369,6 +408,36 @@ impl DistinctAttributeAnalytics {
     }
 }
 
+#[derive(Serialize, Default)]
+pub struct TtlAttributeAnalytics {
+    pub set: bool,
+}
+
+impl TtlAttributeAnalytics {
+    pub fn new(ttl: Option<&String>) -> Self {
+        Self { set: ttl.is_some() }
+    }
+
+    pub fn into_settings(self) -> SettingsAnalytics {
+        SettingsAnalytics { ttl_attribute: self, ..Default::default() }
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct DocumentSchemaAnalytics {
+    pub set: bool,
+}
+
+impl DocumentSchemaAnalytics {
+    pub fn new(schema: Option<&serde_json::Value>) -> Self {
+        Self { set: schema.is_some() }
+    }
+
+    pub fn into_settings(self) -> SettingsAnalytics {
+        SettingsAnalytics { document_schema: self, ..Default::default() }
+    }
+}
+
 #[derive(Serialize, Default)]
 pub struct ProximityPrecisionAnalytics {
     pub set: bool,
@@ -504,6 +573,12 @@ pub struct EmbeddersAnalytics {
     pub document_template_max_bytes: Option<usize>,
     // |=
     pub binary_quantization_used: Option<bool>,
+    // |=
+    pub int8_rescoring_used: Option<bool>,
+    // |=
+    pub chunking_used: Option<bool>,
+    // |=
+    pub request_policy_used: Option<bool>,
 }
 
 impl EmbeddersAnalytics {
@@ -524,6 +599,7 @@ impl EmbeddersAnalytics {
                     EmbedderSource::Ollama => sources.insert("ollama".to_string()),
                     EmbedderSource::Rest => sources.insert("rest".to_string()),
                     EmbedderSource::Composite => sources.insert("composite".to_string()),
+                    EmbedderSource::Sparse => sources.insert("sparse".to_string()),
                 };
             }
         };
@@ -547,6 +623,21 @@ impl EmbeddersAnalytics {
                     .filter_map(|config| config.inner.clone().set())
                     .any(|config| config.binary_quantized.set().is_some())
             }),
+            int8_rescoring_used: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .any(|config| config.int8_rescoring.set().is_some())
+            }),
+            chunking_used: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .any(|config| config.chunking.set().is_some())
+            }),
+            request_policy_used: setting.as_ref().map(|map| {
+                map.values()
+                    .filter_map(|config| config.inner.clone().set())
+                    .any(|config| config.request_policy.set().is_some())
+            }),
         }
     }
 
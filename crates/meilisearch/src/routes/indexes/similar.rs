@@ -19,7 +19,7 @@ use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::routes::indexes::similar_analytics::{SimilarAggregator, SimilarGET, SimilarPOST};
 use crate::search::{
-    add_search_rules, perform_similar, RankingScoreThresholdSimilar, RetrieveVectors, Route,
+    add_index_search_rules, perform_similar, RankingScoreThresholdSimilar, RetrieveVectors, Route,
     SearchKind, SimilarQuery, SimilarResult, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET,
 };
 
@@ -219,9 +219,12 @@ async fn similar(
     let retrieve_vectors = RetrieveVectors::new(query.retrieve_vectors);
 
     // Tenant token search_rules.
-    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut query.filter, search_rules);
-    }
+    add_index_search_rules(
+        &index_scheduler,
+        index_scheduler.filters(),
+        &index_uid,
+        &mut query.filter,
+    )?;
 
     let index = index_scheduler.index(&index_uid)?;
 
@@ -252,9 +255,15 @@ async fn similar(
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
 #[into_params(parameter_in = Query)]
 pub struct SimilarQueryGet {
-    #[deserr(error = DeserrQueryParamError<InvalidSimilarId>)]
-    #[param(value_type = String)]
-    id: Param<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSimilarId>)]
+    #[param(value_type = Option<String>)]
+    id: Option<Param<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSimilarPositive>)]
+    #[param(value_type = Vec<String>, explode = false)]
+    positive: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSimilarNegative>)]
+    #[param(value_type = Vec<String>, explode = false)]
+    negative: Option<CS<String>>,
     #[deserr(default = Param(DEFAULT_SEARCH_OFFSET()), error = DeserrQueryParamError<InvalidSimilarOffset>)]
     #[param(value_type = usize, default = DEFAULT_SEARCH_OFFSET)]
     offset: Param<usize>,
@@ -299,6 +308,8 @@ impl From<SimilarQueryGet> for SimilarQuery {
     fn from(
         SimilarQueryGet {
             id,
+            positive,
+            negative,
             offset,
             limit,
             attributes_to_retrieve,
@@ -319,7 +330,13 @@ impl From<SimilarQueryGet> for SimilarQuery {
         };
 
         SimilarQuery {
-            id: serde_json::Value::String(id.0),
+            id: id.map(|id| serde_json::Value::String(id.0)),
+            positive: positive
+                .map(|ids| ids.into_iter().map(serde_json::Value::String).collect())
+                .unwrap_or_default(),
+            negative: negative
+                .map(|ids| ids.into_iter().map(serde_json::Value::String).collect())
+                .unwrap_or_default(),
             offset: offset.0,
             limit: limit.0,
             filter,
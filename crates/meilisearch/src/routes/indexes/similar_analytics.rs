@@ -32,6 +32,10 @@ pub struct SimilarAggregator<Method: AggregateMethod> {
     // Whether a non-default embedder was specified
     retrieve_vectors: bool,
 
+    // examples
+    max_positive_examples: usize,
+    negative_examples: bool,
+
     // pagination
     max_limit: usize,
     max_offset: usize,
@@ -51,7 +55,9 @@ impl<Method: AggregateMethod> SimilarAggregator<Method> {
     #[allow(clippy::field_reassign_with_default)]
     pub fn from_query(query: &SimilarQuery) -> Self {
         let SimilarQuery {
-            id: _,
+            id,
+            positive,
+            negative,
             embedder: _,
             offset,
             limit,
@@ -100,6 +106,9 @@ impl<Method: AggregateMethod> SimilarAggregator<Method> {
 
         ret.retrieve_vectors = *retrieve_vectors;
 
+        ret.max_positive_examples = positive.len() + usize::from(id.is_some());
+        ret.negative_examples = !negative.is_empty();
+
         ret
     }
 
@@ -135,6 +144,8 @@ impl<Method: AggregateMethod> Aggregate for SimilarAggregator<Method> {
             show_ranking_score_details,
             ranking_score_threshold,
             retrieve_vectors,
+            max_positive_examples,
+            negative_examples,
             marker: _,
         } = *new;
 
@@ -157,6 +168,10 @@ impl<Method: AggregateMethod> Aggregate for SimilarAggregator<Method> {
 
         self.retrieve_vectors |= retrieve_vectors;
 
+        // examples
+        self.max_positive_examples = self.max_positive_examples.max(max_positive_examples);
+        self.negative_examples |= negative_examples;
+
         // pagination
         self.max_limit = self.max_limit.max(max_limit);
         self.max_offset = self.max_offset.max(max_offset);
@@ -190,6 +205,8 @@ impl<Method: AggregateMethod> Aggregate for SimilarAggregator<Method> {
             show_ranking_score_details,
             ranking_score_threshold,
             retrieve_vectors,
+            max_positive_examples,
+            negative_examples,
             marker: _,
         } = *self;
 
@@ -216,6 +233,10 @@ impl<Method: AggregateMethod> Aggregate for SimilarAggregator<Method> {
             "vector": {
                 "retrieve_vectors": retrieve_vectors,
             },
+            "examples": {
+                "max_positive": max_positive_examples,
+                "negative": negative_examples,
+            },
             "pagination": {
                "max_limit": max_limit,
                "max_offset": max_offset,
@@ -50,9 +50,11 @@ use crate::Opt;
 const PAGINATION_DEFAULT_LIMIT: usize = 20;
 const PAGINATION_DEFAULT_LIMIT_FN: fn() -> usize = || 20;
 
+mod aliases;
 mod api_key;
 pub mod batches;
 mod dump;
+pub mod events;
 pub mod features;
 pub mod indexes;
 mod logs;
@@ -71,12 +73,14 @@ mod tasks_test;
 #[openapi(
     nest(
         (path = "/tasks", api = tasks::TaskApi),
+        (path = "/aliases", api = aliases::AliasesApi),
         (path = "/batches", api = batches::BatchesApi),
         (path = "/indexes", api = indexes::IndexesApi),
         // We must stop the search path here because the rest must be configured by each route individually
         (path = "/indexes", api = indexes::search::SearchApi),
         (path = "/snapshots", api = snapshot::SnapshotApi),
         (path = "/dumps", api = dump::DumpApi),
+        (path = "/events", api = events::EventsApi),
         (path = "/keys", api = api_key::ApiKeyApi),
         (path = "/metrics", api = metrics::MetricApi),
         (path = "/logs", api = logs::LogsApi),
@@ -110,7 +114,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(web::resource("/version").route(web::get().to(get_version)))
         .service(web::scope("/indexes").configure(indexes::configure))
         .service(web::scope("/multi-search").configure(multi_search::configure))
+        .service(web::scope("/events").configure(events::configure))
         .service(web::scope("/swap-indexes").configure(swap_indexes::configure))
+        .service(web::scope("/aliases").configure(aliases::configure))
         .service(web::scope("/metrics").configure(metrics::configure))
         .service(web::scope("/experimental-features").configure(features::configure))
         .service(web::scope("/network").configure(network::configure));
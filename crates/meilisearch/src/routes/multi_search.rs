@@ -3,8 +3,10 @@ use actix_web::web::{self, Data};
 use actix_web::{HttpRequest, HttpResponse};
 use deserr::actix_web::AwebJson;
 use index_scheduler::IndexScheduler;
+use meilisearch_auth::AuthFilter;
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::keys::actions;
 use serde::Serialize;
 use tracing::debug;
@@ -16,13 +18,13 @@ use crate::error::MeilisearchHttpError;
 use crate::extractors::authentication::policies::ActionPolicy;
 use crate::extractors::authentication::{AuthenticationError, GuardedData};
 use crate::extractors::sequential_extractor::SeqHandler;
-use crate::routes::indexes::search::search_kind;
+use crate::routes::indexes::search::{search_kind, search_reranker};
 use crate::search::{
-    add_search_rules, perform_federated_search, perform_search, FederatedSearch,
-    FederatedSearchResult, RetrieveVectors, SearchQueryWithIndex, SearchResultWithIndex,
-    PROXY_SEARCH_HEADER, PROXY_SEARCH_HEADER_VALUE,
+    add_alias_target_search_rules, add_search_rules, perform_federated_search, perform_search,
+    FederatedSearch, FederatedSearchResult, RetrieveVectors, SearchQueryWithIndex,
+    SearchResultWithIndex, PROXY_SEARCH_HEADER, PROXY_SEARCH_HEADER_VALUE,
 };
-use crate::search_queue::SearchQueue;
+use crate::search_queue::{SearchQueue, SharedPermit};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -174,17 +176,37 @@ pub async fn multi_search_with_post(
             {
                 add_search_rules(&mut federated_query.filter, search_rules);
             }
+            let is_remote =
+                federated_query.federation_options.as_ref().is_some_and(|o| o.remote.is_some());
+            if is_remote {
+                continue;
+            }
+            // Check and apply the rules of the index the alias resolves to, unless the federated
+            // search fans it out to each of its indexes.
+            let alias = match index_scheduler.alias(index_uid) {
+                Ok(alias) => alias,
+                Err(err) => break 'check_authorization Err(err).with_index(query_index),
+            };
+            match alias {
+                Some(alias) if federation.is_some() && alias.indexes.len() > 1 => (),
+                Some(alias) => {
+                    if let Some(target) = alias.target() {
+                        if let Err(err) = add_alias_target_search_rules(
+                            index_scheduler.filters(),
+                            target,
+                            &mut federated_query.filter,
+                        ) {
+                            break 'check_authorization Err(err).with_index(query_index);
+                        }
+                    }
+                }
+                None => (),
+            }
         }
         Ok(())
     };
 
-    auth.map_err(|(mut err, query_index)| {
-        // Add the query index that failed as context for the error message.
-        // We're doing it only here and not directly in the `WithIndex` trait so that the `with_index` function returns a different type
-        // of result and we can benefit from static typing.
-        err.message = format!("Inside `.queries[{query_index}]`: {}", err.message);
-        err
-    })?;
+    auth.map_err(with_query_index_context)?;
 
     let response = match federation {
         Some(federation) => {
@@ -193,11 +215,26 @@ pub async fn multi_search_with_post(
                 .headers()
                 .get(PROXY_SEARCH_HEADER)
                 .is_some_and(|value| value.as_bytes() == PROXY_SEARCH_HEADER_VALUE.as_bytes());
+            let queries = fan_out_aliases(&index_scheduler, index_scheduler.filters(), queries)
+                .map_err(with_query_index_context)?;
+            let local_queries: Vec<_> = queries
+                .iter()
+                .filter(|query| {
+                    query.federation_options.as_ref().is_none_or(|o| o.remote.is_none())
+                })
+                .map(|query| (query.index_uid.to_string(), query.q.clone().unwrap_or_default()))
+                .collect();
             let search_result =
                 perform_federated_search(&index_scheduler, queries, federation, features, is_proxy)
                     .await;
             permit.drop().await;
 
+            if let (Some(local), Ok(search_result)) = (analytics.local(), &search_result) {
+                for (index_uid, q) in &local_queries {
+                    local.record_federated(index_uid, q, search_result);
+                }
+            }
+
             if search_result.is_ok() {
                 multi_aggregate.succeed();
             }
@@ -209,6 +246,9 @@ pub async fn multi_search_with_post(
             // Explicitly expect a `(ResponseError, usize)` for the error type rather than `ResponseError` only,
             // so that `?` doesn't work if it doesn't use `with_index`, ensuring that it is not forgotten in case of code
             // changes.
+            // The permit is released while a query waits on the reranker, in which case we get a new one
+            // before processing the next query.
+            let mut permit = SharedPermit::new(permit);
             let search_results: Result<_, (ResponseError, usize)> = async {
                 let mut search_results = Vec::with_capacity(queries.len());
                 for (query_index, (index_uid, query, federation_options)) in queries
@@ -248,17 +288,35 @@ pub async fn multi_search_with_post(
                         &index,
                     )
                     .with_index(query_index)?;
+                    let reranker = search_reranker(&query, index_scheduler.get_ref())
+                        .with_index(query_index)?;
                     let retrieve_vector = RetrieveVectors::new(query.retrieve_vectors);
 
+                    if permit.is_released() {
+                        permit = SharedPermit::new(
+                            search_queue.try_get_search_permit().await.with_index(query_index)?,
+                        );
+                    }
+                    let search_permit = permit.clone();
+                    let local_analytics = analytics.clone().into_inner();
                     let search_result = tokio::task::spawn_blocking(move || {
-                        perform_search(
+                        let mut search_result = perform_search(
                             index_uid_str.clone(),
                             &index,
                             query,
                             search_kind,
+                            reranker,
+                            &search_permit,
                             retrieve_vector,
                             features,
-                        )
+                        );
+                        if let (Some(local), Ok(search_result)) =
+                            (local_analytics.local(), &mut search_result)
+                        {
+                            search_result.query_id =
+                                Some(local.record(&index_uid_str, search_result));
+                        }
+                        search_result
                     })
                     .await
                     .with_index(query_index)?;
@@ -308,3 +366,44 @@ impl<T, E: Into<ResponseError>> WithIndex for Result<T, E> {
         self.map_err(|err| (err.into(), index))
     }
 }
+
+/// Add the query index that failed as context for the error message.
+///
+/// We're doing it only here and not directly in the `WithIndex` trait so that the `with_index` function returns a different type
+/// of result and we can benefit from static typing.
+fn with_query_index_context((mut err, query_index): (ResponseError, usize)) -> ResponseError {
+    err.message = format!("Inside `.queries[{query_index}]`: {}", err.message);
+    err
+}
+
+/// Replaces the local queries made on an alias pointing to several indexes by one query per index.
+///
+/// The API key must be allowed to search each of these indexes, and the search rules of the tenant token
+/// are applied to each of them, on top of those of the alias.
+/// Aliases pointing to a single index are resolved when opening the index, and their index is checked
+/// along with the other queries.
+fn fan_out_aliases(
+    index_scheduler: &IndexScheduler,
+    filters: &AuthFilter,
+    queries: Vec<SearchQueryWithIndex>,
+) -> Result<Vec<SearchQueryWithIndex>, (ResponseError, usize)> {
+    let mut fanned_out = Vec::with_capacity(queries.len());
+    for (query_index, query) in queries.into_iter().enumerate() {
+        let is_remote = query.federation_options.as_ref().is_some_and(|o| o.remote.is_some());
+        match index_scheduler.alias(&query.index_uid).with_index(query_index)? {
+            Some(alias) if !is_remote && alias.indexes.len() > 1 => {
+                for index in alias.indexes {
+                    let mut query = SearchQueryWithIndex {
+                        index_uid: IndexUid::new_unchecked(&index),
+                        ..query.clone()
+                    };
+                    add_alias_target_search_rules(filters, &index, &mut query.filter)
+                        .with_index(query_index)?;
+                    fanned_out.push(query);
+                }
+            }
+            _ => fanned_out.push(query),
+        }
+    }
+    Ok(fanned_out)
+}
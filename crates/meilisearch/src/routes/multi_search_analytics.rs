@@ -61,11 +61,15 @@ impl MultiSearchAggregator {
             highlight_pre_tag: _,
             highlight_post_tag: _,
             crop_marker: _,
+            crop_mode: _,
+            snippets_per_attribute: _,
+            snippet_length: _,
             matching_strategy: _,
             attributes_to_search_on: _,
             hybrid: _,
             ranking_score_threshold: _,
             locales: _,
+            rerank: _,
         } in &federated_search.queries
         {
             if let Some(federation_options) = federation_options {
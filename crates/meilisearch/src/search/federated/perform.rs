@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 use std::iter::Zip;
 use std::rc::Rc;
 use std::str::FromStr as _;
@@ -13,14 +14,15 @@ use meilisearch_types::error::ResponseError;
 use meilisearch_types::features::{Network, Remote};
 use meilisearch_types::milli::order_by_map::OrderByMap;
 use meilisearch_types::milli::score_details::{ScoreDetails, WeightedScoreValue};
+use meilisearch_types::milli::search::hybrid::reciprocal_rank;
 use meilisearch_types::milli::{self, DocumentId, OrderBy, TimeBudget, DEFAULT_VALUES_PER_FACET};
 use roaring::RoaringBitmap;
 use tokio::task::JoinHandle;
 
 use super::super::ranking_rules::{self, RankingRules};
 use super::super::{
-    compute_facet_distribution_stats, prepare_search, AttributesFormat, ComputedFacets, HitMaker,
-    HitsInfo, RetrieveVectors, SearchHit, SearchKind, SearchQuery, SearchQueryWithIndex,
+    compute_facet_distribution_stats, prepare_search, AttributesFormat, ComputedFacets, Fusion,
+    HitMaker, HitsInfo, RetrieveVectors, SearchHit, SearchKind, SearchQuery, SearchQueryWithIndex,
 };
 use super::proxy::{proxy_search, ProxySearchError, ProxySearchParams};
 use super::types::{
@@ -108,19 +110,23 @@ pub async fn perform_federated_search(
         merge_metadata(&mut results_by_index, &remote_results);
 
     // 3.2. merge hits
-    let merged_hits: Vec<_> = merge_index_global_results(results_by_index, &mut remote_results)
-        .skip(federation.offset)
-        .take(federation.limit)
-        .inspect(|hit| {
-            if let Some(semantic_hit_count) = &mut semantic_hit_count {
-                if hit.to_score().0.any(|score| matches!(&score, WeightedScoreValue::VectorSort(_)))
-                {
-                    *semantic_hit_count += 1;
+    let merged_hits: Vec<_> =
+        merge_index_global_results(results_by_index, &mut remote_results, federation.fusion)
+            .skip(federation.offset)
+            .take(federation.limit)
+            .inspect(|hit| {
+                if let Some(semantic_hit_count) = &mut semantic_hit_count {
+                    if hit
+                        .to_score()
+                        .0
+                        .any(|score| matches!(&score, WeightedScoreValue::VectorSort(_)))
+                    {
+                        *semantic_hit_count += 1;
+                    }
                 }
-            }
-        })
-        .map(|hit| hit.hit())
-        .collect();
+            })
+            .map(|hit| hit.hit())
+            .collect();
 
     // 3.3. merge facets
     let (facet_distribution, facet_stats, facets_by_index) =
@@ -216,9 +222,46 @@ fn merge_index_local_results(
     )
 }
 
+/// Merges the results of the queries to an index with Reciprocal Rank Fusion.
+///
+/// The fused score of a document is the sum of `weight / (k + rank)` over the queries that returned it,
+/// and the document is attributed to the query that contributed the most to this score.
+fn fuse_index_local_results(
+    results_by_query: Vec<SearchResultByQuery<'_>>,
+    k: u32,
+) -> Vec<(SearchResultByQueryIterItem<'_>, f64)> {
+    let mut fused: Vec<(SearchResultByQueryIterItem, f64, f64)> = Vec::new();
+    let mut positions: HashMap<DocumentId, usize> = HashMap::new();
+
+    for result_by_query in results_by_query {
+        for (rank, item) in SearchResultByQueryIter::new(result_by_query).enumerate() {
+            let contribution = reciprocal_rank(k, rank) * *item.weight;
+            match positions.entry(item.docid) {
+                Entry::Occupied(entry) => {
+                    let (best_item, fused_score, best_contribution) = &mut fused[*entry.get()];
+                    *fused_score += contribution;
+                    if contribution > *best_contribution {
+                        *best_item = item;
+                        *best_contribution = contribution;
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(fused.len());
+                    fused.push((item, contribution, contribution));
+                }
+            }
+        }
+    }
+
+    // stable sort: ties are broken using query index, then rank
+    fused.sort_by(|(_, left, _), (_, right, _)| right.total_cmp(left));
+    fused.into_iter().map(|(item, fused_score, _)| (item, fused_score)).collect()
+}
+
 fn merge_index_global_results(
     results_by_index: Vec<SearchResultByIndex>,
     remote_results: &mut [FederatedSearchResult],
+    fusion: Fusion,
 ) -> impl Iterator<Item = MergedSearchHit> + '_ {
     itertools::kmerge_by(
         // local results
@@ -233,12 +276,17 @@ fn merge_index_global_results(
             let (left_it, left_weighted_global_score, left_query_index) = left.to_score();
             let (right_it, right_weighted_global_score, right_query_index) = right.to_score();
 
-            match weighted_scores::compare(
-                left_it,
-                left_weighted_global_score,
-                right_it,
-                right_weighted_global_score,
-            ) {
+            let ordering = match fusion {
+                Fusion::Score => weighted_scores::compare(
+                    left_it,
+                    left_weighted_global_score,
+                    right_it,
+                    right_weighted_global_score,
+                ),
+                // the global score of a hit is its fused score
+                Fusion::Rrf => left_weighted_global_score.total_cmp(&right_weighted_global_score),
+            };
+            match ordering {
                 // the biggest score goes first
                 Ordering::Greater => true,
                 // break ties using query index
@@ -320,8 +368,10 @@ impl MergedSearchHit {
                     search_hit_by_index.score.iter(),
                     *search_hit_by_index.weight,
                 )),
-                ScoreDetails::global_score(search_hit_by_index.score.iter())
-                    * *search_hit_by_index.weight,
+                search_hit_by_index.fused_score.unwrap_or_else(|| {
+                    ScoreDetails::global_score(search_hit_by_index.score.iter())
+                        * *search_hit_by_index.weight
+                }),
                 search_hit_by_index.query_index,
             ),
             MergedSearchHit::Remote { hit: _, score, global_weighted_score, query_index } => {
@@ -366,6 +416,8 @@ struct SearchHitByIndex {
     score: Vec<ScoreDetails>,
     weight: Weight,
     query_index: usize,
+    /// The Reciprocal Rank Fusion score of the hit, when using this fusion.
+    fused_score: Option<f64>,
 }
 
 struct SearchResultByIndex {
@@ -474,6 +526,10 @@ impl PartitionedQueries {
             .into());
         }
 
+        if federated_query.has_rerank() {
+            return Err(MeilisearchHttpError::RerankInFederatedQuery(query_index).into());
+        }
+
         let (index_uid, query, federation_options) = federated_query.into_index_query_federation();
 
         let federation_options = federation_options.unwrap_or_default();
@@ -822,9 +878,12 @@ impl SearchByIndex {
                     attributes_to_crop: query.attributes_to_crop,
                     crop_length: query.crop_length,
                     crop_marker: query.crop_marker,
+                    crop_mode: query.crop_mode,
                     highlight_pre_tag: query.highlight_pre_tag,
                     highlight_post_tag: query.highlight_post_tag,
                     show_matches_position: query.show_matches_position,
+                    snippets_per_attribute: query.snippets_per_attribute.map(|count| *count),
+                    snippet_length: query.snippet_length.map(|length| *length),
                     sort: query.sort,
                     show_ranking_score: query.show_ranking_score,
                     show_ranking_score_details: query.show_ranking_score_details,
@@ -868,52 +927,64 @@ impl SearchByIndex {
                 return Err(error);
             }
         }
-        let mut documents_seen = RoaringBitmap::new();
-        let merged_result: Result<Vec<_>, ResponseError> =
-            merge_index_local_results(results_by_query)
-                // skip documents we've already seen & mark that we saw the current document
-                .filter(|SearchResultByQueryIterItem { docid, .. }| documents_seen.insert(*docid))
-                .take(params.required_hit_count)
-                // 2.3 make hits
-                .map(
-                    |SearchResultByQueryIterItem {
-                         docid,
-                         score,
-                         weight,
-                         hit_maker,
-                         query_index,
-                     }| {
-                        let mut hit = hit_maker.make_hit(docid, &score)?;
-                        let weighted_score = ScoreDetails::global_score(score.iter()) * (*weight);
-
-                        let mut _federation = serde_json::json!(
-                            {
-                                INDEX_UID: index_uid,
-                                QUERIES_POSITION: query_index,
-                                WEIGHTED_RANKING_SCORE: weighted_score,
-                            }
-                        );
-                        if params.has_remote && !params.is_proxy {
-                            _federation.as_object_mut().unwrap().insert(
-                                FEDERATION_REMOTE.to_string(),
-                                params.network.local.clone().into(),
-                            );
-                        }
-                        if params.is_proxy {
-                            _federation.as_object_mut().unwrap().insert(
-                                WEIGHTED_SCORE_VALUES.to_string(),
-                                serde_json::json!(ScoreDetails::weighted_score_values(
-                                    score.iter(),
-                                    *weight
-                                )
-                                .collect_vec()),
-                            );
-                        }
-                        hit.document.insert(FEDERATION_HIT.to_string(), _federation);
-                        Ok(SearchHitByIndex { hit, score, weight, query_index })
-                    },
+        let local_results = match self.federation.fusion {
+            Fusion::Score => {
+                let mut documents_seen = RoaringBitmap::new();
+                either::Left(
+                    merge_index_local_results(results_by_query)
+                        // skip documents we've already seen & mark that we saw the current document
+                        .filter(move |SearchResultByQueryIterItem { docid, .. }| {
+                            documents_seen.insert(*docid)
+                        })
+                        .map(|item| (item, None)),
                 )
-                .collect();
+            }
+            Fusion::Rrf => either::Right(
+                fuse_index_local_results(results_by_query, self.federation.rrf_k)
+                    .into_iter()
+                    .map(|(item, fused_score)| (item, Some(fused_score))),
+            ),
+        };
+        let merged_result: Result<Vec<_>, ResponseError> = local_results
+            .take(params.required_hit_count)
+            // 2.3 make hits
+            .map(
+                |(
+                    SearchResultByQueryIterItem { docid, score, weight, hit_maker, query_index },
+                    fused_score,
+                )| {
+                    let mut hit = hit_maker.make_hit(docid, &score)?;
+                    let weighted_score = fused_score
+                        .unwrap_or_else(|| ScoreDetails::global_score(score.iter()) * (*weight));
+
+                    let mut _federation = serde_json::json!(
+                        {
+                            INDEX_UID: index_uid,
+                            QUERIES_POSITION: query_index,
+                            WEIGHTED_RANKING_SCORE: weighted_score,
+                        }
+                    );
+                    if params.has_remote && !params.is_proxy {
+                        _federation.as_object_mut().unwrap().insert(
+                            FEDERATION_REMOTE.to_string(),
+                            params.network.local.clone().into(),
+                        );
+                    }
+                    if params.is_proxy {
+                        _federation.as_object_mut().unwrap().insert(
+                            WEIGHTED_SCORE_VALUES.to_string(),
+                            serde_json::json!(ScoreDetails::weighted_score_values(
+                                score.iter(),
+                                *weight
+                            )
+                            .collect_vec()),
+                        );
+                    }
+                    hit.document.insert(FEDERATION_HIT.to_string(), _federation);
+                    Ok(SearchHitByIndex { hit, score, weight, query_index, fused_score })
+                },
+            )
+            .collect();
         let merged_result = merged_result?;
         let estimated_total_hits = candidates.len() as usize;
         let facets = facets_by_index
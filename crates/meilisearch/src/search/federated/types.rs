@@ -6,9 +6,9 @@ use std::vec::Vec;
 use indexmap::IndexMap;
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::{
-    InvalidMultiSearchFacetsByIndex, InvalidMultiSearchMaxValuesPerFacet,
+    InvalidMultiSearchFacetsByIndex, InvalidMultiSearchFusion, InvalidMultiSearchMaxValuesPerFacet,
     InvalidMultiSearchMergeFacets, InvalidMultiSearchQueryPosition, InvalidMultiSearchRemote,
-    InvalidMultiSearchWeight, InvalidSearchLimit, InvalidSearchOffset,
+    InvalidMultiSearchRrfK, InvalidMultiSearchWeight, InvalidSearchLimit, InvalidSearchOffset,
 };
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::index_uid::IndexUid;
@@ -17,7 +17,9 @@ use meilisearch_types::milli::OrderBy;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use super::super::{ComputedFacets, FacetStats, HitsInfo, SearchHit, SearchQueryWithIndex};
+use super::super::{
+    ComputedFacets, FacetStats, Fusion, HitsInfo, SearchHit, SearchQueryWithIndex, DEFAULT_RRF_K,
+};
 
 pub const DEFAULT_FEDERATED_WEIGHT: f64 = 1.0;
 
@@ -87,6 +89,12 @@ pub struct Federation {
     pub facets_by_index: BTreeMap<IndexUid, Option<Vec<String>>>,
     #[deserr(default, error = DeserrJsonError<InvalidMultiSearchMergeFacets>)]
     pub merge_facets: Option<MergeFacets>,
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchFusion>)]
+    #[schema(default)]
+    pub fusion: Fusion,
+    #[deserr(default = DEFAULT_RRF_K(), error = DeserrJsonError<InvalidMultiSearchRrfK>)]
+    #[schema(default = DEFAULT_RRF_K)]
+    pub rrf_k: u32,
 }
 
 #[derive(Copy, Clone, Debug, deserr::Deserr, Serialize, Default, ToSchema)]
@@ -7,17 +7,20 @@ use std::time::{Duration, Instant};
 
 use deserr::Deserr;
 use either::Either;
-use index_scheduler::RoFeatures;
+use index_scheduler::{IndexScheduler, RoFeatures};
 use indexmap::IndexMap;
-use meilisearch_auth::IndexSearchRules;
+use meilisearch_auth::{AuthFilter, IndexSearchRules};
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::heed::RoTxn;
+use meilisearch_types::index_alias::IndexAlias;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::locales::Locale;
 use meilisearch_types::milli::score_details::{ScoreDetails, ScoringStrategy};
+use meilisearch_types::milli::vector::chunking::{ChunkMatcher, MatchedChunk};
 use meilisearch_types::milli::vector::parsed_vectors::ExplicitVectors;
+use meilisearch_types::milli::vector::rerank::Reranker;
 use meilisearch_types::milli::vector::Embedder;
 use meilisearch_types::milli::{
     FacetValueHit, InternalError, OrderBy, PatternMatch, SearchForFacetValues, TimeBudget,
@@ -27,7 +30,8 @@ use meilisearch_types::{milli, Document};
 use milli::tokenizer::{Language, TokenizerBuilder};
 use milli::{
     AscDesc, FieldId, FieldsIdsMap, Filter, FormatOptions, Index, LocalizedAttributesRule,
-    MatchBounds, MatcherBuilder, SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    MatchBounds, MatcherBuilder, Snippet, SortError, TermsMatchingStrategy,
+    DEFAULT_VALUES_PER_FACET,
 };
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -35,8 +39,11 @@ use serde_json::{json, Value};
 #[cfg(test)]
 mod mod_test;
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::AuthenticationError;
+use crate::search_queue::SharedPermit;
 
 mod federated;
 pub use federated::{
@@ -47,6 +54,7 @@ pub use federated::{
 mod ranking_rules;
 
 type MatchesPosition = BTreeMap<String, Vec<MatchBounds>>;
+type Snippets = BTreeMap<String, Vec<Snippet>>;
 
 pub const DEFAULT_SEARCH_OFFSET: fn() -> usize = || 0;
 pub const DEFAULT_SEARCH_LIMIT: fn() -> usize = || 20;
@@ -55,6 +63,14 @@ pub const DEFAULT_CROP_MARKER: fn() -> String = || "…".to_string();
 pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
+pub const DEFAULT_RERANK_TOP_K: fn() -> RerankTopK = || RerankTopK(20);
+/// The maximum number of hits that can be sent to the reranker, as it is called on the search path.
+pub const MAX_RERANK_TOP_K: usize = 100;
+/// The maximum number of snippets returned for each attribute of a hit.
+pub const MAX_SNIPPETS_PER_ATTRIBUTE: usize = 20;
+/// The maximum number of words of a snippet.
+pub const MAX_SNIPPET_LENGTH: usize = 1000;
+pub const DEFAULT_RRF_K: fn() -> u32 = || 60;
 
 #[derive(Clone, Default, PartialEq, Deserr, ToSchema)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
@@ -109,6 +125,14 @@ pub struct SearchQuery {
     #[deserr(error = DeserrJsonError<InvalidSearchCropMarker>, default = DEFAULT_CROP_MARKER())]
     #[schema(default = DEFAULT_CROP_MARKER)]
     pub crop_marker: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCropMode>)]
+    pub crop_mode: CropMode,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSnippetsPerAttribute>)]
+    #[schema(value_type = Option<usize>)]
+    pub snippets_per_attribute: Option<SnippetsPerAttribute>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSnippetLength>)]
+    #[schema(value_type = Option<usize>)]
+    pub snippet_length: Option<SnippetLength>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>)]
     pub matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>)]
@@ -117,6 +141,8 @@ pub struct SearchQuery {
     pub ranking_score_threshold: Option<RankingScoreThreshold>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchLocales>)]
     pub locales: Option<Vec<Locale>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>)]
+    pub rerank: Option<RerankQuery>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserr, ToSchema, Serialize)]
@@ -182,10 +208,14 @@ impl fmt::Debug for SearchQuery {
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
+            crop_mode,
+            snippets_per_attribute,
+            snippet_length,
             matching_strategy,
             attributes_to_search_on,
             ranking_score_threshold,
             locales,
+            rerank,
         } = self;
 
         let mut debug = f.debug_struct("SearchQuery");
@@ -263,6 +293,15 @@ impl fmt::Debug for SearchQuery {
         debug.field("highlight_pre_tag", &highlight_pre_tag);
         debug.field("highlight_post_tag", &highlight_post_tag);
         debug.field("crop_marker", &crop_marker);
+        if *crop_mode != CropMode::default() {
+            debug.field("crop_mode", &crop_mode);
+        }
+        if let Some(snippets_per_attribute) = snippets_per_attribute {
+            debug.field("snippets_per_attribute", &snippets_per_attribute);
+        }
+        if let Some(snippet_length) = snippet_length {
+            debug.field("snippet_length", &snippet_length);
+        }
         if let Some(ranking_score_threshold) = ranking_score_threshold {
             debug.field("ranking_score_threshold", &ranking_score_threshold);
         }
@@ -271,6 +310,10 @@ impl fmt::Debug for SearchQuery {
             debug.field("locales", &locales);
         }
 
+        if let Some(rerank) = rerank {
+            debug.field("rerank", &rerank);
+        }
+
         debug.finish()
     }
 }
@@ -285,16 +328,95 @@ pub struct HybridQuery {
     pub semantic_ratio: SemanticRatio,
     #[deserr(error = DeserrJsonError<InvalidSearchEmbedder>)]
     pub embedder: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFusion>)]
+    #[schema(default)]
+    #[serde(default)]
+    pub fusion: Fusion,
+    #[deserr(default = DEFAULT_RRF_K(), error = DeserrJsonError<InvalidSearchRrfK>)]
+    #[schema(default = DEFAULT_RRF_K)]
+    #[serde(default = "DEFAULT_RRF_K")]
+    pub rrf_k: u32,
+    /// Compare the query with the embeddings of all the candidates instead of using the approximate nearest neighbors search.
+    ///
+    /// Slower on large indexes, but always returns the exact nearest neighbors.
+    /// The search fails if more than 100,000 documents are candidates.
+    /// Searches with more than 100,000 candidates left by the filter use the approximate search regardless.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchExhaustive>)]
+    #[schema(default)]
+    #[serde(default)]
+    pub exhaustive: bool,
+}
+
+impl HybridQuery {
+    pub fn milli_fusion(&self) -> milli::search::hybrid::Fusion {
+        self.fusion.to_milli(self.rrf_k)
+    }
+}
+
+/// How the results of several lists of hits are merged together.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserr, ToSchema, Serialize)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum Fusion {
+    /// Compare the ranking scores of the hits
+    #[default]
+    Score,
+    /// Reciprocal Rank Fusion: compare the hits by their ranks in each list
+    Rrf,
+}
+
+impl Fusion {
+    pub fn to_milli(self, rrf_k: u32) -> milli::search::hybrid::Fusion {
+        match self {
+            Fusion::Score => milli::search::hybrid::Fusion::WeightedScore,
+            Fusion::Rrf => milli::search::hybrid::Fusion::ReciprocalRank { k: rrf_k },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserr, ToSchema, Serialize)]
+#[deserr(error = DeserrJsonError<InvalidSearchRerank>, rename_all = camelCase, deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RerankQuery {
+    /// The number of first hits sent to the reranker.
+    #[deserr(default = DEFAULT_RERANK_TOP_K(), error = DeserrJsonError<InvalidSearchRerank>)]
+    #[schema(value_type = usize, default = 20)]
+    #[serde(default = "DEFAULT_RERANK_TOP_K")]
+    pub top_k: RerankTopK,
+    /// The attributes sent to the reranker, defaults to the searchable attributes.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>)]
+    #[serde(default)]
+    pub attributes: Option<Vec<String>>,
 }
 
 #[derive(Clone)]
 pub enum SearchKind {
     KeywordOnly,
-    SemanticOnly { embedder_name: String, embedder: Arc<Embedder>, quantized: bool },
-    Hybrid { embedder_name: String, embedder: Arc<Embedder>, quantized: bool, semantic_ratio: f32 },
+    SemanticOnly {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        quantized: bool,
+    },
+    Hybrid {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        quantized: bool,
+        semantic_ratio: f32,
+        fusion: milli::search::hybrid::Fusion,
+    },
 }
 
 impl SearchKind {
+    fn semantic_embedder(&self) -> Option<(&str, &Arc<Embedder>)> {
+        match self {
+            SearchKind::KeywordOnly => None,
+            SearchKind::SemanticOnly { embedder_name, embedder, .. }
+            | SearchKind::Hybrid { embedder_name, embedder, .. } => {
+                Some((embedder_name.as_str(), embedder))
+            }
+        }
+    }
+
     pub(crate) fn semantic(
         index_scheduler: &index_scheduler::IndexScheduler,
         index_uid: String,
@@ -319,6 +441,7 @@ impl SearchKind {
         index: &Index,
         embedder_name: &str,
         semantic_ratio: f32,
+        fusion: milli::search::hybrid::Fusion,
         vector_len: Option<usize>,
     ) -> Result<Self, ResponseError> {
         let (embedder_name, embedder, quantized) = Self::embedder(
@@ -329,7 +452,7 @@ impl SearchKind {
             vector_len,
             Route::Search,
         )?;
-        Ok(Self::Hybrid { embedder_name, embedder, quantized, semantic_ratio })
+        Ok(Self::Hybrid { embedder_name, embedder, quantized, semantic_ratio, fusion })
     }
 
     pub(crate) fn embedder(
@@ -341,7 +464,8 @@ impl SearchKind {
         route: Route,
     ) -> Result<(String, Arc<Embedder>, bool), ResponseError> {
         let rtxn = index.read_txn()?;
-        let embedder_configs = index.embedding_configs(&rtxn)?;
+        let mut embedder_configs = index.embedding_configs(&rtxn)?;
+        embedder_configs.extend(index.sparse_embedding_configs(&rtxn)?);
         let embedders = index_scheduler.embedders(index_uid, embedder_configs)?;
 
         let (embedder, _, quantized) = embedders
@@ -357,6 +481,12 @@ impl SearchKind {
             .map_err(milli::Error::from)?;
 
         if let Some(vector_len) = vector_len {
+            if matches!(embedder.as_ref(), Embedder::Sparse(_)) {
+                return Err(milli::Error::UserError(
+                    milli::UserError::InvalidVectorForSparseEmbedder(embedder_name.to_owned()),
+                )
+                .into());
+            }
             if vector_len != embedder.dimensions() {
                 return Err(meilisearch_types::milli::Error::UserError(
                     meilisearch_types::milli::UserError::InvalidVectorDimensions {
@@ -404,6 +534,78 @@ impl std::ops::Deref for SemanticRatio {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize)]
+#[deserr(try_from(usize) = TryFrom::try_from -> InvalidSearchRerank)]
+pub struct RerankTopK(usize);
+
+impl std::convert::TryFrom<usize> for RerankTopK {
+    type Error = InvalidSearchRerank;
+
+    fn try_from(top_k: usize) -> Result<Self, Self::Error> {
+        if top_k == 0 || top_k > MAX_RERANK_TOP_K {
+            Err(InvalidSearchRerank)
+        } else {
+            Ok(RerankTopK(top_k))
+        }
+    }
+}
+
+impl std::ops::Deref for RerankTopK {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize)]
+#[deserr(try_from(usize) = TryFrom::try_from -> InvalidSearchSnippetsPerAttribute)]
+pub struct SnippetsPerAttribute(usize);
+
+impl std::convert::TryFrom<usize> for SnippetsPerAttribute {
+    type Error = InvalidSearchSnippetsPerAttribute;
+
+    fn try_from(count: usize) -> Result<Self, Self::Error> {
+        if count > MAX_SNIPPETS_PER_ATTRIBUTE {
+            Err(InvalidSearchSnippetsPerAttribute)
+        } else {
+            Ok(SnippetsPerAttribute(count))
+        }
+    }
+}
+
+impl std::ops::Deref for SnippetsPerAttribute {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize)]
+#[deserr(try_from(usize) = TryFrom::try_from -> InvalidSearchSnippetLength)]
+pub struct SnippetLength(usize);
+
+impl std::convert::TryFrom<usize> for SnippetLength {
+    type Error = InvalidSearchSnippetLength;
+
+    fn try_from(length: usize) -> Result<Self, Self::Error> {
+        if length > MAX_SNIPPET_LENGTH {
+            Err(InvalidSearchSnippetLength)
+        } else {
+            Ok(SnippetLength(length))
+        }
+    }
+}
+
+impl std::ops::Deref for SnippetLength {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 impl SearchQuery {
     pub fn is_finite_pagination(&self) -> bool {
         self.page.or(self.hits_per_page).is_some()
@@ -465,6 +667,14 @@ pub struct SearchQueryWithIndex {
     pub highlight_post_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchCropMarker>, default = DEFAULT_CROP_MARKER())]
     pub crop_marker: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCropMode>, default)]
+    pub crop_mode: CropMode,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSnippetsPerAttribute>)]
+    #[schema(value_type = Option<usize>)]
+    pub snippets_per_attribute: Option<SnippetsPerAttribute>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSnippetLength>)]
+    #[schema(value_type = Option<usize>)]
+    pub snippet_length: Option<SnippetLength>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>, default)]
     pub matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
@@ -473,6 +683,8 @@ pub struct SearchQueryWithIndex {
     pub ranking_score_threshold: Option<RankingScoreThreshold>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchLocales>, default)]
     pub locales: Option<Vec<Locale>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRerank>, default)]
+    pub rerank: Option<RerankQuery>,
 
     #[deserr(default)]
     pub federation_options: Option<FederationOptions>,
@@ -497,6 +709,10 @@ impl SearchQueryWithIndex {
         self.facets.as_deref().filter(|v| !v.is_empty())
     }
 
+    pub fn has_rerank(&self) -> bool {
+        self.rerank.is_some()
+    }
+
     pub fn from_index_query_federation(
         index_uid: IndexUid,
         query: SearchQuery,
@@ -525,10 +741,14 @@ impl SearchQueryWithIndex {
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
+            crop_mode,
+            snippets_per_attribute,
+            snippet_length,
             matching_strategy,
             attributes_to_search_on,
             ranking_score_threshold,
             locales,
+            rerank,
         } = query;
 
         SearchQueryWithIndex {
@@ -555,10 +775,14 @@ impl SearchQueryWithIndex {
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
+            crop_mode,
+            snippets_per_attribute,
+            snippet_length,
             matching_strategy,
             attributes_to_search_on,
             ranking_score_threshold,
             locales,
+            rerank,
             federation_options,
         }
     }
@@ -588,11 +812,15 @@ impl SearchQueryWithIndex {
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
+            crop_mode,
+            snippets_per_attribute,
+            snippet_length,
             matching_strategy,
             attributes_to_search_on,
             hybrid,
             ranking_score_threshold,
             locales,
+            rerank,
         } = self;
         (
             index_uid,
@@ -618,11 +846,15 @@ impl SearchQueryWithIndex {
                 highlight_pre_tag,
                 highlight_post_tag,
                 crop_marker,
+                crop_mode,
+                snippets_per_attribute,
+                snippet_length,
                 matching_strategy,
                 attributes_to_search_on,
                 hybrid,
                 ranking_score_threshold,
                 locales,
+                rerank,
                 // do not use ..Default::default() here,
                 // rather add any missing field from `SearchQuery` to `SearchQueryWithIndex`
             },
@@ -634,9 +866,24 @@ impl SearchQueryWithIndex {
 #[derive(Debug, Clone, PartialEq, Deserr, ToSchema)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
 pub struct SimilarQuery {
-    #[deserr(error = DeserrJsonError<InvalidSimilarId>)]
-    #[schema(value_type = String)]
-    pub id: serde_json::Value,
+    /// The document the returned documents should be similar to.
+    ///
+    /// Optional when `positive` is provided.
+    #[deserr(default, error = DeserrJsonError<InvalidSimilarId>)]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<serde_json::Value>,
+    /// More examples of what the returned documents should be similar to.
+    ///
+    /// Each example is either a document id, or an array of numbers used as a raw embedding.
+    #[deserr(default, error = DeserrJsonError<InvalidSimilarPositive>)]
+    #[schema(value_type = Vec<serde_json::Value>)]
+    pub positive: Vec<serde_json::Value>,
+    /// Examples of what the returned documents should not be similar to.
+    ///
+    /// Each example is either a document id, or an array of numbers used as a raw embedding.
+    #[deserr(default, error = DeserrJsonError<InvalidSimilarNegative>)]
+    #[schema(value_type = Vec<serde_json::Value>)]
+    pub negative: Vec<serde_json::Value>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSimilarOffset>)]
     pub offset: usize,
     #[deserr(default = DEFAULT_SEARCH_LIMIT(), error = DeserrJsonError<InvalidSimilarLimit>)]
@@ -717,6 +964,27 @@ impl From<MatchingStrategy> for TermsMatchingStrategy {
     }
 }
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserr, ToSchema, Serialize)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum CropMode {
+    /// Crop by counting words around the matches
+    #[default]
+    Word,
+    /// Crop on the boundaries of the sentences containing the matches,
+    /// without exceeding the crop length
+    Sentence,
+}
+
+impl From<CropMode> for milli::CropMode {
+    fn from(other: CropMode) -> Self {
+        match other {
+            CropMode::Word => Self::Word,
+            CropMode::Sentence => Self::Sentence,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserr)]
 #[deserr(rename_all = camelCase)]
 pub enum FacetValuesSort {
@@ -747,10 +1015,17 @@ pub struct SearchHit {
     pub formatted: Document,
     #[serde(default, rename = "_matchesPosition", skip_serializing_if = "Option::is_none")]
     pub matches_position: Option<MatchesPosition>,
+    #[serde(default, rename = "_snippets", skip_serializing_if = "Option::is_none")]
+    pub snippets: Option<Snippets>,
     #[serde(default, rename = "_rankingScore", skip_serializing_if = "Option::is_none")]
     pub ranking_score: Option<f64>,
+    /// The ranking score of the hit before it was reordered by the reranker.
+    #[serde(default, rename = "_originalRankingScore", skip_serializing_if = "Option::is_none")]
+    pub original_ranking_score: Option<f64>,
     #[serde(default, rename = "_rankingScoreDetails", skip_serializing_if = "Option::is_none")]
     pub ranking_score_details: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default, rename = "_matchedChunk", skip_serializing_if = "Option::is_none")]
+    pub matched_chunk: Option<MatchedChunk>,
 }
 
 #[derive(Serialize, Clone, PartialEq, ToSchema)]
@@ -770,8 +1045,16 @@ pub struct SearchResult {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_hit_count: Option<u32>,
+    /// Identifies the search in the click and conversion events sent to the `/events` route.
+    /// Only returned when the local search analytics are enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub query_id: Option<Uuid>,
 
     // These fields are only used for analytics purposes
+    /// The internal ids of the hits, in order, used to check the events sent about them.
+    #[serde(skip)]
+    pub documents_ids: Vec<milli::DocumentId>,
     #[serde(skip)]
     pub degraded: bool,
     #[serde(skip)]
@@ -788,6 +1071,8 @@ impl fmt::Debug for SearchResult {
             facet_distribution,
             facet_stats,
             semantic_hit_count,
+            query_id,
+            documents_ids: _,
             degraded,
             used_negative_operator,
         } = self;
@@ -813,6 +1098,9 @@ impl fmt::Debug for SearchResult {
         if let Some(semantic_hit_count) = semantic_hit_count {
             debug.field("semantic_hit_count", &semantic_hit_count);
         }
+        if let Some(query_id) = query_id {
+            debug.field("query_id", &query_id);
+        }
 
         debug.finish()
     }
@@ -822,7 +1110,8 @@ impl fmt::Debug for SearchResult {
 #[serde(rename_all = "camelCase")]
 pub struct SimilarResult {
     pub hits: Vec<SearchHit>,
-    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub processing_time_ms: u128,
     #[serde(flatten)]
     pub hits_info: HitsInfo,
@@ -882,6 +1171,42 @@ pub fn add_search_rules(filter: &mut Option<Value>, rules: IndexSearchRules) {
     }
 }
 
+/// Applies the tenant token search rules of `index_uid` to the filter of a single-index search.
+///
+/// When `index_uid` is an alias, the search runs on the index it resolves to: the API key must be
+/// allowed to search this index too, and its search rules are applied on top of those of the alias.
+pub fn add_index_search_rules(
+    index_scheduler: &IndexScheduler,
+    filters: &AuthFilter,
+    index_uid: &str,
+    filter: &mut Option<Value>,
+) -> Result<(), ResponseError> {
+    if let Some(search_rules) = filters.get_index_search_rules(index_uid) {
+        add_search_rules(filter, search_rules);
+    }
+    let alias = index_scheduler.alias(index_uid)?;
+    if let Some(target) = alias.as_ref().and_then(IndexAlias::target) {
+        add_alias_target_search_rules(filters, target, filter)?;
+    }
+    Ok(())
+}
+
+/// Checks that the API key can search an index an alias points to,
+/// and applies the tenant token search rules of this index.
+pub fn add_alias_target_search_rules(
+    filters: &AuthFilter,
+    index_uid: &str,
+    filter: &mut Option<Value>,
+) -> Result<(), AuthenticationError> {
+    if !filters.is_index_authorized(index_uid) {
+        return Err(AuthenticationError::InvalidToken);
+    }
+    if let Some(search_rules) = filters.get_index_search_rules(index_uid) {
+        add_search_rules(filter, search_rules);
+    }
+    Ok(())
+}
+
 fn prepare_search<'t>(
     index: &'t Index,
     rtxn: &'t RoTxn,
@@ -906,25 +1231,24 @@ fn prepare_search<'t>(
                 search.query(q);
             }
         }
+        SearchKind::SemanticOnly { embedder_name, embedder, quantized }
+            if matches!(embedder.as_ref(), Embedder::Sparse(_)) =>
+        {
+            // sparse embedders embed the query during the search
+            if let Some(q) = &query.q {
+                search.query(q);
+            }
+            search.semantic(embedder_name.clone(), embedder.clone(), *quantized, None);
+        }
         SearchKind::SemanticOnly { embedder_name, embedder, quantized } => {
             let vector = match query.vector.clone() {
                 Some(vector) => vector,
-                None => {
-                    let span = tracing::trace_span!(target: "search::vector", "embed_one");
-                    let _entered = span.enter();
-
-                    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
-
-                    embedder
-                        .embed_search(query.q.as_ref().unwrap(), Some(deadline))
-                        .map_err(milli::vector::Error::from)
-                        .map_err(milli::Error::from)?
-                }
+                None => embed_query(embedder, query.q.as_ref().unwrap())?,
             };
 
             search.semantic(embedder_name.clone(), embedder.clone(), *quantized, Some(vector));
         }
-        SearchKind::Hybrid { embedder_name, embedder, quantized, semantic_ratio: _ } => {
+        SearchKind::Hybrid { embedder_name, embedder, quantized, semantic_ratio: _, fusion: _ } => {
             if let Some(q) = &query.q {
                 search.query(q);
             }
@@ -952,6 +1276,9 @@ fn prepare_search<'t>(
         .unwrap_or(DEFAULT_PAGINATION_MAX_TOTAL_HITS);
 
     search.exhaustive_number_hits(is_finite_pagination);
+    if let Some(hybrid) = &query.hybrid {
+        search.exhaustive_vector_search(hybrid.exhaustive);
+    }
     search.scoring_strategy(
         if query.show_ranking_score
             || query.show_ranking_score_details
@@ -1009,8 +1336,10 @@ fn prepare_search<'t>(
 pub fn perform_search(
     index_uid: String,
     index: &Index,
-    query: SearchQuery,
+    mut query: SearchQuery,
     search_kind: SearchKind,
+    reranker: Option<Arc<Reranker>>,
+    search_permit: &SharedPermit,
     retrieve_vectors: RetrieveVectors,
     features: RoFeatures,
 ) -> Result<SearchResult, ResponseError> {
@@ -1021,9 +1350,31 @@ pub fn perform_search(
         None => TimeBudget::default(),
     };
 
-    let (search, is_finite_pagination, max_total_hits, offset) =
+    // embedders that chunk documents return the chunk of each hit that is the most similar to the query
+    let chunk_matcher = match search_kind.semantic_embedder() {
+        Some((embedder_name, embedder)) => ChunkMatcher::new(index, &rtxn, embedder_name)?
+            .map(|chunk_matcher| (chunk_matcher, embedder.clone())),
+        None => None,
+    };
+    // a semantic search always embeds the query, embed it once so that it is used both by the search
+    // and to match the chunks. Hybrid searches may not need it, so they embed it after the search.
+    if let (Some((_, embedder)), SearchKind::SemanticOnly { .. }, None, Some(q)) =
+        (&chunk_matcher, &search_kind, &query.vector, &query.q)
+    {
+        query.vector = Some(embed_query(embedder, q)?);
+    }
+
+    let (mut search, is_finite_pagination, max_total_hits, offset) =
         prepare_search(index, &rtxn, &query, &search_kind, time_budget, features)?;
 
+    if let (Some(reranker), Some(rerank)) = (reranker, &query.rerank) {
+        // no need to hold the search permit while waiting on the reranker
+        let search_permit = search_permit.clone();
+        search
+            .rerank(reranker, *rerank.top_k, rerank.attributes.clone())
+            .before_rerank_request(move || search_permit.release());
+    }
+
     let (
         milli::SearchResult {
             documents_ids,
@@ -1055,9 +1406,12 @@ pub fn perform_search(
         highlight_pre_tag,
         highlight_post_tag,
         crop_marker,
+        crop_mode,
+        snippets_per_attribute,
+        snippet_length,
         locales,
+        vector,
         // already used in prepare_search
-        vector: _,
         hybrid: _,
         offset: _,
         ranking_score_threshold: _,
@@ -1065,6 +1419,7 @@ pub fn perform_search(
         attributes_to_search_on: _,
         filter: _,
         distinct: _,
+        rerank: _,
     } = query;
 
     let format = AttributesFormat {
@@ -1074,16 +1429,19 @@ pub fn perform_search(
         attributes_to_crop,
         crop_length,
         crop_marker,
+        crop_mode,
         highlight_pre_tag,
         highlight_post_tag,
         show_matches_position,
+        snippets_per_attribute: snippets_per_attribute.map(|count| *count),
+        snippet_length: snippet_length.map(|length| *length),
         sort,
         show_ranking_score,
         show_ranking_score_details,
         locales: locales.map(|l| l.iter().copied().map(Into::into).collect()),
     };
 
-    let documents = make_hits(
+    let mut documents = make_hits(
         index,
         &rtxn,
         format,
@@ -1091,6 +1449,22 @@ pub fn perform_search(
         documents_ids.iter().copied().zip(document_scores.iter()),
     )?;
 
+    if let Some((chunk_matcher, embedder)) = chunk_matcher.filter(|_| !documents.is_empty()) {
+        let vector = match (vector, &q) {
+            (Some(vector), _) => Some(vector),
+            // same as for the hybrid search, failing to embed the query doesn't fail the search
+            (None, Some(q)) => embed_query(&embedder, q)
+                .inspect_err(|error| tracing::error!(error=%error, "Embedding failed"))
+                .ok(),
+            (None, None) => None,
+        };
+        if let Some(vector) = vector {
+            for (hit, docid) in documents.iter_mut().zip(documents_ids.iter().copied()) {
+                hit.matched_chunk = chunk_matcher.matched_chunk(index, &rtxn, docid, &vector)?;
+            }
+        }
+    }
+
     let number_of_hits = min(candidates.len() as usize, max_total_hits);
     let hits_info = if is_finite_pagination {
         let hits_per_page = hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
@@ -1127,6 +1501,8 @@ pub fn perform_search(
         degraded,
         used_negative_operator,
         semantic_hit_count,
+        query_id: None,
+        documents_ids,
     };
     Ok(result)
 }
@@ -1188,6 +1564,19 @@ fn compute_facet_distribution_stats<S: AsRef<str>>(
     Ok(ComputedFacets { distribution, stats })
 }
 
+/// Embeds the query of a semantic search.
+fn embed_query(embedder: &Embedder, q: &str) -> milli::Result<Vec<f32>> {
+    let span = tracing::trace_span!(target: "search::vector", "embed_one");
+    let _entered = span.enter();
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+
+    embedder
+        .embed_search(q, Some(deadline))
+        .map_err(milli::vector::Error::from)
+        .map_err(milli::Error::from)
+}
+
 pub fn search_from_kind(
     index_uid: String,
     search_kind: SearchKind,
@@ -1207,8 +1596,8 @@ pub fn search_from_kind(
             let semantic_hit_count = results.document_scores.len() as u32;
             (results, Some(semantic_hit_count))
         }
-        SearchKind::Hybrid { semantic_ratio, .. } => search
-            .execute_hybrid(*semantic_ratio)
+        SearchKind::Hybrid { semantic_ratio, fusion, .. } => search
+            .execute_hybrid(*semantic_ratio, *fusion)
             .map_err(|e| MeilisearchHttpError::from_milli(e, Some(index_uid)))?,
     };
     Ok((milli_result, semantic_hit_count))
@@ -1221,9 +1610,12 @@ struct AttributesFormat {
     attributes_to_crop: Option<Vec<String>>,
     crop_length: usize,
     crop_marker: String,
+    crop_mode: CropMode,
     highlight_pre_tag: String,
     highlight_post_tag: String,
     show_matches_position: bool,
+    snippets_per_attribute: Option<usize>,
+    snippet_length: Option<usize>,
     sort: Option<Vec<String>>,
     show_ranking_score: bool,
     show_ranking_score_details: bool,
@@ -1267,6 +1659,8 @@ struct HitMaker<'a> {
     show_ranking_score_details: bool,
     sort: Option<Vec<String>>,
     show_matches_position: bool,
+    snippets_per_attribute: Option<usize>,
+    snippet_length: Option<usize>,
     locales: Option<Vec<Language>>,
 }
 
@@ -1305,6 +1699,7 @@ impl<'a> HitMaker<'a> {
         mut formatter_builder: MatcherBuilder<'a>,
     ) -> milli::Result<Self> {
         formatter_builder.crop_marker(format.crop_marker);
+        formatter_builder.crop_mode(format.crop_mode.into());
         formatter_builder.highlight_prefix(format.highlight_pre_tag);
         formatter_builder.highlight_suffix(format.highlight_post_tag);
 
@@ -1391,6 +1786,8 @@ impl<'a> HitMaker<'a> {
             show_ranking_score: format.show_ranking_score,
             show_ranking_score_details: format.show_ranking_score_details,
             show_matches_position: format.show_matches_position,
+            snippets_per_attribute: format.snippets_per_attribute,
+            snippet_length: format.snippet_length,
             sort: format.sort,
             locales: format.locales,
         })
@@ -1450,12 +1847,13 @@ impl<'a> HitMaker<'a> {
         let localized_attributes =
             self.index.localized_attributes_rules(self.rtxn)?.unwrap_or_default();
 
-        let (matches_position, formatted) = format_fields(
+        let (matches_position, formatted, snippets) = format_fields(
             &displayed_document,
             &self.fields_ids_map,
             &self.formatter_builder,
             &self.formatted_options,
             self.show_matches_position,
+            self.snippets_per_attribute.map(|count| (count, self.snippet_length)),
             &self.displayed_ids,
             self.locales.as_deref(),
             &localized_attributes,
@@ -1467,6 +1865,8 @@ impl<'a> HitMaker<'a> {
 
         let ranking_score =
             self.show_ranking_score.then(|| ScoreDetails::global_score(score.iter()));
+        let original_ranking_score =
+            self.show_ranking_score.then(|| ScoreDetails::original_global_score(score)).flatten();
         let ranking_score_details =
             self.show_ranking_score_details.then(|| ScoreDetails::to_json_map(score.iter()));
 
@@ -1474,8 +1874,11 @@ impl<'a> HitMaker<'a> {
             document,
             formatted,
             matches_position,
+            snippets,
             ranking_score_details,
             ranking_score,
+            original_ranking_score,
+            matched_chunk: None,
         };
 
         Ok(hit)
@@ -1587,6 +1990,8 @@ pub fn perform_similar(
 
     let SimilarQuery {
         id,
+        positive,
+        negative,
         offset,
         limit,
         filter: _,
@@ -1598,22 +2003,52 @@ pub fn perform_similar(
         ranking_score_threshold,
     } = query;
 
-    let id: ExternalDocumentId = id.try_into().map_err(|error| {
-        let msg = format!("Invalid value at `.id`: {error}");
-        ResponseError::from_msg(msg, Code::InvalidSimilarId)
-    })?;
+    let id = match id {
+        Some(id) => {
+            let id: ExternalDocumentId = id.try_into().map_err(|error| {
+                let msg = format!("Invalid value at `.id`: {error}");
+                ResponseError::from_msg(msg, Code::InvalidSimilarId)
+            })?;
+            Some(id)
+        }
+        None => None,
+    };
 
-    // using let-else rather than `?` so that the borrow checker identifies we're always returning here,
-    // preventing a use-after-move
-    let Some(internal_id) = index.external_documents_ids().get(&rtxn, &id)? else {
+    let mut positive_examples = Vec::with_capacity(positive.len() + 1);
+    if let Some(id) = &id {
+        positive_examples.push(similar_document_example(index, &rtxn, id)?);
+    }
+    for (i, example) in positive.into_iter().enumerate() {
+        positive_examples.push(similar_example(
+            index,
+            &rtxn,
+            example,
+            &format!(".positive[{i}]"),
+            Code::InvalidSimilarPositive,
+        )?);
+    }
+    if positive_examples.is_empty() {
         return Err(ResponseError::from_msg(
-            MeilisearchHttpError::DocumentNotFound(id.into_inner()).to_string(),
-            Code::NotFoundSimilarId,
+            "At least one of `id` or `positive` must be provided".to_string(),
+            Code::InvalidSimilarPositive,
         ));
-    };
+    }
+    let negative_examples = negative
+        .into_iter()
+        .enumerate()
+        .map(|(i, example)| {
+            similar_example(
+                index,
+                &rtxn,
+                example,
+                &format!(".negative[{i}]"),
+                Code::InvalidSimilarNegative,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     let mut similar = milli::Similar::new(
-        internal_id,
+        positive_examples,
         offset,
         limit,
         index,
@@ -1622,6 +2057,7 @@ pub fn perform_similar(
         embedder,
         quantized,
     );
+    similar.negative(negative_examples);
 
     if let Some(ref filter) = query.filter {
         if let Some(facets) = parse_filter(filter, Code::InvalidSimilarFilter, features)? {
@@ -1654,9 +2090,12 @@ pub fn perform_similar(
         attributes_to_crop: None,
         crop_length: DEFAULT_CROP_LENGTH(),
         crop_marker: DEFAULT_CROP_MARKER(),
+        crop_mode: CropMode::default(),
         highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
         highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
         show_matches_position: false,
+        snippets_per_attribute: None,
+        snippet_length: None,
         sort: None,
         show_ranking_score,
         show_ranking_score_details,
@@ -1683,12 +2122,50 @@ pub fn perform_similar(
     let result = SimilarResult {
         hits,
         hits_info,
-        id: id.into_inner(),
+        id: id.map(ExternalDocumentId::into_inner),
         processing_time_ms: before_search.elapsed().as_millis(),
     };
     Ok(result)
 }
 
+/// Parses an example of the similar route, either a document id or a raw embedding.
+fn similar_example(
+    index: &Index,
+    rtxn: &RoTxn,
+    example: Value,
+    path: &str,
+    code: Code,
+) -> Result<milli::SimilarExample, ResponseError> {
+    if let Value::Array(_) = example {
+        let vector: Vec<f32> = serde_json::from_value(example).map_err(|_| {
+            let msg =
+                format!("Invalid value at `{path}`: an embedding must be an array of numbers");
+            ResponseError::from_msg(msg, code)
+        })?;
+        return Ok(milli::SimilarExample::Vector(vector));
+    }
+
+    let id: ExternalDocumentId = example.try_into().map_err(|error| {
+        let msg = format!("Invalid value at `{path}`: {error}");
+        ResponseError::from_msg(msg, code)
+    })?;
+    similar_document_example(index, rtxn, &id)
+}
+
+fn similar_document_example(
+    index: &Index,
+    rtxn: &RoTxn,
+    id: &ExternalDocumentId,
+) -> Result<milli::SimilarExample, ResponseError> {
+    match index.external_documents_ids().get(rtxn, id)? {
+        Some(internal_id) => Ok(milli::SimilarExample::Document(internal_id)),
+        None => Err(ResponseError::from_msg(
+            MeilisearchHttpError::DocumentNotFound(id.as_ref().to_owned()).to_string(),
+            Code::NotFoundSimilarId,
+        )),
+    }
+}
+
 pub fn insert_geo_distance(sorts: &[String], document: &mut Document) {
     lazy_static::lazy_static! {
         static ref GEO_REGEX: Regex =
@@ -1849,11 +2326,13 @@ fn format_fields(
     builder: &MatcherBuilder<'_>,
     formatted_options: &BTreeMap<FieldId, FormatOptions>,
     compute_matches: bool,
+    snippets_options: Option<(usize, Option<usize>)>,
     displayable_ids: &BTreeSet<FieldId>,
     locales: Option<&[Language]>,
     localized_attributes: &[LocalizedAttributesRule],
-) -> milli::Result<(Option<MatchesPosition>, Document)> {
+) -> milli::Result<(Option<MatchesPosition>, Document, Option<Snippets>)> {
     let mut matches_position = compute_matches.then(BTreeMap::new);
+    let mut snippets_by_attribute: Option<Snippets> = snippets_options.map(|_| BTreeMap::new());
     let mut document = document.clone();
 
     // reduce the formatted option list to the attributes that should be formatted,
@@ -1886,6 +2365,19 @@ fn format_fields(
                 .map(|(_, option)| **option)
                 .reduce(|acc, option| acc.merge(option));
             let mut infos = Vec::new();
+            let mut snippets = Vec::new();
+
+            // snippets are only computed for the cropped attributes, using the crop length by default.
+            // The count applies to the whole attribute, the elements of an array only get the snippets left.
+            let snippets_options = snippets_options.zip(format.and_then(|f| f.crop)).map(
+                |((count, snippet_length), crop_length)| {
+                    let taken = snippets_by_attribute
+                        .as_ref()
+                        .and_then(|snippets| snippets.get(key))
+                        .map_or(0, Vec::len);
+                    (count.saturating_sub(taken), snippet_length.unwrap_or(crop_length))
+                },
+            );
 
             // if no locales has been provided, we try to find the locales in the localized_attributes.
             let locales = locales.or_else(|| {
@@ -1901,6 +2393,8 @@ fn format_fields(
                 format,
                 &mut infos,
                 compute_matches,
+                snippets_options,
+                &mut snippets,
                 array_indices,
                 locales,
             );
@@ -1910,6 +2404,12 @@ fn format_fields(
                     matches.insert(key.to_owned(), infos);
                 }
             }
+
+            if let Some(snippets_by_attribute) = snippets_by_attribute.as_mut() {
+                if !snippets.is_empty() {
+                    snippets_by_attribute.entry(key.to_owned()).or_default().extend(snippets);
+                }
+            }
         },
     );
 
@@ -1920,18 +2420,23 @@ fn format_fields(
         .map(|&fid| field_ids_map.name(fid).unwrap());
     let document = permissive_json_pointer::select_values(&document, selectors);
 
-    Ok((matches_position, document))
+    Ok((matches_position, document, snippets_by_attribute))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn format_value(
     value: Value,
     builder: &MatcherBuilder<'_>,
     format_options: Option<FormatOptions>,
     infos: &mut Vec<MatchBounds>,
     compute_matches: bool,
+    snippets_options: Option<(usize, usize)>,
+    snippets: &mut Vec<Snippet>,
     array_indices: &[usize],
     locales: Option<&[Language]>,
 ) -> Value {
+    let highlight = format_options.is_some_and(|f| f.highlight);
+
     match value {
         Value::String(old_string) => {
             let mut matcher = builder.build(&old_string, locales);
@@ -1940,6 +2445,10 @@ fn format_value(
                 infos.extend_from_slice(&matches[..]);
             }
 
+            if let Some((count, snippet_length)) = snippets_options {
+                snippets.extend(matcher.snippets(highlight, snippet_length, count, array_indices));
+            }
+
             match format_options {
                 Some(format_options) => {
                     let value = matcher.format(format_options);
@@ -1960,6 +2469,10 @@ fn format_value(
                 infos.extend_from_slice(&matches[..]);
             }
 
+            if let Some((count, snippet_length)) = snippets_options {
+                snippets.extend(matcher.snippets(highlight, snippet_length, count, array_indices));
+            }
+
             match format_options {
                 Some(format_options) => {
                     let value = matcher.format(format_options);
@@ -5,6 +5,10 @@ use itertools::Itertools as _;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::milli::{AscDesc, Criterion, Member, TermsMatchingStrategy};
 
+/// The key under which the `popularity` ranking rule is deduplicated, it mirrors the name
+/// used in the ranking score details.
+const POPULARITY_RULE_NAME: &str = "_popularity";
+
 pub struct RankingRules {
     canonical_criteria: Vec<Criterion>,
     canonical_sort: Option<Vec<AscDesc>>,
@@ -247,6 +251,24 @@ impl RankingRules {
                         canonical_criteria.push(criterion.clone())
                     }
                 },
+                Criterion::Popularity => {
+                    match sorted_fields.entry(POPULARITY_RULE_NAME.to_string()) {
+                        std::collections::hash_map::Entry::Occupied(entry) => {
+                            canonicalization_actions.push(
+                                CanonicalizationAction::RemovedDuplicate {
+                                    earlier_occurrence: *entry.get(),
+                                    removed_occurrence: RankingRuleSource::Criterion(
+                                        criterion_index,
+                                    ),
+                                },
+                            )
+                        }
+                        std::collections::hash_map::Entry::Vacant(entry) => {
+                            entry.insert(RankingRuleSource::Criterion(criterion_index));
+                            canonical_criteria.push(criterion.clone())
+                        }
+                    }
+                }
             }
         }
 
@@ -327,6 +349,24 @@ impl RankingRules {
                         canonical_criteria.push(criterion.clone())
                     }
                 },
+                Criterion::Popularity => {
+                    match sorted_fields.entry(POPULARITY_RULE_NAME.to_string()) {
+                        std::collections::hash_map::Entry::Occupied(entry) => {
+                            canonicalization_actions.push(
+                                CanonicalizationAction::RemovedDuplicate {
+                                    earlier_occurrence: *entry.get(),
+                                    removed_occurrence: RankingRuleSource::Criterion(
+                                        criterion_index,
+                                    ),
+                                },
+                            )
+                        }
+                        std::collections::hash_map::Entry::Vacant(entry) => {
+                            entry.insert(RankingRuleSource::Criterion(criterion_index));
+                            canonical_criteria.push(criterion.clone())
+                        }
+                    }
+                }
             }
         }
 
@@ -461,6 +501,24 @@ impl RankingRules {
                         canonical_criteria.push(criterion)
                     }
                 },
+                Criterion::Popularity => {
+                    match sorted_fields.entry(POPULARITY_RULE_NAME.to_string()) {
+                        std::collections::hash_map::Entry::Occupied(entry) => {
+                            canonicalization_actions.push(
+                                CanonicalizationAction::RemovedDuplicate {
+                                    earlier_occurrence: *entry.get(),
+                                    removed_occurrence: RankingRuleSource::Criterion(
+                                        criterion_index,
+                                    ),
+                                },
+                            )
+                        }
+                        std::collections::hash_map::Entry::Vacant(entry) => {
+                            entry.insert(RankingRuleSource::Criterion(criterion_index));
+                            canonical_criteria.push(criterion)
+                        }
+                    }
+                }
             }
         }
 
@@ -729,7 +787,7 @@ impl RankingRule {
             Criterion::Asc(_) => RankingRuleKind::AscendingSort,
             Criterion::Desc(s) if s == "_geo" => RankingRuleKind::DescendingGeoSort,
 
-            Criterion::Desc(_) => RankingRuleKind::DescendingSort,
+            Criterion::Desc(_) | Criterion::Popularity => RankingRuleKind::DescendingSort,
             Criterion::Sort => {
                 return either::Right(sort.iter().flatten().enumerate().map(
                     move |(rule_index, asc_desc)| {
@@ -19,7 +19,7 @@
 
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use rand::rngs::StdRng;
@@ -66,6 +66,40 @@ impl Drop for Permit {
     }
 }
 
+/// A [`Permit`] that the search holding it can give back to the queue early, from a blocking thread.
+///
+/// It is used while a search waits on a remote service, e.g. the reranker,
+/// so that it doesn't prevent the other searches from running in the meantime.
+#[derive(Debug, Clone)]
+pub struct SharedPermit(Arc<Mutex<Option<Permit>>>);
+
+impl SharedPermit {
+    pub fn new(permit: Permit) -> Self {
+        Self(Arc::new(Mutex::new(Some(permit))))
+    }
+
+    /// Gives the permit back to the search queue if it's still held.
+    ///
+    /// Must be called from within the tokio runtime, e.g. in a `spawn_blocking` task.
+    pub fn release(&self) {
+        // the implicit drop of the permit notifies the queue from a spawned task
+        std::mem::drop(self.0.lock().unwrap().take());
+    }
+
+    /// Whether the permit was given back to the search queue.
+    pub fn is_released(&self) -> bool {
+        self.0.lock().unwrap().is_none()
+    }
+
+    /// Drop the permit giving it back to the search queue if it's still held.
+    pub async fn drop(self) {
+        let permit = self.0.lock().unwrap().take();
+        if let Some(permit) = permit {
+            permit.drop().await;
+        }
+    }
+}
+
 impl SearchQueue {
     pub fn new(capacity: usize, paralellism: NonZeroUsize) -> Self {
         // Search requests are going to wait until we're available anyway,
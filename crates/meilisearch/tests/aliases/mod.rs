@@ -0,0 +1,356 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn alias_reads_and_writes() {
+    let server = Server::new().await;
+    let v1 = server.index("movies-v1");
+    let (task, _code) = v1.add_documents(json!([{ "id": 1, "title": "Carol" }]), None).await;
+    v1.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) =
+        server.create_alias(json!({ "uid": "movies", "indexes": ["movies-v1"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "uid": "movies",
+      "indexes": [
+        "movies-v1"
+      ],
+      "writeTarget": "movies-v1"
+    }
+    "###);
+
+    let alias = server.index("movies");
+    let (response, code) = alias.search_post(json!({ "q": "carol" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "Carol"
+      }
+    ]
+    "###);
+
+    // Writes made through the alias are registered on the index it points to
+    let (task, code) = alias.add_documents(json!([{ "id": 2, "title": "Batman" }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    let response = alias.wait_task(task.uid()).await.succeeded();
+    snapshot!(response["indexUid"], @r###""movies-v1""###);
+    let (response, code) = v1.get_document(2, None).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["title"], @r###""Batman""###);
+
+    // Switching the alias to another index
+    let v2 = server.index("movies-v2");
+    let (task, _code) = v2.add_documents(json!([{ "id": 3, "title": "Dune" }]), None).await;
+    v2.wait_task(task.uid()).await.succeeded();
+    let (_response, code) =
+        server.create_alias(json!({ "uid": "movies", "indexes": ["movies-v2"] })).await;
+    snapshot!(code, @"200 OK");
+    let (response, _code) = alias.search_post(json!({ "q": "" })).await;
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 3,
+        "title": "Dune"
+      }
+    ]
+    "###);
+
+    let (response, code) = server.list_aliases().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "uid": "movies",
+          "indexes": [
+            "movies-v2"
+          ],
+          "writeTarget": "movies-v2"
+        }
+      ]
+    }
+    "###);
+
+    let (_response, code) = server.delete_alias("movies").await;
+    snapshot!(code, @"204 No Content");
+    let (response, code) = server.get_alias("movies").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Alias `movies` not found.",
+      "code": "alias_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#alias_not_found"
+    }
+    "###);
+
+    // The indexes are left untouched
+    let (response, code) = v2.search_post(json!({ "q": "" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["estimatedTotalHits"], @"1");
+}
+
+#[actix_rt::test]
+async fn alias_on_several_indexes() {
+    let server = Server::new().await;
+    let a = server.index("a");
+    let (task, _code) = a.add_documents(json!([{ "id": 1, "index": "a" }]), None).await;
+    a.wait_task(task.uid()).await.succeeded();
+    let b = server.index("b");
+    let (task, _code) = b.add_documents(json!([{ "id": 2, "index": "b" }]), None).await;
+    b.wait_task(task.uid()).await.succeeded();
+
+    let (_response, code) =
+        server.create_alias(json!({ "uid": "all", "indexes": ["a", "b"] })).await;
+    snapshot!(code, @"200 OK");
+
+    // Without a write target, the alias can only be used in federated searches
+    let (response, code) = server.index("all").search_post(json!({ "q": "" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Alias `all` points to several indexes and has no `writeTarget`, it can only be used in federated searches.",
+      "code": "alias_without_write_target",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#alias_without_write_target"
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({ "federation": {}, "queries": [{ "indexUid": "all", "q": "" }] }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let mut hits: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| (hit["id"].clone(), hit["_federation"]["indexUid"].clone()))
+        .collect();
+    hits.sort_by_key(|(id, _)| id.as_u64());
+    snapshot!(json_string!(hits), @r###"
+    [
+      [
+        1,
+        "a"
+      ],
+      [
+        2,
+        "b"
+      ]
+    ]
+    "###);
+
+    let (_response, code) = server
+        .create_alias(json!({ "uid": "all", "indexes": ["a", "b"], "writeTarget": "b" }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let (response, code) = server.index("all").search_post(json!({ "q": "" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2,
+        "index": "b"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn create_alias_errors() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.create(None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = server.create_alias(json!({ "uid": "test", "indexes": ["test"] })).await;
+    snapshot!(code, @"409 Conflict");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index `test` already exists.",
+      "code": "index_already_exists",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_already_exists"
+    }
+    "###);
+
+    let (response, code) =
+        server.create_alias(json!({ "uid": "alias", "indexes": ["nope"] })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index `nope` not found.",
+      "code": "index_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_not_found"
+    }
+    "###);
+
+    let (response, code) = server.create_alias(json!({ "uid": "alias", "indexes": [] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "An alias must point to at least one index.",
+      "code": "invalid_alias_indexes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_alias_indexes"
+    }
+    "###);
+
+    let (response, code) = server
+        .create_alias(json!({ "uid": "alias", "indexes": ["test"], "writeTarget": "other" }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The write target `other` must be one of the indexes of the alias.",
+      "code": "invalid_alias_write_target",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_alias_write_target"
+    }
+    "###);
+
+    let (response, code) = server.create_alias(json!({ "indexes": ["test"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `uid`",
+      "code": "missing_alias_uid",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_alias_uid"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn deleting_an_index_updates_its_aliases() {
+    let server = Server::new().await;
+    for uid in ["a", "b"] {
+        let (task, _code) = server.index(uid).create(None).await;
+        server.wait_task(task.uid()).await.succeeded();
+    }
+    let (_response, code) = server.create_alias(json!({ "uid": "only-a", "indexes": ["a"] })).await;
+    snapshot!(code, @"200 OK");
+    let (_response, code) = server
+        .create_alias(json!({ "uid": "all", "indexes": ["a", "b"], "writeTarget": "a" }))
+        .await;
+    snapshot!(code, @"200 OK");
+
+    let (task, _code) = server.index("a").delete().await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = server.list_aliases().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "uid": "all",
+          "indexes": [
+            "b"
+          ],
+          "writeTarget": "b"
+        }
+      ]
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn federated_search_on_an_alias_checks_each_index() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+    for uid in ["a", "b"] {
+        let (task, _code) = server.index(uid).add_documents(json!([{ "id": 1 }]), None).await;
+        server.wait_task(task.uid()).await.succeeded();
+    }
+    let (_response, code) =
+        server.create_alias(json!({ "uid": "all", "indexes": ["a", "b"] })).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = server
+        .add_api_key(json!({ "indexes": ["all", "a"], "actions": ["search"], "expiresAt": null }))
+        .await;
+    snapshot!(code, @"201 Created");
+    server.use_api_key(response["key"].as_str().unwrap());
+
+    let (response, code) = server
+        .multi_search(json!({ "federation": {}, "queries": [{ "indexUid": "all", "q": "" }] }))
+        .await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Inside `.queries[0]`: The provided API key is invalid.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_on_a_single_index_alias_checks_its_index() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+    let (task, _code) = server.index("a").add_documents(json!([{ "id": 1 }]), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+    let (_response, code) = server.create_alias(json!({ "uid": "only-a", "indexes": ["a"] })).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = server
+        .add_api_key(json!({ "indexes": ["only-a"], "actions": ["search"], "expiresAt": null }))
+        .await;
+    snapshot!(code, @"201 Created");
+    let alias_only_key = response["key"].as_str().unwrap().to_string();
+    let (response, code) = server
+        .add_api_key(
+            json!({ "indexes": ["only-a", "a"], "actions": ["search"], "expiresAt": null }),
+        )
+        .await;
+    snapshot!(code, @"201 Created");
+    let alias_and_index_key = response["key"].as_str().unwrap().to_string();
+
+    server.use_api_key(&alias_only_key);
+    let (response, code) = server.index("only-a").search_post(json!({ "q": "" })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The provided API key is invalid.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+    let (response, code) =
+        server.multi_search(json!({ "queries": [{ "indexUid": "only-a", "q": "" }] })).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Inside `.queries[0]`: The provided API key is invalid.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+
+    server.use_api_key(&alias_and_index_key);
+    let (response, code) = server.index("only-a").search_post(json!({ "q": "" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      }
+    ]
+    "###);
+    let (_response, code) = server
+        .multi_search(json!({ "federation": {}, "queries": [{ "indexUid": "only-a", "q": "" }] }))
+        .await;
+    snapshot!(code, @"200 OK");
+}
@@ -79,6 +79,11 @@ impl<'a> Index<'a, Owned> {
         self.service.patch_encoded(url, body, self.encoder).await
     }
 
+    pub async fn clone_index(&self, new_uid: &str) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/clone", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, json!({ "uid": new_uid }), self.encoder).await
+    }
+
     pub async fn delete(&self) -> (Value, StatusCode) {
         let url = format!("/indexes/{}", urlencode(self.uid.as_ref()));
         self.service.delete(url).await
@@ -102,6 +107,16 @@ impl<'a> Index<'a, Owned> {
         self.service.post_str(url, payload, headers).await
     }
 
+    pub async fn raw_add_documents_bytes(
+        &self,
+        payload: Vec<u8>,
+        headers: Vec<(&str, &str)>,
+        query_parameter: &str,
+    ) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/documents{}", urlencode(self.uid.as_ref()), query_parameter);
+        self.service.post_bytes(url, payload, headers).await
+    }
+
     pub async fn update_documents(
         &self,
         documents: Value,
@@ -257,6 +272,11 @@ impl<'a> Index<'a, Owned> {
             format!("/indexes/{}/settings/{}", urlencode(self.uid.as_ref()), "distinct-attribute");
         self.service.put_encoded(url, value, self.encoder).await
     }
+
+    pub async fn update_ttl_attribute(&self, value: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/{}", urlencode(self.uid.as_ref()), "ttl-attribute");
+        self.service.put_encoded(url, value, self.encoder).await
+    }
 }
 
 impl Index<'_, Shared> {
@@ -416,6 +436,11 @@ impl<State> Index<'_, State> {
         self.service.post(url, payload).await
     }
 
+    pub async fn export_documents(&self, options: &str) -> (String, StatusCode) {
+        let url = format!("/indexes/{}/documents/export{}", urlencode(self.uid.as_ref()), options);
+        self.service.get_raw(url).await
+    }
+
     pub async fn get_all_documents_raw(&self, options: &str) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/documents{}", urlencode(self.uid.as_ref()), options);
         self.service.get(url).await
@@ -441,6 +466,18 @@ impl<State> Index<'_, State> {
         self.service.get(url).await
     }
 
+    pub async fn top_queries(&self, query: &str) -> (Value, StatusCode) {
+        let url =
+            format!("/indexes/{}/analytics/top-queries{}", urlencode(self.uid.as_ref()), query);
+        self.service.get(url).await
+    }
+
+    pub async fn no_results_queries(&self, query: &str) -> (Value, StatusCode) {
+        let url =
+            format!("/indexes/{}/analytics/no-results{}", urlencode(self.uid.as_ref()), query);
+        self.service.get(url).await
+    }
+
     /// Performs both GET and POST search queries
     pub async fn search(
         &self,
@@ -515,6 +552,20 @@ impl<State> Index<'_, State> {
         self.service.get(url).await
     }
 
+    pub async fn embed(&self, embedder: &str, query: Value) -> (Value, StatusCode) {
+        let url = format!(
+            "/indexes/{}/embedders/{}/embed",
+            urlencode(self.uid.as_ref()),
+            urlencode(embedder)
+        );
+        self.service.post_encoded(url, query, self.encoder).await
+    }
+
+    pub async fn render(&self, query: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/render", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, query, self.encoder).await
+    }
+
     pub async fn facet_search(&self, query: Value) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/facet-search", urlencode(self.uid.as_ref()));
         self.service.post_encoded(url, query, self.encoder).await
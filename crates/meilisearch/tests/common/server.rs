@@ -8,8 +8,10 @@ use std::time::Duration;
 use actix_http::body::MessageBody;
 use actix_web::dev::ServiceResponse;
 use actix_web::http::StatusCode;
+use actix_web::web::Data;
 use byte_unit::{Byte, Unit};
 use clap::Parser;
+use meilisearch::analytics::Analytics;
 use meilisearch::option::{IndexerOpts, MaxMemory, MaxThreads, Opt};
 use meilisearch::setup_meilisearch;
 use once_cell::sync::Lazy;
@@ -51,7 +53,13 @@ impl Server<Owned> {
         let options = default_settings(dir.path());
 
         let (index_scheduler, auth) = setup_meilisearch(&options).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service {
+            index_scheduler,
+            auth,
+            options,
+            analytics: Data::new(Analytics::no_analytics()),
+            api_key: None,
+        };
 
         Server { service, _dir: Some(dir), _marker: PhantomData }
     }
@@ -66,7 +74,13 @@ impl Server<Owned> {
         options.master_key = Some("MASTER_KEY".to_string());
 
         let (index_scheduler, auth) = setup_meilisearch(&options).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service {
+            index_scheduler,
+            auth,
+            options,
+            analytics: Data::new(Analytics::no_analytics()),
+            api_key: None,
+        };
 
         Server { service, _dir: Some(dir), _marker: PhantomData }
     }
@@ -79,7 +93,14 @@ impl Server<Owned> {
 
     pub async fn new_with_options(options: Opt) -> Result<Self, anyhow::Error> {
         let (index_scheduler, auth) = setup_meilisearch(&options)?;
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let analytics = Analytics::new(&options, index_scheduler.clone(), auth.clone()).await;
+        let service = Service {
+            index_scheduler,
+            auth,
+            options,
+            analytics: Data::new(analytics),
+            api_key: None,
+        };
 
         Ok(Server { service, _dir: None, _marker: PhantomData })
     }
@@ -199,7 +220,13 @@ impl Server<Shared> {
         let options = default_settings(dir.path());
 
         let (index_scheduler, auth) = setup_meilisearch(&options).unwrap();
-        let service = Service { index_scheduler, auth, api_key: None, options };
+        let service = Service {
+            index_scheduler,
+            auth,
+            analytics: Data::new(Analytics::no_analytics()),
+            api_key: None,
+            options,
+        };
 
         Server { service, _dir: Some(dir), _marker: PhantomData }
     }
@@ -360,6 +387,10 @@ impl<State> Server<State> {
         self.service.post("/multi-search", queries).await
     }
 
+    pub async fn send_event(&self, event: Value) -> (Value, StatusCode) {
+        self.service.post("/events", event).await
+    }
+
     pub async fn list_indexes_raw(&self, parameters: &str) -> (Value, StatusCode) {
         self.service.get(format!("/indexes{parameters}")).await
     }
@@ -388,6 +419,22 @@ impl<State> Server<State> {
         self.service.post("/swap-indexes", value).await
     }
 
+    pub async fn create_alias(&self, value: Value) -> (Value, StatusCode) {
+        self.service.post("/aliases", value).await
+    }
+
+    pub async fn get_alias(&self, alias: &str) -> (Value, StatusCode) {
+        self.service.get(format!("/aliases/{alias}")).await
+    }
+
+    pub async fn list_aliases(&self) -> (Value, StatusCode) {
+        self.service.get("/aliases").await
+    }
+
+    pub async fn delete_alias(&self, alias: &str) -> (Value, StatusCode) {
+        self.service.delete(format!("/aliases/{alias}")).await
+    }
+
     pub async fn cancel_tasks(&self, value: &str) -> (Value, StatusCode) {
         self.service.post(format!("/tasks/cancel?{}", value), json!(null)).await
     }
@@ -23,6 +23,7 @@ pub struct Service {
     pub index_scheduler: Arc<IndexScheduler>,
     pub auth: Arc<AuthController>,
     pub options: Opt,
+    pub analytics: Data<Analytics>,
     pub api_key: Option<String>,
 }
 
@@ -57,6 +58,20 @@ impl Service {
         self.request(req).await
     }
 
+    /// Send a test post request from a binary body.
+    pub async fn post_bytes(
+        &self,
+        url: impl AsRef<str>,
+        body: Vec<u8>,
+        headers: Vec<(&str, &str)>,
+    ) -> (Value, StatusCode) {
+        let mut req = test::TestRequest::post().uri(url.as_ref()).set_payload(body);
+        for header in headers {
+            req = req.insert_header(header);
+        }
+        self.request(req).await
+    }
+
     pub async fn get(&self, url: impl AsRef<str>) -> (Value, StatusCode) {
         let req = test::TestRequest::get().uri(url.as_ref());
         self.request(req).await
@@ -142,7 +157,7 @@ impl Service {
             Data::new(search_queue),
             self.options.clone(),
             (route_layer_handle, stderr_layer_handle),
-            Data::new(Analytics::no_analytics()),
+            self.analytics.clone(),
             true,
         ))
         .await
@@ -163,6 +178,21 @@ impl Service {
         (response, status_code)
     }
 
+    /// Send a test get request and return the body as text, for the routes that don't respond with JSON.
+    pub async fn get_raw(&self, url: impl AsRef<str>) -> (String, StatusCode) {
+        let app = self.init_web_app().await;
+
+        let mut req = test::TestRequest::get().uri(url.as_ref());
+        if let Some(api_key) = &self.api_key {
+            req = req.insert_header(("Authorization", ["Bearer ", api_key].concat()));
+        }
+        let res = test::call_service(&app, req.to_request()).await;
+        let status_code = res.status();
+
+        let body = test::read_body(res).await;
+        (String::from_utf8(body.to_vec()).unwrap(), status_code)
+    }
+
     fn encode(&self, req: TestRequest, body: Value, encoder: Encoder) -> TestRequest {
         let bytes = serde_json::to_string(&body).expect("Failed to serialize test data to json");
         let encoded_body = encoder.encode(bytes);
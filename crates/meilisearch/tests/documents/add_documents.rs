@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
 use actix_web::test;
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, TimestampSecondArray};
+use arrow_ipc::writer::StreamWriter;
 use meili_snap::{json_string, snapshot};
 use meilisearch::Opt;
+use parquet::arrow::ArrowWriter;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
@@ -509,6 +514,81 @@ async fn add_csv_document_with_types_error() {
 }
 
 /// any other content-type is must be refused
+fn arrow_record_batch() -> RecordBatch {
+    let id: ArrayRef = Arc::new(Int64Array::from(vec![0, 1]));
+    let name: ArrayRef = Arc::new(StringArray::from(vec![Some("jean"), None]));
+    let birthdate: ArrayRef = Arc::new(TimestampSecondArray::from(vec![1_700_000_000, 0]));
+    RecordBatch::try_from_iter(vec![("id", id), ("name", name), ("birthdate", birthdate)]).unwrap()
+}
+
+#[actix_rt::test]
+async fn add_parquet_and_arrow_stream_documents() {
+    let server = Server::new().await;
+    let batch = arrow_record_batch();
+
+    let mut parquet = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut parquet, batch.schema(), None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let mut arrow_stream = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut arrow_stream, &batch.schema()).unwrap();
+    writer.write(&batch).unwrap();
+    writer.finish().unwrap();
+    drop(writer);
+
+    for (uid, content_type, payload) in [
+        ("parquet", "application/vnd.apache.parquet", parquet),
+        ("arrow", "application/vnd.apache.arrow.stream", arrow_stream),
+    ] {
+        let index = server.index(uid);
+        let (response, code) =
+            index.raw_add_documents_bytes(payload, vec![("Content-Type", content_type)], "").await;
+        snapshot!(code, @"202 Accepted");
+        let response = index.wait_task(response["taskUid"].as_u64().unwrap()).await;
+        snapshot!(response["status"], @r###""succeeded""###);
+        snapshot!(json_string!(response["details"]), @r###"
+        {
+          "receivedDocuments": 2,
+          "indexedDocuments": 2
+        }
+        "###);
+
+        let (documents, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+        snapshot!(code, @"200 OK");
+        snapshot!(json_string!(documents["results"]), @r###"
+        [
+          {
+            "id": 0,
+            "name": "jean",
+            "birthdate": 1700000000
+          },
+          {
+            "id": 1,
+            "name": null,
+            "birthdate": 0
+          }
+        ]
+        "###);
+    }
+}
+
+#[actix_rt::test]
+async fn add_malformed_parquet_document() {
+    let server = Server::new().await;
+    let index = server.index("parquet");
+
+    let (response, code) = index
+        .raw_add_documents_bytes(
+            br#"{ "id": 0 }"#.to_vec(),
+            vec![("Content-Type", "application/vnd.apache.parquet")],
+            "",
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""malformed_payload""###);
+}
+
 #[actix_rt::test]
 async fn error_add_documents_test_bad_content_types() {
     let document = json!([
@@ -535,7 +615,7 @@ async fn error_add_documents_test_bad_content_types() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/vnd.apache.arrow.stream`",
       "code": "invalid_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_content_type"
@@ -556,7 +636,7 @@ async fn error_add_documents_test_bad_content_types() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/vnd.apache.arrow.stream`",
       "code": "invalid_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_content_type"
@@ -590,7 +670,7 @@ async fn error_add_documents_test_no_content_type() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/vnd.apache.arrow.stream`",
       "code": "missing_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#missing_content_type"
@@ -610,7 +690,7 @@ async fn error_add_documents_test_no_content_type() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/vnd.apache.arrow.stream`",
       "code": "missing_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#missing_content_type"
@@ -2986,3 +3066,138 @@ async fn dry_register_file() {
     "###);
     snapshot!(code, @"404 Not Found");
 }
+
+#[actix_rt::test]
+async fn add_documents_with_document_schema() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, code) = index
+        .update_settings(json!({
+            "documentSchema": {
+                "type": "object",
+                "required": ["title"],
+                "properties": {
+                    "title": { "type": "string" },
+                    "price": { "type": "number", "minimum": 0 }
+                }
+            }
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await.succeeded();
+
+    let documents = json!([
+        { "id": 1, "title": "Carol", "price": 12.5 },
+        { "id": 2, "price": -1 },
+        { "id": 3, "title": 3 },
+    ]);
+    let (task, code) = index.add_documents(documents, Some("id")).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await.failed();
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "2 documents do not match the `documentSchema` of the index:\n  - document `2` at `/title`: missing required property\n  - document `2` at `/price`: `-1` is lower than the minimum `0`\n  - document `3` at `/title`: expected `string`, but found `number`",
+      "code": "document_schema_mismatch",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#document_schema_mismatch"
+    }
+    "###);
+
+    // partial updates don't need to contain the required properties
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "title": "Carol" }]), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+    let (task, _code) = index.update_documents(json!([{ "id": 1, "price": 10 }]), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.get_document(1, None).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {
+      "id": 1,
+      "title": "Carol",
+      "price": 10
+    }
+    "###);
+
+    // but they cannot remove them
+    let (task, _code) =
+        index.update_documents(json!([{ "id": 1, "$unset": ["title", "price"] }]), None).await;
+    let response = index.wait_task(task.uid()).await.failed();
+    snapshot!(response["error"]["message"], @r###""1 document does not match the `documentSchema` of the index:\n  - document `1` at `/title`: required property cannot be removed""###);
+
+    // the results of the operators are validated too
+    let documents = json!([
+        { "id": 1, "$inc": { "price": -20 } },
+        { "id": 2, "title": "Bob", "$push": { "price": "x" } },
+    ]);
+    let (task, _code) = index.update_documents(documents, None).await;
+    let response = index.wait_task(task.uid()).await.failed();
+    snapshot!(response["error"]["message"], @r###""2 documents do not match the `documentSchema` of the index:\n  - document `1` at `/price`: `-10` is lower than the minimum `0`\n  - document `2` at `/price`: expected `number`, but found `array`""###);
+    let (task, _code) =
+        index.update_documents(json!([{ "id": 1, "$inc": { "price": 5 } }]), None).await;
+    index.wait_task(task.uid()).await.succeeded();
+    let (response, _code) = index.get_document(1, None).await;
+    snapshot!(response["price"], @"15");
+}
+
+#[actix_rt::test]
+async fn add_documents_skipping_invalid_documents() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _code) = index.update_settings(json!({ "sortableAttributes": ["_geo"] })).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let documents = json!([
+        { "id": 1, "title": "valid", "_geo": { "lat": 12, "lng": 13 } },
+        { "id": "foo & bar", "title": "bad id" },
+        { "id": 3, "title": "bad geo", "_geo": { "lat": 12 } },
+        { "id": 4, "title": "bad vectors", "_vectors": [1, 2, 3] },
+    ]);
+    let (task, code) = index
+        .raw_add_documents(
+            &documents.to_string(),
+            vec![("Content-Type", "application/json")],
+            "?primaryKey=id&onError=skip",
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await.succeeded();
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 4,
+      "indexedDocuments": 1,
+      "skippedDocuments": [
+        {
+          "documentId": null,
+          "reason": "Document identifier `\"foo & bar\"` is invalid. A document identifier can be of type integer or string, only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and underscores (_), and can not be more than 511 bytes."
+        },
+        {
+          "documentId": "3",
+          "reason": "Could not find longitude in the document with the id: `\"3\"`. Was expecting a `_geo.lng` field."
+        },
+        {
+          "documentId": "4",
+          "reason": "The `_vectors` field in the document with id: `4` is not an object. Was expecting an object with a key for each embedder with manually provided vectors, but instead got `[1,2,3]`"
+        }
+      ],
+      "skippedDocumentsCount": 3
+    }
+    "###);
+
+    let (response, code) = index.get_all_documents_raw("?fields=id").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "id": 1
+      }
+    ]
+    "###);
+
+    // without `onError=skip` the whole task fails
+    let (task, _code) =
+        index.add_documents(json!([{ "id": 5 }, { "id": "foo & bar" }]), None).await;
+    index.wait_task(task.uid()).await.failed();
+}
@@ -1034,3 +1034,60 @@ async fn get_document_with_vectors() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn export_documents() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.update_settings_filterable_attributes(json!(["color"])).await;
+    index.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "color": "red", "info": { "size": 3 }, "tags": ["a", "b"] },
+        { "id": 2, "color": "blue", "info": { "size": 5 } },
+        { "id": 3, "color": "red", "name": "kevin, the \"third\"" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.export_documents("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {"id":1,"color":"red","info":{"size":3},"tags":["a","b"]}
+    {"id":2,"color":"blue","info":{"size":5}}
+    {"id":3,"color":"red","name":"kevin, the \"third\""}
+    "###);
+
+    let (response, code) = index.export_documents("?filter=color%20%3D%20red&fields=id,name").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {"id":1}
+    {"id":3,"name":"kevin, the \"third\""}
+    "###);
+
+    let (response, code) =
+        index.export_documents("?format=csv&fields=id,name,info.size,tags").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    id,name,info.size,tags
+    1,,3,"[""a"",""b""]"
+    2,,5,
+    3,"kevin, the ""third""",,
+    "###);
+
+    let (response, code) = index.export_documents("?format=csv").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    id,color,info,tags,name
+    1,red,"{""size"":3}","[""a"",""b""]",
+    2,blue,"{""size"":5}",,
+    3,red,,,"kevin, the ""third"""
+    "###);
+
+    let (response, code) = index.export_documents("?format=xml").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"{"message":"Unknown value `xml` for parameter `format`: expected one of `ndjson`, `csv`","code":"invalid_document_export_format","type":"invalid_request","link":"https://docs.meilisearch.com/errors#invalid_document_export_format"}"###);
+
+    let (response, code) = index.export_documents("?filter=name%20%3D%20kevin").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"{"message":"Attribute `name` is not filterable. Available filterable attribute patterns are: `color`.\n1:5 name = kevin","code":"invalid_document_filter","type":"invalid_request","link":"https://docs.meilisearch.com/errors#invalid_document_filter"}"###);
+}
@@ -298,3 +298,205 @@ async fn update_faceted_document() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn update_document_with_operators() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        {
+            "doc_id": 1,
+            "content": "foo",
+            "views": 1,
+            "tags": ["a", "b"],
+            "draft": true,
+        }
+    ]);
+    let (response, code) = index.add_documents(documents, None).await;
+    assert_eq!(code, 202);
+    index.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+        {
+            "doc_id": 1,
+            "$inc": { "views": 2 },
+            "$push": { "tags": "new" },
+            "$unset": ["draft"],
+        },
+        {
+            "doc_id": 1,
+            "$inc": { "views": 1, "likes": 1 },
+            "$pull": { "tags": "a" },
+        }
+    ]);
+    let (response, code) = index.update_documents(documents, None).await;
+    assert_eq!(code, 202, "response: {}", response);
+    index.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index.get_document(1, None).await;
+    assert_eq!(code, 200);
+    snapshot!(response, @r###"
+    {
+      "doc_id": 1,
+      "content": "foo",
+      "views": 4,
+      "tags": [
+        "b",
+        "new"
+      ],
+      "likes": 1
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_update_documents_bad_operator() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([{ "doc_id": 1, "content": "foo" }]);
+    let (response, code) = index.add_documents(documents, None).await;
+    assert_eq!(code, 202);
+    index.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([{ "doc_id": 1, "$unset": ["doc_id"] }]);
+    let (task, _code) = index.update_documents(documents, None).await;
+    let response = index.wait_task(task.uid()).await.failed();
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "Invalid update operator in the document with id: `1`. `$unset` cannot be applied to the primary key field `doc_id`.",
+      "code": "invalid_document_operator",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_operator"
+    }
+    "###);
+
+    let documents = json!([{ "doc_id": 1, "$inc": { "content": 1 } }]);
+    let (task, _code) = index.update_documents(documents, None).await;
+    let response = index.wait_task(task.uid()).await.failed();
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "Invalid update operator in the document with id: `1`. `$inc` cannot be applied to field `content`, whose value `\"foo\"` is not a number.",
+      "code": "invalid_document_operator",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_operator"
+    }
+    "###);
+
+    // the operators that cannot be applied to the stored values only skip their own document
+    let documents = json!([
+        { "doc_id": 1, "$push": { "content": "bar" } },
+        { "doc_id": 2, "content": "baz", "$push": { "tags": "new" } },
+    ]);
+    let (task, code) = index
+        .raw_update_documents(&documents.to_string(), Some("application/json"), "?onError=skip")
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await.succeeded();
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 2,
+      "indexedDocuments": 1,
+      "skippedDocuments": [
+        {
+          "documentId": "1",
+          "reason": "Invalid update operator in the document with id: `1`. `$push` cannot be applied to field `content`, whose value `\"foo\"` is not an array."
+        }
+      ],
+      "skippedDocumentsCount": 1
+    }
+    "###);
+    let (response, _code) = index.get_document(2, None).await;
+    snapshot!(response, @r###"
+    {
+      "doc_id": 2,
+      "content": "baz",
+      "tags": [
+        "new"
+      ]
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn update_document_if_version() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([{ "doc_id": 1, "content": "foo" }]);
+    let (response, code) = index.add_documents(documents, None).await;
+    assert_eq!(code, 202);
+    index.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index.get_document(1, Some(json!({ "retrieveVersion": true }))).await;
+    assert_eq!(code, 200);
+    snapshot!(response, @r###"
+    {
+      "doc_id": 1,
+      "content": "foo",
+      "_version": 1
+    }
+    "###);
+
+    let documents = json!([
+        { "doc_id": 1, "content": "bar", "_ifVersion": 1 },
+        { "doc_id": 2, "content": "baz", "_ifVersion": 0 },
+    ]);
+    let (response, code) = index.update_documents(documents, None).await;
+    assert_eq!(code, 202);
+    let response = index.wait_task(response.uid()).await.succeeded();
+    snapshot!(response["details"], @r###"{"receivedDocuments":2,"indexedDocuments":2}"###);
+
+    // the first worker already wrote the document, the second one must not overwrite it
+    let documents = json!([
+        { "doc_id": 1, "content": "qux", "_ifVersion": 1 },
+        { "doc_id": 2, "content": "qux", "_ifVersion": 1 },
+    ]);
+    let (response, code) = index.update_documents(documents, None).await;
+    assert_eq!(code, 202);
+    let response = index.wait_task(response.uid()).await.succeeded();
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 2,
+      "indexedDocuments": 1,
+      "versionConflicts": [
+        {
+          "documentId": "1",
+          "expectedVersion": 1,
+          "currentVersion": 2
+        }
+      ],
+      "versionConflictsCount": 1
+    }
+    "###);
+
+    let (response, code) =
+        index.fetch_documents(json!({ "retrieveVersion": true, "fields": ["content"] })).await;
+    assert_eq!(code, 200);
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "content": "bar",
+        "_version": 2
+      },
+      {
+        "content": "qux",
+        "_version": 2
+      }
+    ]
+    "###);
+
+    let documents = json!([{ "doc_id": 1, "_ifVersion": "2" }]);
+    let (response, code) = index.update_documents(documents, None).await;
+    assert_eq!(code, 202);
+    let response = index.wait_task(response.uid()).await.failed();
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "The `_ifVersion` field in the document with id: `1` must be a positive integer or `0`, but got `\"2\"`.",
+      "code": "invalid_document_if_version",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_if_version"
+    }
+    "###);
+}
@@ -79,6 +79,8 @@ async fn import_dump_v1_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -252,6 +254,8 @@ async fn import_dump_v1_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -424,6 +428,8 @@ async fn import_dump_v1_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -582,6 +588,8 @@ async fn import_dump_v2_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -752,6 +760,8 @@ async fn import_dump_v2_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -921,6 +931,8 @@ async fn import_dump_v2_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -1079,6 +1091,8 @@ async fn import_dump_v3_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -1249,6 +1263,8 @@ async fn import_dump_v3_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -1418,6 +1434,8 @@ async fn import_dump_v3_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -1576,6 +1594,8 @@ async fn import_dump_v4_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -1746,6 +1766,8 @@ async fn import_dump_v4_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -1915,6 +1937,8 @@ async fn import_dump_v4_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -2204,6 +2228,8 @@ async fn import_dump_v6_containing_experimental_features() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byAttribute",
       "typoTolerance": {
         "enabled": true,
@@ -2436,6 +2462,8 @@ async fn generate_and_import_dump_containing_vectors() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
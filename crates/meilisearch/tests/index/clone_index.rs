@@ -0,0 +1,119 @@
+use actix_web::http::StatusCode;
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{GetAllDocumentsOptions, Server, Value};
+use crate::json;
+
+#[actix_rt::test]
+async fn clone_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index.update_settings_filterable_attributes(json!(["color"])).await;
+    index.wait_task(task.uid()).await.succeeded();
+    let documents = json!([
+        { "id": 1, "color": "red" },
+        { "id": 2, "color": "blue" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (task, code) = index.clone_index("test_copy").await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await.succeeded();
+    snapshot!(json_string!(response, { ".uid" => "[uid]", ".batchUid" => "[batch_uid]", ".duration" => "[duration]", ".enqueuedAt" => "[date]", ".startedAt" => "[date]", ".finishedAt" => "[date]" }), @r###"
+    {
+      "uid": "[uid]",
+      "batchUid": "[batch_uid]",
+      "indexUid": "test",
+      "status": "succeeded",
+      "type": "indexClone",
+      "canceledBy": null,
+      "details": {
+        "newIndexUid": "test_copy"
+      },
+      "error": null,
+      "duration": "[duration]",
+      "enqueuedAt": "[date]",
+      "startedAt": "[date]",
+      "finishedAt": "[date]"
+    }
+    "###);
+
+    // The clone is independent from the original index
+    let (task, _code) = index.delete().await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let copy = server.index("test_copy");
+    let (response, code) = copy.get().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["primaryKey"], @r###""id""###);
+    let (response, _code) = copy.settings().await;
+    snapshot!(response["filterableAttributes"], @r###"["color"]"###);
+    let (response, code) = copy.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "id": 1,
+        "color": "red"
+      },
+      {
+        "id": 2,
+        "color": "blue"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn clone_index_into_existing_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.create(None).await;
+    index.wait_task(task.uid()).await.succeeded();
+    let (task, _code) = server.index("other").create(None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (task, code) = index.clone_index("other").await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await.failed();
+    snapshot!(response["error"], @r###"{"message":"Index `other` already exists.","code":"index_already_exists","type":"invalid_request","link":"https://docs.meilisearch.com/errors#index_already_exists"}"###);
+
+    let (response, code) = index.clone_index("test").await;
+    snapshot!(code, @"409 Conflict");
+    snapshot!(response["code"], @r###""index_already_exists""###);
+}
+
+async fn clone_with_actions(server: &mut Server, actions: &[&str]) -> (Value, StatusCode) {
+    server.use_api_key("MASTER_KEY");
+    let content = json!({
+        "indexes": ["test", "test_copy"],
+        "actions": actions,
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(code, 201, "{response}");
+    server.use_api_key(response["key"].as_str().unwrap());
+    server.index("test").clone_index("test_copy").await
+}
+
+#[actix_rt::test]
+async fn clone_index_requires_reading_the_documents() {
+    let mut server = Server::new_auth().await;
+
+    let (response, code) = clone_with_actions(&mut server, &["indexes.create"]).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The provided API key is invalid.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+
+    let (_response, code) =
+        clone_with_actions(&mut server, &["indexes.create", "documents.get"]).await;
+    snapshot!(code, @"202 Accepted");
+}
@@ -579,6 +579,211 @@ async fn search_bad_highlight_post_tag() {
     // Can't make the `highlight_post_tag` fail with a get search since it'll accept anything as a strings.
 }
 
+#[actix_rt::test]
+async fn search_bad_crop_mode() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index.search_post(json!({"cropMode": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` at `.cropMode`: expected one of `word`, `sentence`",
+      "code": "invalid_search_crop_mode",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_crop_mode"
+    }
+    "###);
+
+    let (response, code) = index.search_get("?cropMode=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` for parameter `cropMode`: expected one of `word`, `sentence`",
+      "code": "invalid_search_crop_mode",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_crop_mode"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_snippets_per_attribute() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index.search_post(json!({"snippetsPerAttribute": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.snippetsPerAttribute`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_search_snippets_per_attribute",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_snippets_per_attribute"
+    }
+    "###);
+
+    let (response, code) = index.search_get("?snippetLength=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `snippetLength`: could not parse `doggo` as a positive integer",
+      "code": "invalid_search_snippet_length",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_snippet_length"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"snippetsPerAttribute": 21})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.snippetsPerAttribute`: the value of `snippetsPerAttribute` is invalid, expected an integer between `0` and `20`.",
+      "code": "invalid_search_snippets_per_attribute",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_snippets_per_attribute"
+    }
+    "###);
+
+    let (response, code) = index.search_get("?snippetLength=1001").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `snippetLength`: the value of `snippetLength` is invalid, expected an integer between `0` and `1000`.",
+      "code": "invalid_search_snippet_length",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_snippet_length"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_fusion() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index
+        .search_post(json!({"q": "doggo", "hybrid": {"embedder": "default", "fusion": "doggo"}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` at `.hybrid.fusion`: expected one of `score`, `rrf`",
+      "code": "invalid_search_fusion",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_fusion"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({"q": "doggo", "hybrid": {"embedder": "default", "rrfK": -1}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.hybrid.rrfK`: expected a positive integer, but found a negative integer: `-1`",
+      "code": "invalid_search_rrf_k",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rrf_k"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({"q": "doggo", "hybrid": {"embedder": "default", "exhaustive": "yes"}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.hybrid.exhaustive`: expected a boolean, but found a string: `\"yes\"`",
+      "code": "invalid_search_exhaustive",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_exhaustive"
+    }
+    "###);
+
+    let (response, code) = index.search_get("?q=doggo&hybridFusion=rrf").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`hybridEmbedder` is mandatory when `hybridFusion` is present",
+      "code": "invalid_search_hybrid_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_hybrid_query"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_rerank() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index.search_post(json!({"rerank": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.rerank`: expected an object, but found a string: `\"doggo\"`",
+      "code": "invalid_search_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rerank"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"rerank": {"topK": "doggo"}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.rerank.topK`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_search_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rerank"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"rerank": {"topK": 0}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rerank.topK`: the value of `topK` is invalid, expected an integer between `1` and `100`.",
+      "code": "invalid_search_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rerank"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"rerank": {"topK": 101}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rerank.topK`: the value of `topK` is invalid, expected an integer between `1` and `100`.",
+      "code": "invalid_search_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_rerank"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_rerank_without_reranker() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (task, _status_code) =
+        index.add_documents(json!([{"id": 1, "title": "doggo"}]), None).await;
+    server.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({"q": "doggo", "rerank": {}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Reranking search results requires configuring a reranker. Relaunch Meilisearch with `--experimental-reranker-url`.",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn search_bad_crop_marker() {
     let server = Server::new_shared();
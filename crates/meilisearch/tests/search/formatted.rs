@@ -585,6 +585,111 @@ async fn displayedattr_2_smol() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_crop_mode_sentence() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "text": "Natalie risk her future. Split The World is a book written by Emily Henry. I never read it." },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await.succeeded();
+
+    index
+        .search(
+            json!({
+                "q": "split the world",
+                "attributesToCrop": ["text"],
+                "cropLength": 10,
+                "cropMode": "sentence",
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                allow_duplicates! {
+                  assert_json_snapshot!(response["hits"][0]["_formatted"]["text"], @r###""…Split The World is a book written by Emily Henry.…""###);
+                }
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_snippets() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let documents = json!([
+        { "id": 1, "text": "split the world void void void void void void void void void void void void void void void void void split the world" },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await.succeeded();
+
+    index
+        .search(
+            json!({
+                "q": "split the world",
+                "attributesToCrop": ["text"],
+                "snippetsPerAttribute": 3,
+                "snippetLength": 3,
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                allow_duplicates! {
+                  assert_json_snapshot!(response["hits"][0]["_snippets"], @r###"
+                  {
+                    "text": [
+                      {
+                        "text": "split the world…",
+                        "matchesPosition": [
+                          {
+                            "start": 0,
+                            "length": 5
+                          },
+                          {
+                            "start": 6,
+                            "length": 3
+                          },
+                          {
+                            "start": 10,
+                            "length": 5
+                          }
+                        ]
+                      },
+                      {
+                        "text": "…split the world",
+                        "matchesPosition": [
+                          {
+                            "start": 101,
+                            "length": 5
+                          },
+                          {
+                            "start": 107,
+                            "length": 3
+                          },
+                          {
+                            "start": 111,
+                            "length": 5
+                          }
+                        ]
+                      }
+                    ]
+                  }
+                  "###);
+                }
+            },
+        )
+        .await;
+
+    // snippets are not returned unless requested.
+    index
+        .search(json!({ "q": "split the world", "attributesToCrop": ["text"] }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert!(response["hits"][0].get("_snippets").is_none(), "{}", response);
+        })
+        .await;
+}
+
 #[cfg(feature = "default")]
 #[actix_rt::test]
 async fn test_cjk_highlight() {
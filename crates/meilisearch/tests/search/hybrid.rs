@@ -128,6 +128,65 @@ async fn simple_search() {
     snapshot!(response["semanticHitCount"], @"3");
 }
 
+#[actix_rt::test]
+async fn rrf_fusion() {
+    let server = Server::new().await;
+    let index = index_with_documents_user_provided(&server, &SIMPLE_SEARCH_DOCUMENTS_VEC).await;
+
+    // keyword and semantic results tie on "Captain Marvel" and "Captain Planet", the semantic results win the tie
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 0.5, "embedder": "default", "fusion": "rrf"}, "showRankingScore": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3","_rankingScore":0.990290343761444},{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_rankingScore":0.9848484848484848},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_rankingScore":0.9472135901451112}]"###);
+    snapshot!(response["semanticHitCount"], @"2");
+
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"semanticRatio": 0.2, "embedder": "default", "fusion": "rrf", "rrfK": 10}}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2"},{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3"},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1"}]"###);
+    snapshot!(response["semanticHitCount"], @"0");
+}
+
+#[actix_rt::test]
+async fn exhaustive_vector_search() {
+    let server = Server::new().await;
+    let index = index_with_documents_user_provided(&server, &SIMPLE_SEARCH_DOCUMENTS_VEC).await;
+    let (response, code) = index.update_settings_filterable_attributes(json!(["id"])).await;
+    assert_eq!(202, code, "{:?}", response);
+    index.wait_task(response.uid()).await.succeeded();
+
+    // the filter only leaves a few candidates, they are all compared with the vector
+    let (response, code) = index
+        .search_post(
+            json!({"vector": [1.0, 1.0], "hybrid": {"semanticRatio": 1.0, "embedder": "default"}, "filter": "id != 3", "showRankingScore": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_rankingScore":0.974341630935669},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_rankingScore":0.9472135901451112}]"###);
+
+    let (response, code) = index
+        .search_post(
+            json!({"vector": [1.0, 1.0], "hybrid": {"semanticRatio": 1.0, "embedder": "default", "exhaustive": true}, "showRankingScore": true}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3","_rankingScore":0.990290343761444},{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_rankingScore":0.974341630935669},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_rankingScore":0.9472135901451112}]"###);
+
+    let (response, code) = index
+        .search_get(
+            "?vector=1.0,1.0&hybridEmbedder=default&hybridSemanticRatio=1.0&hybridExhaustive=true",
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3"},{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2"},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1"}]"###);
+}
+
 #[actix_rt::test]
 async fn limit_offset() {
     let server = Server::new().await;
@@ -0,0 +1,483 @@
+use std::time::Duration;
+
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+use tempfile::TempDir;
+use tokio::time::sleep;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+async fn analytics_server(dir: &TempDir) -> Server {
+    let options = Opt { experimental_local_search_analytics: true, ..default_settings(dir.path()) };
+    Server::new_with_options(options).await.unwrap()
+}
+
+/// Waits for the background tasks to have updated the popularity of `expected` documents in total.
+///
+/// The searches and the events are registered together at most once per minute, so waiting for the
+/// impressions of a search before sending events ensures the events are registered in a later task.
+async fn wait_popularity_updates(server: &Server, expected: u64) {
+    // the popularity updates are registered at most once per minute
+    for _ in 0..150 {
+        let (response, code) =
+            server.tasks_filter("types=documentPopularityUpdate&statuses=succeeded").await;
+        assert_eq!(code, 200, "{response}");
+        let updated: u64 = response["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["details"]["updatedDocuments"].as_u64().unwrap())
+            .sum();
+        if updated >= expected {
+            return;
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+    panic!("Timeout waiting for the popularity updates");
+}
+
+#[actix_rt::test]
+async fn local_analytics_not_enabled() {
+    let server = Server::new_shared();
+    let index = server.unique_index();
+
+    let (response, code) = index.top_queries("").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Getting search analytics requires enabling the `local search analytics` experimental feature. Relaunch Meilisearch with `--experimental-local-search-analytics`.",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn local_analytics_top_queries_and_no_results() {
+    let dir = TempDir::new().unwrap();
+    let server = analytics_server(&dir).await;
+    let index = server.index("movies");
+
+    let (task, _code) = index
+        .add_documents(
+            json!([{ "id": 1, "title": "Star Wars" }, { "id": 2, "title": "Star Trek" }]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    for q in ["Star Wars", "  star   WARS ", "trek", "zorglub", "Zorglub", ""] {
+        let (_response, code) = index.search_post(json!({ "q": q })).await;
+        snapshot!(code, @"200 OK");
+    }
+
+    let (response, code) = index.top_queries("?limit=2").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".results[].averageProcessingTimeMs" => "[time]", ".results[].lastSearchedAt" => "[date]" }), @r###"
+    {
+      "results": [
+        {
+          "query": "star wars",
+          "count": 2,
+          "noResultsCount": 0,
+          "averageHits": 2.0,
+          "averageProcessingTimeMs": "[time]",
+          "clickCount": 0,
+          "conversionCount": 0,
+          "clickThroughRate": 0.0,
+          "lastSearchedAt": "[date]"
+        },
+        {
+          "query": "zorglub",
+          "count": 2,
+          "noResultsCount": 2,
+          "averageHits": 0.0,
+          "averageProcessingTimeMs": "[time]",
+          "clickCount": 0,
+          "conversionCount": 0,
+          "clickThroughRate": 0.0,
+          "lastSearchedAt": "[date]"
+        }
+      ],
+      "from": null,
+      "to": null,
+      "limit": 2,
+      "total": 3
+    }
+    "###);
+
+    let (response, code) = index.no_results_queries("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".results[].averageProcessingTimeMs" => "[time]", ".results[].lastSearchedAt" => "[date]" }), @r###"
+    {
+      "results": [
+        {
+          "query": "zorglub",
+          "count": 2,
+          "noResultsCount": 2,
+          "averageHits": 0.0,
+          "averageProcessingTimeMs": "[time]",
+          "clickCount": 0,
+          "conversionCount": 0,
+          "clickThroughRate": 0.0,
+          "lastSearchedAt": "[date]"
+        }
+      ],
+      "from": null,
+      "to": null,
+      "limit": 20,
+      "total": 1
+    }
+    "###);
+
+    // no search was made before 2000
+    let (response, code) = index.top_queries("?to=1999-12-31").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [],
+      "from": null,
+      "to": "2000-01-01T00:00:00Z",
+      "limit": 20,
+      "total": 0
+    }
+    "###);
+
+    let (response, code) = index.top_queries("?from=yesterday").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `from`: `yesterday` is an invalid date-time. It should follow the YYYY-MM-DD or RFC 3339 date-time format.",
+      "code": "invalid_analytics_from",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_analytics_from"
+    }
+    "###);
+
+    let (response, code) = server.index("nope").top_queries("").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index `nope` not found.",
+      "code": "index_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_not_found"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn events_not_enabled() {
+    let server = Server::new_shared();
+
+    let (response, code) = server
+        .send_event(json!({
+            "type": "click",
+            "queryId": "4c8fd4d2-cd9c-4ac4-8a5d-7e7f3a0e2a2b",
+            "documentId": "1",
+            "position": 0
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Sending search events requires enabling the `local search analytics` experimental feature. Relaunch Meilisearch with `--experimental-local-search-analytics`.",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn events_update_popularity_and_query_stats() {
+    let dir = TempDir::new().unwrap();
+    let server = analytics_server(&dir).await;
+    let index = server.index("movies");
+
+    let (task, _code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "title": "Star Wars" },
+                { "id": 2, "title": "Star Trek" },
+                { "id": 3, "title": "Stargate" },
+            ]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+    let (task, _code) = index.update_settings_ranking_rules(json!(["words", "popularity"])).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "q": "star" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "Star Wars"
+      },
+      {
+        "id": 2,
+        "title": "Star Trek"
+      },
+      {
+        "id": 3,
+        "title": "Stargate"
+      }
+    ]
+    "###);
+    let query_id = response["queryId"].as_str().unwrap().to_string();
+    // the three hits were returned once
+    wait_popularity_updates(&server, 3).await;
+
+    let (response, code) = server
+        .send_event(
+            json!({ "type": "click", "queryId": query_id, "documentId": "3", "position": 2 }),
+        )
+        .await;
+    snapshot!(code, @"204 No Content");
+    snapshot!(json_string!(response), @"null");
+    let (_response, code) = server
+        .send_event(
+            json!({ "type": "conversion", "queryId": query_id, "documentId": "2", "position": 1 }),
+        )
+        .await;
+    snapshot!(code, @"204 No Content");
+    // the same events sent again are accepted but only counted once
+    let (_response, code) = server
+        .send_event(
+            json!({ "type": "click", "queryId": query_id, "documentId": "3", "position": 2 }),
+        )
+        .await;
+    snapshot!(code, @"204 No Content");
+    let (_response, code) = server
+        .send_event(
+            json!({ "type": "conversion", "queryId": query_id, "documentId": "2", "position": 1 }),
+        )
+        .await;
+    snapshot!(code, @"204 No Content");
+    wait_popularity_updates(&server, 5).await;
+
+    // the converted document is now ranked before the clicked one
+    let (response, code) =
+        index.search_post(json!({ "q": "star", "attributesToRetrieve": ["id"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2
+      },
+      {
+        "id": 3
+      },
+      {
+        "id": 1
+      }
+    ]
+    "###);
+
+    let (response, code) = index.top_queries("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".results[].averageProcessingTimeMs" => "[time]", ".results[].lastSearchedAt" => "[date]" }), @r###"
+    {
+      "results": [
+        {
+          "query": "star",
+          "count": 2,
+          "noResultsCount": 0,
+          "averageHits": 3.0,
+          "averageProcessingTimeMs": "[time]",
+          "clickCount": 1,
+          "conversionCount": 1,
+          "clickThroughRate": 0.5,
+          "lastSearchedAt": "[date]"
+        }
+      ],
+      "from": null,
+      "to": null,
+      "limit": 20,
+      "total": 1
+    }
+    "###);
+
+    let (response, code) = server
+        .send_event(json!({
+            "type": "click",
+            "queryId": "4c8fd4d2-cd9c-4ac4-8a5d-7e7f3a0e2a2b",
+            "documentId": "1",
+            "position": 0
+        }))
+        .await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Query `4c8fd4d2-cd9c-4ac4-8a5d-7e7f3a0e2a2b` not found. Events must refer to the `queryId` of a search made with the local search analytics enabled.",
+      "code": "event_query_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#event_query_not_found"
+    }
+    "###);
+
+    let (response, code) = server
+        .send_event(
+            json!({ "type": "click", "queryId": query_id, "documentId": "1", "position": 2 }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response, { ".message" => "[message]" }), @r###"
+    {
+      "message": "[message]",
+      "code": "invalid_event_document_id",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_event_document_id"
+    }
+    "###);
+    let (response, code) = server
+        .send_event(
+            json!({ "type": "click", "queryId": query_id, "documentId": "1", "position": 3 }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_event_document_id""###);
+
+    let (response, code) = server
+        .send_event(
+            json!({ "type": "like", "queryId": query_id, "documentId": "1", "position": 0 }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `like` at `.type`: expected one of `click`, `conversion`",
+      "code": "invalid_event_type",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_event_type"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn local_analytics_multi_search() {
+    let dir = TempDir::new().unwrap();
+    let server = analytics_server(&dir).await;
+    let index = server.index("movies");
+
+    let (task, _code) = index
+        .add_documents(
+            json!([{ "id": 1, "title": "Star Wars" }, { "id": 2, "title": "Star Trek" }]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = server
+        .multi_search(json!({ "queries": [
+            { "indexUid": "movies", "q": "Star" },
+            { "indexUid": "movies", "q": "trek" },
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert!(response["results"][0]["queryId"].is_string());
+    assert!(response["results"][1]["queryId"].is_string());
+
+    let (_response, code) = server
+        .multi_search(json!({ "federation": {}, "queries": [
+            { "indexUid": "movies", "q": "star" },
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index.top_queries("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"], { "[].averageProcessingTimeMs" => "[time]", "[].lastSearchedAt" => "[date]" }), @r###"
+    [
+      {
+        "query": "star",
+        "count": 2,
+        "noResultsCount": 0,
+        "averageHits": 2.0,
+        "averageProcessingTimeMs": "[time]",
+        "clickCount": 0,
+        "conversionCount": 0,
+        "clickThroughRate": 0.0,
+        "lastSearchedAt": "[date]"
+      },
+      {
+        "query": "trek",
+        "count": 1,
+        "noResultsCount": 0,
+        "averageHits": 1.0,
+        "averageProcessingTimeMs": "[time]",
+        "clickCount": 0,
+        "conversionCount": 0,
+        "clickThroughRate": 0.0,
+        "lastSearchedAt": "[date]"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn popularity_is_kept_in_dumps() {
+    let temp = TempDir::new().unwrap();
+    let mut options =
+        Opt { experimental_local_search_analytics: true, ..default_settings(temp.path()) };
+    let server = Server::new_with_options(options.clone()).await.unwrap();
+    let index = server.index("movies");
+
+    let (task, _code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "title": "Star Wars" },
+                { "id": 2, "title": "Star Trek" },
+                { "id": 3, "title": "Stargate" },
+            ]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+    let (task, _code) = index.update_settings_ranking_rules(json!(["words", "popularity"])).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, _code) = index.search_post(json!({ "q": "star" })).await;
+    let query_id = response["queryId"].as_str().unwrap().to_string();
+    wait_popularity_updates(&server, 3).await;
+    let (_response, code) = server
+        .send_event(
+            json!({ "type": "click", "queryId": query_id, "documentId": "3", "position": 2 }),
+        )
+        .await;
+    snapshot!(code, @"204 No Content");
+    wait_popularity_updates(&server, 4).await;
+
+    let (response, code) = server.create_dump().await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await.succeeded();
+
+    drop(server);
+    tokio::fs::remove_dir_all(&options.db_path).await.unwrap();
+    let dump_name = format!("{}.dump", response["details"]["dumpUid"].as_str().unwrap());
+    options.import_dump = Some(options.dump_dir.join(dump_name));
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("movies");
+
+    let (response, code) =
+        index.search_post(json!({ "q": "star", "attributesToRetrieve": ["id"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 3
+      },
+      {
+        "id": 1
+      },
+      {
+        "id": 2
+      }
+    ]
+    "###);
+}
@@ -8,11 +8,13 @@ mod filters;
 mod formatted;
 mod geo;
 mod hybrid;
+mod local_analytics;
 #[cfg(not(feature = "chinese-pinyin"))]
 mod locales;
 mod matching_strategy;
 mod multi;
 mod pagination;
+mod rerank;
 mod restrict_searchable;
 mod search_queue;
 
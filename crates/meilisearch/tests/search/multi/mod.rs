@@ -3598,6 +3598,27 @@ async fn federation_federated_contains_facets() {
     "###);
 }
 
+#[actix_rt::test]
+async fn federation_rerank_in_query() {
+    let server = Server::new().await;
+
+    let (response, code) = server
+        .multi_search(json!({"federation": {}, "queries": [
+        {"indexUid" : "fruits", "q": "apple red"},
+        {"indexUid": "fruits", "q": "apple red", "rerank": {}},
+        ]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    insta::assert_json_snapshot!(response, { ".processingTimeMs" => "[time]" }, @r###"
+    {
+      "message": "Inside `.queries[1]`: Using `rerank` is not allowed in federated queries.\n - Hint: remove `rerank` from query #1 or remove `federation` from the request",
+      "code": "invalid_multi_search_query_rerank",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_multi_search_query_rerank"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn federation_non_faceted_for_an_index() {
     let server = Server::new().await;
@@ -0,0 +1,189 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+use crate::common::index::Index;
+use crate::common::{default_settings, Server, Value};
+use crate::json;
+
+async fn server_with_reranker(mock_server: &MockServer, temp: &TempDir) -> Server {
+    Server::new_with_options(Opt {
+        experimental_reranker_url: Some(mock_server.uri()),
+        ..default_settings(temp.path())
+    })
+    .await
+    .unwrap()
+}
+
+async fn add_movies(server: &Server) -> Index<'_> {
+    let index = server.index("movies");
+    let (task, _code) = index
+        .add_documents(
+            json!([
+                {"id": 1, "title": "Star Wars"},
+                {"id": 2, "title": "Star Trek"},
+                {"id": 3, "title": "Star Gate"},
+            ]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await.succeeded();
+    index
+}
+
+fn assert_decreasing_ranking_scores(response: &Value) {
+    let scores: Vec<f64> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["_rankingScore"].as_f64().unwrap())
+        .collect();
+    assert!(scores.windows(2).all(|scores| scores[0] >= scores[1]), "{scores:?}");
+}
+
+#[actix_rt::test]
+async fn rerank_reorders_the_hits() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(|req: &Request| {
+            let body: serde_json::Value = req.body_json().unwrap();
+            let results: Vec<_> = body["documents"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .enumerate()
+                .map(|(index, document)| {
+                    let document = document.as_str().unwrap();
+                    let relevance_score = if document.contains("Trek") {
+                        0.9
+                    } else if document.contains("Gate") {
+                        0.5
+                    } else {
+                        0.1
+                    };
+                    serde_json::json!({ "index": index, "relevance_score": relevance_score })
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "results": results }))
+        })
+        .mount(&mock_server)
+        .await;
+
+    let temp = TempDir::new().unwrap();
+    let server = server_with_reranker(&mock_server, &temp).await;
+    let index = add_movies(&server).await;
+
+    let (response, code) = index
+        .search_post(json!({"q": "star", "rerank": {"topK": 3}, "showRankingScore": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert_decreasing_ranking_scores(&response);
+    snapshot!(json_string!(response["hits"], { "[]._rankingScore" => "[score]", "[]._originalRankingScore" => "[score]" }), @r###"
+    [
+      {
+        "id": 2,
+        "title": "Star Trek",
+        "_rankingScore": "[score]",
+        "_originalRankingScore": "[score]"
+      },
+      {
+        "id": 3,
+        "title": "Star Gate",
+        "_rankingScore": "[score]",
+        "_originalRankingScore": "[score]"
+      },
+      {
+        "id": 1,
+        "title": "Star Wars",
+        "_rankingScore": "[score]",
+        "_originalRankingScore": "[score]"
+      }
+    ]
+    "###);
+
+    // the ranking score of a reranked hit is the score of the reranker
+    let scores: Vec<f64> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["_rankingScore"].as_f64().unwrap())
+        .collect();
+    assert!(
+        scores.iter().zip([0.9, 0.5, 0.1]).all(|(score, expected)| (score - expected).abs() < 1e-6),
+        "{scores:?}"
+    );
+
+    // the ranking score threshold applies to the scores of the reranker
+    let (response, code) = index
+        .search_post(json!({"q": "star", "rerank": {"topK": 3}, "rankingScoreThreshold": 0.4}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2,
+        "title": "Star Trek"
+      },
+      {
+        "id": 3,
+        "title": "Star Gate"
+      }
+    ]
+    "###);
+    snapshot!(response["estimatedTotalHits"], @"2");
+
+    // the pagination applies to the reranked hits
+    let (response, code) =
+        index.search_post(json!({"q": "star", "rerank": {"topK": 3}, "limit": 1})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2,
+        "title": "Star Trek"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn rerank_falls_back_to_the_original_order() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("the reranker is down"))
+        .mount(&mock_server)
+        .await;
+
+    let temp = TempDir::new().unwrap();
+    let server = server_with_reranker(&mock_server, &temp).await;
+    let index = add_movies(&server).await;
+
+    let (response, code) = index
+        .search_post(json!({"q": "star", "rerank": {"topK": 3}, "showRankingScore": true}))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert_decreasing_ranking_scores(&response);
+    snapshot!(json_string!(response["hits"], { "[]._rankingScore" => "[score]" }), @r###"
+    [
+      {
+        "id": 1,
+        "title": "Star Wars",
+        "_rankingScore": "[score]"
+      },
+      {
+        "id": 2,
+        "title": "Star Trek",
+        "_rankingScore": "[score]"
+      },
+      {
+        "id": 3,
+        "title": "Star Gate",
+        "_rankingScore": "[score]"
+      }
+    ]
+    "###);
+}
@@ -106,6 +106,16 @@ test_setting_routes!(
         update_verb: put,
         default_value: null
     },
+    {
+        setting: ttl_attribute,
+        update_verb: put,
+        default_value: null
+    },
+    {
+        setting: document_schema,
+        update_verb: put,
+        default_value: null
+    },
     {
         setting: stop_words,
         update_verb: put,
@@ -199,12 +209,14 @@ async fn get_settings() {
     let (response, code) = index.settings().await;
     assert_eq!(code, 200);
     let settings = response.as_object().unwrap();
-    assert_eq!(settings.keys().len(), 20);
+    assert_eq!(settings.keys().len(), 22);
     assert_eq!(settings["displayedAttributes"], json!(["*"]));
     assert_eq!(settings["searchableAttributes"], json!(["*"]));
     assert_eq!(settings["filterableAttributes"], json!([]));
     assert_eq!(settings["sortableAttributes"], json!([]));
     assert_eq!(settings["distinctAttribute"], json!(null));
+    assert_eq!(settings["ttlAttribute"], json!(null));
+    assert_eq!(settings["documentSchema"], json!(null));
     assert_eq!(
         settings["rankingRules"],
         json!(["words", "typo", "proximity", "attribute", "sort", "exactness"])
@@ -300,6 +312,8 @@ async fn secrets_are_hidden_in_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "ttlAttribute": null,
+      "documentSchema": null,
       "proximityPrecision": "byWord",
       "typoTolerance": {
         "enabled": true,
@@ -484,7 +498,7 @@ async fn error_set_invalid_ranking_rules() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
     {
-      "message": "Invalid value at `.rankingRules[0]`: `manyTheFish` ranking rule is invalid. Valid ranking rules are words, typo, sort, proximity, attribute, exactness and custom ranking rules.",
+      "message": "Invalid value at `.rankingRules[0]`: `manyTheFish` ranking rule is invalid. Valid ranking rules are words, typo, sort, proximity, attribute, exactness, popularity and custom ranking rules.",
       "code": "invalid_settings_ranking_rules",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
@@ -4,4 +4,5 @@ mod get_settings;
 mod prefix_search_settings;
 mod proximity_settings;
 mod tokenizer_customization;
+mod ttl;
 mod vectors;
@@ -0,0 +1,71 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn set_and_reset_ttl_attribute() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index.update_settings(json!({ "ttlAttribute": "expiresAt" })).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, _) = index.settings().await;
+    assert_eq!(response["ttlAttribute"], "expiresAt");
+
+    let (task, _code) = index.update_settings(json!({ "ttlAttribute": null })).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, _) = index.settings().await;
+    assert_eq!(response["ttlAttribute"], json!(null));
+}
+
+#[actix_rt::test]
+async fn expired_documents_are_excluded_from_search() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index.update_ttl_attribute(json!("expiresAt")).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let documents = json!([
+        { "id": 1, "title": "expired", "expiresAt": 0 },
+        { "id": 2, "title": "alive", "expiresAt": 32503680000_u64 },
+        { "id": 3, "title": "forever" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "attributesToRetrieve": ["id"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2
+      },
+      {
+        "id": 3
+      }
+    ]
+    "###);
+
+    let (task, _code) = index.update_ttl_attribute(json!(null)).await;
+    index.wait_task(task.uid()).await.succeeded();
+
+    let (response, code) = index.search_post(json!({ "attributesToRetrieve": ["id"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      },
+      {
+        "id": 2
+      },
+      {
+        "id": 3
+      }
+    ]
+    "###);
+}
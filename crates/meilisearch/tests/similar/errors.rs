@@ -189,6 +189,80 @@ async fn similar_not_found_id() {
     "###);
 }
 
+#[actix_rt::test]
+async fn similar_bad_examples() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({
+        "embedders": {
+            "manual": {
+                "source": "userProvided",
+                "dimensions": 3,
+            }
+        },
+        "filterableAttributes": ["title"]}))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await;
+
+    let documents = DOCUMENTS.clone();
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    let (response, code) = index.similar_post(json!({"embedder": "manual"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "At least one of `id` or `positive` must be provided",
+      "code": "invalid_similar_positive",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_similar_positive"
+    }
+    "###);
+
+    let (response, code) =
+        index.similar_post(json!({"positive": [["doggo"]], "embedder": "manual"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.positive[0]`: an embedding must be an array of numbers",
+      "code": "invalid_similar_positive",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_similar_positive"
+    }
+    "###);
+
+    let (response, code) = index
+        .similar_post(
+            json!({"id": 143, "negative": ["definitely-doesnt-exist"], "embedder": "manual"}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Document `definitely-doesnt-exist` not found.",
+      "code": "not_found_similar_id",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#not_found_similar_id"
+    }
+    "###);
+
+    let (response, code) =
+        index.similar_post(json!({"positive": [[1.0, 0.5]], "embedder": "manual"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid vector dimensions: expected: `3`, found: `2`.",
+      "code": "invalid_vector_dimensions",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_vector_dimensions"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn similar_bad_offset() {
     let server = Server::new().await;
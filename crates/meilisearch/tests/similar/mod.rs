@@ -619,6 +619,107 @@ async fn filter() {
         .await;
 }
 
+#[actix_rt::test]
+async fn positive_and_negative_examples() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({
+        "embedders": {
+            "manual": {
+                "source": "userProvided",
+                "dimensions": 3,
+            }
+        },
+        "filterableAttributes": ["title"]}))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await;
+
+    let documents = DOCUMENTS.clone();
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(value.uid()).await.succeeded();
+
+    // liked "Shazam!" and "Captain Marvel", but not "Escape Room"
+    let (response, code) = index
+        .similar_post(
+            json!({"positive": ["287947", 299537], "negative": [522681], "embedder": "manual"}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "How to Train Your Dragon: The Hidden World",
+        "release_year": 2019,
+        "id": "166428"
+      },
+      {
+        "title": "All Quiet on the Western Front",
+        "release_year": 1930,
+        "id": "143"
+      }
+    ]
+    "###);
+    snapshot!(response["id"], @"null");
+
+    let (response, code) =
+        index.similar_get("?positive=287947,299537&negative=522681&embedder=manual").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "How to Train Your Dragon: The Hidden World",
+        "release_year": 2019,
+        "id": "166428"
+      },
+      {
+        "title": "All Quiet on the Western Front",
+        "release_year": 1930,
+        "id": "143"
+      }
+    ]
+    "###);
+
+    // raw embeddings can be used as examples, they don't exclude any document
+    let (response, code) = index
+        .similar_post(json!({"positive": [[-0.5, 0.3, 0.85]], "limit": 2, "embedder": "manual"}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "All Quiet on the Western Front",
+        "release_year": 1930,
+        "id": "143"
+      },
+      {
+        "title": "Escape Room",
+        "release_year": 2019,
+        "id": "522681"
+      }
+    ]
+    "###);
+
+    // the filter still applies
+    let (response, code) = index
+        .similar_post(json!({"id": 143, "positive": [522681], "filter": "title = \"Captain Marvel\"", "embedder": "manual"}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "title": "Captain Marvel",
+        "release_year": 2019,
+        "id": "299537"
+      }
+    ]
+    "###);
+    snapshot!(response["id"], @r###""143""###);
+}
+
 #[actix_rt::test]
 async fn limit_and_offset() {
     let server = Server::new().await;
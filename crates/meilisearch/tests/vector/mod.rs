@@ -4,6 +4,7 @@ mod ollama;
 mod openai;
 mod rest;
 mod settings;
+mod sparse;
 
 use std::str::FromStr;
 
@@ -245,6 +246,66 @@ async fn user_provide_mismatched_embedding_dimension() {
     "###);
 }
 
+#[actix_rt::test]
+async fn skip_documents_with_invalid_vectors() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "manual": {
+                  "source": "userProvided",
+                  "dimensions": 3,
+              }
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([
+      {"id": 0, "name": "kefir", "_vectors": { "manual": [0, 0, 0] }},
+      {"id": 1, "name": "echo", "_vectors": { "manual": [[1, 1, 1], [1, 1]] }},
+      {"id": 2, "name": "intel", "_vectors": { "other": [1, 1, 1] }},
+    ]);
+    let (value, code) = index
+        .raw_add_documents(
+            &documents.to_string(),
+            vec![("Content-Type", "application/json")],
+            "?onError=skip",
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(value.uid()).await.succeeded();
+    snapshot!(json_string!(task["details"]), @r###"
+    {
+      "receivedDocuments": 3,
+      "indexedDocuments": 1,
+      "skippedDocuments": [
+        {
+          "documentId": "1",
+          "reason": "Invalid vector dimensions in document with id `1` in `._vectors.manual`.\n  - note: embedding #1 has dimensions 2\n  - note: embedder `manual` requires 3"
+        },
+        {
+          "documentId": "2",
+          "reason": "Bad embedder configuration in the document with id: `2`. Could not find embedder `other` in the index."
+        }
+      ],
+      "skippedDocumentsCount": 2
+    }
+    "###);
+
+    let (documents, _code) = index.get_all_documents_raw("?fields=id").await;
+    snapshot!(json_string!(documents["results"]), @r###"
+    [
+      {
+        "id": 0
+      }
+    ]
+    "###);
+}
+
 async fn generate_default_user_provided_documents(server: &Server) -> Index {
     let index = server.index("doggo");
 
@@ -334,6 +334,52 @@ async fn create_mock_raw() -> (MockServer, Value) {
     (mock_server, embedder_settings)
 }
 
+async fn create_mock_chunks() -> (MockServer, Value) {
+    let mock_server = MockServer::start().await;
+
+    let text_to_embedding: BTreeMap<_, _> = vec![
+        // chunk -> embedding
+        ("kefir is a", [1.0, 0.0, 0.0]),
+        ("a good dog", [0.0, 0.0, 1.0]),
+    ]
+    // turn into btree
+    .into_iter()
+    .collect();
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(move |req: &Request| {
+            let text: String = req.body_json().unwrap();
+            match text_to_embedding.get(text.as_str()) {
+                Some(embedding) => {
+                    ResponseTemplate::new(200).set_body_json(json!({ "data": embedding }))
+                }
+                None => ResponseTemplate::new(404)
+                    .set_body_json(json!({"error": "text not found", "text": text})),
+            }
+        })
+        .mount(&mock_server)
+        .await;
+    let url = mock_server.uri();
+
+    let embedder_settings = json!({
+        "source": "rest",
+        "url": url,
+        "dimensions": 3,
+        "request": "{{text}}",
+        "response": {
+          "data": "{{embedding}}"
+        },
+        "chunking": {
+          "maxWords": 3,
+          "overlapWords": 1,
+          "field": "description"
+        }
+    });
+
+    (mock_server, embedder_settings)
+}
+
 pub async fn post<T: IntoUrl>(url: T, text: &str) -> reqwest::Result<reqwest::Response> {
     reqwest::Client::builder().build()?.post(url).json(&json!(text)).send().await
 }
@@ -2111,3 +2157,387 @@ async fn searchable_reindex() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn chunked_documents() {
+    let (_mock, setting) = create_mock_chunks().await;
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": setting,
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    // split into "kefir is a" and "a good dog"
+    let (response, code) = index
+        .add_documents(
+            json!( { "id": 1, "name": "kefir", "description": "kefir is a good dog" }),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [0.1, 0.0, 1.0],
+            "hybrid": {"semanticRatio": 1.0, "embedder": "rest"},
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {
+      "hits": [
+        {
+          "id": 1,
+          "_matchedChunk": {
+            "text": "a good dog",
+            "start": 9,
+            "end": 19
+          }
+        }
+      ],
+      "query": "",
+      "processingTimeMs": "[duration]",
+      "limit": 20,
+      "offset": 0,
+      "estimatedTotalHits": 1,
+      "semanticHitCount": 1
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [1.0, 0.0, 0.1],
+            "hybrid": {"semanticRatio": 1.0, "embedder": "rest"},
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "_matchedChunk": {
+          "text": "kefir is a",
+          "start": 0,
+          "end": 10
+        }
+      }
+    ]
+    "###);
+
+    // the query is embedded to match the chunks
+    let (response, code) = index
+        .search_post(json!({
+            "q": "a good dog",
+            "hybrid": {"semanticRatio": 0.5, "embedder": "rest"},
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "_matchedChunk": {
+          "text": "a good dog",
+          "start": 9,
+          "end": 19
+        }
+      }
+    ]
+    "###);
+
+    // failing to embed the query of a hybrid search only skips the chunks
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "hybrid": {"semanticRatio": 0.5, "embedder": "rest"},
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      }
+    ]
+    "###);
+
+    // but fails a semantic search
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir",
+            "hybrid": {"semanticRatio": 1.0, "embedder": "rest"},
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""vector_embedding_error""###);
+}
+
+#[actix_rt::test]
+async fn embed_route() {
+    let (_mock, setting) = create_mock().await;
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": setting,
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index.embed("rest", json!({ "texts": ["kefir", "intel"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["embeddings"]), @r###"
+    [
+      [
+        0.0,
+        0.0,
+        0.0
+      ],
+      [
+        1.0,
+        1.0,
+        1.0
+      ]
+    ]
+    "###);
+
+    // documents are rendered through the document template
+    let (response, code) = index
+        .embed(
+            "rest",
+            json!({ "documents": [{ "id": 1, "name": "intel" }, { "id": 2, "name": "echo" }] }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["embeddings"]), @r###"
+    [
+      [
+        1.0,
+        1.0,
+        1.0
+      ],
+      [
+        99.0,
+        99.0,
+        99.0
+      ]
+    ]
+    "###);
+
+    let (response, code) =
+        index.embed("rest", json!({ "texts": ["kefir"], "documents": [{ "id": 1 }] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`texts` and `documents` cannot be used together",
+      "code": "invalid_embed_input",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_embed_input"
+    }
+    "###);
+
+    let texts: Vec<String> = (0..101).map(|i| format!("kefir {i}")).collect();
+    let (response, code) = index.embed("rest", json!({ "texts": texts })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`texts` cannot contain more than 100 elements, but it contains 101 elements",
+      "code": "invalid_embed_texts",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_embed_texts"
+    }
+    "###);
+
+    let documents: Vec<_> = (0..101).map(|i| serde_json::json!({ "id": i })).collect();
+    let (response, code) = index.embed("rest", json!({ "documents": documents })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_embed_documents""###);
+
+    let (response, code) = index.embed("doggo", json!({ "texts": ["kefir"] })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Embedder `doggo` not found in index `doggo`.",
+      "code": "embedder_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#embedder_not_found"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn render_route() {
+    let (_mock, setting) = create_mock().await;
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": setting,
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let documents = json!([{ "id": 1, "name": "kefir" }]);
+    let (value, code) = index.add_documents(documents, None).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(value.uid()).await.succeeded();
+
+    let (response, code) = index.render(json!({ "embedder": "rest", "id": 1 })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".processingTimeMs" => "[duration]" }), @r###"
+    {
+      "template": "{{doc.name}}",
+      "rendered": "kefir",
+      "truncatedAt": null,
+      "processingTimeMs": "[duration]"
+    }
+    "###);
+
+    let (response, code) = index
+        .render(json!({
+            "template": "{{doc.name}} is a good doggo",
+            "documentTemplateMaxBytes": 10,
+            "document": { "name": "intel" },
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".processingTimeMs" => "[duration]" }), @r###"
+    {
+      "template": "{{doc.name}} is a good doggo",
+      "rendered": "intel is a good doggo",
+      "truncatedAt": 10,
+      "processingTimeMs": "[duration]"
+    }
+    "###);
+
+    let (response, code) = index.render(json!({ "embedder": "rest", "id": 2 })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Document `2` not found.",
+      "code": "document_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#document_not_found"
+    }
+    "###);
+
+    let (response, code) =
+        index.render(json!({ "template": "{{doc.name}}", "embedder": "rest", "id": 1 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`template` and `embedder` cannot be used together",
+      "code": "invalid_render_input",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_render_input"
+    }
+    "###);
+
+    let (response, code) = index.render(json!({ "embedder": "rest" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "One of `id` or `document` must be provided",
+      "code": "invalid_render_input",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_render_input"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn request_policy() {
+    let mock_server = MockServer::start().await;
+    let requests = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let received = requests.clone();
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(move |req: &Request| {
+            // the first request is rate limited
+            if received.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                return ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "0")
+                    .set_body_json(json!({ "error": "slow down" }));
+            }
+            let texts: Vec<String> = req.body_json().unwrap();
+            let embeddings: Vec<_> = texts.iter().map(|_| json!([0.0, 1.0, 2.0])).collect();
+            ResponseTemplate::new(200).set_body_json(json!({ "data": embeddings }))
+        })
+        .mount(&mock_server)
+        .await;
+
+    let server = get_server_vector().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": {
+              "rest": {
+                  "source": "rest",
+                  "url": mock_server.uri(),
+                  "dimensions": 3,
+                  "request": ["{{text}}", "{{..}}"],
+                  "response": { "data": ["{{embedding}}", "{{..}}"] },
+                  "documentTemplate": "{{doc.name}}",
+                  "requestPolicy": { "maxConcurrentRequests": 1, "batchSize": 2, "requestsPerMinute": 6000 }
+              },
+          },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (settings, _) = index.settings().await;
+    snapshot!(json_string!(settings["embedders"]["rest"]["requestPolicy"]), @r###"
+    {
+      "maxConcurrentRequests": 1,
+      "batchSize": 2,
+      "requestsPerMinute": 6000
+    }
+    "###);
+
+    let documents: Vec<_> =
+        (0..5).map(|id| json!({ "id": id, "name": format!("doggo {id}") })).collect();
+    let (response, code) = index.add_documents(json!(documents), None).await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(response.uid()).await.succeeded();
+
+    // 3 batches of at most 2 texts, plus the retried request
+    snapshot!(requests.load(std::sync::atomic::Ordering::SeqCst), @"4");
+
+    let (batch, _) = server.get_batch(task["batchUid"].as_u64().unwrap() as u32).await;
+    let trace = &batch["stats"]["progressTrace"];
+    snapshot!(trace["embedder `rest` requests > retries"], @r###""1""###);
+    snapshot!(trace["embedder `rest` requests > rate limited responses"], @r###""1""###);
+
+    // changing the policy does not regenerate the embeddings
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "rest": { "requestPolicy": { "maxRetries": 0 } } },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+    snapshot!(requests.load(std::sync::atomic::Ordering::SeqCst), @"4");
+}
@@ -1,6 +1,7 @@
 use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
 
-use crate::common::{GetAllDocumentsOptions, Server};
+use crate::common::{default_settings, GetAllDocumentsOptions, Server};
 use crate::json;
 use crate::vector::generate_default_user_provided_documents;
 
@@ -17,7 +18,7 @@ async fn field_unavailable_for_source() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(response, @r###"
     {
-      "message": "`.embedders.manual`: Field `documentTemplate` unavailable for source `userProvided`.\n  - note: `documentTemplate` is available for sources: `openAi`, `huggingFace`, `ollama`, `rest`\n  - note: available fields for source `userProvided`: `source`, `dimensions`, `distribution`, `binaryQuantized`",
+      "message": "`.embedders.manual`: Field `documentTemplate` unavailable for source `userProvided`.\n  - note: `documentTemplate` is available for sources: `openAi`, `huggingFace`, `ollama`, `rest`, `sparse`\n  - note: available fields for source `userProvided`: `source`, `dimensions`, `distribution`, `binaryQuantized`, `int8Rescoring`",
       "code": "invalid_settings_embedders",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
@@ -32,7 +33,7 @@ async fn field_unavailable_for_source() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(response, @r###"
     {
-      "message": "`.embedders.default`: Field `revision` unavailable for source `openAi`.\n  - note: `revision` is available for sources: `huggingFace`\n  - note: available fields for source `openAi`: `source`, `model`, `apiKey`, `dimensions`, `documentTemplate`, `documentTemplateMaxBytes`, `url`, `distribution`, `binaryQuantized`",
+      "message": "`.embedders.default`: Field `revision` unavailable for source `openAi`.\n  - note: `revision` is available for sources: `huggingFace`\n  - note: available fields for source `openAi`: `source`, `model`, `apiKey`, `dimensions`, `documentTemplate`, `documentTemplateMaxBytes`, `url`, `distribution`, `binaryQuantized`, `int8Rescoring`, `chunking`, `requestPolicy`",
       "code": "invalid_settings_embedders",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
@@ -40,6 +41,107 @@ async fn field_unavailable_for_source() {
     "###);
 }
 
+#[actix_rt::test]
+async fn chunking_checks() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "manual": {"source": "userProvided", "dimensions": 3, "chunking": {}}},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.manual`: Field `chunking` unavailable for source `userProvided`.\n  - note: `chunking` is available for sources: `openAi`, `huggingFace`, `ollama`, `rest`, `composite`\n  - note: available fields for source `userProvided`: `source`, `dimensions`, `distribution`, `binaryQuantized`, `int8Rescoring`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "rest": {
+            "source": "rest",
+            "url": "http://localhost:1337",
+            "dimensions": 3,
+            "request": "{{text}}",
+            "response": "{{embedding}}",
+            "chunking": {"maxWords": 10, "overlapWords": 10}
+          }},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.rest.chunking`: `overlapWords` (10) must be lower than `maxWords` (10)",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn request_policy_checks() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "manual": {"source": "userProvided", "dimensions": 3, "requestPolicy": {}}},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.manual`: Field `requestPolicy` unavailable for source `userProvided`.\n  - note: `requestPolicy` is available for sources: `openAi`, `ollama`, `rest`\n  - note: available fields for source `userProvided`: `source`, `dimensions`, `distribution`, `binaryQuantized`, `int8Rescoring`",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "rest": {
+            "source": "rest",
+            "url": "http://localhost:1337",
+            "dimensions": 3,
+            "request": "{{text}}",
+            "response": "{{embedding}}",
+            "requestPolicy": {"requestsPerMinute": 0}
+          }},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.rest.requestPolicy`: `requestsPerMinute` must be greater than 0",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "rest": {
+            "source": "rest",
+            "url": "http://localhost:1337",
+            "dimensions": 3,
+            "request": "{{text}}",
+            "response": "{{embedding}}",
+            "requestPolicy": {"maxConcurrency": 2}
+          }},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_settings_embedders""###);
+}
+
 #[actix_rt::test]
 async fn update_embedder() {
     let server = Server::new().await;
@@ -1066,3 +1168,51 @@ async fn composite_checks() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn local_model_checks() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "local": {"source": "huggingFace", "model": "/etc/models/bge"}},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "`.embedders.local.model`: loading models from the local filesystem is disabled.\n  - Hint: start Meilisearch with `--experimental-local-models-directory` pointing to the directory containing the models",
+      "code": "invalid_settings_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_embedders"
+    }
+    "###);
+
+    let temp = tempfile::TempDir::new().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_local_models_directory: Some(temp.path().join("models")),
+        ..default_settings(temp.path())
+    })
+    .await
+    .unwrap();
+    let index = server.index("doggo");
+
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "local": {"source": "huggingFace", "model": "/etc/models/bge"}},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["message"], @r###""`.embedders.local.model`: the local model `/etc/models/bge` is outside of the directory allowed for local models.\n  - Hint: move the model inside the directory passed to `--experimental-local-models-directory`""###);
+
+    // going up the directory tree is refused
+    let model = format!("{}/models/../../bge", temp.path().display());
+    let (response, code) = index
+        .update_settings(json!({
+          "embedders": { "local": {"source": "huggingFace", "model": model}},
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_settings_embedders""###);
+}
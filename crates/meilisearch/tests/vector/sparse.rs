@@ -0,0 +1,181 @@
+use meili_snap::{json_string, snapshot};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+use crate::common::index::Index;
+use crate::common::{Server, Value};
+use crate::json;
+use crate::vector::get_server_vector;
+
+/// Sparse embedder giving one token per known word of the text.
+async fn create_sparse_mock() -> (MockServer, Value) {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(|req: &Request| {
+            let body: serde_json::Value = req.body_json().unwrap();
+            let text = body["input"].as_str().unwrap();
+            let mut embedding = serde_json::Map::new();
+            for (token, word) in ["kefir", "intel", "dog"].into_iter().enumerate() {
+                if text.contains(word) {
+                    embedding.insert(token.to_string(), serde_json::json!(1.0));
+                }
+            }
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "embedding": embedding }))
+        })
+        .mount(&mock_server)
+        .await;
+
+    let embedder_settings = json!({
+        "source": "sparse",
+        "url": mock_server.uri(),
+        "request": { "input": "{{text}}" },
+        "response": { "embedding": "{{embedding}}" },
+        "documentTemplate": "{{doc.name}} is a {{doc.kind}}",
+    });
+
+    (mock_server, embedder_settings)
+}
+
+async fn index_with_sparse_embedder(server: &Server) -> (MockServer, Index<'_>) {
+    let (mock, setting) = create_sparse_mock().await;
+    let index = server.index("doggo");
+
+    let (response, code) =
+        index.update_settings(json!({ "embedders": { "splade": setting } })).await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    let (response, code) = index
+        .add_documents(
+            json!([
+                { "id": 0, "name": "kefir", "kind": "dog" },
+                { "id": 1, "name": "intel", "kind": "cat" },
+                { "id": 2, "name": "echo", "kind": "dog" },
+            ]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(response.uid()).await.succeeded();
+
+    (mock, index)
+}
+
+#[actix_rt::test]
+async fn sparse_search() {
+    let server = get_server_vector().await;
+    let (_mock, index) = index_with_sparse_embedder(&server).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "kefir the dog",
+            "hybrid": { "semanticRatio": 1.0, "embedder": "splade" },
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 0
+      },
+      {
+        "id": 2
+      }
+    ]
+    "###);
+    snapshot!(response["semanticHitCount"], @"2");
+
+    // documents that don't share any token with the query are not returned
+    let (response, code) = index
+        .search_post(json!({
+            "q": "intel",
+            "hybrid": { "semanticRatio": 1.0, "embedder": "splade" },
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      }
+    ]
+    "###);
+
+    // sparse embedders embed the query themselves
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [1.0],
+            "hybrid": { "semanticRatio": 1.0, "embedder": "splade" },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_search_vector""###);
+}
+
+#[actix_rt::test]
+async fn hybrid_search_with_sparse_embedder() {
+    let server = get_server_vector().await;
+    let (_mock, index) = index_with_sparse_embedder(&server).await;
+
+    // the keyword search doesn't find any `doggo`, the sparse embedding of the query matches the dogs
+    let (response, code) = index
+        .search_post(json!({
+            "q": "doggo",
+            "hybrid": { "semanticRatio": 0.5, "embedder": "splade" },
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 0
+      },
+      {
+        "id": 2
+      }
+    ]
+    "###);
+    snapshot!(response["semanticHitCount"], @"2");
+
+    // the keyword results are merged with the semantic ones
+    let (response, code) = index
+        .search_post(json!({
+            "q": "echo",
+            "hybrid": { "semanticRatio": 0.5, "embedder": "splade" },
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn embed_route_with_sparse_embedder() {
+    let server = get_server_vector().await;
+    let (_mock, index) = index_with_sparse_embedder(&server).await;
+
+    let (response, code) = index.embed("splade", json!({ "texts": ["kefir", "intel dog"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["embeddings"]), @r###"
+    [
+      {
+        "0": 1.0
+      },
+      {
+        "1": 1.0,
+        "2": 1.0
+      }
+    ]
+    "###);
+}
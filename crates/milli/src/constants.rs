@@ -4,3 +4,7 @@ pub static VERSION_PATCH: &str = env!("CARGO_PKG_VERSION_PATCH");
 
 pub const RESERVED_VECTORS_FIELD_NAME: &str = "_vectors";
 pub const RESERVED_GEO_FIELD_NAME: &str = "_geo";
+/// Version of a document, incremented by the indexer each time the document is written.
+pub const RESERVED_VERSION_FIELD_NAME: &str = "_version";
+/// Condition on the current version of a document, for the document to be written.
+pub const RESERVED_IF_VERSION_FIELD_NAME: &str = "_ifVersion";
@@ -2,6 +2,7 @@ mod builder;
 mod enriched;
 mod primary_key;
 mod reader;
+mod schema;
 mod serde_impl;
 
 use std::fmt::Debug;
@@ -17,6 +18,7 @@ pub use primary_key::{
     PrimaryKey, DEFAULT_PRIMARY_KEY,
 };
 pub use reader::{DocumentsBatchCursor, DocumentsBatchCursorError, DocumentsBatchReader};
+pub use schema::{DocumentSchema, SchemaMismatch, SchemaViolation};
 use serde::{Deserialize, Serialize};
 
 use crate::error::{FieldIdMapMissingEntry, InternalError};
@@ -0,0 +1,444 @@
+//! A subset of [JSON Schema](https://json-schema.org) used to validate the documents sent to an index.
+//!
+//! The supported keywords are `type`, `enum`, `properties`, `required`, `additionalProperties`,
+//! `items`, `minimum`, `maximum`, `minLength` and `maxLength`. The `$schema`, `title` and
+//! `description` annotations are accepted and ignored, any other keyword is rejected.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+const ANNOTATIONS: &[&str] = &["$schema", "title", "description"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SchemaType {
+    Null,
+    Boolean,
+    Object,
+    Array,
+    Number,
+    Integer,
+    String,
+}
+
+impl SchemaType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "null" => Some(Self::Null),
+            "boolean" => Some(Self::Boolean),
+            "object" => Some(Self::Object),
+            "array" => Some(Self::Array),
+            "number" => Some(Self::Number),
+            "integer" => Some(Self::Integer),
+            "string" => Some(Self::String),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Boolean => "boolean",
+            Self::Object => "object",
+            Self::Array => "array",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::String => "string",
+        }
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Null, Value::Null)
+            | (Self::Boolean, Value::Bool(_))
+            | (Self::Object, Value::Object(_))
+            | (Self::Array, Value::Array(_))
+            | (Self::Number, Value::Number(_))
+            | (Self::String, Value::String(_)) => true,
+            (Self::Integer, Value::Number(number)) => {
+                number.is_i64()
+                    || number.is_u64()
+                    || number.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            _ => false,
+        }
+    }
+
+    fn of(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+}
+
+/// A compiled document schema.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentSchema {
+    types: Option<Vec<SchemaType>>,
+    enumeration: Option<Vec<Value>>,
+    properties: BTreeMap<String, DocumentSchema>,
+    required: Vec<String>,
+    additional_properties: bool,
+    items: Option<Box<DocumentSchema>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+/// A document that doesn't match the document schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// The [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the offending value.
+    pub pointer: String,
+    pub message: String,
+}
+
+/// The violations of the document schema found in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    pub document_id: String,
+    pub violations: Vec<SchemaViolation>,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.pointer, self.message)
+    }
+}
+
+impl DocumentSchema {
+    /// Compiles the schema, returning a description of the first invalid keyword.
+    pub fn new(schema: &Value) -> Result<Self, String> {
+        Self::compile(schema, "")
+    }
+
+    fn compile(schema: &Value, pointer: &str) -> Result<Self, String> {
+        let Value::Object(schema) = schema else {
+            return Err(format!(
+                "`{}`: expected an object, but found {}",
+                display_pointer(pointer),
+                SchemaType::of(schema)
+            ));
+        };
+
+        let mut compiled = DocumentSchema { additional_properties: true, ..Default::default() };
+        for (keyword, value) in schema {
+            let keyword_pointer = format!("{pointer}/{}", escape(keyword));
+            let invalid = |expected: &str| {
+                format!(
+                    "`{keyword_pointer}`: expected {expected}, but found `{}`",
+                    serde_json::to_string(value).unwrap_or_default()
+                )
+            };
+            match keyword.as_str() {
+                "type" => {
+                    let names = match value {
+                        Value::String(name) => vec![name.as_str()],
+                        Value::Array(names) => names
+                            .iter()
+                            .map(|name| name.as_str().ok_or_else(|| invalid("a type name")))
+                            .collect::<Result<_, _>>()?,
+                        _ => return Err(invalid("a type name or an array of type names")),
+                    };
+                    let types = names
+                        .into_iter()
+                        .map(|name| {
+                            SchemaType::from_name(name).ok_or_else(|| {
+                                format!(
+                                    "`{keyword_pointer}`: unknown type `{name}`, expected one of \
+                                     `null`, `boolean`, `object`, `array`, `number`, `integer` or `string`"
+                                )
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    compiled.types = Some(types);
+                }
+                "enum" => {
+                    let Value::Array(values) = value else { return Err(invalid("an array")) };
+                    compiled.enumeration = Some(values.clone());
+                }
+                "properties" => {
+                    let Value::Object(properties) = value else {
+                        return Err(invalid("an object"));
+                    };
+                    for (name, property) in properties {
+                        let property_pointer = format!("{keyword_pointer}/{}", escape(name));
+                        let property = Self::compile(property, &property_pointer)?;
+                        compiled.properties.insert(name.clone(), property);
+                    }
+                }
+                "required" => {
+                    let Value::Array(names) = value else {
+                        return Err(invalid("an array of property names"));
+                    };
+                    compiled.required = names
+                        .iter()
+                        .map(|name| {
+                            name.as_str()
+                                .map(String::from)
+                                .ok_or_else(|| invalid("a property name"))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "additionalProperties" => {
+                    let Value::Bool(additional) = value else { return Err(invalid("a boolean")) };
+                    compiled.additional_properties = *additional;
+                }
+                "items" => compiled.items = Some(Box::new(Self::compile(value, &keyword_pointer)?)),
+                "minimum" => {
+                    compiled.minimum = Some(value.as_f64().ok_or_else(|| invalid("a number"))?)
+                }
+                "maximum" => {
+                    compiled.maximum = Some(value.as_f64().ok_or_else(|| invalid("a number"))?)
+                }
+                "minLength" => {
+                    let length = value.as_u64().ok_or_else(|| invalid("a positive integer"))?;
+                    compiled.min_length = Some(length as usize);
+                }
+                "maxLength" => {
+                    let length = value.as_u64().ok_or_else(|| invalid("a positive integer"))?;
+                    compiled.max_length = Some(length as usize);
+                }
+                keyword if ANNOTATIONS.contains(&keyword) => (),
+                _ => return Err(format!("`{keyword_pointer}`: unsupported keyword `{keyword}`")),
+            }
+        }
+
+        Ok(compiled)
+    }
+
+    /// Validates a whole document against the schema.
+    pub fn validate(&self, document: &Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.validate_value(document, &mut String::new(), true, &mut violations);
+        violations
+    }
+
+    /// Validates a partial update of a document against the schema.
+    ///
+    /// The `required` properties of the top-level object are not checked,
+    /// as they may already be present in the stored document. The fields removed
+    /// by the update are checked with [`Self::validate_removed_fields`].
+    pub fn validate_update(&self, document: &Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.validate_value(document, &mut String::new(), false, &mut violations);
+        violations
+    }
+
+    /// Validates that none of the top-level fields removed by a partial update are required.
+    pub fn validate_removed_fields<'a>(
+        &self,
+        fields: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<SchemaViolation> {
+        fields
+            .into_iter()
+            .filter(|field| self.required.iter().any(|name| name == field))
+            .map(|field| SchemaViolation {
+                pointer: format!("/{}", escape(field)),
+                message: String::from("required property cannot be removed"),
+            })
+            .collect()
+    }
+
+    fn validate_value(
+        &self,
+        value: &Value,
+        pointer: &mut String,
+        check_required: bool,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        let mut violation = |pointer: &str, message: String| {
+            violations.push(SchemaViolation { pointer: display_pointer(pointer), message })
+        };
+
+        if let Some(types) = &self.types {
+            if !types.iter().any(|t| t.matches(value)) {
+                let expected: Vec<_> = types.iter().map(|t| format!("`{}`", t.name())).collect();
+                violation(
+                    pointer,
+                    format!(
+                        "expected {}, but found `{}`",
+                        expected.join(" or "),
+                        SchemaType::of(value)
+                    ),
+                );
+                return;
+            }
+        }
+
+        if let Some(enumeration) = &self.enumeration {
+            if !enumeration.contains(value) {
+                violation(
+                    pointer,
+                    format!(
+                        "`{value}` is not one of the allowed values: `{}`",
+                        Value::from(enumeration.clone())
+                    ),
+                );
+            }
+        }
+
+        match value {
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = self.minimum.filter(|minimum| number < *minimum) {
+                    violation(pointer, format!("`{number}` is lower than the minimum `{minimum}`"));
+                }
+                if let Some(maximum) = self.maximum.filter(|maximum| number > *maximum) {
+                    violation(
+                        pointer,
+                        format!("`{number}` is greater than the maximum `{maximum}`"),
+                    );
+                }
+            }
+            Value::String(string) => {
+                let length = string.chars().count();
+                if let Some(min_length) = self.min_length.filter(|min| length < *min) {
+                    violation(
+                        pointer,
+                        format!("expected at least {min_length} characters, but found {length}"),
+                    );
+                }
+                if let Some(max_length) = self.max_length.filter(|max| length > *max) {
+                    violation(
+                        pointer,
+                        format!("expected at most {max_length} characters, but found {length}"),
+                    );
+                }
+            }
+            Value::Array(values) => {
+                if let Some(items) = &self.items {
+                    for (i, item) in values.iter().enumerate() {
+                        let len = pointer.len();
+                        pointer.push_str(&format!("/{i}"));
+                        items.validate_value(item, pointer, true, violations);
+                        pointer.truncate(len);
+                    }
+                }
+            }
+            Value::Object(object) => {
+                self.validate_object(object, pointer, check_required, violations)
+            }
+            Value::Null | Value::Bool(_) => (),
+        }
+    }
+
+    fn validate_object(
+        &self,
+        object: &Map<String, Value>,
+        pointer: &mut String,
+        check_required: bool,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        if check_required {
+            for name in self.required.iter().filter(|name| !object.contains_key(*name)) {
+                violations.push(SchemaViolation {
+                    pointer: display_pointer(&format!("{pointer}/{}", escape(name))),
+                    message: String::from("missing required property"),
+                });
+            }
+        }
+
+        for (name, value) in object {
+            let len = pointer.len();
+            pointer.push('/');
+            pointer.push_str(&escape(name));
+            match self.properties.get(name) {
+                Some(property) => property.validate_value(value, pointer, true, violations),
+                None if !self.additional_properties => violations.push(SchemaViolation {
+                    pointer: display_pointer(pointer),
+                    message: String::from("property is not allowed by the schema"),
+                }),
+                None => (),
+            }
+            pointer.truncate(len);
+        }
+    }
+}
+
+/// Escapes a property name as a JSON pointer reference token.
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// The root of the document is displayed as `/` rather than the empty pointer.
+fn display_pointer(pointer: &str) -> String {
+    if pointer.is_empty() {
+        String::from("/")
+    } else {
+        pointer.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use meili_snap::snapshot;
+    use serde_json::json;
+
+    use super::*;
+
+    fn violations(violations: Vec<SchemaViolation>) -> String {
+        violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn compile_errors() {
+        let error = DocumentSchema::new(&json!(["string"])).unwrap_err();
+        snapshot!(error, @"`/`: expected an object, but found array");
+        let error = DocumentSchema::new(&json!({ "type": "text" })).unwrap_err();
+        snapshot!(error, @"`/type`: unknown type `text`, expected one of `null`, `boolean`, `object`, `array`, `number`, `integer` or `string`");
+        let error = DocumentSchema::new(&json!({ "properties": { "price": { "minimum": "0" } } }))
+            .unwrap_err();
+        snapshot!(error, @r###"`/properties/price/minimum`: expected a number, but found `"0"`"###);
+        let error = DocumentSchema::new(&json!({ "oneOf": [] })).unwrap_err();
+        snapshot!(error, @"`/oneOf`: unsupported keyword `oneOf`");
+    }
+
+    #[test]
+    fn validate_documents() {
+        let schema = DocumentSchema::new(&json!({
+            "type": "object",
+            "required": ["id", "price"],
+            "properties": {
+                "id": { "type": ["integer", "string"] },
+                "price": { "type": "number", "minimum": 0 },
+                "color": { "enum": ["red", "blue"] },
+                "tags": { "type": "array", "items": { "type": "string", "maxLength": 5 } },
+                "a/b": { "type": "boolean" }
+            },
+            "additionalProperties": false
+        }))
+        .unwrap();
+
+        let valid = json!({ "id": 1, "price": 2.5, "color": "red", "tags": ["new"] });
+        assert!(schema.validate(&valid).is_empty());
+
+        let invalid = json!({
+            "id": 1.5,
+            "color": "green",
+            "tags": ["new", "discounted"],
+            "a/b": 1,
+            "size": 3
+        });
+        snapshot!(violations(schema.validate(&invalid)), @r###"
+        `/price`: missing required property
+        `/id`: expected `integer` or `string`, but found `number`
+        `/color`: `"green"` is not one of the allowed values: `["red","blue"]`
+        `/tags/1`: expected at most 5 characters, but found 10
+        `/a~1b`: expected `boolean`, but found `number`
+        `/size`: property is not allowed by the schema
+        "###);
+
+        let update = json!({ "id": 1, "price": -1 });
+        snapshot!(violations(schema.validate_update(&update)), @"`/price`: `-1` is lower than the minimum `0`");
+
+        snapshot!(violations(schema.validate_removed_fields(["color", "price"])), @"`/price`: required property cannot be removed");
+    }
+}
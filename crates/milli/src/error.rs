@@ -12,7 +12,7 @@ use serde_json::Value;
 use thiserror::Error;
 
 use crate::constants::RESERVED_GEO_FIELD_NAME;
-use crate::documents::{self, DocumentsBatchCursorError};
+use crate::documents::{self, DocumentsBatchCursorError, SchemaMismatch};
 use crate::thread_pool_no_abort::PanicCatched;
 use crate::vector::settings::EmbeddingSettings;
 use crate::{CriterionError, DocumentId, FieldId, Object, SortError};
@@ -154,6 +154,8 @@ and can not be more than 511 bytes.", .document_id.to_string()
     InvalidGeoField(#[from] Box<GeoError>),
     #[error("Invalid vector dimensions: expected: `{}`, found: `{}`.", .expected, .found)]
     InvalidVectorDimensions { expected: usize, found: usize },
+    #[error("Cannot search with a `vector` using the sparse embedder `{0}`.\n  - Hint: sparse embedders embed the `q` parameter.")]
+    InvalidVectorForSparseEmbedder(String),
     #[error("Invalid vector dimensions in document with id `{document_id}` in `._vectors.{embedder_name}`.\n  - note: embedding #{embedding_index} has dimensions {found}\n  - note: embedder `{embedder_name}` requires {expected}")]
     InvalidIndexingVectorDimensions {
         embedder_name: String,
@@ -166,6 +168,14 @@ and can not be more than 511 bytes.", .document_id.to_string()
     InvalidVectorsMapType { document_id: String, value: Value },
     #[error("Bad embedder configuration in the document with id: `{document_id}`. {error}")]
     InvalidVectorsEmbedderConf { document_id: String, error: String },
+    #[error("Invalid update operator in the document with id: `{document_id}`. {error}")]
+    InvalidDocumentOperator { document_id: String, error: String },
+    #[error("The `_ifVersion` field in the document with id: `{document_id}` must be a positive integer or `0`, but got `{value}`.")]
+    InvalidDocumentIfVersion { document_id: String, value: String },
+    #[error("Invalid `documentSchema`: {0}.")]
+    InvalidDocumentSchema(String),
+    #[error("{}", format_schema_mismatches(.0))]
+    DocumentSchemaMismatch(Vec<SchemaMismatch>),
     #[error("{0}")]
     InvalidFilter(String),
     #[error("Invalid type for filter subexpression: expected: {}, found: {}.", .0.join(", "), .1)]
@@ -293,6 +303,8 @@ and can not be more than 511 bytes.", .document_id.to_string()
     InvalidSearchEmbedder(String),
     #[error("Cannot find embedder with name `{0}`.")]
     InvalidSimilarEmbedder(String),
+    #[error("An exhaustive vector search can compare the query with at most {max} documents, but {candidates} documents are candidates. Use a filter to reduce the number of candidates, or set `hybrid.exhaustive` to `false`.")]
+    TooManyCandidatesForExhaustiveSearch { candidates: u64, max: u64 },
     #[error("Too many vectors for document with id {0}: found {1}, but limited to 256.")]
     TooManyVectors(String, usize),
     #[error("`.embedders.{embedder_name}`: Field `{field}` unavailable for source `{source_}`{for_context}.{available_sources}{available_fields}{available_contexts}",
@@ -375,8 +387,20 @@ and can not be more than 511 bytes.", .document_id.to_string()
         "`.embedders.{embedder_name}.binaryQuantized`: Cannot disable the binary quantization.\n - Note: Binary quantization is a lossy operation that cannot be reverted.\n - Hint: Add a new embedder that is non-quantized and regenerate the vectors."
     )]
     InvalidDisableBinaryQuantization { embedder_name: String },
+    #[error(
+        "`.embedders.{embedder_name}.int8Rescoring`: Cannot disable the int8 rescoring.\n - Note: The embeddings were binary and int8 quantized, which is a lossy operation that cannot be reverted.\n - Hint: Add a new embedder that is non-quantized and regenerate the vectors."
+    )]
+    InvalidDisableInt8Rescoring { embedder_name: String },
+    #[error(
+        "`.embedders.{embedder_name}.int8Rescoring`: Cannot enable the int8 rescoring of a binary quantized embedder.\n - Note: Binary quantized embeddings cannot be turned back into the full-precision embeddings that int8 quantization needs.\n - Hint: Add a new embedder with only `int8Rescoring` enabled and regenerate the vectors."
+    )]
+    InvalidInt8RescoringOfBinaryQuantized { embedder_name: String },
     #[error("`.embedders.{embedder_name}.documentTemplateMaxBytes`: `documentTemplateMaxBytes` cannot be zero")]
     InvalidSettingsDocumentTemplateMaxBytes { embedder_name: String },
+    #[error("`.embedders.{embedder_name}.chunking`: {reason}")]
+    InvalidSettingsChunking { embedder_name: String, reason: String },
+    #[error("`.embedders.{embedder_name}.requestPolicy`: {reason}")]
+    InvalidSettingsRequestPolicy { embedder_name: String, reason: String },
     #[error("`.embedders.{embedder_name}.url`: could not parse `{url}`: {inner_error}")]
     InvalidUrl { embedder_name: String, inner_error: url::ParseError, url: String },
     #[error("Document editions cannot modify a document's primary key")]
@@ -444,6 +468,34 @@ pub enum GeoError {
     BadLongitude { document_id: Value, value: Value },
 }
 
+/// The maximum number of documents listed in a [`UserError::DocumentSchemaMismatch`] error.
+const MAX_REPORTED_SCHEMA_MISMATCHES: usize = 10;
+/// The maximum number of violations listed per document in a [`UserError::DocumentSchemaMismatch`] error.
+const MAX_REPORTED_SCHEMA_VIOLATIONS: usize = 10;
+
+fn format_schema_mismatches(mismatches: &[SchemaMismatch]) -> String {
+    let mut result = match mismatches.len() {
+        1 => String::from("1 document does not match the `documentSchema` of the index:"),
+        n => format!("{n} documents do not match the `documentSchema` of the index:"),
+    };
+    for SchemaMismatch { document_id, violations } in
+        mismatches.iter().take(MAX_REPORTED_SCHEMA_MISMATCHES)
+    {
+        for violation in violations.iter().take(MAX_REPORTED_SCHEMA_VIOLATIONS) {
+            write!(result, "\n  - document `{document_id}` at {violation}").unwrap();
+        }
+        let remaining = violations.len().saturating_sub(MAX_REPORTED_SCHEMA_VIOLATIONS);
+        if remaining > 0 {
+            write!(result, "\n  - document `{document_id}`: {remaining} more violations").unwrap();
+        }
+    }
+    let remaining = mismatches.len().saturating_sub(MAX_REPORTED_SCHEMA_MISMATCHES);
+    if remaining > 0 {
+        write!(result, "\n  - {remaining} more documents").unwrap();
+    }
+    result
+}
+
 #[allow(dead_code)]
 fn format_invalid_filter_distribution(
     invalid_facets_name: &BTreeSet<String>,
@@ -20,6 +20,8 @@ pub struct Metadata {
     pub sortable: bool,
     /// The field is defined as the distinct attribute.
     pub distinct: bool,
+    /// The field is defined as the ttl attribute.
+    pub ttl: bool,
     /// The field has been defined as asc/desc in the ranking rules.
     pub asc_desc: bool,
     /// The field is a geo field (`_geo`, `_geo.lat`, `_geo.lng`).
@@ -173,6 +175,10 @@ impl Metadata {
         self.distinct
     }
 
+    pub fn is_ttl(&self) -> bool {
+        self.ttl
+    }
+
     pub fn is_asc_desc(&self) -> bool {
         self.asc_desc
     }
@@ -181,9 +187,9 @@ impl Metadata {
         self.geo
     }
 
-    /// Returns `true` if the field is part of the facet databases. (sortable, distinct, asc_desc, filterable or facet searchable)
+    /// Returns `true` if the field is part of the facet databases. (sortable, distinct, ttl, asc_desc, filterable or facet searchable)
     pub fn is_faceted(&self, rules: &[FilterableAttributesRule]) -> bool {
-        if self.is_distinct() || self.is_sortable() || self.is_asc_desc() {
+        if self.is_distinct() || self.is_ttl() || self.is_sortable() || self.is_asc_desc() {
             return true;
         }
 
@@ -198,7 +204,10 @@ impl Metadata {
     pub fn require_facet_level_database(&self, rules: &[FilterableAttributesRule]) -> bool {
         let features = self.filterable_attributes_features(rules);
 
-        self.is_sortable() || self.is_asc_desc() || features.is_filterable_comparison()
+        self.is_sortable()
+            || self.is_asc_desc()
+            || self.is_ttl()
+            || features.is_filterable_comparison()
     }
 }
 
@@ -209,6 +218,7 @@ pub struct MetadataBuilder {
     sortable_attributes: HashSet<String>,
     localized_attributes: Option<Vec<LocalizedAttributesRule>>,
     distinct_attribute: Option<String>,
+    ttl_attribute: Option<String>,
     asc_desc_attributes: HashSet<String>,
 }
 
@@ -221,6 +231,7 @@ impl MetadataBuilder {
         let sortable_attributes = index.sortable_fields(rtxn)?;
         let localized_attributes = index.localized_attributes_rules(rtxn)?;
         let distinct_attribute = index.distinct_field(rtxn)?.map(|s| s.to_string());
+        let ttl_attribute = index.ttl_field(rtxn)?.map(|s| s.to_string());
         let asc_desc_attributes = index.asc_desc_fields(rtxn)?;
 
         Ok(Self::new(
@@ -229,6 +240,7 @@ impl MetadataBuilder {
             sortable_attributes,
             localized_attributes,
             distinct_attribute,
+            ttl_attribute,
             asc_desc_attributes,
         ))
     }
@@ -242,6 +254,7 @@ impl MetadataBuilder {
         sortable_attributes: HashSet<String>,
         localized_attributes: Option<Vec<LocalizedAttributesRule>>,
         distinct_attribute: Option<String>,
+        ttl_attribute: Option<String>,
         asc_desc_attributes: HashSet<String>,
     ) -> Self {
         let searchable_attributes = match searchable_attributes {
@@ -256,6 +269,7 @@ impl MetadataBuilder {
             sortable_attributes,
             localized_attributes,
             distinct_attribute,
+            ttl_attribute,
             asc_desc_attributes,
         }
     }
@@ -267,6 +281,7 @@ impl MetadataBuilder {
                 searchable: None,
                 sortable: false,
                 distinct: false,
+                ttl: false,
                 asc_desc: false,
                 geo: false,
                 localized_attributes_rule_id: None,
@@ -293,6 +308,7 @@ impl MetadataBuilder {
                 searchable: None,
                 sortable,
                 distinct: false,
+                ttl: false,
                 asc_desc: false,
                 geo: true,
                 localized_attributes_rule_id: None,
@@ -312,6 +328,7 @@ impl MetadataBuilder {
 
         let distinct =
             self.distinct_attribute.as_ref().is_some_and(|distinct_field| field == distinct_field);
+        let ttl = self.ttl_attribute.as_ref().is_some_and(|ttl_field| field == ttl_field);
         let asc_desc = self.asc_desc_attributes.contains(field);
 
         let localized_attributes_rule_id = self
@@ -326,6 +343,7 @@ impl MetadataBuilder {
             searchable,
             sortable,
             distinct,
+            ttl,
             asc_desc,
             geo: false,
             localized_attributes_rule_id,
@@ -295,13 +295,14 @@ pub fn matching_features(
     None
 }
 
-/// Match a field against a set of filterable, facet searchable fields, distinct field, sortable fields, and asc_desc fields.
+/// Match a field against a set of filterable, facet searchable fields, distinct field, ttl field, sortable fields, and asc_desc fields.
 pub fn match_faceted_field(
     field_name: &str,
     filterable_fields: &[FilterableAttributesRule],
     sortable_fields: &HashSet<String>,
     asc_desc_fields: &HashSet<String>,
     distinct_field: &Option<String>,
+    ttl_field: &Option<String>,
 ) -> PatternMatch {
     // Check if the field matches any filterable or facet searchable field
     let mut selection = match_pattern_by_features(field_name, filterable_fields, &|features| {
@@ -313,10 +314,12 @@ pub fn match_faceted_field(
         return selection;
     }
 
-    match match_distinct_field(distinct_field.as_deref(), field_name) {
-        PatternMatch::Match => return PatternMatch::Match,
-        PatternMatch::Parent => selection = PatternMatch::Parent,
-        PatternMatch::NoMatch => (),
+    for field in [distinct_field, ttl_field] {
+        match match_distinct_field(field.as_deref(), field_name) {
+            PatternMatch::Match => return PatternMatch::Match,
+            PatternMatch::Parent => selection = PatternMatch::Parent,
+            PatternMatch::NoMatch => (),
+        }
     }
 
     // Otherwise, check if the field matches any sortable/asc_desc field
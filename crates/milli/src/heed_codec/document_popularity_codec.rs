@@ -0,0 +1,42 @@
+use std::borrow::Cow;
+
+use heed::{BoxedError, BytesDecode, BytesEncode};
+
+use crate::index::DocumentPopularity;
+
+const DOCUMENT_POPULARITY_SIZE: usize = std::mem::size_of::<u64>() + std::mem::size_of::<f64>();
+
+#[derive(thiserror::Error, Debug)]
+#[error(
+    "Could not decode the document popularity: Expected {DOCUMENT_POPULARITY_SIZE} bytes but instead received {0} bytes"
+)]
+pub struct DecodeDocumentPopularityError(usize);
+
+/// Encodes the impressions as a big-endian `u64` followed by the engagement as a big-endian `f64`.
+pub struct DocumentPopularityCodec;
+
+impl<'a> BytesEncode<'a> for DocumentPopularityCodec {
+    type EItem = DocumentPopularity;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError> {
+        let mut ret = Vec::with_capacity(DOCUMENT_POPULARITY_SIZE);
+        ret.extend(&item.impressions.to_be_bytes());
+        ret.extend(&item.engagement.to_be_bytes());
+        Ok(Cow::Owned(ret))
+    }
+}
+
+impl<'a> BytesDecode<'a> for DocumentPopularityCodec {
+    type DItem = DocumentPopularity;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        if bytes.len() != DOCUMENT_POPULARITY_SIZE {
+            return Err(Box::new(DecodeDocumentPopularityError(bytes.len())));
+        }
+        let (impressions, engagement) = bytes.split_at(std::mem::size_of::<u64>());
+        Ok(DocumentPopularity {
+            impressions: u64::from_be_bytes(impressions.try_into().unwrap()),
+            engagement: f64::from_be_bytes(engagement.try_into().unwrap()),
+        })
+    }
+}
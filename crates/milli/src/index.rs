@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
+use std::ops::Bound;
 use std::path::Path;
 
 use heed::{types::*, DatabaseStat, WithoutTls};
@@ -21,9 +22,12 @@ use crate::heed_codec::facet::{
     FieldIdCodec, OrderedF64Codec,
 };
 use crate::heed_codec::version::VersionCodec;
-use crate::heed_codec::{BEU16StrCodec, FstSetCodec, StrBEU16Codec, StrRefCodec};
+use crate::heed_codec::{
+    BEU16StrCodec, DocumentPopularityCodec, FstSetCodec, StrBEU16Codec, StrRefCodec,
+};
 use crate::order_by_map::OrderByMap;
 use crate::proximity::ProximityPrecision;
+use crate::vector::sparse::SparseVectorStore;
 use crate::vector::{ArroyStats, ArroyWrapper, Embedding, EmbeddingConfig};
 use crate::{
     default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
@@ -41,6 +45,8 @@ pub mod main_key {
     pub const CRITERIA_KEY: &str = "criteria";
     pub const DISPLAYED_FIELDS_KEY: &str = "displayed-fields";
     pub const DISTINCT_FIELD_KEY: &str = "distinct-field-key";
+    pub const TTL_FIELD_KEY: &str = "ttl-field-key";
+    pub const DOCUMENT_SCHEMA_KEY: &str = "document-schema";
     pub const DOCUMENTS_IDS_KEY: &str = "documents-ids";
     pub const HIDDEN_FACETED_FIELDS_KEY: &str = "hidden-faceted-fields";
     pub const FILTERABLE_FIELDS_KEY: &str = "filterable-fields";
@@ -73,6 +79,7 @@ pub mod main_key {
     pub const PAGINATION_MAX_TOTAL_HITS: &str = "pagination-max-total-hits";
     pub const PROXIMITY_PRECISION: &str = "proximity-precision";
     pub const EMBEDDING_CONFIGS: &str = "embedding_configs";
+    pub const SPARSE_EMBEDDING_CONFIGS: &str = "sparse_embedding_configs";
     pub const SEARCH_CUTOFF: &str = "search_cutoff";
     pub const LOCALIZED_ATTRIBUTES_RULES: &str = "localized_attributes_rules";
     pub const FACET_SEARCH: &str = "facet_search";
@@ -105,7 +112,11 @@ pub mod db_name {
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
     pub const VECTOR_EMBEDDER_CATEGORY_ID: &str = "vector-embedder-category-id";
     pub const VECTOR_ARROY: &str = "vector-arroy";
+    pub const VECTOR_INT8: &str = "vector-int8";
+    pub const SPARSE_VECTORS: &str = "sparse-vectors";
     pub const DOCUMENTS: &str = "documents";
+    pub const DOCUMENTS_VERSIONS: &str = "documents-versions";
+    pub const DOCUMENT_POPULARITY: &str = "document-popularity";
 }
 
 #[derive(Clone)]
@@ -171,9 +182,18 @@ pub struct Index {
     pub embedder_category_id: Database<Str, U8>,
     /// Vector store based on arroy™.
     pub vector_arroy: arroy::Database<Unspecified>,
+    /// Int8 copies of the embeddings of the embedders with int8 rescoring, see [`crate::vector::scalar`].
+    pub vector_int8: Database<Bytes, Bytes>,
+    /// Inverted index of the sparse embeddings, see [`SparseVectorStore`].
+    pub sparse_vectors: Database<Bytes, Bytes>,
 
     /// Maps the document id to the document as an obkv store.
     pub(crate) documents: Database<BEU32, ObkvCodec>,
+    /// Maps the document id to its version, incremented each time the document is written.
+    pub(crate) documents_versions: Database<BEU32, BEU64>,
+
+    /// Maps the document id to the impressions and engagement it received in the search results.
+    pub document_popularity: Database<BEU32, DocumentPopularityCodec>,
 }
 
 impl Index {
@@ -186,7 +206,7 @@ impl Index {
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(25);
+        options.max_dbs(28);
 
         let env = unsafe { options.open(path) }?;
         let mut wtxn = env.write_txn()?;
@@ -228,8 +248,12 @@ impl Index {
         let embedder_category_id =
             env.create_database(&mut wtxn, Some(VECTOR_EMBEDDER_CATEGORY_ID))?;
         let vector_arroy = env.create_database(&mut wtxn, Some(VECTOR_ARROY))?;
+        let vector_int8 = env.create_database(&mut wtxn, Some(VECTOR_INT8))?;
+        let sparse_vectors = env.create_database(&mut wtxn, Some(SPARSE_VECTORS))?;
 
         let documents = env.create_database(&mut wtxn, Some(DOCUMENTS))?;
+        let documents_versions = env.create_database(&mut wtxn, Some(DOCUMENTS_VERSIONS))?;
+        let document_popularity = env.create_database(&mut wtxn, Some(DOCUMENT_POPULARITY))?;
 
         let this = Index {
             env: env.clone(),
@@ -255,8 +279,12 @@ impl Index {
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_int8,
+            sparse_vectors,
             embedder_category_id,
             documents,
+            documents_versions,
+            document_popularity,
         };
         if this.get_version(&wtxn)?.is_none() && creation {
             this.put_version(
@@ -1016,6 +1044,67 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(wtxn, main_key::DISTINCT_FIELD_KEY)
     }
 
+    /* ttl field */
+
+    pub(crate) fn put_ttl_field(&self, wtxn: &mut RwTxn<'_>, ttl_field: &str) -> heed::Result<()> {
+        self.main.remap_types::<Str, Str>().put(wtxn, main_key::TTL_FIELD_KEY, ttl_field)
+    }
+
+    /// Returns the field containing the Unix timestamp, in seconds, at which a document expires.
+    pub fn ttl_field<'a>(&self, rtxn: &'a RoTxn<'_>) -> heed::Result<Option<&'a str>> {
+        self.main.remap_types::<Str, Str>().get(rtxn, main_key::TTL_FIELD_KEY)
+    }
+
+    pub(crate) fn delete_ttl_field(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::TTL_FIELD_KEY)
+    }
+
+    /// Returns the ids of the documents that expired at `now`, a Unix timestamp in seconds.
+    ///
+    /// Returns an empty bitmap when no ttl field is defined.
+    pub fn expired_documents_ids(&self, rtxn: &RoTxn<'_>, now: f64) -> Result<RoaringBitmap> {
+        let mut expired = RoaringBitmap::new();
+        let Some(ttl_field) = self.ttl_field(rtxn)? else { return Ok(expired) };
+        let Some(field_id) = self.fields_ids_map(rtxn)?.id(ttl_field) else { return Ok(expired) };
+
+        crate::search::facet::find_docids_of_facet_within_bounds::<OrderedF64Codec>(
+            rtxn,
+            self.facet_id_f64_docids,
+            field_id,
+            &Bound::Unbounded,
+            &Bound::Included(now),
+            None,
+            &mut expired,
+        )?;
+
+        Ok(expired)
+    }
+
+    /* document schema */
+
+    pub(crate) fn put_document_schema(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        schema: &serde_json::Value,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<serde_json::Value>>().put(
+            wtxn,
+            main_key::DOCUMENT_SCHEMA_KEY,
+            schema,
+        )
+    }
+
+    /// Returns the JSON schema the documents sent to the index must match, as defined by the user.
+    pub fn document_schema(&self, rtxn: &RoTxn<'_>) -> heed::Result<Option<serde_json::Value>> {
+        self.main
+            .remap_types::<Str, SerdeJson<serde_json::Value>>()
+            .get(rtxn, main_key::DOCUMENT_SCHEMA_KEY)
+    }
+
+    pub(crate) fn delete_document_schema(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::DOCUMENT_SCHEMA_KEY)
+    }
+
     /* criteria */
 
     pub(crate) fn put_criteria(
@@ -1275,6 +1364,63 @@ impl Index {
         self.word_docids.remap_data_type::<RoaringBitmapLenCodec>().get(rtxn, word)
     }
 
+    /* documents versions */
+
+    /// Returns the version of the document with the given internal id,
+    /// `0` if the document doesn't exist or was written before documents were versioned.
+    pub fn document_version(&self, rtxn: &RoTxn<'_>, docid: DocumentId) -> heed::Result<u64> {
+        Ok(self.documents_versions.get(rtxn, &docid)?.unwrap_or(0))
+    }
+
+    /// Returns an iterator over the internal ids and versions of the versioned documents.
+    pub fn documents_versions<'t>(
+        &self,
+        rtxn: &'t RoTxn<'t>,
+    ) -> heed::Result<impl Iterator<Item = heed::Result<(DocumentId, u64)>> + 't> {
+        self.documents_versions.iter(rtxn)
+    }
+
+    /// Overwrites the version of the document with the given internal id,
+    /// used to restore the versions of the documents imported from a dump.
+    pub fn put_document_version(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        docid: DocumentId,
+        version: u64,
+    ) -> heed::Result<()> {
+        self.documents_versions.put(wtxn, &docid, &version)
+    }
+
+    /* document popularity */
+
+    /// Returns the impressions and engagement of the document with the given internal id.
+    pub fn document_popularity(
+        &self,
+        rtxn: &RoTxn<'_>,
+        docid: DocumentId,
+    ) -> heed::Result<Option<DocumentPopularity>> {
+        self.document_popularity.get(rtxn, &docid)
+    }
+
+    /// Adds the impressions and engagement of `increment` to the ones of the document
+    /// with the given internal id and returns the new totals.
+    ///
+    /// The popularity is deleted with the document.
+    pub fn increment_document_popularity(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        docid: DocumentId,
+        increment: DocumentPopularity,
+    ) -> heed::Result<DocumentPopularity> {
+        let current = self.document_popularity.get(wtxn, &docid)?.unwrap_or_default();
+        let popularity = DocumentPopularity {
+            impressions: current.impressions + increment.impressions,
+            engagement: current.engagement + increment.engagement,
+        };
+        self.document_popularity.put(wtxn, &docid, &popularity)?;
+        Ok(popularity)
+    }
+
     /* documents */
 
     /// Returns a document by using the document id.
@@ -1679,6 +1825,48 @@ impl Index {
             .unwrap_or_default())
     }
 
+    /// Put the configs of the sparse embedders.
+    ///
+    /// They are kept apart from the other embedding configs, as sparse embeddings are not stored in arroy.
+    pub(crate) fn put_sparse_embedding_configs(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        configs: Vec<IndexEmbeddingConfig>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<Vec<IndexEmbeddingConfig>>>().put(
+            wtxn,
+            main_key::SPARSE_EMBEDDING_CONFIGS,
+            &configs,
+        )
+    }
+
+    pub(crate) fn delete_sparse_embedding_configs(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SPARSE_EMBEDDING_CONFIGS)
+    }
+
+    pub fn sparse_embedding_configs(&self, rtxn: &RoTxn<'_>) -> Result<Vec<IndexEmbeddingConfig>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<Vec<IndexEmbeddingConfig>>>()
+            .get(rtxn, main_key::SPARSE_EMBEDDING_CONFIGS)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the store of the sparse embeddings of the given embedder, if it exists.
+    pub fn sparse_vector_store(
+        &self,
+        rtxn: &RoTxn<'_>,
+        embedder_name: &str,
+    ) -> Result<Option<SparseVectorStore>> {
+        Ok(self
+            .embedder_category_id
+            .get(rtxn, embedder_name)?
+            .map(|embedder_id| SparseVectorStore::new(self.sparse_vectors, embedder_id)))
+    }
+
     pub(crate) fn put_search_cutoff(&self, wtxn: &mut RwTxn<'_>, cutoff: u64) -> heed::Result<()> {
         self.main.remap_types::<Str, BEU64>().put(wtxn, main_key::SEARCH_CUTOFF, &cutoff)
     }
@@ -1700,8 +1888,12 @@ impl Index {
         let embedding_configs = self.embedding_configs(rtxn)?;
         for config in embedding_configs {
             let embedder_id = self.embedder_category_id.get(rtxn, &config.name)?.unwrap();
-            let reader =
-                ArroyWrapper::new(self.vector_arroy, embedder_id, config.config.quantized());
+            let reader = ArroyWrapper::new(
+                self.vector_arroy,
+                self.vector_int8,
+                embedder_id,
+                config.config.quantized(),
+            );
             let embeddings = reader.item_vectors(rtxn, docid)?;
             res.insert(config.name.to_owned(), embeddings);
         }
@@ -1718,8 +1910,12 @@ impl Index {
         let embedding_configs = self.embedding_configs(rtxn)?;
         for config in embedding_configs {
             let embedder_id = self.embedder_category_id.get(rtxn, &config.name)?.unwrap();
-            let reader =
-                ArroyWrapper::new(self.vector_arroy, embedder_id, config.config.quantized());
+            let reader = ArroyWrapper::new(
+                self.vector_arroy,
+                self.vector_int8,
+                embedder_id,
+                config.config.quantized(),
+            );
             reader.aggregate_stats(rtxn, &mut stats)?;
         }
         Ok(stats)
@@ -1764,8 +1960,12 @@ impl Index {
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_int8,
+            sparse_vectors,
             embedder_category_id,
             documents,
+            documents_versions,
+            document_popularity,
         } = self;
 
         fn compute_size(stats: DatabaseStat) -> usize {
@@ -1835,8 +2035,12 @@ impl Index {
             field_id_docid_facet_strings.stat(rtxn).map(compute_size)?,
         );
         sizes.insert("vector_arroy", vector_arroy.stat(rtxn).map(compute_size)?);
+        sizes.insert("vector_int8", vector_int8.stat(rtxn).map(compute_size)?);
+        sizes.insert("sparse_vectors", sparse_vectors.stat(rtxn).map(compute_size)?);
         sizes.insert("embedder_category_id", embedder_category_id.stat(rtxn).map(compute_size)?);
         sizes.insert("documents", documents.stat(rtxn).map(compute_size)?);
+        sizes.insert("documents_versions", documents_versions.stat(rtxn).map(compute_size)?);
+        sizes.insert("document_popularity", document_popularity.stat(rtxn).map(compute_size)?);
 
         Ok(sizes)
     }
@@ -1864,6 +2068,33 @@ pub enum PrefixSearch {
     Disabled,
 }
 
+/// Number of impressions without engagement every document is assumed to start with,
+/// so that a document clicked once out of a handful of impressions does not outrank
+/// the documents with a slightly lower click-through rate over thousands of impressions.
+const POPULARITY_PRIOR_IMPRESSIONS: f64 = 10.0;
+
+/// How often a document is engaged with when it is returned by a search,
+/// maintained from the searches and the click and conversion events.
+///
+/// It is aggregated over all the queries that returned the document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentPopularity {
+    /// Number of times the document was returned in the hits of a search.
+    pub impressions: u64,
+    /// The clicks and conversions on the document, weighted by their kind.
+    pub engagement: f64,
+}
+
+impl DocumentPopularity {
+    /// The weighted engagement per impression, smoothed by [`POPULARITY_PRIOR_IMPRESSIONS`].
+    ///
+    /// This is the score the `popularity` ranking rule sorts the documents by.
+    pub fn click_through_rate(&self) -> f64 {
+        self.engagement / (self.impressions as f64 + POPULARITY_PRIOR_IMPRESSIONS)
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
 struct OffsetDateTime(#[serde(with = "time::serde::rfc3339")] time::OffsetDateTime);
@@ -73,13 +73,14 @@ pub use self::heed_codec::{
     RoaringBitmapCodec, RoaringBitmapLenCodec, StrBEU32Codec, U8StrStrCodec,
     UncheckedU8StrStrCodec,
 };
-pub use self::index::Index;
+pub use self::index::{DocumentPopularity, Index};
 pub use self::localized_attributes_rules::LocalizedAttributesRule;
 pub use self::search::facet::{FacetValueHit, SearchForFacetValues};
-pub use self::search::similar::Similar;
+pub use self::search::similar::{Similar, SimilarExample};
 pub use self::search::{
-    FacetDistribution, Filter, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OrderBy,
-    Search, SearchResult, SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    CropMode, FacetDistribution, Filter, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords,
+    OrderBy, Search, SearchResult, SemanticSearch, Snippet, TermsMatchingStrategy,
+    DEFAULT_VALUES_PER_FACET,
 };
 pub use self::update::ChannelCongestion;
 
@@ -28,12 +28,14 @@ struct InnerProgress {
     steps: Vec<(TypeId, Box<dyn Step>, Instant)>,
     /// The durations associated to each steps.
     durations: Vec<(String, Duration)>,
+    /// Other measures reported in the trace along with the durations.
+    metrics: Vec<(String, String)>,
 }
 
 impl Progress {
     pub fn update_progress<P: Step>(&self, sub_progress: P) {
         let mut inner = self.steps.write().unwrap();
-        let InnerProgress { steps, durations } = &mut *inner;
+        let InnerProgress { steps, durations, .. } = &mut *inner;
 
         let now = Instant::now();
         let step_type = TypeId::of::<P>();
@@ -68,14 +70,23 @@ impl Progress {
         ProgressView { steps: step_view, percentage: percentage * 100.0 }
     }
 
+    /// Reports a measure that is not a duration, such as a count, in the trace of the progress.
+    pub fn record_metric(&self, name: String, value: String) {
+        self.steps.write().unwrap().metrics.push((name, value));
+    }
+
     pub fn accumulated_durations(&self) -> IndexMap<String, String> {
         let mut inner = self.steps.write().unwrap();
-        let InnerProgress { steps, durations, .. } = &mut *inner;
+        let InnerProgress { steps, durations, metrics } = &mut *inner;
 
         let now = Instant::now();
         push_steps_durations(steps, durations, now, 0);
 
-        durations.drain(..).map(|(name, duration)| (name, format!("{duration:.2?}"))).collect()
+        durations
+            .drain(..)
+            .map(|(name, duration)| (name, format!("{duration:.2?}")))
+            .chain(metrics.drain(..))
+            .collect()
     }
 
     // TODO: ideally we should expose the progress in a way that let arroy use it directly
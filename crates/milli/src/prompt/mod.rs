@@ -8,33 +8,42 @@ use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::num::NonZeroUsize;
+use std::sync::RwLock;
 
+use bumpalo::collections::Vec as BVec;
 use bumpalo::Bump;
+use bumparaw_collections::RawMap;
 use document::ParseableDocument;
 use error::{NewPromptError, RenderPromptError};
 use fields::{BorrowedFields, OwnedFields};
+use rustc_hash::FxBuildHasher;
 
 use self::context::Context;
 use self::document::Document;
 use crate::fields_ids_map::metadata::FieldIdMapWithMetadata;
 use crate::update::del_add::DelAdd;
-use crate::GlobalFieldsIdsMap;
+use crate::update::new::document::{Document as _, DocumentFromVersions, Versions};
+use crate::vector::chunking::ChunkingSettings;
+use crate::{GlobalFieldsIdsMap, InternalError, Object};
 
 pub struct Prompt {
     template: liquid::Template,
     template_text: String,
     max_bytes: Option<NonZeroUsize>,
+    chunking: Option<ChunkingSettings>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PromptData {
     pub template: String,
     pub max_bytes: Option<NonZeroUsize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking: Option<ChunkingSettings>,
 }
 
 impl From<Prompt> for PromptData {
     fn from(value: Prompt) -> Self {
-        Self { template: value.template_text, max_bytes: value.max_bytes }
+        Self { template: value.template_text, max_bytes: value.max_bytes, chunking: value.chunking }
     }
 }
 
@@ -42,7 +51,7 @@ impl TryFrom<PromptData> for Prompt {
     type Error = NewPromptError;
 
     fn try_from(value: PromptData) -> Result<Self, Self::Error> {
-        Prompt::new(value.template, value.max_bytes)
+        Ok(Prompt::new(value.template, value.max_bytes)?.with_chunking(value.chunking))
     }
 }
 
@@ -53,6 +62,7 @@ impl Clone for Prompt {
             template: new_template(&template_text).unwrap(),
             template_text,
             max_bytes: self.max_bytes,
+            chunking: self.chunking.clone(),
         }
     }
 }
@@ -83,13 +93,18 @@ impl Default for Prompt {
             template: default_template(),
             template_text: default_template_text().into(),
             max_bytes: Some(default_max_bytes()),
+            chunking: None,
         }
     }
 }
 
 impl Default for PromptData {
     fn default() -> Self {
-        Self { template: default_template_text().into(), max_bytes: Some(default_max_bytes()) }
+        Self {
+            template: default_template_text().into(),
+            max_bytes: Some(default_max_bytes()),
+            chunking: None,
+        }
     }
 }
 
@@ -103,6 +118,7 @@ impl Prompt {
                 .map_err(NewPromptError::cannot_parse_template)?,
             template_text: template,
             max_bytes,
+            chunking: None,
         };
 
         // render template with special object that's OK with `doc.*` and `fields.*`
@@ -113,6 +129,66 @@ impl Prompt {
         Ok(this)
     }
 
+    pub fn with_chunking(mut self, chunking: Option<ChunkingSettings>) -> Self {
+        self.chunking = chunking;
+        self
+    }
+
+    pub fn template(&self) -> &str {
+        &self.template_text
+    }
+
+    pub fn chunking(&self) -> Option<&ChunkingSettings> {
+        self.chunking.as_ref()
+    }
+
+    /// Renders the texts to embed for a document: a single text, or one text per chunk if chunking is enabled.
+    pub fn render_chunks<
+        'a,       // lifetime of the borrow of the document
+        'doc: 'a, // lifetime of the allocator, will live for an entire chunk of documents
+    >(
+        &self,
+        external_docid: &str,
+        document: impl crate::update::new::document::Document<'a> + Debug,
+        field_id_map: &RefCell<GlobalFieldsIdsMap>,
+        doc_alloc: &'doc Bump,
+    ) -> crate::Result<&'doc [&'doc str]> {
+        let text = self.render_chunked_text(external_docid, document, field_id_map, doc_alloc)?;
+        let chunks = match &self.chunking {
+            Some(chunking) => BVec::from_iter_in(
+                chunking.split(text).into_iter().map(|range| &text[range]),
+                doc_alloc,
+            ),
+            None => BVec::from_iter_in(std::iter::once(text), doc_alloc),
+        };
+        Ok(chunks.into_bump_slice())
+    }
+
+    /// Renders the whole text that is split into chunks.
+    ///
+    /// This is the value of the chunked field if any, or the document rendered through the template.
+    pub fn render_chunked_text<
+        'a,       // lifetime of the borrow of the document
+        'doc: 'a, // lifetime of the allocator, will live for an entire chunk of documents
+    >(
+        &self,
+        external_docid: &str,
+        document: impl crate::update::new::document::Document<'a> + Debug,
+        field_id_map: &RefCell<GlobalFieldsIdsMap>,
+        doc_alloc: &'doc Bump,
+    ) -> crate::Result<&'doc str> {
+        match self.chunking.as_ref().and_then(|chunking| chunking.field.as_deref()) {
+            Some(field) => {
+                let text = document
+                    .top_level_field(field)?
+                    .map(|value| field_text(value.get()))
+                    .unwrap_or_default();
+                Ok(doc_alloc.alloc_str(&text))
+            }
+            None => Ok(self.render_document(external_docid, document, field_id_map, doc_alloc)?),
+        }
+    }
+
     pub fn render_document<
         'a,       // lifetime of the borrow of the document
         'doc: 'a, // lifetime of the allocator, will live for an entire chunk of documents
@@ -140,6 +216,58 @@ impl Prompt {
             .expect("render can only write UTF-8 because all inputs and processing preserve utf-8"))
     }
 
+    /// Renders a JSON document that is not necessarily stored in the index.
+    ///
+    /// The chunking of the prompt is ignored: the whole document is rendered through the template,
+    /// and the text is truncated to the maximum number of bytes of the prompt.
+    pub fn render_json_document(
+        &self,
+        external_docid: &str,
+        document: &Object,
+        fields_ids_map: FieldIdMapWithMetadata,
+    ) -> crate::Result<String> {
+        let mut rendered =
+            self.render_json_document_untruncated(external_docid, document, fields_ids_map)?;
+        if let Some(max_bytes) = self.max_bytes {
+            truncate(&mut rendered, max_bytes.get());
+        }
+        Ok(rendered)
+    }
+
+    /// Renders a JSON document through the template, without truncating the text.
+    pub fn render_json_document_untruncated(
+        &self,
+        external_docid: &str,
+        document: &Object,
+        fields_ids_map: FieldIdMapWithMetadata,
+    ) -> crate::Result<String> {
+        let doc_alloc = Bump::new();
+        let document =
+            serde_json::value::to_raw_value(document).map_err(InternalError::SerdeJson)?;
+        let document = RawMap::from_raw_value_and_hasher(&document, FxBuildHasher, &doc_alloc)
+            .map_err(InternalError::SerdeJson)?;
+        let versions = Versions::single(document);
+        let document = DocumentFromVersions::new(&versions);
+
+        let fields_ids_map = RwLock::new(fields_ids_map);
+        let fields_ids_map = RefCell::new(GlobalFieldsIdsMap::new(&fields_ids_map));
+
+        Ok(self.render_document(external_docid, document, &fields_ids_map, &doc_alloc)?.to_owned())
+    }
+
+    /// The byte offset at which a rendered text is truncated before being embedded, if it is.
+    ///
+    /// Texts are never truncated when chunking is enabled.
+    pub fn truncation_point(&self, rendered: &str) -> Option<usize> {
+        match (self.max_bytes, &self.chunking) {
+            (Some(max_bytes), None) => truncation_point(rendered, max_bytes.get()),
+            _ => None,
+        }
+    }
+
+    /// Renders the text to embed for a document.
+    ///
+    /// If chunking is enabled, the returned text is not truncated and must be split into chunks by the caller.
     pub fn render_kvdeladd(
         &self,
         document: &obkv::KvReaderU16,
@@ -147,28 +275,43 @@ impl Prompt {
         field_id_map: &FieldIdMapWithMetadata,
     ) -> Result<String, RenderPromptError> {
         let document = Document::new(document, side, field_id_map.as_fields_ids_map());
+        if let Some(chunking) = &self.chunking {
+            if let Some(field) = &chunking.field {
+                return Ok(document
+                    .raw_field(field)
+                    .map(|raw| field_text(&String::from_utf8_lossy(raw)))
+                    .unwrap_or_default());
+            }
+        }
         let fields = OwnedFields::new(&document, field_id_map);
         let context = Context::new(&document, &fields);
 
         let mut rendered =
             self.template.render(&context).map_err(RenderPromptError::missing_context)?;
-        if let Some(max_bytes) = self.max_bytes {
+        if let (Some(max_bytes), None) = (self.max_bytes, &self.chunking) {
             truncate(&mut rendered, max_bytes.get());
         }
         Ok(rendered)
     }
 }
 
+/// Text of a chunked field: strings are used as is, other values as their JSON representation.
+fn field_text(raw: &str) -> String {
+    serde_json::from_str(raw).unwrap_or_else(|_| raw.to_string())
+}
+
 fn truncate(s: &mut String, max_bytes: usize) {
-    if max_bytes >= s.len() {
-        return;
+    if let Some(point) = truncation_point(s, max_bytes) {
+        s.truncate(point);
     }
-    for i in (0..=max_bytes).rev() {
-        if s.is_char_boundary(i) {
-            s.truncate(i);
-            break;
-        }
+}
+
+/// The last char boundary at or before `max_bytes`, if the string is longer than `max_bytes`.
+fn truncation_point(s: &str, max_bytes: usize) -> Option<usize> {
+    if max_bytes >= s.len() {
+        return None;
     }
+    (0..=max_bytes).rev().find(|&i| s.is_char_boundary(i))
 }
 
 #[cfg(test)]
@@ -17,6 +17,8 @@ pub enum ScoreDetails {
     Sort(Sort),
     Vector(Vector),
     GeoSort(GeoSort),
+    /// Returned for the documents reordered by a reranker after the ranking rules were applied
+    Rerank(Rerank),
 
     /// Returned when we don't have the time to finish applying all the subsequent ranking-rules
     Skipped,
@@ -62,10 +64,20 @@ impl ScoreDetails {
             ScoreDetails::Sort(_) => None,
             ScoreDetails::GeoSort(_) => None,
             ScoreDetails::Vector(_) => None,
+            ScoreDetails::Rerank(_) => None,
             ScoreDetails::Skipped => Some(Rank { rank: 0, max_rank: 1 }),
         }
     }
 
+    /// The global score the document had before being reordered by a reranker,
+    /// or `None` if it was not reranked.
+    pub fn original_global_score(details: &[Self]) -> Option<f64> {
+        match details.split_first() {
+            Some((ScoreDetails::Rerank(_), details)) => Some(Self::global_score(details.iter())),
+            _ => None,
+        }
+    }
+
     pub fn global_score<'a>(details: impl Iterator<Item = &'a Self> + 'a) -> f64 {
         Self::score_values(details)
             .find_map(|x| {
@@ -134,6 +146,7 @@ impl ScoreDetails {
             ScoreDetails::Vector(vector) => {
                 RankOrValue::Score(vector.similarity.as_ref().map(|s| *s as f64).unwrap_or(0.0f64))
             }
+            ScoreDetails::Rerank(rerank) => RankOrValue::Score(rerank.ranking_score),
             ScoreDetails::Skipped => RankOrValue::Rank(Rank { rank: 0, max_rank: 1 }),
         }
     }
@@ -291,6 +304,15 @@ impl ScoreDetails {
                     details_map.insert("vectorSort".into(), details);
                     order += 1;
                 }
+                ScoreDetails::Rerank(rerank) => {
+                    let details = serde_json::json!({
+                        "order": order,
+                        "score": rerank.score,
+                        "rankingScore": rerank.ranking_score,
+                    });
+                    details_map.insert("rerank".into(), details);
+                    order += 1;
+                }
                 ScoreDetails::Skipped => {
                     details_map
                         .insert("skipped".to_string(), serde_json::json!({ "order": order }));
@@ -545,6 +567,14 @@ pub struct Vector {
     pub similarity: Option<f32>,
 }
 
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Rerank {
+    /// The relevance score returned by the reranker.
+    pub score: f32,
+    /// The relevance score clamped to `[0, 1]`, used as the ranking score of the document.
+    pub ranking_score: f64,
+}
+
 impl GeoSort {
     pub fn distance(&self) -> Option<f64> {
         self.value.map(|value| distance_between_two_points(&self.target_point, &value))
@@ -1,12 +1,37 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use itertools::Itertools;
 use roaring::RoaringBitmap;
 
 use crate::score_details::{ScoreDetails, ScoreValue, ScoringStrategy};
+use crate::search::new::VectorQuery;
 use crate::search::SemanticSearch;
+use crate::vector::Embedder;
 use crate::{MatchingWords, Result, Search, SearchResult};
 
+/// How the keyword and semantic results of a hybrid search are merged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fusion {
+    /// Compares the ranking scores of the hits, weighted by the semantic ratio.
+    #[default]
+    WeightedScore,
+    /// Reciprocal Rank Fusion: compares the hits by their ranks in each list,
+    /// which does not require the ranking scores of both lists to be comparable.
+    ReciprocalRank { k: u32 },
+}
+
+/// The contribution of a hit at the 0-based `rank` of a list to its Reciprocal Rank Fusion score.
+pub fn reciprocal_rank(k: u32, rank: usize) -> f64 {
+    1.0 / (k as f64 + rank as f64 + 1.0)
+}
+
+#[derive(Clone, Copy)]
+enum ResultSource {
+    Semantic,
+    Keyword,
+}
+
 struct ScoreWithRatioResult {
     matching_words: MatchingWords,
     candidates: RoaringBitmap,
@@ -92,11 +117,6 @@ impl ScoreWithRatioResult {
         from: usize,
         length: usize,
     ) -> (SearchResult, u32) {
-        #[derive(Clone, Copy)]
-        enum ResultSource {
-            Semantic,
-            Keyword,
-        }
         let mut semantic_hit_count = 0;
 
         let mut documents_ids = Vec::with_capacity(
@@ -149,11 +169,104 @@ impl ScoreWithRatioResult {
             semantic_hit_count,
         )
     }
+
+    /// Merges the results by summing the reciprocal ranks of each hit in both lists, weighted by their ratio.
+    ///
+    /// The ranking score details of a hit are those of the list that contributed the most to its fused score.
+    #[tracing::instrument(level = "trace", skip_all, target = "search::hybrid")]
+    fn merge_reciprocal_rank(
+        vector_results: Self,
+        keyword_results: Self,
+        k: u32,
+        from: usize,
+        length: usize,
+    ) -> (SearchResult, u32) {
+        struct FusedHit {
+            docid: u32,
+            fused_score: f64,
+            best_contribution: f64,
+            score: Vec<ScoreDetails>,
+            source: ResultSource,
+        }
+
+        let mut fused: Vec<FusedHit> = Vec::with_capacity(
+            vector_results.document_scores.len() + keyword_results.document_scores.len(),
+        );
+        let mut positions = HashMap::with_capacity(fused.capacity());
+
+        for (document_scores, source) in [
+            (vector_results.document_scores, ResultSource::Semantic),
+            (keyword_results.document_scores, ResultSource::Keyword),
+        ] {
+            for (rank, (docid, (score, ratio))) in document_scores.into_iter().enumerate() {
+                let contribution = reciprocal_rank(k, rank) * ratio as f64;
+                match positions.get(&docid) {
+                    Some(&position) => {
+                        let hit: &mut FusedHit = &mut fused[position];
+                        hit.fused_score += contribution;
+                        if contribution > hit.best_contribution {
+                            hit.best_contribution = contribution;
+                            hit.score = score;
+                            hit.source = source;
+                        }
+                    }
+                    None => {
+                        positions.insert(docid, fused.len());
+                        fused.push(FusedHit {
+                            docid,
+                            fused_score: contribution,
+                            best_contribution: contribution,
+                            score,
+                            source,
+                        });
+                    }
+                }
+            }
+        }
+
+        // stable sort: ties are broken in favor of the semantic results, then by rank
+        fused.sort_by(|left, right| right.fused_score.total_cmp(&left.fused_score));
+
+        let mut semantic_hit_count = 0;
+        let (documents_ids, document_scores) = fused
+            .into_iter()
+            .skip(from)
+            .take(length)
+            .inspect(|hit| {
+                if let ResultSource::Semantic = hit.source {
+                    semantic_hit_count += 1;
+                }
+            })
+            .map(|hit| (hit.docid, hit.score))
+            .unzip();
+
+        (
+            SearchResult {
+                matching_words: keyword_results.matching_words,
+                candidates: vector_results.candidates | keyword_results.candidates,
+                documents_ids,
+                document_scores,
+                degraded: vector_results.degraded | keyword_results.degraded,
+                used_negative_operator: vector_results.used_negative_operator
+                    | keyword_results.used_negative_operator,
+            },
+            semantic_hit_count,
+        )
+    }
 }
 
 impl Search<'_> {
     #[tracing::instrument(level = "trace", skip_all, target = "search::hybrid")]
-    pub fn execute_hybrid(&self, semantic_ratio: f32) -> Result<(SearchResult, Option<u32>)> {
+    pub fn execute_hybrid(
+        &self,
+        semantic_ratio: f32,
+        fusion: Fusion,
+    ) -> Result<(SearchResult, Option<u32>)> {
+        if let Some(rerank) = &self.rerank {
+            return self
+                .execute_reranked(rerank, |search| search.execute_hybrid(semantic_ratio, fusion));
+        }
+
         // TODO: find classier way to achieve that than to reset vector and query params
         // create separate keyword and semantic searches
         let mut search = Search {
@@ -169,19 +282,24 @@ impl Search<'_> {
             scoring_strategy: ScoringStrategy::Detailed,
             words_limit: self.words_limit,
             exhaustive_number_hits: self.exhaustive_number_hits,
+            exhaustive_vector_search: self.exhaustive_vector_search,
             rtxn: self.rtxn,
             index: self.index,
             semantic: self.semantic.clone(),
             time_budget: self.time_budget.clone(),
             ranking_score_threshold: self.ranking_score_threshold,
             locales: self.locales.clone(),
+            rerank: None,
         };
 
         let semantic = search.semantic.take();
         let keyword_results = search.execute()?;
 
-        // completely skip semantic search if the results of the keyword search are good enough
-        if self.results_good_enough(&keyword_results, semantic_ratio) {
+        // completely skip semantic search if the results of the keyword search are good enough.
+        // Ranks don't tell whether the keyword results are good, so this only applies to score fusion.
+        if fusion == Fusion::WeightedScore
+            && self.results_good_enough(&keyword_results, semantic_ratio)
+        {
             return Ok(return_keyword_results(self.limit, self.offset, keyword_results));
         }
 
@@ -203,7 +321,16 @@ impl Search<'_> {
 
                 let deadline = std::time::Instant::now() + std::time::Duration::from_secs(3);
 
-                match embedder.embed_search(&query, Some(deadline)) {
+                let embedding = match embedder.as_ref() {
+                    Embedder::Sparse(embedder) => {
+                        embedder.embed_one(&query, Some(deadline)).map(VectorQuery::Sparse)
+                    }
+                    embedder => {
+                        embedder.embed_search(&query, Some(deadline)).map(VectorQuery::Dense)
+                    }
+                };
+
+                match embedding {
                     Ok(embedding) => embedding,
                     Err(error) => {
                         tracing::error!(error=%error, "Embedding failed");
@@ -226,8 +353,21 @@ impl Search<'_> {
         let keyword_results = ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
         let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
 
-        let (merge_results, semantic_hit_count) =
-            ScoreWithRatioResult::merge(vector_results, keyword_results, self.offset, self.limit);
+        let (merge_results, semantic_hit_count) = match fusion {
+            Fusion::WeightedScore => ScoreWithRatioResult::merge(
+                vector_results,
+                keyword_results,
+                self.offset,
+                self.limit,
+            ),
+            Fusion::ReciprocalRank { k } => ScoreWithRatioResult::merge_reciprocal_rank(
+                vector_results,
+                keyword_results,
+                k,
+                self.offset,
+                self.limit,
+            ),
+        };
         assert!(merge_results.documents_ids.len() <= self.limit);
         Ok((merge_results, Some(semantic_hit_count)))
     }
@@ -7,8 +7,11 @@ use once_cell::sync::Lazy;
 use roaring::bitmap::RoaringBitmap;
 
 pub use self::facet::{FacetDistribution, Filter, OrderBy, DEFAULT_VALUES_PER_FACET};
-pub use self::new::matches::{FormatOptions, MatchBounds, MatcherBuilder, MatchingWords};
-use self::new::{execute_vector_search, PartialSearchResult};
+pub use self::new::matches::{
+    CropMode, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, Snippet,
+};
+use self::new::{execute_vector_search, PartialSearchResult, VectorQuery};
+use self::rerank::RerankSearch;
 use crate::filterable_attributes_rules::{filtered_matching_patterns, matching_features};
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::vector::Embedder;
@@ -26,11 +29,12 @@ pub mod facet;
 mod fst_utils;
 pub mod hybrid;
 pub mod new;
+mod rerank;
 pub mod similar;
 
 #[derive(Debug, Clone)]
 pub struct SemanticSearch {
-    vector: Option<Vec<f32>>,
+    vector: Option<VectorQuery>,
     embedder_name: String,
     embedder: Arc<Embedder>,
     quantized: bool,
@@ -50,12 +54,14 @@ pub struct Search<'a> {
     scoring_strategy: ScoringStrategy,
     words_limit: usize,
     exhaustive_number_hits: bool,
+    exhaustive_vector_search: bool,
     rtxn: &'a heed::RoTxn<'a>,
     index: &'a Index,
     semantic: Option<SemanticSearch>,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
     locales: Option<Vec<Language>>,
+    rerank: Option<RerankSearch>,
 }
 
 impl<'a> Search<'a> {
@@ -72,6 +78,7 @@ impl<'a> Search<'a> {
             terms_matching_strategy: TermsMatchingStrategy::default(),
             scoring_strategy: Default::default(),
             exhaustive_number_hits: false,
+            exhaustive_vector_search: false,
             words_limit: 10,
             rtxn,
             index,
@@ -79,6 +86,7 @@ impl<'a> Search<'a> {
             locales: None,
             time_budget: TimeBudget::max(),
             ranking_score_threshold: None,
+            rerank: None,
         }
     }
 
@@ -94,6 +102,7 @@ impl<'a> Search<'a> {
         quantized: bool,
         vector: Option<Vec<f32>>,
     ) -> &mut Search<'a> {
+        let vector = vector.map(VectorQuery::Dense);
         self.semantic = Some(SemanticSearch { embedder_name, embedder, quantized, vector });
         self
     }
@@ -162,6 +171,15 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Forces the semantic search to compare the query vector with the embeddings of all the candidates,
+    /// instead of using the approximate nearest neighbors search.
+    ///
+    /// This is slower on large sets of candidates, but always returns the exact nearest neighbors.
+    pub fn exhaustive_vector_search(&mut self, exhaustive_vector_search: bool) -> &mut Search<'a> {
+        self.exhaustive_vector_search = exhaustive_vector_search;
+        self
+    }
+
     pub fn time_budget(&mut self, time_budget: TimeBudget) -> &mut Search<'a> {
         self.time_budget = time_budget;
         self
@@ -187,6 +205,12 @@ impl<'a> Search<'a> {
     }
 
     pub fn execute(&self) -> Result<SearchResult> {
+        if let Some(rerank) = &self.rerank {
+            let (result, ()) =
+                self.execute_reranked(rerank, |search| Ok((search.execute()?, ())))?;
+            return Ok(result);
+        }
+
         let mut ctx = SearchContext::new(self.index, self.rtxn)?;
 
         if let Some(searchable_attributes) = self.searchable_attributes {
@@ -221,6 +245,19 @@ impl<'a> Search<'a> {
             }
         }
 
+        // sparse embedders are not handled by the callers, the query is embedded here
+        let sparse_vector = match self.semantic.as_ref() {
+            Some(SemanticSearch { vector: None, embedder, .. }) => {
+                match (embedder.as_ref(), self.query.as_deref()) {
+                    (Embedder::Sparse(embedder), Some(query)) => Some(VectorQuery::Sparse(
+                        embedder.embed_one(query, None).map_err(crate::vector::Error::from)?,
+                    )),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
         let universe = filtered_universe(ctx.index, ctx.txn, &self.filter)?;
         let PartialSearchResult {
             located_query_terms,
@@ -230,7 +267,10 @@ impl<'a> Search<'a> {
             degraded,
             used_negative_operator,
         } = match self.semantic.as_ref() {
-            Some(SemanticSearch { vector: Some(vector), embedder_name, embedder, quantized }) => {
+            Some(SemanticSearch { vector, embedder_name, embedder, quantized })
+                if vector.is_some() || sparse_vector.is_some() =>
+            {
+                let vector = vector.as_ref().or(sparse_vector.as_ref()).unwrap();
                 execute_vector_search(
                     &mut ctx,
                     vector,
@@ -244,6 +284,7 @@ impl<'a> Search<'a> {
                     embedder_name,
                     embedder,
                     *quantized,
+                    self.exhaustive_vector_search,
                     self.time_budget.clone(),
                     self.ranking_score_threshold,
                 )?
@@ -301,12 +342,14 @@ impl fmt::Debug for Search<'_> {
             scoring_strategy,
             words_limit,
             exhaustive_number_hits,
+            exhaustive_vector_search,
             rtxn: _,
             index: _,
             semantic,
             time_budget,
             ranking_score_threshold,
             locales,
+            rerank,
         } = self;
         f.debug_struct("Search")
             .field("query", query)
@@ -320,6 +363,7 @@ impl fmt::Debug for Search<'_> {
             .field("terms_matching_strategy", terms_matching_strategy)
             .field("scoring_strategy", scoring_strategy)
             .field("exhaustive_number_hits", exhaustive_number_hits)
+            .field("exhaustive_vector_search", exhaustive_vector_search)
             .field("words_limit", words_limit)
             .field(
                 "semantic.embedder_name",
@@ -328,6 +372,7 @@ impl fmt::Debug for Search<'_> {
             .field("time_budget", time_budget)
             .field("ranking_score_threshold", ranking_score_threshold)
             .field("locales", locales)
+            .field("rerank.top_k", &rerank.as_ref().map(|rerank| rerank.top_k))
             .finish()
     }
 }
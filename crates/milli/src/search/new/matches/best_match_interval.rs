@@ -137,3 +137,40 @@ pub fn find_best_match_interval(matches: &[Match], crop_size: usize) -> [&Match;
         |MatchIntervalWithScore { interval: [first, last], .. }| [&matches[first], &matches[last]],
     )
 }
+
+/// Returns the intervals of matches that fit in `crop_size` words, one for each starting match,
+/// sorted from the best to the worst.
+///
+/// Intervals are ranked by their number of unique matches, then by their number of matches,
+/// then by the distance and order scores of [`find_best_match_interval`].
+pub fn find_best_match_intervals(matches: &[Match], crop_size: usize) -> Vec<[&Match; 2]> {
+    let mut intervals = Vec::with_capacity(matches.len());
+
+    let mut interval_last = 0;
+    for interval_first in 0..matches.len() {
+        let interval_first_match_first_word_pos = matches[interval_first].get_first_word_pos();
+
+        // extend the interval while the next match keeps it under crop_size.
+        interval_last = interval_last.max(interval_first);
+        while let Some(next_match) = matches.get(interval_last + 1) {
+            let next_match_last_word_pos = next_match.get_last_word_pos();
+            if next_match_last_word_pos.saturating_sub(interval_first_match_first_word_pos)
+                >= crop_size
+            {
+                break;
+            }
+            interval_last += 1;
+        }
+
+        let interval = &matches[interval_first..=interval_last];
+        let [uniq_score, distance_score, order_score] = get_interval_score(interval);
+        let density_score = interval.len().min(i16::MAX as usize) as i16;
+        let score = [uniq_score, density_score, distance_score, order_score];
+        intervals.push((score, [interval_first, interval_last]));
+    }
+
+    // the sort is stable, so the first interval of the text wins ties.
+    intervals.sort_by(|(left, _), (right, _)| right.cmp(left));
+
+    intervals.into_iter().map(|(_, [first, last])| [&matches[first], &matches[last]]).collect()
+}
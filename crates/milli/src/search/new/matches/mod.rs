@@ -1,6 +1,7 @@
 mod best_match_interval;
 mod r#match;
 mod matching_words;
+mod sentences;
 mod simple_token_kind;
 
 use std::borrow::Cow;
@@ -12,6 +13,7 @@ use itertools::Itertools;
 pub use matching_words::MatchingWords;
 use matching_words::{MatchType, PartialMatch};
 use r#match::{Match, MatchPosition};
+use sentences::split_sentences;
 use serde::{Deserialize, Serialize};
 use simple_token_kind::SimpleTokenKind;
 use utoipa::ToSchema;
@@ -25,6 +27,7 @@ pub struct MatcherBuilder<'m> {
     matching_words: MatchingWords,
     tokenizer: Tokenizer<'m>,
     crop_marker: Option<String>,
+    crop_mode: CropMode,
     highlight_prefix: Option<String>,
     highlight_suffix: Option<String>,
 }
@@ -35,6 +38,7 @@ impl<'m> MatcherBuilder<'m> {
             matching_words,
             tokenizer,
             crop_marker: None,
+            crop_mode: CropMode::default(),
             highlight_prefix: None,
             highlight_suffix: None,
         }
@@ -45,6 +49,11 @@ impl<'m> MatcherBuilder<'m> {
         self
     }
 
+    pub fn crop_mode(&mut self, mode: CropMode) -> &Self {
+        self.crop_mode = mode;
+        self
+    }
+
     pub fn highlight_prefix(&mut self, prefix: String) -> &Self {
         self.highlight_prefix = Some(prefix);
         self
@@ -78,6 +87,7 @@ impl<'m> MatcherBuilder<'m> {
             matching_words: &self.matching_words,
             tokenizer: &self.tokenizer,
             crop_marker,
+            crop_mode: self.crop_mode,
             highlight_prefix,
             highlight_suffix,
             matches: None,
@@ -86,6 +96,17 @@ impl<'m> MatcherBuilder<'m> {
     }
 }
 
+/// Defines how the boundaries of a crop window are chosen.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum CropMode {
+    /// Count words around the best matches, regardless of the sentences.
+    #[default]
+    Word,
+    /// Expand or trim the window to the sentences containing the best matches,
+    /// the crop length being the maximum number of words of the window.
+    Sentence,
+}
+
 #[derive(Copy, Clone, Default, Debug)]
 pub struct FormatOptions {
     pub highlight: bool,
@@ -110,6 +131,16 @@ pub struct MatchBounds {
     pub indices: Option<Vec<usize>>,
 }
 
+/// A fragment of a longer text, built around a group of matches.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    /// The cropped, and optionally highlighted, text of the fragment.
+    pub text: String,
+    /// Boundaries of the matches contained in the fragment, relative to the original text.
+    pub matches_position: Vec<MatchBounds>,
+}
+
 /// Structure used to analyze a string, compute words that match,
 /// and format the source string, returning a highlighted and cropped sub-string.
 pub struct Matcher<'t, 'tokenizer, 'b, 'lang> {
@@ -118,6 +149,7 @@ pub struct Matcher<'t, 'tokenizer, 'b, 'lang> {
     tokenizer: &'b Tokenizer<'tokenizer>,
     locales: Option<&'lang [Language]>,
     crop_marker: &'b str,
+    crop_mode: CropMode,
     highlight_prefix: &'b str,
     highlight_suffix: &'b str,
     matches: Option<(Vec<Token<'t>>, Vec<Match>)>,
@@ -226,18 +258,22 @@ impl<'t> Matcher<'t, '_, '_, '_> {
     pub fn matches(&mut self, array_indices: &[usize]) -> Vec<MatchBounds> {
         match &self.matches {
             None => self.compute_matches().matches(array_indices),
-            Some((tokens, matches)) => matches
-                .iter()
-                .map(|m| MatchBounds {
-                    start: tokens[m.get_first_token_pos()].byte_start,
-                    length: self.calc_byte_length(tokens, m),
-                    indices: if array_indices.is_empty() {
-                        None
-                    } else {
-                        Some(array_indices.to_owned())
-                    },
-                })
-                .collect(),
+            Some((tokens, matches)) => {
+                matches.iter().map(|m| self.match_bounds(tokens, m, array_indices)).collect()
+            }
+        }
+    }
+
+    fn match_bounds(
+        &self,
+        tokens: &[Token<'t>],
+        m: &Match,
+        array_indices: &[usize],
+    ) -> MatchBounds {
+        MatchBounds {
+            start: tokens[m.get_first_token_pos()].byte_start,
+            length: self.calc_byte_length(tokens, m),
+            indices: if array_indices.is_empty() { None } else { Some(array_indices.to_owned()) },
         }
     }
 
@@ -255,15 +291,109 @@ impl<'t> Matcher<'t, '_, '_, '_> {
 
     /// Returns the bounds in byte index of the crop window.
     fn crop_bounds(&self, tokens: &[Token<'_>], matches: &[Match], crop_size: usize) -> [usize; 2] {
+        let interval = if matches.is_empty() {
+            None
+        } else {
+            Some(best_match_interval::find_best_match_interval(matches, crop_size))
+        };
+
+        self.window_bounds(tokens, interval, crop_size)
+    }
+
+    /// Returns the bounds in byte index of the window built around the given match interval,
+    /// depending on the crop mode.
+    fn window_bounds(
+        &self,
+        tokens: &[Token<'_>],
+        interval: Option<[&Match; 2]>,
+        crop_size: usize,
+    ) -> [usize; 2] {
+        match self.crop_mode {
+            CropMode::Word => self.crop_bounds_around(tokens, interval, crop_size),
+            CropMode::Sentence => self.sentence_crop_bounds(tokens, interval, crop_size),
+        }
+    }
+
+    /// Returns the bounds in byte index of the largest group of whole sentences
+    /// around the given match interval that contains at most `crop_size` words.
+    ///
+    /// Falls back to counting words if the sentence of the first match is already too long.
+    fn sentence_crop_bounds(
+        &self,
+        tokens: &[Token<'_>],
+        interval: Option<[&Match; 2]>,
+        crop_size: usize,
+    ) -> [usize; 2] {
+        let sentences = split_sentences(tokens);
+        if sentences.is_empty() {
+            return self.crop_bounds_around(tokens, interval, crop_size);
+        }
+
+        // the window starts from the sentence containing the first match of the interval.
+        let first_match_token_position =
+            interval.map_or(0, |[first, _]| first.get_first_token_pos());
+        let anchor = sentences
+            .iter()
+            .position(|sentence| first_match_token_position <= sentence.last_token_position)
+            .unwrap_or(sentences.len() - 1);
+
+        let mut word_count = sentences[anchor].word_count;
+        if word_count > crop_size {
+            return self.crop_bounds_around(tokens, interval, crop_size);
+        }
+
+        // grow the window one sentence at a time on each side, as long as it fits in the crop size.
+        let [mut first, mut last] = [anchor, anchor];
+        loop {
+            let mut has_grown = false;
+
+            if let Some(next) = sentences.get(last + 1) {
+                if word_count + next.word_count <= crop_size {
+                    word_count += next.word_count;
+                    last += 1;
+                    has_grown = true;
+                }
+            }
+
+            if let Some(previous) = first.checked_sub(1).map(|index| &sentences[index]) {
+                if word_count + previous.word_count <= crop_size {
+                    word_count += previous.word_count;
+                    first -= 1;
+                    has_grown = true;
+                }
+            }
+
+            if !has_grown {
+                break;
+            }
+        }
+
+        // keep the separators surrounding the text when the window reaches its boundaries.
+        let crop_byte_start =
+            if first == 0 { 0 } else { tokens[sentences[first].first_token_position].byte_start };
+        let crop_byte_end = if last == sentences.len() - 1 {
+            self.text.len()
+        } else {
+            tokens[sentences[last].last_token_position].byte_end
+        };
+
+        [crop_byte_start, crop_byte_end]
+    }
+
+    /// Returns the bounds in byte index of the crop window built around the given match interval,
+    /// or around the start of the text if there is no interval.
+    fn crop_bounds_around(
+        &self,
+        tokens: &[Token<'_>],
+        interval: Option<[&Match; 2]>,
+        crop_size: usize,
+    ) -> [usize; 2] {
         let (
             mut remaining_words,
             is_iterating_forward,
             before_tokens_starting_index,
             after_tokens_starting_index,
-        ) = if !matches.is_empty() {
-            let [matches_first, matches_last] =
-                best_match_interval::find_best_match_interval(matches, crop_size);
-
+        ) = if let Some([matches_first, matches_last]) = interval {
             let matches_size =
                 matches_last.get_last_word_pos() - matches_first.get_first_word_pos() + 1;
 
@@ -401,93 +531,162 @@ impl<'t> Matcher<'t, '_, '_, '_> {
             match &self.matches {
                 Some((tokens, matches)) => {
                     // If the text has to be cropped, crop around the best interval.
-                    let [crop_byte_start, crop_byte_end] = match format_options.crop {
+                    let crop_bounds = match format_options.crop {
                         Some(crop_size) if crop_size > 0 => {
                             self.crop_bounds(tokens, matches, crop_size)
                         }
                         _ => [0, self.text.len()],
                     };
 
-                    let mut formatted = Vec::new();
+                    self.format_window(tokens, matches, crop_bounds, format_options.highlight)
+                }
+                None => self.compute_matches().format(format_options),
+            }
+        }
+    }
 
-                    // push crop marker if it's not the start of the text.
-                    if crop_byte_start > 0 && !self.crop_marker.is_empty() {
-                        formatted.push(self.crop_marker);
+    /// Returns up to `count` non-overlapping fragments of `snippet_length` words,
+    /// each one cropped around a different interval of matches.
+    ///
+    /// The fragments are sorted from the best to the worst interval, intervals being ranked
+    /// by the number of distinct query words they contain, then by their density of matches.
+    pub fn snippets(
+        &mut self,
+        highlight: bool,
+        snippet_length: usize,
+        count: usize,
+        array_indices: &[usize],
+    ) -> Vec<Snippet> {
+        match &self.matches {
+            Some((tokens, matches)) => {
+                if matches.is_empty() || snippet_length == 0 || count == 0 {
+                    return Vec::new();
+                }
+
+                let mut snippets: Vec<Snippet> = Vec::new();
+                let mut windows: Vec<[usize; 2]> = Vec::new();
+                for interval in
+                    best_match_interval::find_best_match_intervals(matches, snippet_length)
+                {
+                    if snippets.len() >= count {
+                        break;
                     }
 
-                    let mut byte_index = crop_byte_start;
+                    let [window_start, window_end] =
+                        self.window_bounds(tokens, Some(interval), snippet_length);
 
-                    if format_options.highlight {
-                        // insert highlight markers around matches.
-                        for m in matches {
-                            let [m_byte_start, m_byte_end] = match m.position {
-                                MatchPosition::Word { token_position, .. } => {
-                                    let token = &tokens[token_position];
-                                    [&token.byte_start, &token.byte_end]
-                                }
-                                MatchPosition::Phrase { token_positions: [ftp, ltp], .. } => {
-                                    [&tokens[ftp].byte_start, &tokens[ltp].byte_end]
-                                }
-                            };
+                    // skip the windows that overlap with an already selected one.
+                    if windows.iter().any(|&[start, end]| window_start < end && start < window_end)
+                    {
+                        continue;
+                    }
+                    windows.push([window_start, window_end]);
+
+                    let text = self
+                        .format_window(tokens, matches, [window_start, window_end], highlight)
+                        .into_owned();
+                    let matches_position = matches
+                        .iter()
+                        .filter(|m| {
+                            let byte_start = tokens[m.get_first_token_pos()].byte_start;
+                            (window_start..window_end).contains(&byte_start)
+                        })
+                        .map(|m| self.match_bounds(tokens, m, array_indices))
+                        .collect();
+
+                    snippets.push(Snippet { text, matches_position });
+                }
 
-                            // skip matches out of the crop window
-                            if *m_byte_end < crop_byte_start || *m_byte_start > crop_byte_end {
-                                continue;
-                            }
+                snippets
+            }
+            None => {
+                self.compute_matches().snippets(highlight, snippet_length, count, array_indices)
+            }
+        }
+    }
 
-                            // adjust start and end to the crop window size
-                            let [m_byte_start, m_byte_end] = [
-                                max(m_byte_start, &crop_byte_start),
-                                min(m_byte_end, &crop_byte_end),
-                            ];
+    /// Returns the text contained in the given bounds, with crop markers and optional highlighting.
+    fn format_window(
+        &self,
+        tokens: &[Token<'t>],
+        matches: &[Match],
+        [crop_byte_start, crop_byte_end]: [usize; 2],
+        highlight: bool,
+    ) -> Cow<'t, str> {
+        let mut formatted = Vec::new();
+
+        // push crop marker if it's not the start of the text.
+        if crop_byte_start > 0 && !self.crop_marker.is_empty() {
+            formatted.push(self.crop_marker);
+        }
 
-                            // push text that is positioned before our matches
-                            if byte_index < *m_byte_start {
-                                formatted.push(&self.text[byte_index..*m_byte_start]);
-                            }
+        let mut byte_index = crop_byte_start;
 
-                            formatted.push(self.highlight_prefix);
+        if highlight {
+            // insert highlight markers around matches.
+            for m in matches {
+                let [m_byte_start, m_byte_end] = match m.position {
+                    MatchPosition::Word { token_position, .. } => {
+                        let token = &tokens[token_position];
+                        [&token.byte_start, &token.byte_end]
+                    }
+                    MatchPosition::Phrase { token_positions: [ftp, ltp], .. } => {
+                        [&tokens[ftp].byte_start, &tokens[ltp].byte_end]
+                    }
+                };
 
-                            // TODO: This is additional work done, charabia::token::Token byte_len
-                            // should already get us the original byte length, however, that doesn't work as
-                            // it's supposed to, investigate why
-                            let highlight_byte_index = self.text[*m_byte_start..]
-                                .char_indices()
-                                .nth(m.char_count)
-                                .map_or(*m_byte_end, |(i, _)| min(i + *m_byte_start, *m_byte_end));
-                            formatted.push(&self.text[*m_byte_start..highlight_byte_index]);
+                // skip matches out of the crop window
+                if *m_byte_end < crop_byte_start || *m_byte_start > crop_byte_end {
+                    continue;
+                }
 
-                            formatted.push(self.highlight_suffix);
+                // adjust start and end to the crop window size
+                let [m_byte_start, m_byte_end] =
+                    [max(m_byte_start, &crop_byte_start), min(m_byte_end, &crop_byte_end)];
 
-                            // if it's a prefix highlight, we put the end of the word after the highlight marker.
-                            if highlight_byte_index < *m_byte_end {
-                                formatted.push(&self.text[highlight_byte_index..*m_byte_end]);
-                            }
+                // push text that is positioned before our matches
+                if byte_index < *m_byte_start {
+                    formatted.push(&self.text[byte_index..*m_byte_start]);
+                }
 
-                            byte_index = *m_byte_end;
-                        }
-                    }
+                formatted.push(self.highlight_prefix);
 
-                    // push the rest of the text between last match and the end of crop.
-                    if byte_index < crop_byte_end {
-                        formatted.push(&self.text[byte_index..crop_byte_end]);
-                    }
+                // TODO: This is additional work done, charabia::token::Token byte_len
+                // should already get us the original byte length, however, that doesn't work as
+                // it's supposed to, investigate why
+                let highlight_byte_index = self.text[*m_byte_start..]
+                    .char_indices()
+                    .nth(m.char_count)
+                    .map_or(*m_byte_end, |(i, _)| min(i + *m_byte_start, *m_byte_end));
+                formatted.push(&self.text[*m_byte_start..highlight_byte_index]);
 
-                    // push crop marker if it's not the end of the text.
-                    if crop_byte_end < self.text.len() && !self.crop_marker.is_empty() {
-                        formatted.push(self.crop_marker);
-                    }
+                formatted.push(self.highlight_suffix);
 
-                    if formatted.len() == 1 {
-                        // avoid concatenating if there is already 1 slice.
-                        Cow::Borrowed(&self.text[crop_byte_start..crop_byte_end])
-                    } else {
-                        Cow::Owned(formatted.concat())
-                    }
+                // if it's a prefix highlight, we put the end of the word after the highlight marker.
+                if highlight_byte_index < *m_byte_end {
+                    formatted.push(&self.text[highlight_byte_index..*m_byte_end]);
                 }
-                None => self.compute_matches().format(format_options),
+
+                byte_index = *m_byte_end;
             }
         }
+
+        // push the rest of the text between last match and the end of crop.
+        if byte_index < crop_byte_end {
+            formatted.push(&self.text[byte_index..crop_byte_end]);
+        }
+
+        // push crop marker if it's not the end of the text.
+        if crop_byte_end < self.text.len() && !self.crop_marker.is_empty() {
+            formatted.push(self.crop_marker);
+        }
+
+        if formatted.len() == 1 {
+            // avoid concatenating if there is already 1 slice.
+            Cow::Borrowed(&self.text[crop_byte_start..crop_byte_end])
+        } else {
+            Cow::Owned(formatted.concat())
+        }
     }
 }
 
@@ -927,6 +1126,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn format_crop_sentence() {
+        let temp_index = temp_index_with_documents();
+        let rtxn = temp_index.read_txn().unwrap();
+        let mut builder = MatcherBuilder::new_test(&rtxn, &temp_index, "split the world");
+        builder.crop_mode(CropMode::Sentence);
+
+        let text = "Natalie risk her future. Split The World is a book written by Emily Henry. I never read it.";
+
+        // the sentence containing the matches fits exactly in the crop length.
+        let format_options = FormatOptions { highlight: false, crop: Some(10) };
+        let mut matcher = builder.build(text, None);
+        insta::assert_snapshot!(
+            matcher.format(format_options),
+            @"…Split The World is a book written by Emily Henry.…"
+        );
+
+        // the surrounding sentences are added while they fit in the crop length.
+        let format_options = FormatOptions { highlight: true, crop: Some(20) };
+        let mut matcher = builder.build(text, None);
+        insta::assert_snapshot!(
+            matcher.format(format_options),
+            @"Natalie risk her future. <em>Split</em> <em>The</em> <em>World</em> is a book written by Emily Henry. I never read it."
+        );
+    }
+
+    #[test]
+    fn format_snippets() {
+        let temp_index = temp_index_with_documents();
+        let rtxn = temp_index.read_txn().unwrap();
+        let builder = MatcherBuilder::new_test(&rtxn, &temp_index, "split the world");
+
+        let text = "split the world void void void void void void void void void void void void void void void void void split the world";
+
+        let mut matcher = builder.build(text, None);
+        let snippets = matcher.snippets(true, 3, 3, &[]);
+        // each occurrence of the query gets its own fragment, in the order of the text on ties.
+        insta::assert_snapshot!(
+            snippets.iter().map(|snippet| snippet.text.as_str()).join(" | "),
+            @"<em>split</em> <em>the</em> <em>world</em>… | …<em>split</em> <em>the</em> <em>world</em>"
+        );
+        assert!(snippets.iter().all(|snippet| snippet.matches_position.len() == 3));
+
+        let mut matcher = builder.build(text, None);
+        let snippets = matcher.snippets(false, 3, 1, &[]);
+        // the number of fragments is bounded by the requested count.
+        insta::assert_snapshot!(
+            snippets.iter().map(|snippet| snippet.text.as_str()).join(" | "),
+            @"split the world…"
+        );
+
+        // no fragment is returned when nothing matches.
+        let mut matcher = builder.build("void void void", None);
+        assert!(matcher.snippets(true, 3, 3, &[]).is_empty());
+    }
+
     #[test]
     fn partial_matches() {
         let temp_index = temp_index_with_documents();
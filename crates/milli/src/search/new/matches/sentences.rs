@@ -0,0 +1,52 @@
+use charabia::{SeparatorKind, Token, TokenKind};
+
+/// A sentence of the text, delimited by hard separators.
+#[derive(Debug, Clone, Copy)]
+pub struct Sentence {
+    /// position of the first word token of the sentence.
+    pub first_token_position: usize,
+    /// position of the last token of the sentence, including its ending hard separators.
+    pub last_token_position: usize,
+    /// number of words in the sentence.
+    pub word_count: usize,
+}
+
+/// Splits the tokens into sentences, using the hard separators detected by the tokenizer.
+///
+/// Separators found before the first word of a sentence are not part of any sentence.
+pub fn split_sentences(tokens: &[Token<'_>]) -> Vec<Sentence> {
+    let mut sentences: Vec<Sentence> = Vec::new();
+    // whether the last sentence has been closed by a hard separator.
+    let mut is_closed = true;
+
+    for (token_position, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Separator(SeparatorKind::Hard) => {
+                if let Some(sentence) = sentences.last_mut() {
+                    // consecutive hard separators, like `?!` or `...`, all close the same sentence.
+                    if !is_closed || sentence.last_token_position + 1 == token_position {
+                        sentence.last_token_position = token_position;
+                        is_closed = true;
+                    }
+                }
+            }
+            TokenKind::Separator(SeparatorKind::Soft) => (),
+            _ => {
+                match sentences.last_mut() {
+                    Some(sentence) if !is_closed => {
+                        sentence.last_token_position = token_position;
+                        sentence.word_count += 1;
+                    }
+                    _ => sentences.push(Sentence {
+                        first_token_position: token_position,
+                        last_token_position: token_position,
+                        word_count: 1,
+                    }),
+                }
+                is_closed = false;
+            }
+        }
+    }
+
+    sentences
+}
@@ -15,6 +15,7 @@ mod resolve_query_graph;
 mod small_bitmap;
 
 mod exact_attribute;
+mod popularity;
 mod sort;
 mod vector_sort;
 
@@ -32,6 +33,7 @@ use heed::RoTxn;
 use interner::{DedupInterner, Interner};
 pub use logger::visual::VisualSearchLogger;
 pub use logger::{DefaultSearchLogger, SearchLogger};
+use popularity::Popularity;
 use query_graph::{QueryGraph, QueryNode};
 use query_term::{
     located_query_terms_from_tokens, ExtractedTokens, LocatedQueryTerm, Phrase, QueryTerm,
@@ -49,6 +51,7 @@ pub use self::geo_sort::Parameter as GeoSortParameter;
 pub use self::geo_sort::Strategy as GeoSortStrategy;
 use self::graph_based_ranking_rule::Words;
 use self::interner::Interned;
+pub use self::vector_sort::VectorQuery;
 use self::vector_sort::VectorSort;
 use crate::constants::RESERVED_GEO_FIELD_NAME;
 use crate::index::PrefixSearch;
@@ -278,6 +281,7 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
     geo_param: geo_sort::Parameter,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     let mut sort = false;
+    let mut popularity = false;
     let mut sorted_fields = HashSet::new();
     let mut geo_sorted = false;
     let mut ranking_rules: Vec<BoxRankingRule<'ctx, PlaceholderQuery>> = vec![];
@@ -290,6 +294,13 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
             | crate::Criterion::Attribute
             | crate::Criterion::Proximity
             | crate::Criterion::Exactness => continue,
+            crate::Criterion::Popularity => {
+                if popularity {
+                    continue;
+                }
+                ranking_rules.push(Box::new(Popularity::new()));
+                popularity = true;
+            }
             crate::Criterion::Sort => {
                 if sort {
                     continue;
@@ -329,14 +340,16 @@ fn get_ranking_rules_for_vector<'ctx>(
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_param: geo_sort::Parameter,
     limit_plus_offset: usize,
-    target: &[f32],
+    target: &VectorQuery,
     embedder_name: &str,
     embedder: &Embedder,
     quantized: bool,
+    exhaustive: bool,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     // query graph search
 
     let mut sort = false;
+    let mut popularity = false;
     let mut sorted_fields = HashSet::new();
     let mut geo_sorted = false;
 
@@ -355,17 +368,25 @@ fn get_ranking_rules_for_vector<'ctx>(
                     let vector_candidates = ctx.index.documents_ids(ctx.txn)?;
                     let vector_sort = VectorSort::new(
                         ctx,
-                        target.to_vec(),
+                        target.clone(),
                         vector_candidates,
                         limit_plus_offset,
                         embedder_name,
                         embedder,
                         quantized,
+                        exhaustive,
                     )?;
                     ranking_rules.push(Box::new(vector_sort));
                     vector = true;
                 }
             }
+            crate::Criterion::Popularity => {
+                if popularity {
+                    continue;
+                }
+                ranking_rules.push(Box::new(Popularity::new()));
+                popularity = true;
+            }
             crate::Criterion::Sort => {
                 if sort {
                     continue;
@@ -414,6 +435,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
     let mut sort = false;
     let mut attribute = false;
     let mut exactness = false;
+    let mut popularity = false;
     let mut sorted_fields = HashSet::new();
     let mut geo_sorted = false;
 
@@ -490,6 +512,13 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 ranking_rules.push(Box::new(Exactness::new()));
                 exactness = true;
             }
+            crate::Criterion::Popularity => {
+                if popularity {
+                    continue;
+                }
+                ranking_rules.push(Box::new(Popularity::new()));
+                popularity = true;
+            }
             crate::Criterion::Asc(field_name) => {
                 if sorted_fields.contains(&field_name) {
                     continue;
@@ -570,17 +599,21 @@ pub fn filtered_universe(
     txn: &RoTxn<'_>,
     filters: &Option<Filter<'_>>,
 ) -> Result<RoaringBitmap> {
-    Ok(if let Some(filters) = filters {
+    let mut universe = if let Some(filters) = filters {
         filters.evaluate(txn, index)?
     } else {
         index.documents_ids(txn)?
-    })
+    };
+    // the expired documents are excluded until they are deleted by the scheduler
+    let now = time::OffsetDateTime::now_utc().unix_timestamp() as f64;
+    universe -= index.expired_documents_ids(txn, now)?;
+    Ok(universe)
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn execute_vector_search(
     ctx: &mut SearchContext<'_>,
-    vector: &[f32],
+    vector: &VectorQuery,
     scoring_strategy: ScoringStrategy,
     universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
@@ -591,6 +624,7 @@ pub fn execute_vector_search(
     embedder_name: &str,
     embedder: &Embedder,
     quantized: bool,
+    exhaustive: bool,
     time_budget: TimeBudget,
     ranking_score_threshold: Option<f64>,
 ) -> Result<PartialSearchResult> {
@@ -607,6 +641,7 @@ pub fn execute_vector_search(
         embedder_name,
         embedder,
         quantized,
+        exhaustive,
     )?;
 
     let mut placeholder_search_logger = logger::DefaultSearchLogger;
@@ -0,0 +1,119 @@
+use roaring::RoaringBitmap;
+
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::score_details::{self, ScoreDetails};
+use crate::Result;
+
+/// The name under which the popularity appears in the ranking score details.
+const POPULARITY_FIELD_NAME: &str = "_popularity";
+
+/// A ranking rule that sorts the documents by decreasing click-through rate,
+/// see [`crate::DocumentPopularity::click_through_rate`].
+///
+/// The rate is aggregated over all the queries that returned a document, it does not depend
+/// on the current query. The documents that were never engaged with are returned last,
+/// in a single bucket.
+pub struct Popularity<Query> {
+    original_query: Option<Query>,
+    /// The popular candidates grouped by score, in increasing order of score
+    /// so that the most popular bucket is popped first.
+    buckets: Vec<(f64, RoaringBitmap)>,
+}
+
+impl<Query> Popularity<Query> {
+    pub fn new() -> Self {
+        Self { original_query: None, buckets: Vec::new() }
+    }
+
+    fn output(query: Query, candidates: RoaringBitmap, score: f64) -> RankingRuleOutput<Query> {
+        RankingRuleOutput {
+            query,
+            candidates,
+            score: ScoreDetails::Sort(score_details::Sort {
+                field_name: POPULARITY_FIELD_NAME.to_string(),
+                ascending: false,
+                redacted: false,
+                value: serde_json::Number::from_f64(score)
+                    .map_or(serde_json::Value::Null, serde_json::Value::Number),
+            }),
+        }
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Popularity<Query> {
+    fn id(&self) -> String {
+        "popularity".to_owned()
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::popularity")]
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        parent_candidates: &RoaringBitmap,
+        parent_query: &Query,
+    ) -> Result<()> {
+        // Only the scores of the candidates are needed, we look them up one by one
+        // unless there are fewer popular documents than candidates.
+        let popularity = ctx.index.document_popularity;
+        let mut scores = Vec::new();
+        if parent_candidates.len() < popularity.len(ctx.txn)? {
+            for docid in parent_candidates {
+                if let Some(popularity) = popularity.get(ctx.txn, &docid)? {
+                    scores.push((popularity.click_through_rate(), docid));
+                }
+            }
+        } else {
+            for result in popularity.iter(ctx.txn)? {
+                let (docid, popularity) = result?;
+                if parent_candidates.contains(docid) {
+                    scores.push((popularity.click_through_rate(), docid));
+                }
+            }
+        }
+        // the documents only returned by searches go to the bucket of the unpopular ones
+        scores.retain(|(score, _)| *score > 0.0);
+        scores.sort_unstable_by(|(left, _), (right, _)| left.total_cmp(right));
+
+        let mut buckets: Vec<(f64, RoaringBitmap)> = Vec::new();
+        for (score, docid) in scores {
+            match buckets.last_mut() {
+                Some((last_score, docids)) if *last_score == score => {
+                    docids.insert(docid);
+                }
+                _ => buckets.push((score, RoaringBitmap::from_iter([docid]))),
+            }
+        }
+
+        self.original_query = Some(parent_query.clone());
+        self.buckets = buckets;
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::popularity")]
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.original_query.as_ref().unwrap().clone();
+        match self.buckets.pop() {
+            Some((score, candidates)) => {
+                Ok(Some(Self::output(query, candidates & universe, score)))
+            }
+            None => Ok(Some(Self::output(query, universe.clone(), 0.0))),
+        }
+    }
+
+    #[tracing::instrument(level = "trace", skip_all, target = "search::popularity")]
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.original_query = None;
+        self.buckets = Vec::new();
+    }
+}
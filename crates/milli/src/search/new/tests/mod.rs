@@ -9,6 +9,7 @@ pub mod integration;
 #[cfg(not(feature = "chinese-pinyin"))]
 pub mod language;
 pub mod ngram_split_words;
+pub mod popularity;
 pub mod proximity;
 pub mod proximity_typo;
 pub mod sort;
@@ -0,0 +1,113 @@
+/*!
+This module tests the `popularity` ranking rule:
+
+1. documents are sorted by decreasing click-through rate, not by total engagement
+2. documents with the same click-through rate are returned in the same bucket
+3. documents without a popularity score are returned last
+4. the popularity of a document is kept when the document is updated
+5. the popularity of a document is deleted with the document
+*/
+
+use meili_snap::insta;
+
+use crate::index::tests::TempIndex;
+use crate::search::new::tests::collect_field_values;
+use crate::{Criterion, DocumentPopularity, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words, Criterion::Popularity]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the fox" },
+            { "id": 1, "text": "the fox" },
+            { "id": 2, "text": "the dog" },
+            { "id": 3, "text": "the fox jumps" },
+            { "id": 4, "text": "fox" },
+            { "id": 5, "text": "a dog" },
+        ]))
+        .unwrap();
+
+    increment_popularity(&index, "3", 10, 5.0);
+    increment_popularity(&index, "1", 0, 2.0);
+    increment_popularity(&index, "4", 5, 1.0);
+    increment_popularity(&index, "4", 5, 1.0);
+    // engaged with much more than the others, but returned far more often too
+    increment_popularity(&index, "2", 1000, 50.0);
+
+    index
+}
+
+fn increment_popularity(index: &TempIndex, external_id: &str, impressions: u64, engagement: f64) {
+    let mut wtxn = index.write_txn().unwrap();
+    let docid = index.external_documents_ids().get(&wtxn, external_id).unwrap().unwrap();
+    let increment = DocumentPopularity { impressions, engagement };
+    index.increment_document_popularity(&mut wtxn, docid, increment).unwrap();
+    wtxn.commit().unwrap();
+}
+
+#[test]
+fn test_popularity_placeholder() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3", "1", "4", "2", "0", "5"]"###);
+}
+
+#[test]
+fn test_popularity_query() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.query("fox");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3", "1", "4", "0"]"###);
+}
+
+#[test]
+fn test_popularity_survives_document_update() {
+    let index = create_index();
+
+    index.add_documents(documents!([{ "id": 0, "text": "the fox again" }])).unwrap();
+    increment_popularity(&index, "0", 10, 4.5);
+
+    index.add_documents(documents!([{ "id": 0, "text": "the fox once more" }])).unwrap();
+
+    let txn = index.read_txn().unwrap();
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["3", "0", "1", "4", "2", "5"]"###);
+}
+
+#[test]
+fn test_popularity_deleted_with_document() {
+    let index = create_index();
+
+    index.delete_documents(vec!["3".to_owned()]);
+    index.add_documents(documents!([{ "id": 3, "text": "the fox jumps" }])).unwrap();
+
+    let txn = index.read_txn().unwrap();
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+    let ids = collect_field_values(&index, &txn, "id", &documents_ids);
+    insta::assert_snapshot!(format!("{ids:?}"), @r###"["1", "4", "2", "0", "3", "5"]"###);
+}
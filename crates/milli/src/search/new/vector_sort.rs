@@ -4,29 +4,55 @@ use roaring::RoaringBitmap;
 
 use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
 use crate::score_details::{self, ScoreDetails};
+use crate::vector::sparse::{self, SparseVector, SparseVectorStore};
 use crate::vector::{ArroyWrapper, DistributionShift, Embedder};
-use crate::{DocumentId, Result, SearchContext, SearchLogger};
+use crate::{DocumentId, Result, SearchContext, SearchLogger, UserError};
+
+/// When a filter leaves at most this number of candidates, the nearest neighbors are computed
+/// with an exact scan of the candidates rather than with the approximate search of arroy.
+///
+/// The approximate search can miss neighbors, or return fewer than requested, when
+/// a restrictive filter only leaves a few candidates.
+pub const EXACT_SEARCH_THRESHOLD: u64 = 10_000;
+
+/// Maximum number of candidates scanned when the exhaustive vector search is requested.
+///
+/// Scanning is linear in the number of candidates, above this number the search is refused.
+pub const MAX_EXHAUSTIVE_SEARCH_CANDIDATES: u64 = 100_000;
+
+/// The embedding that documents are compared to in a vector search.
+#[derive(Debug, Clone)]
+pub enum VectorQuery {
+    /// Nearest neighbors are searched in the arroy store of the embedder.
+    Dense(Vec<f32>),
+    /// Nearest neighbors are searched in the inverted index of a sparse embedder.
+    Sparse(SparseVector),
+}
 
 pub struct VectorSort<Q: RankingRuleQueryTrait> {
     query: Option<Q>,
-    target: Vec<f32>,
+    target: VectorQuery,
     vector_candidates: RoaringBitmap,
     cached_sorted_docids: std::vec::IntoIter<(DocumentId, f32)>,
     limit: usize,
     distribution_shift: Option<DistributionShift>,
     embedder_index: u8,
     quantized: bool,
+    exhaustive: bool,
+    /// Whether the current iteration scans the candidates instead of using arroy.
+    exact: bool,
 }
 
 impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
     pub fn new(
         ctx: &SearchContext<'_>,
-        target: Vec<f32>,
+        target: VectorQuery,
         vector_candidates: RoaringBitmap,
         limit: usize,
         embedder_name: &str,
         embedder: &Embedder,
         quantized: bool,
+        exhaustive: bool,
     ) -> Result<Self> {
         let embedder_index = ctx
             .index
@@ -43,6 +69,8 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
             distribution_shift: embedder.distribution(),
             embedder_index,
             quantized,
+            exhaustive,
+            exact: exhaustive,
         })
     }
 
@@ -51,10 +79,31 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
         ctx: &mut SearchContext<'_>,
         vector_candidates: &RoaringBitmap,
     ) -> Result<()> {
-        let target = &self.target;
-
-        let reader = ArroyWrapper::new(ctx.index.vector_arroy, self.embedder_index, self.quantized);
-        let results = reader.nns_by_vector(ctx.txn, target, self.limit, Some(vector_candidates))?;
+        let results = match &self.target {
+            VectorQuery::Dense(target) => {
+                let reader = ArroyWrapper::new(
+                    ctx.index.vector_arroy,
+                    ctx.index.vector_int8,
+                    self.embedder_index,
+                    self.quantized,
+                );
+                if self.exact {
+                    reader.exact_nns_by_vector(ctx.txn, target, self.limit, vector_candidates)?
+                } else {
+                    reader.nns_by_vector(ctx.txn, target, self.limit, Some(vector_candidates))?
+                }
+            }
+            VectorQuery::Sparse(target) => {
+                let store = SparseVectorStore::new(ctx.index.sparse_vectors, self.embedder_index);
+                let results =
+                    store.nns_by_vector(ctx.txn, target, self.limit, vector_candidates)?;
+                // expressed as distances, like the results of arroy
+                results
+                    .into_iter()
+                    .map(|(docid, dot)| (docid, 1.0 - sparse::similarity(dot)))
+                    .collect()
+            }
+        };
         self.cached_sorted_docids = results.into_iter();
 
         Ok(())
@@ -78,6 +127,19 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
 
         self.query = Some(query.clone());
         let vector_candidates = &self.vector_candidates & universe;
+        let is_filtered = vector_candidates.len() < self.vector_candidates.len();
+        self.exact = if self.exhaustive {
+            if vector_candidates.len() > MAX_EXHAUSTIVE_SEARCH_CANDIDATES {
+                return Err(UserError::TooManyCandidatesForExhaustiveSearch {
+                    candidates: vector_candidates.len(),
+                    max: MAX_EXHAUSTIVE_SEARCH_CANDIDATES,
+                }
+                .into());
+            }
+            true
+        } else {
+            is_filtered && vector_candidates.len() <= EXACT_SEARCH_THRESHOLD
+        };
         self.fill_buffer(ctx, &vector_candidates)?;
         Ok(())
     }
@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use crate::score_details::{self, ScoreDetails, ScoringStrategy};
+use crate::vector::rerank::Reranker;
+use crate::{Index, Result, Search, SearchResult};
+
+#[derive(Clone)]
+pub struct RerankSearch {
+    pub(super) reranker: Arc<Reranker>,
+    pub(super) top_k: usize,
+    pub(super) attributes: Option<Vec<String>>,
+    pub(super) before_request: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl std::fmt::Debug for RerankSearch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let RerankSearch { reranker, top_k, attributes, before_request } = self;
+        f.debug_struct("RerankSearch")
+            .field("reranker", reranker)
+            .field("top_k", top_k)
+            .field("attributes", attributes)
+            .field("before_request", &before_request.is_some())
+            .finish()
+    }
+}
+
+impl<'a> Search<'a> {
+    /// Reorders the `top_k` first hits with the given reranker.
+    ///
+    /// The text sent to the reranker for each document is made of the given top-level `attributes`,
+    /// or of the searchable attributes of the index if `None`.
+    pub fn rerank(
+        &mut self,
+        reranker: Arc<Reranker>,
+        top_k: usize,
+        attributes: Option<Vec<String>>,
+    ) -> &mut Search<'a> {
+        self.rerank = Some(RerankSearch { reranker, top_k, attributes, before_request: None });
+        self
+    }
+
+    /// Calls `before_request` once the texts of the documents are read, right before waiting
+    /// on the reranker, e.g. to let other searches run in the meantime.
+    ///
+    /// Does nothing if the search is not reranked.
+    pub fn before_rerank_request(
+        &mut self,
+        before_request: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Search<'a> {
+        if let Some(rerank) = &mut self.rerank {
+            rerank.before_request = Some(Arc::new(before_request));
+        }
+        self
+    }
+
+    /// Executes `search` on the `top_k` first hits, or on the requested page if it goes further,
+    /// then reranks the `top_k` first hits before paginating them.
+    ///
+    /// The reranker is a best effort: if it fails, the hits are returned in their original order.
+    ///
+    /// The ranking score of a reranked hit is the score returned by the reranker, so the ranking score
+    /// threshold is applied once the hits are reranked rather than during the first search.
+    pub(super) fn execute_reranked<T>(
+        &self,
+        rerank: &RerankSearch,
+        search: impl FnOnce(&Search<'a>) -> Result<(SearchResult, T)>,
+    ) -> Result<(SearchResult, T)> {
+        // there is nothing to rerank against in a placeholder search
+        let query = self.query.as_deref().filter(|query| !query.trim().is_empty());
+        let unpaginated = Search {
+            query: self.query.clone(),
+            filter: self.filter.clone(),
+            offset: 0,
+            limit: (self.offset + self.limit).max(rerank.top_k),
+            sort_criteria: self.sort_criteria.clone(),
+            distinct: self.distinct.clone(),
+            searchable_attributes: self.searchable_attributes,
+            geo_param: self.geo_param,
+            terms_matching_strategy: self.terms_matching_strategy,
+            // the scores of the reranked documents are stacked onto the existing ones
+            scoring_strategy: ScoringStrategy::Detailed,
+            words_limit: self.words_limit,
+            exhaustive_number_hits: self.exhaustive_number_hits,
+            exhaustive_vector_search: self.exhaustive_vector_search,
+            rtxn: self.rtxn,
+            index: self.index,
+            semantic: self.semantic.clone(),
+            time_budget: self.time_budget.clone(),
+            ranking_score_threshold: if query.is_some() {
+                None
+            } else {
+                self.ranking_score_threshold
+            },
+            locales: self.locales.clone(),
+            rerank: None,
+        };
+
+        let (mut result, extra) = search(&unpaginated)?;
+
+        if let Some(query) = query {
+            let top_k = rerank.top_k.min(result.documents_ids.len());
+            let span = tracing::trace_span!(target: "search::rerank", "rerank", top_k);
+            let _entered = span.enter();
+
+            let documents = documents_texts(
+                self.index,
+                self.rtxn,
+                &result.documents_ids[..top_k],
+                rerank.attributes.as_deref(),
+            )?;
+            if let Some(before_request) = &rerank.before_request {
+                before_request();
+            }
+            match rerank.reranker.rerank(query, &documents) {
+                Ok(scores) => apply_scores(&mut result, scores),
+                Err(error) => tracing::error!(error=%error, "Reranking failed"),
+            }
+            if let Some(ranking_score_threshold) = self.ranking_score_threshold {
+                retain_above_threshold(&mut result, ranking_score_threshold);
+            }
+        }
+
+        let offset = self.offset.min(result.documents_ids.len());
+        result.documents_ids.drain(..offset);
+        result.documents_ids.truncate(self.limit);
+        result.document_scores.drain(..offset);
+        result.document_scores.truncate(self.limit);
+
+        Ok((result, extra))
+    }
+}
+
+/// Stable sorts the reranked documents by decreasing score, and adds the score to their details.
+///
+/// The score of the reranker, clamped to `[0, 1]`, becomes the ranking score of the document.
+fn apply_scores(result: &mut SearchResult, scores: Vec<f32>) {
+    let top_k = scores.len();
+    let mut reranked: Vec<_> = result
+        .documents_ids
+        .drain(..top_k)
+        .zip(result.document_scores.drain(..top_k))
+        .zip(scores)
+        .collect();
+    reranked.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+
+    let (documents_ids, document_scores): (Vec<_>, Vec<_>) = reranked
+        .into_iter()
+        .map(|((docid, mut details), score)| {
+            let ranking_score = f64::from(score).clamp(0.0, 1.0);
+            details.insert(0, ScoreDetails::Rerank(score_details::Rerank { score, ranking_score }));
+            (docid, details)
+        })
+        .unzip();
+    result.documents_ids.splice(..0, documents_ids);
+    result.document_scores.splice(..0, document_scores);
+}
+
+/// Removes the documents whose ranking score is below the threshold from the hits and the candidates.
+fn retain_above_threshold(result: &mut SearchResult, ranking_score_threshold: f64) {
+    let mut documents_ids = Vec::with_capacity(result.documents_ids.len());
+    let mut document_scores = Vec::with_capacity(result.document_scores.len());
+    for (docid, details) in result.documents_ids.drain(..).zip(result.document_scores.drain(..)) {
+        if ScoreDetails::global_score(details.iter()) < ranking_score_threshold {
+            result.candidates.remove(docid);
+        } else {
+            documents_ids.push(docid);
+            document_scores.push(details);
+        }
+    }
+    result.documents_ids = documents_ids;
+    result.document_scores = document_scores;
+}
+
+/// Renders each document as a `name: value` line per attribute.
+fn documents_texts(
+    index: &Index,
+    rtxn: &heed::RoTxn<'_>,
+    documents_ids: &[u32],
+    attributes: Option<&[String]>,
+) -> Result<Vec<String>> {
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let attributes = match attributes {
+        Some(attributes) => attributes.to_vec(),
+        None => index.searchable_fields(rtxn)?.into_iter().map(|name| name.into_owned()).collect(),
+    };
+    let fields: Vec<_> = attributes
+        .iter()
+        .filter_map(|name| fields_ids_map.id(name).map(|fid| (name.as_str(), fid)))
+        .collect();
+
+    let mut texts = Vec::with_capacity(documents_ids.len());
+    for &docid in documents_ids {
+        let document = index.document(rtxn, docid)?;
+        let mut text = String::new();
+        for &(name, fid) in &fields {
+            let Some(value) = document.get(fid) else { continue };
+            let value: serde_json::Value =
+                serde_json::from_slice(value).map_err(crate::InternalError::SerdeJson)?;
+            let value = match value {
+                serde_json::Value::Null => continue,
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            };
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(name);
+            text.push_str(": ");
+            text.push_str(&value);
+        }
+        texts.push(text);
+    }
+    Ok(texts)
+}
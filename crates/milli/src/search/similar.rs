@@ -4,10 +4,23 @@ use roaring::RoaringBitmap;
 
 use crate::score_details::{self, ScoreDetails};
 use crate::vector::{ArroyWrapper, Embedder};
-use crate::{filtered_universe, DocumentId, Filter, Index, Result, SearchResult};
+use crate::{filtered_universe, DocumentId, Filter, Index, Result, SearchResult, UserError};
+
+/// How much the centroid of the negative examples is subtracted from the centroid of the positive ones.
+const NEGATIVE_EXAMPLES_WEIGHT: f32 = 0.25;
+
+/// An example of what the similar documents should, or should not, look like.
+#[derive(Debug, Clone)]
+pub enum SimilarExample {
+    /// The embeddings of a document of the index.
+    Document(DocumentId),
+    /// A raw embedding, that must have the dimensions of the embedder.
+    Vector(Vec<f32>),
+}
 
 pub struct Similar<'a> {
-    id: DocumentId,
+    positive: Vec<SimilarExample>,
+    negative: Vec<SimilarExample>,
     // this should be linked to the String in the query
     filter: Option<Filter<'a>>,
     offset: usize,
@@ -23,7 +36,7 @@ pub struct Similar<'a> {
 impl<'a> Similar<'a> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        id: DocumentId,
+        positive: Vec<SimilarExample>,
         offset: usize,
         limit: usize,
         index: &'a Index,
@@ -33,7 +46,8 @@ impl<'a> Similar<'a> {
         quantized: bool,
     ) -> Self {
         Self {
-            id,
+            positive,
+            negative: Vec::new(),
             filter: None,
             offset,
             limit,
@@ -46,6 +60,14 @@ impl<'a> Similar<'a> {
         }
     }
 
+    /// Examples of documents that should not be returned.
+    ///
+    /// The returned documents are pushed away from these examples, following the Rocchio algorithm.
+    pub fn negative(&mut self, negative: Vec<SimilarExample>) -> &mut Self {
+        self.negative = negative;
+        self
+    }
+
     pub fn filter(&mut self, filter: Filter<'a>) -> &mut Self {
         self.filter = Some(filter);
         self
@@ -59,8 +81,17 @@ impl<'a> Similar<'a> {
     pub fn execute(&self) -> Result<SearchResult> {
         let mut universe = filtered_universe(self.index, self.rtxn, &self.filter)?;
 
-        // we never want to receive the docid
-        universe.remove(self.id);
+        // we never want to receive the documents used as examples
+        let seeds: RoaringBitmap = self
+            .positive
+            .iter()
+            .chain(&self.negative)
+            .filter_map(|example| match example {
+                SimilarExample::Document(docid) => Some(*docid),
+                SimilarExample::Vector(_) => None,
+            })
+            .collect();
+        universe -= &seeds;
 
         let universe = universe;
 
@@ -69,20 +100,32 @@ impl<'a> Similar<'a> {
                 || crate::UserError::InvalidSimilarEmbedder(self.embedder_name.to_owned()),
             )?;
 
-        let reader = ArroyWrapper::new(self.index.vector_arroy, embedder_index, self.quantized);
-        let results = reader.nns_by_item(
-            self.rtxn,
-            self.id,
-            self.limit + self.offset + 1,
-            Some(&universe),
-        )?;
+        let reader = ArroyWrapper::new(
+            self.index.vector_arroy,
+            self.index.vector_int8,
+            embedder_index,
+            self.quantized,
+        );
+        let results = match (self.positive.as_slice(), self.negative.as_slice()) {
+            ([SimilarExample::Document(id)], []) => {
+                reader.nns_by_item(self.rtxn, *id, self.limit + self.offset + 1, Some(&universe))?
+            }
+            _ => match self.query_vector(&reader)? {
+                Some(vector) => reader.nns_by_vector(
+                    self.rtxn,
+                    &vector,
+                    self.limit + self.offset,
+                    Some(&universe),
+                )?,
+                None => Vec::new(),
+            },
+        };
 
         let mut documents_ids = Vec::with_capacity(self.limit);
         let mut document_scores = Vec::with_capacity(self.limit);
         // list of documents we've already seen, so that we don't return the same document multiple times.
-        // initialized to the target document, that we never want to return.
-        let mut documents_seen = RoaringBitmap::new();
-        documents_seen.insert(self.id);
+        // initialized to the example documents, that we never want to return.
+        let mut documents_seen = seeds;
 
         let mut candidates = universe;
 
@@ -129,4 +172,75 @@ impl<'a> Similar<'a> {
             used_negative_operator: false,
         })
     }
+
+    /// Combines the examples into a single query vector, as `centroid(positive) - weight * centroid(negative)`.
+    ///
+    /// Returns `None` when none of the positive examples has an embedding.
+    fn query_vector(&self, reader: &ArroyWrapper) -> Result<Option<Vec<f32>>> {
+        let Some(positive) = self.centroid(reader, &self.positive)? else {
+            return Ok(None);
+        };
+        let Some(negative) = self.centroid(reader, &self.negative)? else {
+            return Ok(Some(positive));
+        };
+
+        Ok(Some(
+            positive
+                .iter()
+                .zip(negative)
+                .map(|(positive, negative)| positive - NEGATIVE_EXAMPLES_WEIGHT * negative)
+                .collect(),
+        ))
+    }
+
+    /// Averages the normalized embeddings of the examples, so that they all weigh the same.
+    ///
+    /// Documents with several embeddings are represented by the average of their embeddings,
+    /// and documents without embeddings are ignored.
+    fn centroid(
+        &self,
+        reader: &ArroyWrapper,
+        examples: &[SimilarExample],
+    ) -> Result<Option<Vec<f32>>> {
+        let dimensions = self.embedder.dimensions();
+        let mut centroid = vec![0.0; dimensions];
+        let mut count = 0;
+
+        for example in examples {
+            let embeddings = match example {
+                SimilarExample::Document(docid) => reader.item_vectors(self.rtxn, *docid)?,
+                SimilarExample::Vector(vector) => {
+                    if vector.len() != dimensions {
+                        return Err(UserError::InvalidVectorDimensions {
+                            expected: dimensions,
+                            found: vector.len(),
+                        }
+                        .into());
+                    }
+                    vec![vector.clone()]
+                }
+            };
+            if embeddings.is_empty() {
+                continue;
+            }
+
+            let weight = 1.0 / embeddings.len() as f32;
+            for embedding in &embeddings {
+                let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if norm == 0.0 {
+                    continue;
+                }
+                for (c, x) in centroid.iter_mut().zip(embedding) {
+                    *c += weight * x / norm;
+                }
+            }
+            count += 1;
+        }
+
+        if count == 0 {
+            return Ok(None);
+        }
+        centroid.iter_mut().for_each(|c| *c /= count as f32);
+        Ok(Some(centroid))
+    }
 }
@@ -46,8 +46,12 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_int8,
+            sparse_vectors,
             embedder_category_id: _,
             documents,
+            documents_versions,
+            document_popularity,
         } = self.index;
 
         let empty_roaring = RoaringBitmap::default();
@@ -93,8 +97,13 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         field_id_docid_facet_strings.clear(self.wtxn)?;
         // vector
         vector_arroy.clear(self.wtxn)?;
+        // keeps the int8 rescoring of the embedders enabled
+        crate::vector::scalar::clear_all_embeddings(self.wtxn, *vector_int8)?;
+        sparse_vectors.clear(self.wtxn)?;
 
         documents.clear(self.wtxn)?;
+        documents_versions.clear(self.wtxn)?;
+        document_popularity.clear(self.wtxn)?;
 
         Ok(number_of_documents)
     }
@@ -105,6 +114,7 @@ mod tests {
     use super::*;
     use crate::constants::RESERVED_GEO_FIELD_NAME;
     use crate::index::tests::TempIndex;
+    use crate::DocumentPopularity;
 
     #[test]
     fn clear_documents() {
@@ -118,6 +128,13 @@ mod tests {
                 { "id": 2, "name": "benoit", "country": "France", RESERVED_GEO_FIELD_NAME: { "lng": 42, "lat": 35 } }
             ]))
             .unwrap();
+        index
+            .increment_document_popularity(
+                &mut wtxn,
+                1,
+                DocumentPopularity { impressions: 1, engagement: 1.0 },
+            )
+            .unwrap();
 
         // Clear all documents from the database.
         let builder = ClearDocuments::new(&mut wtxn, &index);
@@ -146,5 +163,9 @@ mod tests {
         assert!(index.field_id_docid_facet_f64s.is_empty(&rtxn).unwrap());
         assert!(index.field_id_docid_facet_strings.is_empty(&rtxn).unwrap());
         assert!(index.documents.is_empty(&rtxn).unwrap());
+        assert!(index.documents_versions.is_empty(&rtxn).unwrap());
+        assert!(index.sparse_vectors.is_empty(&rtxn).unwrap());
+        assert!(index.vector_int8.is_empty(&rtxn).unwrap());
+        assert!(index.document_popularity.is_empty(&rtxn).unwrap());
     }
 }
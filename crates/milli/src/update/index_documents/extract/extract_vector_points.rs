@@ -20,6 +20,7 @@ use crate::index::IndexEmbeddingConfig;
 use crate::prompt::Prompt;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::settings::InnerIndexSettingsDiff;
+use crate::vector::chunking::ChunkingSettings;
 use crate::vector::error::{EmbedErrorKind, PossibleEmbeddingMistakes, UnusedVectorsDistribution};
 use crate::vector::parsed_vectors::{ParsedVectorsDiff, VectorState};
 use crate::vector::settings::ReindexAction;
@@ -40,6 +41,7 @@ pub struct ExtractedVectorPoints {
     // embedder
     pub embedder_name: String,
     pub embedder: Arc<Embedder>,
+    pub chunking: Option<ChunkingSettings>,
     pub add_to_user_provided: RoaringBitmap,
     pub remove_from_user_provided: RoaringBitmap,
 }
@@ -444,7 +446,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     for EmbedderVectorExtractor {
         embedder_name,
         embedder,
-        prompt: _,
+        prompt,
         prompts_writer,
         remove_vectors_writer,
         action,
@@ -468,6 +470,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
             prompts: writer_into_reader(prompts_writer)?,
             embedder,
             embedder_name,
+            chunking: prompt.chunking().cloned(),
             add_to_user_provided,
             remove_from_user_provided,
         })
@@ -675,12 +678,14 @@ fn compare_vectors(a: &[f32], b: &[f32]) -> Ordering {
 }
 
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
+#[allow(clippy::too_many_arguments)]
 pub fn extract_embeddings<R: io::Read + io::Seek>(
     // docid, prompt
     prompt_reader: grenad::Reader<R>,
     indexer: GrenadParameters,
     embedder: Arc<Embedder>,
     embedder_name: &str,
+    chunking: Option<&ChunkingSettings>,
     possible_embedding_mistakes: &PossibleEmbeddingMistakes,
     unused_vectors_distribution: &UnusedVectorsDistribution,
     request_threads: &ThreadPoolNoAbort,
@@ -705,7 +710,30 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
         let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
         // SAFETY: precondition, the grenad value was saved from a string
         let prompt = unsafe { std::str::from_utf8_unchecked(value) };
-        if current_chunk.len() == current_chunk.capacity() {
+        let texts: Vec<&str> = match chunking {
+            Some(chunking) => {
+                chunking.split(prompt).into_iter().map(|range| &prompt[range]).collect()
+            }
+            None => vec![prompt],
+        };
+        for text in texts {
+            if current_chunk.len() == current_chunk.capacity() {
+                chunks.push(std::mem::replace(
+                    &mut current_chunk,
+                    Vec::with_capacity(n_vectors_per_chunk),
+                ));
+                chunks_ids.push(std::mem::replace(
+                    &mut current_chunk_ids,
+                    Vec::with_capacity(n_vectors_per_chunk),
+                ));
+            };
+            current_chunk.push(text.to_owned());
+            current_chunk_ids.push(docid);
+        }
+
+        // only embed once all the texts of the document are pushed,
+        // so that all its embeddings are written at once.
+        if chunks.len() >= n_chunks {
             chunks.push(std::mem::replace(
                 &mut current_chunk,
                 Vec::with_capacity(n_vectors_per_chunk),
@@ -714,11 +742,6 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
                 &mut current_chunk_ids,
                 Vec::with_capacity(n_vectors_per_chunk),
             ));
-        };
-        current_chunk.push(prompt.to_owned());
-        current_chunk_ids.push(docid);
-
-        if chunks.len() == chunks.capacity() {
             let chunked_embeds = embed_chunks(
                 &embedder,
                 std::mem::replace(&mut chunks, Vec::with_capacity(n_chunks)),
@@ -728,54 +751,61 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
                 request_threads,
             )?;
 
-            for (docid, embeddings) in chunks_ids
-                .iter()
-                .flat_map(|docids| docids.iter())
-                .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-            {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings))?;
-            }
-            chunks_ids.clear();
+            write_embeddings(
+                &mut state_writer,
+                chunks_ids.drain(..).flatten(),
+                chunked_embeds.into_iter().flatten(),
+            )?;
         }
     }
 
-    // send last chunk
+    // send last chunks
+    if !current_chunk.is_empty() {
+        chunks.push(current_chunk);
+        chunks_ids.push(current_chunk_ids);
+    }
     if !chunks.is_empty() {
         let chunked_embeds = embed_chunks(
             &embedder,
-            std::mem::take(&mut chunks),
+            chunks,
             embedder_name,
             possible_embedding_mistakes,
             unused_vectors_distribution,
             request_threads,
         )?;
-        for (docid, embeddings) in chunks_ids
-            .iter()
-            .flat_map(|docids| docids.iter())
-            .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-        {
-            state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings))?;
-        }
+        write_embeddings(
+            &mut state_writer,
+            chunks_ids.into_iter().flatten(),
+            chunked_embeds.into_iter().flatten(),
+        )?;
     }
 
-    if !current_chunk.is_empty() {
-        let embeds = embed_chunks(
-            &embedder,
-            vec![std::mem::take(&mut current_chunk)],
-            embedder_name,
-            possible_embedding_mistakes,
-            unused_vectors_distribution,
-            request_threads,
-        )?;
+    writer_into_reader(state_writer)
+}
 
-        if let Some(embeds) = embeds.first() {
-            for (docid, embeddings) in current_chunk_ids.iter().zip(embeds.iter()) {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings))?;
+/// Writes the embeddings of each document, all the embeddings of a document being contiguous.
+fn write_embeddings(
+    state_writer: &mut Writer<BufWriter<File>>,
+    docids: impl IntoIterator<Item = DocumentId>,
+    embeddings: impl IntoIterator<Item = Embedding>,
+) -> Result<()> {
+    let mut current: Option<(DocumentId, Embedding)> = None;
+    for (docid, embedding) in docids.into_iter().zip(embeddings) {
+        match &mut current {
+            Some((current_docid, data)) if *current_docid == docid => {
+                data.extend_from_slice(&embedding)
+            }
+            _ => {
+                if let Some((docid, data)) = current.replace((docid, embedding)) {
+                    state_writer.insert(docid.to_be_bytes(), cast_slice(&data))?;
+                }
             }
         }
     }
-
-    writer_into_reader(state_writer)
+    if let Some((docid, data)) = current {
+        state_writer.insert(docid.to_be_bytes(), cast_slice(&data))?;
+    }
+    Ok(())
 }
 
 fn embed_chunks(
@@ -11,6 +11,7 @@ use std::num::NonZeroU32;
 use std::sync::Arc;
 
 use crossbeam_channel::{Receiver, Sender};
+use deserr::Deserr;
 use enrich::enrich_documents_batch;
 use grenad::{Merger, MergerBuilder};
 use hashbrown::HashMap;
@@ -22,6 +23,7 @@ use serde::{Deserialize, Serialize};
 use slice_group_by::GroupBy;
 use tracing::debug;
 use typed_chunk::{write_typed_chunk_into_index, ChunkAccumulator, TypedChunk};
+use utoipa::ToSchema;
 
 pub use self::enrich::{extract_finite_float_from_value, DocumentId};
 pub use self::helpers::*;
@@ -35,6 +37,7 @@ use crate::index::{PrefixSearch, PrefixSettings};
 use crate::progress::Progress;
 use crate::thread_pool_no_abort::ThreadPoolNoAbortBuilder;
 pub use crate::update::index_documents::helpers::CursorClonableMmap;
+use crate::update::sparse_vectors::update_sparse_vectors;
 use crate::update::{
     IndexerConfig, UpdateIndexingStep, WordPrefixDocids, WordPrefixIntegerDocids, WordsPrefixesFst,
 };
@@ -71,6 +74,26 @@ impl Default for IndexDocumentsMethod {
     }
 }
 
+/// What to do with the documents of a payload that cannot be indexed.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Deserr, ToSchema,
+)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum OnError {
+    /// Fail the whole payload on the first invalid document.
+    #[default]
+    Fail,
+    /// Skip the invalid documents and index the other ones.
+    Skip,
+}
+
+impl OnError {
+    pub fn is_fail(&self) -> bool {
+        *self == OnError::Fail
+    }
+}
+
 pub struct IndexDocuments<'t, 'i, 'a, FP, FA> {
     wtxn: &'t mut heed::RwTxn<'i>,
     index: &'i Index,
@@ -498,8 +521,12 @@ where
                         key: None,
                     },
                 )?;
-                let reader =
-                    ArroyWrapper::new(self.index.vector_arroy, index, action.was_quantized);
+                let reader = ArroyWrapper::new(
+                    self.index.vector_arroy,
+                    self.index.vector_int8,
+                    index,
+                    action.was_quantized,
+                );
                 let dim = reader.dimensions(self.wtxn)?;
                 dimension.insert(name.to_string(), dim);
             }
@@ -508,6 +535,7 @@ where
         for (embedder_name, dimension) in dimension {
             let wtxn = &mut *self.wtxn;
             let vector_arroy = self.index.vector_arroy;
+            let vector_int8 = self.index.vector_int8;
             let cancel = &self.should_abort;
 
             let embedder_index = self.index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
@@ -517,9 +545,14 @@ where
             let was_quantized =
                 settings_diff.old.embedding_configs.get(&embedder_name).is_some_and(|conf| conf.2);
             let is_quantizing = embedder_config.is_some_and(|action| action.is_being_quantized);
+            let documents_ids =
+                if is_quantizing { self.index.documents_ids(wtxn)? } else { RoaringBitmap::new() };
 
             pool.install(|| {
-                let mut writer = ArroyWrapper::new(vector_arroy, embedder_index, was_quantized);
+                let mut writer =
+                    ArroyWrapper::new(vector_arroy, vector_int8, embedder_index, was_quantized);
+                // embedders with int8 rescoring keep an int8 copy of the embeddings that are about to be binary quantized
+                writer.scalar_quantize_items(wtxn, dimension, &documents_ids)?;
                 writer.build_and_quantize(
                     wtxn,
                     // In the settings we don't have any progress to share
@@ -535,6 +568,11 @@ where
             .map_err(InternalError::from)??;
         }
 
+        // settings updates rebuild the sparse embeddings themselves, only when their embedder changed
+        if !settings_diff.settings_update_only {
+            update_sparse_vectors(self.wtxn, self.index, &modified_docids, pool)?;
+        }
+
         self.execute_prefix_databases(
             word_docids.map(MergerBuilder::build),
             exact_word_docids.map(MergerBuilder::build),
@@ -2784,6 +2822,9 @@ mod tests {
                         search_embedder: Setting::NotSet,
                         indexing_embedder: Setting::NotSet,
                         binary_quantized: Setting::NotSet,
+                        int8_rescoring: Setting::NotSet,
+                        chunking: Setting::NotSet,
+                        request_policy: Setting::NotSet,
                     }),
                 );
                 settings.set_embedder_settings(embedders);
@@ -843,6 +843,7 @@ impl<'a, 'i> Transform<'a, 'i> {
                 {
                     let reader = ArroyWrapper::new(
                         self.index.vector_arroy,
+                        self.index.vector_int8,
                         *embedder_id,
                         action.was_quantized,
                     );
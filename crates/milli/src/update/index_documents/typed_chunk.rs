@@ -218,6 +218,8 @@ pub(crate) fn write_typed_chunk_into_index(
                     modified_docids.insert(docid);
                 } else {
                     db.delete(wtxn, &docid)?;
+                    index.documents_versions.delete(wtxn, &docid)?;
+                    index.document_popularity.delete(wtxn, &docid)?;
                     operations.push(DocumentOperation {
                         external_id: external_id.to_string(),
                         internal_id: docid,
@@ -667,7 +669,12 @@ pub(crate) fn write_typed_chunk_into_index(
             let binary_quantized =
                 settings_diff.old.embedding_configs.get(&embedder_name).is_some_and(|conf| conf.2);
             // FIXME: allow customizing distance
-            let writer = ArroyWrapper::new(index.vector_arroy, embedder_index, binary_quantized);
+            let writer = ArroyWrapper::new(
+                index.vector_arroy,
+                index.vector_int8,
+                embedder_index,
+                binary_quantized,
+            );
 
             // remove vectors for docids we want them removed
             let merger = remove_vectors_builder.build();
@@ -21,6 +21,7 @@ mod index_documents;
 mod indexer_config;
 pub mod new;
 pub(crate) mod settings;
+pub(crate) mod sparse_vectors;
 mod update_step;
 pub mod upgrade;
 mod word_prefix_docids;
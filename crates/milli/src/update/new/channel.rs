@@ -384,6 +384,8 @@ impl ArroySetVectors {
 pub enum Database {
     Main,
     Documents,
+    DocumentsVersions,
+    DocumentPopularity,
     ExternalDocumentsIds,
     ExactWordDocids,
     FidWordCountDocids,
@@ -405,6 +407,8 @@ impl Database {
         match self {
             Database::Main => index.main.remap_types(),
             Database::Documents => index.documents.remap_types(),
+            Database::DocumentsVersions => index.documents_versions.remap_types(),
+            Database::DocumentPopularity => index.document_popularity.remap_types(),
             Database::ExternalDocumentsIds => index.external_documents_ids.remap_types(),
             Database::ExactWordDocids => index.exact_word_docids.remap_types(),
             Database::WordDocids => index.word_docids.remap_types(),
@@ -426,6 +430,8 @@ impl Database {
         match self {
             Database::Main => db_name::MAIN,
             Database::Documents => db_name::DOCUMENTS,
+            Database::DocumentsVersions => db_name::DOCUMENTS_VERSIONS,
+            Database::DocumentPopularity => db_name::DOCUMENT_POPULARITY,
             Database::ExternalDocumentsIds => db_name::EXTERNAL_DOCUMENTS_IDS,
             Database::ExactWordDocids => db_name::EXACT_WORD_DOCIDS,
             Database::WordDocids => db_name::WORD_DOCIDS,
@@ -908,9 +914,15 @@ impl DocumentsSender<'_, '_> {
         &self,
         docid: DocumentId,
         external_id: String,
+        version: u64,
         document: &KvReaderFieldId,
     ) -> crate::Result<()> {
         self.0.write_key_value(Database::Documents, &docid.to_be_bytes(), document.as_bytes())?;
+        self.0.write_key_value(
+            Database::DocumentsVersions,
+            &docid.to_be_bytes(),
+            &version.to_be_bytes(),
+        )?;
         self.0.write_key_value(
             Database::ExternalDocumentsIds,
             external_id.as_bytes(),
@@ -920,6 +932,8 @@ impl DocumentsSender<'_, '_> {
 
     pub fn delete(&self, docid: DocumentId, external_id: String) -> crate::Result<()> {
         self.0.delete_entry(Database::Documents, &docid.to_be_bytes())?;
+        self.0.delete_entry(Database::DocumentsVersions, &docid.to_be_bytes())?;
+        self.0.delete_entry(Database::DocumentPopularity, &docid.to_be_bytes())?;
         self.0.delete_vector(docid)?;
         self.0.delete_entry(Database::ExternalDocumentsIds, external_id.as_bytes())
     }
@@ -7,7 +7,10 @@ use serde_json::value::RawValue;
 
 use super::vector_document::VectorDocument;
 use super::{KvReaderFieldId, KvWriterFieldId};
-use crate::constants::{RESERVED_GEO_FIELD_NAME, RESERVED_VECTORS_FIELD_NAME};
+use crate::constants::{
+    RESERVED_GEO_FIELD_NAME, RESERVED_IF_VERSION_FIELD_NAME, RESERVED_VECTORS_FIELD_NAME,
+    RESERVED_VERSION_FIELD_NAME,
+};
 use crate::documents::FieldIdMapper;
 use crate::{DocumentId, GlobalFieldsIdsMap, Index, InternalError, Result, UserError};
 
@@ -152,6 +155,11 @@ impl<'a, 'doc> DocumentFromVersions<'a, 'doc> {
     pub fn new(versions: &'a Versions<'doc>) -> Self {
         Self { versions }
     }
+
+    /// Whether the top-level field was removed from the document, so that it must not be taken from the DB.
+    pub fn is_unset(&self, k: &str) -> bool {
+        self.versions.is_unset(k)
+    }
 }
 
 impl<'doc> Document<'doc> for DocumentFromVersions<'_, 'doc> {
@@ -168,14 +176,7 @@ impl<'doc> Document<'doc> for DocumentFromVersions<'_, 'doc> {
     }
 
     fn top_level_fields_count(&self) -> usize {
-        let has_vectors_field = self.vectors_field().unwrap_or(None).is_some();
-        let has_geo_field = self.geo_field().unwrap_or(None).is_some();
-        let count = self.versions.len();
-        match (has_vectors_field, has_geo_field) {
-            (true, true) => count - 2,
-            (true, false) | (false, true) => count - 1,
-            (false, false) => count,
-        }
+        self.versions.iter_top_level_fields().count()
     }
 
     fn top_level_field(&self, k: &str) -> Result<Option<&'doc RawValue>> {
@@ -224,7 +225,7 @@ impl<'d, 'doc: 'd, 't: 'd, Mapper: FieldIdMapper> Document<'d>
             loop {
                 match db_it.next()? {
                     Ok((name, value)) => {
-                        if seen_fields.contains(name) {
+                        if seen_fields.contains(name) || self.new_doc.is_unset(name) {
                             continue;
                         }
                         return Some(Ok((name, value)));
@@ -263,6 +264,9 @@ impl<'d, 'doc: 'd, 't: 'd, Mapper: FieldIdMapper> Document<'d>
         if let Some(f) = self.new_doc.top_level_field(k)? {
             return Ok(Some(f));
         }
+        if self.new_doc.is_unset(k) {
+            return Ok(None);
+        }
         if let Some(db) = self.db {
             return db.field(k);
         }
@@ -389,6 +393,8 @@ pub type Entry<'doc> = (&'doc str, &'doc RawValue);
 #[derive(Debug)]
 pub struct Versions<'doc> {
     data: RawMap<'doc, FxBuildHasher>,
+    /// Top-level fields removed by an update, hiding both their value in `data` and in the DB.
+    unset: BTreeSet<&'doc str>,
 }
 
 impl<'doc> Versions<'doc> {
@@ -407,13 +413,31 @@ impl<'doc> Versions<'doc> {
     }
 
     pub fn single(version: RawMap<'doc, FxBuildHasher>) -> Self {
-        Self { data: version }
+        Self { data: version, unset: BTreeSet::new() }
+    }
+
+    /// Sets the value of a top-level field, overriding its value in the previous versions.
+    pub fn insert(&mut self, k: &'doc str, value: &'doc RawValue) {
+        self.unset.remove(k);
+        self.data.insert(k, value);
+    }
+
+    /// Removes a top-level field from the document.
+    pub fn unset(&mut self, k: &'doc str) {
+        self.unset.insert(k);
+    }
+
+    pub fn is_unset(&self, k: &str) -> bool {
+        self.unset.contains(k)
+    }
+
+    /// Iterate over the top-level fields removed from the document.
+    pub fn unset_fields(&self) -> impl Iterator<Item = &'doc str> + '_ {
+        self.unset.iter().copied()
     }
 
     pub fn iter_top_level_fields(&self) -> impl Iterator<Item = (&'doc str, &'doc RawValue)> + '_ {
-        self.data
-            .iter()
-            .filter(|(k, _)| *k != RESERVED_VECTORS_FIELD_NAME && *k != RESERVED_GEO_FIELD_NAME)
+        self.data.iter().filter(|(k, _)| !is_reserved_in_versions(k) && !self.unset.contains(k))
     }
 
     pub fn vectors_field(&self) -> Option<&'doc RawValue> {
@@ -425,17 +449,27 @@ impl<'doc> Versions<'doc> {
     }
 
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.data.len() - self.unset.iter().filter(|k| self.data.get(**k).is_some()).count()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.len() == 0
     }
 
     pub fn top_level_field(&self, k: &str) -> Option<&'doc RawValue> {
-        if k == RESERVED_VECTORS_FIELD_NAME || k == RESERVED_GEO_FIELD_NAME {
+        if is_reserved_in_versions(k) || self.is_unset(k) {
             return None;
         }
         self.data.get(k)
     }
 }
+
+/// Fields of the new versions of a document that are not top-level fields.
+///
+/// The version of a document is maintained by the indexer, so a `_version` sent in a payload is ignored.
+fn is_reserved_in_versions(k: &str) -> bool {
+    k == RESERVED_VECTORS_FIELD_NAME
+        || k == RESERVED_GEO_FIELD_NAME
+        || k == RESERVED_VERSION_FIELD_NAME
+        || k == RESERVED_IF_VERSION_FIELD_NAME
+}
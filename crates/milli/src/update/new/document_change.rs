@@ -211,7 +211,23 @@ impl<'doc> Update<'doc> {
         }
 
         if !self.from_scratch {
-            // no field deletion or update, so fields that don't appear in `updated` cannot have changed
+            if !changed {
+                // fields removed by the update changed if they were present in `current`
+                for key in self.new.unset_fields() {
+                    if selector(key) == PatternMatch::NoMatch {
+                        continue;
+                    }
+                    let current = match cached_current {
+                        Some(current) => current,
+                        None => self.current(rtxn, index, mapper)?,
+                    };
+                    if current.top_level_field(key)?.is_some() {
+                        return Ok(true);
+                    }
+                    cached_current = Some(current);
+                }
+            }
+            // no other field deletion, so fields that don't appear in `updated` cannot have changed
             return Ok(changed);
         }
 
@@ -124,7 +124,10 @@ impl<'extractor> Extractor<'extractor> for DocumentsExtractor<'_, '_> {
                         &mut new_fields_ids_map,
                         &mut document_buffer,
                     )?;
-                    self.document_sender.uncompressed(docid, external_docid, content).unwrap();
+                    let version = context.index.document_version(&context.rtxn, docid)? + 1;
+                    self.document_sender
+                        .uncompressed(docid, external_docid, version, content)
+                        .unwrap();
                 }
                 DocumentChange::Insertion(insertion) => {
                     let docid = insertion.docid();
@@ -150,7 +153,8 @@ impl<'extractor> Extractor<'extractor> for DocumentsExtractor<'_, '_> {
                         &mut document_buffer,
                     )?;
                     document_extractor_data.docids_delta.insert_add_u32(docid);
-                    self.document_sender.uncompressed(docid, external_docid, content).unwrap();
+                    // inserted documents start at the first version
+                    self.document_sender.uncompressed(docid, external_docid, 1, content).unwrap();
                 }
             }
         }
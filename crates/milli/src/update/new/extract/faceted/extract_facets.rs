@@ -34,6 +34,7 @@ pub struct FacetedExtractorData<'a, 'b> {
     sortable_fields: &'a HashSet<String>,
     asc_desc_fields: &'a HashSet<String>,
     distinct_field: &'a Option<String>,
+    ttl_field: &'a Option<String>,
     is_geo_enabled: bool,
 }
 
@@ -61,6 +62,7 @@ impl<'extractor> Extractor<'extractor> for FacetedExtractorData<'_, '_> {
                 self.sortable_fields,
                 self.asc_desc_fields,
                 self.distinct_field,
+                self.ttl_field,
                 self.is_geo_enabled,
                 change,
                 self.sender,
@@ -80,6 +82,7 @@ impl FacetedDocidsExtractor {
         sortable_fields: &HashSet<String>,
         asc_desc_fields: &HashSet<String>,
         distinct_field: &Option<String>,
+        ttl_field: &Option<String>,
         is_geo_enabled: bool,
         document_change: DocumentChange,
         sender: &FieldIdDocidFacetSender,
@@ -99,6 +102,7 @@ impl FacetedDocidsExtractor {
                 sortable_fields,
                 asc_desc_fields,
                 distinct_field,
+                ttl_field,
                 is_geo_enabled,
                 &mut |fid, meta, depth, value| {
                     Self::facet_fn_with_options(
@@ -125,6 +129,7 @@ impl FacetedDocidsExtractor {
                             sortable_fields,
                             asc_desc_fields,
                             distinct_field,
+                            ttl_field,
                         )
                     },
                     rtxn,
@@ -145,6 +150,7 @@ impl FacetedDocidsExtractor {
                     sortable_fields,
                     asc_desc_fields,
                     distinct_field,
+                    ttl_field,
                     is_geo_enabled,
                     &mut |fid, meta, depth, value| {
                         Self::facet_fn_with_options(
@@ -171,6 +177,7 @@ impl FacetedDocidsExtractor {
                     sortable_fields,
                     asc_desc_fields,
                     distinct_field,
+                    ttl_field,
                     is_geo_enabled,
                     &mut |fid, meta, depth, value| {
                         Self::facet_fn_with_options(
@@ -197,6 +204,7 @@ impl FacetedDocidsExtractor {
                 sortable_fields,
                 asc_desc_fields,
                 distinct_field,
+                ttl_field,
                 is_geo_enabled,
                 &mut |fid, meta, depth, value| {
                     Self::facet_fn_with_options(
@@ -469,6 +477,7 @@ impl FacetedDocidsExtractor {
         let sortable_fields = index.sortable_fields(&rtxn)?;
         let asc_desc_fields = index.asc_desc_fields(&rtxn)?;
         let distinct_field = index.distinct_field(&rtxn)?.map(|s| s.to_string());
+        let ttl_field = index.ttl_field(&rtxn)?.map(|s| s.to_string());
         let is_geo_enabled = index.is_geo_enabled(&rtxn)?;
         let datastore = ThreadLocal::new();
 
@@ -485,6 +494,7 @@ impl FacetedDocidsExtractor {
                 sortable_fields: &sortable_fields,
                 asc_desc_fields: &asc_desc_fields,
                 distinct_field: &distinct_field,
+                ttl_field: &ttl_field,
                 is_geo_enabled,
             };
             extract(
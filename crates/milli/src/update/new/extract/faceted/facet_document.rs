@@ -22,6 +22,7 @@ pub fn extract_document_facets<'doc>(
     sortable_fields: &HashSet<String>,
     asc_desc_fields: &HashSet<String>,
     distinct_field: &Option<String>,
+    ttl_field: &Option<String>,
     is_geo_enabled: bool,
     facet_fn: &mut impl FnMut(FieldId, Metadata, perm_json_p::Depth, &Value) -> Result<()>,
 ) -> Result<()> {
@@ -33,6 +34,7 @@ pub fn extract_document_facets<'doc>(
             sortable_fields,
             asc_desc_fields,
             distinct_field,
+            ttl_field,
         )
     };
 
@@ -233,6 +233,7 @@ mod test {
                 Default::default(),
                 None,
                 None,
+                None,
                 Default::default(),
             ),
         );
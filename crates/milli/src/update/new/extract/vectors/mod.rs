@@ -131,7 +131,7 @@ impl<'extractor> Extractor<'extractor> for EmbeddingExtractor<'_, '_> {
                                         })?,
                                 )?;
                             } else if new_vectors.regenerate {
-                                let new_rendered = prompt.render_document(
+                                let new_rendered = prompt.render_chunks(
                                     update.external_document_id(),
                                     update.current(
                                         &context.rtxn,
@@ -141,7 +141,7 @@ impl<'extractor> Extractor<'extractor> for EmbeddingExtractor<'_, '_> {
                                     context.new_fields_ids_map,
                                     &context.doc_alloc,
                                 )?;
-                                let old_rendered = prompt.render_document(
+                                let old_rendered = prompt.render_chunks(
                                     update.external_document_id(),
                                     update.merged(
                                         &context.rtxn,
@@ -161,7 +161,7 @@ impl<'extractor> Extractor<'extractor> for EmbeddingExtractor<'_, '_> {
                                 }
                             }
                         } else if old_vectors.regenerate {
-                            let old_rendered = prompt.render_document(
+                            let old_rendered = prompt.render_chunks(
                                 update.external_document_id(),
                                 update.current(
                                     &context.rtxn,
@@ -171,7 +171,7 @@ impl<'extractor> Extractor<'extractor> for EmbeddingExtractor<'_, '_> {
                                 context.new_fields_ids_map,
                                 &context.doc_alloc,
                             )?;
-                            let new_rendered = prompt.render_document(
+                            let new_rendered = prompt.render_chunks(
                                 update.external_document_id(),
                                 update.merged(
                                     &context.rtxn,
@@ -222,7 +222,7 @@ impl<'extractor> Extractor<'extractor> for EmbeddingExtractor<'_, '_> {
                                         })?,
                                 )?;
                             } else if new_vectors.regenerate {
-                                let rendered = prompt.render_document(
+                                let rendered = prompt.render_chunks(
                                     insertion.external_document_id(),
                                     insertion.inserted(),
                                     context.new_fields_ids_map,
@@ -236,7 +236,7 @@ impl<'extractor> Extractor<'extractor> for EmbeddingExtractor<'_, '_> {
                                 )?;
                             }
                         } else {
-                            let rendered = prompt.render_document(
+                            let rendered = prompt.render_chunks(
                                 insertion.external_document_id(),
                                 insertion.inserted(),
                                 context.new_fields_ids_map,
@@ -317,36 +317,39 @@ impl<'a, 'b, 'extractor> Chunks<'a, 'b, 'extractor> {
         }
     }
 
+    /// Pushes the rendered texts of a document, one per chunk.
+    ///
+    /// All the texts of a document are embedded in the same batch, as they replace all its vectors at once.
     pub fn set_autogenerated(
         &mut self,
         docid: DocumentId,
         external_docid: &'a str,
-        rendered: &'a str,
+        rendered: &[&'a str],
         unused_vectors_distribution: &UnusedVectorsDistributionBump,
     ) -> Result<()> {
+        if !self.texts.is_empty() && self.texts.len() + rendered.len() > self.texts.capacity() {
+            Self::embed_chunks(
+                &mut self.texts,
+                &mut self.ids,
+                self.embedder,
+                self.embedder_id,
+                self.embedder_name,
+                self.possible_embedding_mistakes,
+                unused_vectors_distribution,
+                self.threads,
+                self.sender,
+                self.has_manual_generation.take(),
+            )?;
+        }
+
         let is_manual = matches!(&self.embedder, &Embedder::UserProvided(_));
         if is_manual {
             self.has_manual_generation.get_or_insert(external_docid);
         }
 
-        if self.texts.len() < self.texts.capacity() {
-            self.texts.push(rendered);
-            self.ids.push(docid);
-            return Ok(());
-        }
-
-        Self::embed_chunks(
-            &mut self.texts,
-            &mut self.ids,
-            self.embedder,
-            self.embedder_id,
-            self.embedder_name,
-            self.possible_embedding_mistakes,
-            unused_vectors_distribution,
-            self.threads,
-            self.sender,
-            self.has_manual_generation.take(),
-        )
+        self.texts.extend_from_slice(rendered);
+        self.ids.extend(std::iter::repeat(docid).take(rendered.len()));
+        Ok(())
     }
 
     pub fn drain(
@@ -421,10 +424,13 @@ impl<'a, 'b, 'extractor> Chunks<'a, 'b, 'extractor> {
             return Err(crate::Error::UserError(crate::UserError::DocumentEmbeddingError(msg)));
         }
 
-        let res = match embedder.embed_index_ref(texts.as_slice(), threads) {
+        let res = match embedder.embed_index_ref(texts.as_slice(), threads, None) {
             Ok(embeddings) => {
-                for (docid, embedding) in ids.into_iter().zip(embeddings) {
-                    sender.set_vector(*docid, embedder_id, embedding).unwrap();
+                let mut embeddings = embeddings.into_iter();
+                // the texts of a document are contiguous, one per chunk
+                for docids in ids.chunk_by(|left, right| left == right) {
+                    let document_embeddings = embeddings.by_ref().take(docids.len()).collect();
+                    sender.set_vectors(docids[0], embedder_id, document_embeddings).unwrap();
                 }
                 Ok(())
             }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::atomic::Ordering;
 
 use bumpalo::collections::CollectIn;
@@ -13,14 +14,25 @@ use serde_json::Deserializer;
 
 use super::super::document_change::DocumentChange;
 use super::document_changes::{DocumentChangeContext, DocumentChanges};
+use super::document_operators::{
+    apply_operator, empty_document, is_operator, may_contain_operators, operator_error,
+    parse_operators, validate_operators, Operator, UNSET_OPERATOR,
+};
 use super::guess_primary_key::retrieve_or_guess_primary_key;
-use crate::documents::PrimaryKey;
+use crate::constants::{
+    RESERVED_GEO_FIELD_NAME, RESERVED_IF_VERSION_FIELD_NAME, RESERVED_VECTORS_FIELD_NAME,
+};
+use crate::documents::{DocumentSchema, PrimaryKey, SchemaMismatch, SchemaViolation};
 use crate::progress::{AtomicPayloadStep, Progress};
-use crate::update::new::document::Versions;
+use crate::update::new::document::{Document, DocumentFromDb, Versions};
+use crate::update::new::extract::extract_geo_coordinates;
 use crate::update::new::steps::IndexingStep;
 use crate::update::new::thread_local::MostlySend;
+use crate::update::new::vector_document::Embeddings;
 use crate::update::new::{Deletion, Insertion, Update};
-use crate::update::{AvailableIds, IndexDocumentsMethod};
+use crate::update::{AvailableIds, IndexDocumentsMethod, OnError};
+use crate::vector::parsed_vectors::RawVectors;
+use crate::vector::{ArroyWrapper, EmbedderOptions};
 use crate::{DocumentId, Error, FieldsIdsMap, Index, InternalError, Result, UserError};
 
 #[derive(Default)]
@@ -37,9 +49,20 @@ impl<'pl> DocumentOperation<'pl> {
     ///
     /// The payload is expected to be in the NDJSON format
     pub fn replace_documents(&mut self, payload: &'pl Mmap) -> Result<()> {
+        self.replace_documents_with_on_error(payload, OnError::Fail)
+    }
+
+    /// Append a replacement of documents, choosing what to do with its invalid documents.
+    ///
+    /// The payload is expected to be in the NDJSON format
+    pub fn replace_documents_with_on_error(
+        &mut self,
+        payload: &'pl Mmap,
+        on_error: OnError,
+    ) -> Result<()> {
         #[cfg(unix)]
         payload.advise(memmap2::Advice::Sequential)?;
-        self.operations.push(Payload::Replace(&payload[..]));
+        self.operations.push(Payload::Replace(&payload[..], on_error));
         Ok(())
     }
 
@@ -47,9 +70,20 @@ impl<'pl> DocumentOperation<'pl> {
     ///
     /// The payload is expected to be in the NDJSON format
     pub fn update_documents(&mut self, payload: &'pl Mmap) -> Result<()> {
+        self.update_documents_with_on_error(payload, OnError::Fail)
+    }
+
+    /// Append an update of documents, choosing what to do with its invalid documents.
+    ///
+    /// The payload is expected to be in the NDJSON format
+    pub fn update_documents_with_on_error(
+        &mut self,
+        payload: &'pl Mmap,
+        on_error: OnError,
+    ) -> Result<()> {
         #[cfg(unix)]
         payload.advise(memmap2::Advice::Sequential)?;
-        self.operations.push(Payload::Update(&payload[..]));
+        self.operations.push(Payload::Update(&payload[..], on_error));
         Ok(())
     }
 
@@ -79,6 +113,24 @@ impl<'pl> DocumentOperation<'pl> {
         let Self { operations } = self;
 
         let documents_ids = index.documents_ids(rtxn)?;
+        let document_schema = index
+            .document_schema(rtxn)?
+            .map(|schema| DocumentSchema::new(&schema))
+            .transpose()
+            .map_err(UserError::InvalidDocumentSchema)?;
+        let db_fields_ids_map = index.fields_ids_map(rtxn)?;
+        let geo_enabled = index.is_geo_enabled(rtxn)?;
+        let skips_invalid_documents = operations.iter().any(|operation| {
+            matches!(
+                operation,
+                Payload::Replace(_, OnError::Skip) | Payload::Update(_, OnError::Skip)
+            )
+        });
+        let embedders = if skips_invalid_documents {
+            embedders_dimensions(index, rtxn)?
+        } else {
+            BTreeMap::new()
+        };
         let mut operations_stats = Vec::new();
         let mut available_docids = AvailableIds::new(&documents_ids);
         let mut docids_version_offsets = hashbrown::HashMap::new();
@@ -95,8 +147,10 @@ impl<'pl> DocumentOperation<'pl> {
             step.store(payload_index as u32, Ordering::Relaxed);
 
             let mut bytes = 0;
+            let mut version_conflicts = Vec::new();
+            let mut skipped_documents = Vec::new();
             let result = match operation {
-                Payload::Replace(payload) => extract_addition_payload_changes(
+                Payload::Replace(payload, on_error) => extract_addition_payload_changes(
                     indexer,
                     index,
                     rtxn,
@@ -105,11 +159,18 @@ impl<'pl> DocumentOperation<'pl> {
                     new_fields_ids_map,
                     &mut available_docids,
                     &mut bytes,
+                    &mut version_conflicts,
+                    &mut skipped_documents,
                     &docids_version_offsets,
+                    &db_fields_ids_map,
+                    document_schema.as_ref(),
+                    geo_enabled,
+                    &embedders,
                     IndexDocumentsMethod::ReplaceDocuments,
+                    on_error,
                     payload,
                 ),
-                Payload::Update(payload) => extract_addition_payload_changes(
+                Payload::Update(payload, on_error) => extract_addition_payload_changes(
                     indexer,
                     index,
                     rtxn,
@@ -118,8 +179,15 @@ impl<'pl> DocumentOperation<'pl> {
                     new_fields_ids_map,
                     &mut available_docids,
                     &mut bytes,
+                    &mut version_conflicts,
+                    &mut skipped_documents,
                     &docids_version_offsets,
+                    &db_fields_ids_map,
+                    document_schema.as_ref(),
+                    geo_enabled,
+                    &embedders,
                     IndexDocumentsMethod::UpdateDocuments,
+                    on_error,
                     payload,
                 ),
                 Payload::Deletion(to_delete) => extract_deletion_payload_changes(
@@ -140,10 +208,20 @@ impl<'pl> DocumentOperation<'pl> {
                     merge_version_offsets(&mut docids_version_offsets, new_docids_version_offsets);
                     None
                 }
-                Err(Error::UserError(user_error)) => Some(user_error),
+                Err(Error::UserError(user_error)) => {
+                    version_conflicts.clear();
+                    skipped_documents.clear();
+                    Some(user_error)
+                }
                 Err(e) => return Err(e),
             };
-            operations_stats.push(PayloadStats { document_count, bytes, error });
+            operations_stats.push(PayloadStats {
+                document_count,
+                bytes,
+                error,
+                version_conflicts,
+                skipped_documents,
+            });
         }
         step.store(payload_count as u32, Ordering::Relaxed);
 
@@ -171,13 +249,22 @@ fn extract_addition_payload_changes<'r, 'pl: 'r>(
     new_fields_ids_map: &mut FieldsIdsMap,
     available_docids: &mut AvailableIds,
     bytes: &mut u64,
+    version_conflicts: &mut Vec<VersionConflict>,
+    skipped_documents: &mut Vec<SkippedDocument>,
     main_docids_version_offsets: &hashbrown::HashMap<&'pl str, PayloadOperations<'pl>>,
+    db_fields_ids_map: &FieldsIdsMap,
+    document_schema: Option<&DocumentSchema>,
+    geo_enabled: bool,
+    embedders: &BTreeMap<String, EmbedderVectors>,
     method: IndexDocumentsMethod,
+    on_error: OnError,
     payload: &'pl [u8],
 ) -> Result<hashbrown::HashMap<&'pl str, PayloadOperations<'pl>>> {
     use IndexDocumentsMethod::{ReplaceDocuments, UpdateDocuments};
 
     let mut new_docids_version_offsets = hashbrown::HashMap::<&str, PayloadOperations<'pl>>::new();
+    let mut schema_mismatches = Vec::new();
+    let mut operators_alloc = Bump::new();
 
     let mut previous_offset = 0;
     let mut iter = Deserializer::from_slice(payload).into_iter::<&RawValue>();
@@ -210,13 +297,92 @@ fn extract_addition_payload_changes<'r, 'pl: 'r>(
             primary_key.as_ref().unwrap()
         };
 
-        let external_id =
-            retrieved_primary_key.extract_fields_and_docid(doc, new_fields_ids_map, indexer)?;
+        let external_id = match retrieved_primary_key.extract_fields_and_docid(
+            doc,
+            new_fields_ids_map,
+            indexer,
+        ) {
+            Ok(external_id) => external_id,
+            Err(Error::UserError(error)) if on_error == OnError::Skip => {
+                skipped_documents
+                    .push(SkippedDocument { document_id: None, reason: error.to_string() });
+                previous_offset = iter.byte_offset();
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
 
         let external_id = external_id.to_de();
         let current_offset = iter.byte_offset();
         let document_offset = DocumentOffset { content: &payload[previous_offset..current_offset] };
 
+        let checked = check_document(
+            doc,
+            external_id,
+            retrieved_primary_key,
+            document_schema,
+            geo_enabled,
+            embedders,
+            method,
+            on_error,
+            indexer,
+        )
+        .and_then(|expected_version| {
+            if method == UpdateDocuments && may_contain_operators(doc) {
+                operators_alloc.reset();
+                check_operator_results(
+                    index,
+                    rtxn,
+                    db_fields_ids_map,
+                    external_id,
+                    [
+                        main_docids_version_offsets.get(external_id),
+                        new_docids_version_offsets.get(external_id),
+                    ],
+                    document_offset.clone(),
+                    document_schema,
+                    &operators_alloc,
+                )?;
+            }
+            Ok(expected_version)
+        });
+        let expected_version = match checked {
+            Ok(expected_version) => expected_version,
+            // the schema mismatches are reported all at once at the end of the payload
+            Err(Error::UserError(UserError::DocumentSchemaMismatch(mut mismatches)))
+                if on_error == OnError::Fail =>
+            {
+                schema_mismatches.append(&mut mismatches);
+                previous_offset = iter.byte_offset();
+                continue;
+            }
+            Err(Error::UserError(error)) if on_error == OnError::Skip => {
+                skipped_documents.push(SkippedDocument {
+                    document_id: Some(external_id.to_string()),
+                    reason: error.to_string(),
+                });
+                previous_offset = iter.byte_offset();
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+
+        if let Some(expected_version) = expected_version {
+            let payload_operations = new_docids_version_offsets
+                .get(external_id)
+                .or_else(|| main_docids_version_offsets.get(external_id));
+            let current_version = current_version(index, rtxn, external_id, payload_operations)?;
+            if current_version != expected_version {
+                version_conflicts.push(VersionConflict {
+                    document_id: external_id.to_string(),
+                    expected_version,
+                    current_version,
+                });
+                previous_offset = iter.byte_offset();
+                continue;
+            }
+        }
+
         match main_docids_version_offsets.get(external_id) {
             None => {
                 match index.external_documents_ids().get(rtxn, external_id) {
@@ -303,6 +469,10 @@ fn extract_addition_payload_changes<'r, 'pl: 'r>(
         previous_offset = iter.byte_offset();
     }
 
+    if !schema_mismatches.is_empty() {
+        return Err(UserError::DocumentSchemaMismatch(schema_mismatches).into());
+    }
+
     if payload.is_empty() {
         let result = retrieve_or_guess_primary_key(
             rtxn,
@@ -324,6 +494,313 @@ fn extract_addition_payload_changes<'r, 'pl: 'r>(
     Ok(new_docids_version_offsets)
 }
 
+/// Validates the operators of the document and its document schema, and returns its `_ifVersion` condition, if any.
+///
+/// When the invalid documents are skipped, the reserved `_geo` and `_vectors` fields are validated too,
+/// as an invalid value would otherwise only be found while extracting the documents and fail the whole batch.
+#[allow(clippy::too_many_arguments)]
+fn check_document(
+    document: &RawValue,
+    external_id: &str,
+    primary_key: &PrimaryKey,
+    document_schema: Option<&DocumentSchema>,
+    geo_enabled: bool,
+    embedders: &BTreeMap<String, EmbedderVectors>,
+    method: IndexDocumentsMethod,
+    on_error: OnError,
+    doc_alloc: &Bump,
+) -> Result<Option<u64>> {
+    if method == IndexDocumentsMethod::UpdateDocuments && may_contain_operators(document) {
+        let document = RawMap::from_raw_value_and_hasher(document, FxBuildHasher, doc_alloc)
+            .map_err(UserError::SerdeJson)?;
+        validate_operators(&document, primary_key.name(), doc_alloc)
+            .map_err(|error| operator_error(external_id, error))?;
+    }
+
+    if let Some(schema) = document_schema {
+        let violations = schema_violations(schema, document, method)?;
+        if !violations.is_empty() {
+            let mismatch = SchemaMismatch { document_id: external_id.to_string(), violations };
+            return Err(UserError::DocumentSchemaMismatch(vec![mismatch]).into());
+        }
+    }
+
+    if on_error == OnError::Skip {
+        validate_reserved_fields(document, external_id, geo_enabled, embedders, doc_alloc)?;
+    }
+
+    if_version(document, external_id, doc_alloc)
+}
+
+/// Validates the reserved `_geo` and `_vectors` fields of the document.
+///
+/// The `_geo` field is only validated when the geo search is enabled on the index.
+/// The `_vectors` field must only contain embeddings for the `embedders` of the index,
+/// with their dimensions when they are known.
+fn validate_reserved_fields(
+    document: &RawValue,
+    external_id: &str,
+    geo_enabled: bool,
+    embedders: &BTreeMap<String, EmbedderVectors>,
+    doc_alloc: &Bump,
+) -> Result<()> {
+    let document = RawMap::from_raw_value_and_hasher(document, FxBuildHasher, doc_alloc)
+        .map_err(UserError::SerdeJson)?;
+
+    if geo_enabled {
+        if let Some(geo) = document.get(RESERVED_GEO_FIELD_NAME) {
+            extract_geo_coordinates(external_id, geo)?;
+        }
+    }
+
+    if let Some(vectors) = document.get(RESERVED_VECTORS_FIELD_NAME) {
+        if vectors.get() == "null" {
+            return Ok(());
+        }
+        let Ok(vectors) = RawMap::from_raw_value_and_hasher(vectors, FxBuildHasher, doc_alloc)
+        else {
+            return Err(UserError::InvalidVectorsMapType {
+                document_id: external_id.to_string(),
+                value: serde_json::from_str(vectors.get()).map_err(InternalError::SerdeJson)?,
+            }
+            .into());
+        };
+        for (embedder_name, vectors) in vectors.iter() {
+            let invalid_conf = |error: String| UserError::InvalidVectorsEmbedderConf {
+                document_id: external_id.to_string(),
+                error,
+            };
+            let Some(embedder) = embedders.get(embedder_name) else {
+                return Err(invalid_conf(format!(
+                    "Could not find embedder `{embedder_name}` in the index."
+                ))
+                .into());
+            };
+            let vectors = RawVectors::from_raw_value(vectors)
+                .map_err(|error| invalid_conf(error.msg(embedder_name)))?;
+            let EmbedderVectors::Dense { dimensions } = embedder else { continue };
+            let embeddings = match vectors {
+                RawVectors::Explicit(vectors) => {
+                    vectors.embeddings.map(Embeddings::FromJsonExplicit)
+                }
+                RawVectors::ImplicitlyUserProvided(vectors) => {
+                    vectors.map(Embeddings::FromJsonImplicityUserProvided)
+                }
+            };
+            let Some(embeddings) = embeddings else { continue };
+            let embeddings = embeddings
+                .into_vec(doc_alloc, embedder_name)
+                .map_err(|error| invalid_conf(error.to_string()))?;
+            let Some(expected) = *dimensions else { continue };
+            if let Some((embedding_index, embedding)) =
+                embeddings.iter().enumerate().find(|(_, embedding)| embedding.len() != expected)
+            {
+                return Err(UserError::InvalidIndexingVectorDimensions {
+                    embedder_name: embedder_name.to_string(),
+                    document_id: external_id.to_string(),
+                    embedding_index,
+                    expected,
+                    found: embedding.len(),
+                }
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The embeddings a document can provide for an embedder of the index in its `_vectors` field.
+enum EmbedderVectors {
+    /// Dense embeddings, with the dimensions of the embedder when they are known.
+    Dense { dimensions: Option<usize> },
+    /// Sparse embeddings, only validated while they are extracted.
+    Sparse,
+}
+
+/// Returns the embedders of the index by name.
+///
+/// The dimensions of a dense embedder are known once it stores embeddings, or when it is user-provided.
+fn embedders_dimensions(index: &Index, rtxn: &RoTxn) -> Result<BTreeMap<String, EmbedderVectors>> {
+    let mut embedders = BTreeMap::new();
+    for config in index.embedding_configs(rtxn)? {
+        let embedder_id = index.embedder_category_id.get(rtxn, &config.name)?.ok_or(
+            InternalError::DatabaseMissingEntry {
+                db_name: crate::index::db_name::VECTOR_EMBEDDER_CATEGORY_ID,
+                key: None,
+            },
+        )?;
+        let reader = ArroyWrapper::new(
+            index.vector_arroy,
+            index.vector_int8,
+            embedder_id,
+            config.config.quantized(),
+        );
+        let dimensions = match reader.dimensions(rtxn) {
+            Ok(dimensions) => Some(dimensions),
+            Err(arroy::Error::MissingMetadata(_)) => match &config.config.embedder_options {
+                EmbedderOptions::UserProvided(options) => Some(options.dimensions),
+                _ => None,
+            },
+            Err(error) => return Err(error.into()),
+        };
+        embedders.insert(config.name, EmbedderVectors::Dense { dimensions });
+    }
+    for config in index.sparse_embedding_configs(rtxn)? {
+        embedders.insert(config.name, EmbedderVectors::Sparse);
+    }
+    Ok(embedders)
+}
+
+/// Returns the violations of the document schema found in the document.
+///
+/// The operators and the `_vectors` and `_ifVersion` reserved fields are not validated,
+/// and the required properties are only checked when the document is replaced.
+/// The results of the operators are validated by [`check_operator_results`].
+fn schema_violations(
+    schema: &DocumentSchema,
+    document: &RawValue,
+    method: IndexDocumentsMethod,
+) -> Result<Vec<SchemaViolation>> {
+    let mut document: serde_json::Value =
+        serde_json::from_str(document.get()).map_err(InternalError::SerdeJson)?;
+    let mut removed_fields = Vec::new();
+    if let Some(fields) = document.as_object_mut() {
+        if let Some(serde_json::Value::Array(unset)) = fields.remove(UNSET_OPERATOR) {
+            removed_fields.extend(unset.into_iter().filter_map(|field| match field {
+                serde_json::Value::String(field) => Some(field),
+                _ => None,
+            }));
+        }
+        fields.retain(|field, _| {
+            !is_operator(field)
+                && field != RESERVED_VECTORS_FIELD_NAME
+                && field != RESERVED_IF_VERSION_FIELD_NAME
+        });
+    }
+    Ok(match method {
+        IndexDocumentsMethod::ReplaceDocuments => schema.validate(&document),
+        IndexDocumentsMethod::UpdateDocuments => {
+            let mut violations =
+                schema.validate_removed_fields(removed_fields.iter().map(String::as_str));
+            violations.extend(schema.validate_update(&document));
+            violations
+        }
+    })
+}
+
+/// Applies the operators of an update on top of the previous operations of the document
+/// and of its stored version, and validates the values of the fields they modify against
+/// the document schema, as the schema of an operator result depends on the current value.
+///
+/// The operators that cannot be applied to the current values, e.g. `$inc` on a string,
+/// are reported here so that they only affect their own document or payload, as
+/// the operators are applied again when the documents are extracted, where an error
+/// would fail the whole batch.
+///
+/// `previous_operations` are the operations of the previous payloads and of the current one.
+#[allow(clippy::too_many_arguments)]
+fn check_operator_results<'pl>(
+    index: &Index,
+    rtxn: &RoTxn,
+    db_fields_ids_map: &FieldsIdsMap,
+    external_id: &'pl str,
+    previous_operations: [Option<&PayloadOperations<'pl>>; 2],
+    document_offset: DocumentOffset<'pl>,
+    schema: Option<&DocumentSchema>,
+    doc_alloc: &Bump,
+) -> Result<()> {
+    let (docid, is_new) = match previous_operations.iter().rev().flatten().next() {
+        Some(operations) => (operations.docid, operations.is_new),
+        None => match index.external_documents_ids().get(rtxn, external_id)? {
+            Some(docid) => (docid, false),
+            // the internal id of a new document is never read
+            None => (0, true),
+        },
+    };
+    let mut operations = PayloadOperations { docid, is_new, operations: Vec::new() };
+    for previous in previous_operations.into_iter().flatten() {
+        operations.append_operations(previous.operations.clone());
+    }
+    operations.push_update(document_offset.clone());
+
+    let document: &RawValue =
+        serde_json::from_slice(document_offset.content).map_err(InternalError::SerdeJson)?;
+    let document = RawMap::from_raw_value_and_hasher(document, FxBuildHasher, doc_alloc)
+        .map_err(UserError::SerdeJson)?;
+    let field_operators = parse_operators(&document, doc_alloc)
+        .map_err(|error| operator_error(external_id, error))?;
+
+    let change = operations.merge(external_id, rtxn, index, db_fields_ids_map, doc_alloc)?;
+    let Some(schema) = schema else { return Ok(()) };
+    let versions = match &change {
+        Some(DocumentChange::Insertion(insertion)) => insertion.inserted(),
+        Some(DocumentChange::Update(update)) => update.only_changed_fields(),
+        Some(DocumentChange::Deletion(_)) | None => return Ok(()),
+    };
+    let mut results = serde_json::Map::new();
+    for (field, operator) in field_operators {
+        if matches!(operator, Operator::Unset) {
+            continue;
+        }
+        if let Some(value) = versions.top_level_field(field)? {
+            let value = serde_json::from_str(value.get()).map_err(InternalError::SerdeJson)?;
+            results.insert(field.to_string(), value);
+        }
+    }
+
+    let violations = schema.validate_update(&serde_json::Value::Object(results));
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        let mismatch = SchemaMismatch { document_id: external_id.to_string(), violations };
+        Err(UserError::DocumentSchemaMismatch(vec![mismatch]).into())
+    }
+}
+
+/// Returns the `_ifVersion` condition of the document, if any.
+fn if_version(document: &RawValue, external_id: &str, doc_alloc: &Bump) -> Result<Option<u64>> {
+    if !document.get().contains(RESERVED_IF_VERSION_FIELD_NAME) {
+        return Ok(None);
+    }
+    let document = RawMap::from_raw_value_and_hasher(document, FxBuildHasher, doc_alloc)
+        .map_err(UserError::SerdeJson)?;
+    let Some(if_version) = document.get(RESERVED_IF_VERSION_FIELD_NAME) else { return Ok(None) };
+    match serde_json::from_str(if_version.get()) {
+        Ok(if_version) => Ok(Some(if_version)),
+        Err(_) => Err(UserError::InvalidDocumentIfVersion {
+            document_id: external_id.to_string(),
+            value: if_version.get().to_string(),
+        }
+        .into()),
+    }
+}
+
+/// Returns the version the document will have once the operations of the previous payloads are applied.
+///
+/// A document that doesn't exist has version `0`.
+fn current_version(
+    index: &Index,
+    rtxn: &RoTxn,
+    external_id: &str,
+    payload_operations: Option<&PayloadOperations>,
+) -> Result<u64> {
+    match payload_operations {
+        Some(PayloadOperations { operations, .. })
+            if matches!(operations.last(), Some(InnerDocOp::Deletion)) =>
+        {
+            Ok(0)
+        }
+        // the document is written once per batch, whatever the number of operations
+        Some(PayloadOperations { is_new: true, .. }) => Ok(1),
+        Some(PayloadOperations { docid, .. }) => Ok(index.document_version(rtxn, *docid)? + 1),
+        None => match index.external_documents_ids().get(rtxn, external_id)? {
+            Some(docid) => Ok(index.document_version(rtxn, docid)?),
+            None => Ok(0),
+        },
+    }
+}
+
 fn extract_deletion_payload_changes<'s, 'pl: 's>(
     index: &Index,
     rtxn: &RoTxn,
@@ -418,7 +895,13 @@ impl<'pl> DocumentChanges<'pl> for DocumentOperationChanges<'pl> {
         'pl: 'doc,
     {
         let (external_doc, payload_operations) = item;
-        payload_operations.merge(external_doc, &context.doc_alloc)
+        payload_operations.merge(
+            external_doc,
+            &context.rtxn,
+            context.index,
+            context.db_fields_ids_map,
+            &context.doc_alloc,
+        )
     }
 
     fn len(&self) -> usize {
@@ -431,8 +914,8 @@ pub struct DocumentOperationChanges<'pl> {
 }
 
 pub enum Payload<'pl> {
-    Replace(&'pl [u8]),
-    Update(&'pl [u8]),
+    Replace(&'pl [u8], OnError),
+    Update(&'pl [u8], OnError),
     Deletion(&'pl [&'pl str]),
 }
 
@@ -440,6 +923,24 @@ pub struct PayloadStats {
     pub bytes: u64,
     pub document_count: u64,
     pub error: Option<UserError>,
+    /// Documents of the payload that were skipped because their `_ifVersion` didn't match their current version.
+    pub version_conflicts: Vec<VersionConflict>,
+    /// Documents of the payload that were skipped because they are invalid, when the invalid documents are skipped.
+    pub skipped_documents: Vec<SkippedDocument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConflict {
+    pub document_id: String,
+    pub expected_version: u64,
+    pub current_version: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedDocument {
+    /// The id of the document, `None` if it couldn't be extracted.
+    pub document_id: Option<String>,
+    pub reason: String,
 }
 
 pub struct PayloadOperations<'pl> {
@@ -494,6 +995,9 @@ impl<'pl> PayloadOperations<'pl> {
     fn merge<'doc>(
         &self,
         external_doc: &'doc str,
+        rtxn: &'doc RoTxn,
+        index: &'doc Index,
+        db_fields_ids_map: &'doc FieldsIdsMap,
         doc_alloc: &'doc Bump,
     ) -> Result<Option<DocumentChange<'doc>>>
     where
@@ -541,22 +1045,87 @@ impl<'pl> PayloadOperations<'pl> {
                     None => &self.operations[..],
                 };
 
+                // The current version of the document, only fetched if an operator needs it.
+                let mut current: Option<DocumentFromDb<'doc, FieldsIdsMap>> = None;
+
                 // We collect the versions to generate the appropriate document.
-                let versions = operations.iter().map(|operation| {
-                    let DocumentOffset { content } = match operation {
-                        InnerDocOp::Replace(offset) | InnerDocOp::Update(offset) => offset,
+                let mut versions: Option<Versions<'doc>> = None;
+                for operation in operations {
+                    let (content, is_update) = match operation {
+                        InnerDocOp::Replace(DocumentOffset { content }) => (content, false),
+                        InnerDocOp::Update(DocumentOffset { content }) => (content, true),
                         InnerDocOp::Deletion => unreachable!("Deletion in document operations"),
                     };
 
                     let document = serde_json::from_slice(content).unwrap();
+                    let has_operators = is_update && may_contain_operators(document);
                     let document =
                         RawMap::from_raw_value_and_hasher(document, FxBuildHasher, doc_alloc)
                             .map_err(UserError::SerdeJson)?;
 
-                    Ok(document)
-                });
+                    if !has_operators {
+                        match versions.as_mut() {
+                            Some(versions) => {
+                                for (field, value) in document {
+                                    versions.insert(field, value);
+                                }
+                            }
+                            None => versions = Some(Versions::single(document)),
+                        }
+                        continue;
+                    }
+
+                    // Operators were validated and applied once when reading the payload.
+                    let field_operators = parse_operators(&document, doc_alloc)
+                        .map_err(|error| operator_error(external_doc, error))?;
+                    if versions.is_none() {
+                        versions = Some(Versions::single(empty_document(doc_alloc)?));
+                    }
+                    let versions = versions.as_mut().unwrap();
+                    for (field, value) in document {
+                        if !is_operator(field) {
+                            versions.insert(field, value);
+                        }
+                    }
+                    for (field, operator) in field_operators {
+                        let current_value = if versions.is_unset(field) {
+                            None
+                        } else if let Some(value) = versions.top_level_field(field) {
+                            Some(value)
+                        } else if self.is_new || from_scratch {
+                            None
+                        } else {
+                            let current = match current {
+                                Some(current) => current,
+                                None => *current.insert(
+                                    DocumentFromDb::new(
+                                        self.docid,
+                                        rtxn,
+                                        index,
+                                        db_fields_ids_map,
+                                    )?
+                                    .ok_or(
+                                        UserError::UnknownInternalDocumentId {
+                                            document_id: self.docid,
+                                        },
+                                    )?,
+                                ),
+                            };
+                            current.field(field)?
+                        };
+
+                        match apply_operator(field, operator, current_value, doc_alloc)
+                            .map_err(|error| operator_error(external_doc, error))?
+                        {
+                            Some(value) => versions.insert(field, value),
+                            None if matches!(operator, Operator::Unset) => versions.unset(field),
+                            // `$pull` on a missing field leaves the document untouched
+                            None => (),
+                        }
+                    }
+                }
 
-                let Some(versions) = Versions::multiple(versions)? else { return Ok(None) };
+                let Some(versions) = versions else { return Ok(None) };
 
                 if self.is_new {
                     Ok(Some(DocumentChange::Insertion(Insertion::create(
@@ -0,0 +1,302 @@
+//! Operators updating the fields of a document relative to their current value.
+//!
+//! They are only interpreted in documents sent to be updated, e.g.
+//! `{"id": 1, "$inc": {"views": 1}, "$push": {"tags": "new"}, "$unset": ["draft"]}`.
+
+use std::collections::BTreeSet;
+
+use bumpalo::Bump;
+use bumparaw_collections::RawMap;
+use rustc_hash::FxBuildHasher;
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::constants::{RESERVED_GEO_FIELD_NAME, RESERVED_VECTORS_FIELD_NAME};
+use crate::{InternalError, Result, UserError};
+
+/// Adds a number to the current value of a field, treating a missing field as `0`.
+pub const INC_OPERATOR: &str = "$inc";
+/// Appends a value to the array of a field, creating the array if the field is missing.
+pub const PUSH_OPERATOR: &str = "$push";
+/// Removes all the elements equal to a value from the array of a field.
+pub const PULL_OPERATOR: &str = "$pull";
+/// Removes fields from the document.
+pub const UNSET_OPERATOR: &str = "$unset";
+
+pub fn is_operator(key: &str) -> bool {
+    matches!(key, INC_OPERATOR | PUSH_OPERATOR | PULL_OPERATOR | UNSET_OPERATOR)
+}
+
+/// Cheap check to skip parsing documents that cannot contain operators.
+pub fn may_contain_operators(document: &RawValue) -> bool {
+    document.get().contains("\"$")
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Operator<'doc> {
+    Inc(&'doc RawValue),
+    Push(&'doc RawValue),
+    Pull(&'doc RawValue),
+    Unset,
+}
+
+impl Operator<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            Operator::Inc(_) => INC_OPERATOR,
+            Operator::Push(_) => PUSH_OPERATOR,
+            Operator::Pull(_) => PULL_OPERATOR,
+            Operator::Unset => UNSET_OPERATOR,
+        }
+    }
+}
+
+/// The operators of a single version of a document, along with the fields they target.
+pub type Operations<'doc> = Vec<(&'doc str, Operator<'doc>)>;
+
+/// Parses the operators declared at the top-level of `document`.
+///
+/// Returns an error message if an operator is malformed.
+pub fn parse_operators<'doc>(
+    document: &RawMap<'doc, FxBuildHasher>,
+    doc_alloc: &'doc Bump,
+) -> std::result::Result<Operations<'doc>, String> {
+    let mut operations = Vec::new();
+
+    for (key, value) in document.iter() {
+        match key {
+            INC_OPERATOR | PUSH_OPERATOR | PULL_OPERATOR => {
+                let fields = RawMap::from_raw_value_and_hasher(value, FxBuildHasher, doc_alloc)
+                    .map_err(|_| {
+                        format!(
+                            "`{key}` must be an object mapping fields to values, but got `{}`.",
+                            value.get()
+                        )
+                    })?;
+                for (field, value) in fields {
+                    let operator = match key {
+                        INC_OPERATOR => {
+                            if serde_json::from_str::<serde_json::Number>(value.get()).is_err() {
+                                return Err(format!(
+                                    "`{INC_OPERATOR}` expects a number for field `{field}`, but got `{}`.",
+                                    value.get()
+                                ));
+                            }
+                            Operator::Inc(value)
+                        }
+                        PUSH_OPERATOR => Operator::Push(value),
+                        _ => Operator::Pull(value),
+                    };
+                    operations.push((field, operator));
+                }
+            }
+            UNSET_OPERATOR => {
+                let fields: Vec<String> = serde_json::from_str(value.get()).map_err(|_| {
+                    format!(
+                        "`{UNSET_OPERATOR}` must be an array of field names, but got `{}`.",
+                        value.get()
+                    )
+                })?;
+                for field in fields {
+                    operations.push((&*doc_alloc.alloc_str(&field), Operator::Unset));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(operations)
+}
+
+/// Checks that the operators of `document` are well-formed and don't target
+/// the primary key, the reserved fields, or a field that is already modified by the document.
+pub fn validate_operators(
+    document: &RawMap<'_, FxBuildHasher>,
+    primary_key: &str,
+    doc_alloc: &Bump,
+) -> std::result::Result<(), String> {
+    let operations = parse_operators(document, doc_alloc)?;
+    let mut seen = BTreeSet::new();
+
+    for (field, operator) in operations {
+        let operator = operator.name();
+        if field == RESERVED_VECTORS_FIELD_NAME || field == RESERVED_GEO_FIELD_NAME {
+            return Err(format!("`{operator}` cannot be applied to the reserved field `{field}`."));
+        }
+        if field == primary_key || primary_key.starts_with(&format!("{field}.")) {
+            return Err(format!(
+                "`{operator}` cannot be applied to the primary key field `{field}`."
+            ));
+        }
+        if is_operator(field) || document.get(field).is_some() || !seen.insert(field) {
+            return Err(format!(
+                "`{operator}` targets the field `{field}`, which is already modified by the document."
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies `operator` to the `current` value of `field`.
+///
+/// Returns `None` when the field must be absent from the updated document.
+pub fn apply_operator<'doc>(
+    field: &str,
+    operator: Operator<'doc>,
+    current: Option<&RawValue>,
+    doc_alloc: &'doc Bump,
+) -> std::result::Result<Option<&'doc RawValue>, String> {
+    let current: Option<Value> = current
+        .map(|current| serde_json::from_str(current.get()))
+        .transpose()
+        .map_err(|error| error.to_string())?;
+
+    let updated = match operator {
+        Operator::Unset => return Ok(None),
+        Operator::Inc(by) => {
+            let by: serde_json::Number =
+                serde_json::from_str(by.get()).map_err(|error| error.to_string())?;
+            match current {
+                None | Some(Value::Null) => Value::Number(by),
+                Some(Value::Number(current)) => {
+                    match (current.as_i64(), by.as_i64()) {
+                        (Some(current), Some(by)) if current.checked_add(by).is_some() => {
+                            Value::from(current + by)
+                        }
+                        _ => {
+                            let sum = current.as_f64().unwrap_or_default()
+                                + by.as_f64().unwrap_or_default();
+                            serde_json::Number::from_f64(sum).map(Value::Number).ok_or_else(
+                                || format!("`{INC_OPERATOR}` on field `{field}` overflowed."),
+                            )?
+                        }
+                    }
+                }
+                Some(current) => {
+                    return Err(format!(
+                        "`{INC_OPERATOR}` cannot be applied to field `{field}`, whose value `{current}` is not a number."
+                    ))
+                }
+            }
+        }
+        Operator::Push(value) => {
+            let value: Value =
+                serde_json::from_str(value.get()).map_err(|error| error.to_string())?;
+            match current {
+                None | Some(Value::Null) => Value::Array(vec![value]),
+                Some(Value::Array(mut values)) => {
+                    values.push(value);
+                    Value::Array(values)
+                }
+                Some(current) => {
+                    return Err(format!(
+                        "`{PUSH_OPERATOR}` cannot be applied to field `{field}`, whose value `{current}` is not an array."
+                    ))
+                }
+            }
+        }
+        Operator::Pull(value) => {
+            let value: Value =
+                serde_json::from_str(value.get()).map_err(|error| error.to_string())?;
+            match current {
+                // nothing to remove from
+                None => return Ok(None),
+                Some(Value::Null) => Value::Null,
+                Some(Value::Array(mut values)) => {
+                    values.retain(|element| *element != value);
+                    Value::Array(values)
+                }
+                Some(current) => {
+                    return Err(format!(
+                        "`{PULL_OPERATOR}` cannot be applied to field `{field}`, whose value `{current}` is not an array."
+                    ))
+                }
+            }
+        }
+    };
+
+    let updated = serde_json::to_string(&updated).map_err(|error| error.to_string())?;
+    let updated = doc_alloc.alloc_str(&updated);
+    serde_json::from_str(updated).map(Some).map_err(|error| error.to_string())
+}
+
+/// Wraps an operator error message into the error of the document it applies to.
+pub fn operator_error(document_id: &str, error: String) -> crate::Error {
+    UserError::InvalidDocumentOperator { document_id: document_id.to_string(), error }.into()
+}
+
+/// Allocates an empty JSON object as a [`RawMap`], to apply the operators of a new document to.
+pub fn empty_document(doc_alloc: &Bump) -> Result<RawMap<'_, FxBuildHasher>> {
+    let empty: &RawValue = serde_json::from_str("{}").map_err(InternalError::SerdeJson)?;
+    Ok(RawMap::from_raw_value_and_hasher(empty, FxBuildHasher, doc_alloc)
+        .map_err(InternalError::SerdeJson)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn raw(json: &str) -> &RawValue {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn apply() {
+        let bump = Bump::new();
+        let inc = |current, by| {
+            apply_operator("views", Operator::Inc(raw(by)), current, &bump)
+                .map(|value| value.map(|value| value.get().to_owned()))
+        };
+        assert_eq!(inc(None, "1").unwrap().as_deref(), Some("1"));
+        assert_eq!(inc(Some(raw("41")), "1").unwrap().as_deref(), Some("42"));
+        assert_eq!(inc(Some(raw("1.5")), "1").unwrap().as_deref(), Some("2.5"));
+        assert_eq!(inc(Some(raw("-3")), "-2").unwrap().as_deref(), Some("-5"));
+        insta::assert_snapshot!(inc(Some(raw("\"a\"")), "1").unwrap_err(), @"`$inc` cannot be applied to field `views`, whose value `\"a\"` is not a number.");
+
+        let push = |current, value| {
+            apply_operator("tags", Operator::Push(raw(value)), current, &bump)
+                .map(|value| value.map(|value| value.get().to_owned()))
+        };
+        assert_eq!(push(None, "\"new\"").unwrap().as_deref(), Some(r#"["new"]"#));
+        assert_eq!(
+            push(Some(raw(r#"["a"]"#)), "\"new\"").unwrap().as_deref(),
+            Some(r#"["a","new"]"#)
+        );
+        assert!(push(Some(raw("3")), "\"new\"").is_err());
+
+        let pull = |current, value| {
+            apply_operator("tags", Operator::Pull(raw(value)), current, &bump)
+                .map(|value| value.map(|value| value.get().to_owned()))
+        };
+        assert_eq!(
+            pull(Some(raw(r#"["a","b","a"]"#)), "\"a\"").unwrap().as_deref(),
+            Some(r#"["b"]"#)
+        );
+        assert_eq!(pull(None, "\"a\"").unwrap(), None);
+
+        assert!(apply_operator("draft", Operator::Unset, Some(raw("true")), &bump)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn validate() {
+        let bump = Bump::new();
+        let validate = |document: &str| {
+            let document =
+                RawMap::from_raw_value_and_hasher(raw(document), FxBuildHasher, &bump).unwrap();
+            validate_operators(&document, "id", &bump)
+        };
+
+        validate(
+            r#"{"id": 1, "$inc": {"views": 1}, "$push": {"tags": "new"}, "$unset": ["draft"]}"#,
+        )
+        .unwrap();
+        insta::assert_snapshot!(validate(r#"{"id": 1, "$inc": {"views": "1"}}"#).unwrap_err(), @r###"`$inc` expects a number for field `views`, but got `"1"`."###);
+        insta::assert_snapshot!(validate(r#"{"id": 1, "$unset": "draft"}"#).unwrap_err(), @r###"`$unset` must be an array of field names, but got `"draft"`."###);
+        insta::assert_snapshot!(validate(r#"{"id": 1, "$unset": ["id"]}"#).unwrap_err(), @"`$unset` cannot be applied to the primary key field `id`.");
+        insta::assert_snapshot!(validate(r#"{"id": 1, "$unset": ["_geo"]}"#).unwrap_err(), @"`$unset` cannot be applied to the reserved field `_geo`.");
+        insta::assert_snapshot!(validate(r#"{"id": 1, "views": 2, "$inc": {"views": 1}}"#).unwrap_err(), @"`$inc` targets the field `views`, which is already modified by the document.");
+    }
+}
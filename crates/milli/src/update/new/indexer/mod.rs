@@ -5,7 +5,7 @@ use std::thread::{self, Builder};
 use big_s::S;
 use document_changes::{DocumentChanges, IndexingContext};
 pub use document_deletion::DocumentDeletion;
-pub use document_operation::{DocumentOperation, PayloadStats};
+pub use document_operation::{DocumentOperation, PayloadStats, SkippedDocument, VersionConflict};
 use hashbrown::HashMap;
 use heed::RwTxn;
 pub use partial_dump::PartialDump;
@@ -19,6 +19,7 @@ use super::thread_local::ThreadLocal;
 use crate::documents::PrimaryKey;
 use crate::fields_ids_map::metadata::{FieldIdMapWithMetadata, MetadataBuilder};
 use crate::progress::Progress;
+use crate::update::sparse_vectors::update_sparse_vectors;
 use crate::update::GrenadParameters;
 use crate::vector::{ArroyWrapper, EmbeddingConfigs};
 use crate::{FieldsIdsMap, GlobalFieldsIdsMap, Index, InternalError, Result, ThreadPoolNoAbort};
@@ -27,6 +28,7 @@ pub(crate) mod de;
 pub mod document_changes;
 mod document_deletion;
 mod document_operation;
+mod document_operators;
 mod extract;
 mod guess_primary_key;
 mod partial_dump;
@@ -133,6 +135,8 @@ where
     let mut field_distribution = index.field_distribution(wtxn)?;
     let mut document_ids = index.documents_ids(wtxn)?;
     let mut modified_docids = roaring::RoaringBitmap::new();
+    // embedders are shared between batches, so only the requests sent during this one are reported
+    let request_stats_before = embedders.request_stats();
 
     let congestion = thread::scope(|s| -> Result<ChannelCongestion> {
         let indexer_span = tracing::Span::current();
@@ -177,7 +181,12 @@ where
                 )?;
 
                 let dimensions = embedder.dimensions();
-                let writer = ArroyWrapper::new(vector_arroy, embedder_index, *was_quantized);
+                let writer = ArroyWrapper::new(
+                    vector_arroy,
+                    index.vector_int8,
+                    embedder_index,
+                    *was_quantized,
+                );
 
                 Ok((
                     embedder_index,
@@ -195,6 +204,14 @@ where
 
         let (facet_field_ids_delta, index_embeddings) = extractor_handle.join().unwrap()?;
 
+        for (name, before) in &request_stats_before {
+            if let Some((embedder, _, _)) = embedders.inner_as_ref().get(name) {
+                if let Some(after) = embedder.request_stats() {
+                    after.since(before).record(name, indexing_context.progress);
+                }
+            }
+        }
+
         indexing_context.progress.update_progress(IndexingStep::WritingEmbeddingsToDatabase);
 
         pool.install(|| {
@@ -236,5 +253,8 @@ where
         document_ids,
     )?;
 
+    // sparse embeddings are computed from the documents once they are written
+    update_sparse_vectors(wtxn, index, &modified_docids, pool)?;
+
     Ok(congestion)
 }
@@ -17,6 +17,7 @@ use super::IndexerConfig;
 use crate::attribute_patterns::PatternMatch;
 use crate::constants::RESERVED_GEO_FIELD_NAME;
 use crate::criterion::Criterion;
+use crate::documents::DocumentSchema;
 use crate::error::UserError;
 use crate::fields_ids_map::metadata::{FieldIdMapWithMetadata, MetadataBuilder};
 use crate::filterable_attributes_rules::match_faceted_field;
@@ -27,13 +28,18 @@ use crate::index::{
 use crate::order_by_map::OrderByMap;
 use crate::prompt::default_max_bytes;
 use crate::proximity::ProximityPrecision;
+use crate::thread_pool_no_abort::ThreadPoolNoAbortBuilder;
 use crate::update::index_documents::IndexDocumentsMethod;
+use crate::update::sparse_vectors::rebuild_sparse_vectors;
 use crate::update::{IndexDocuments, UpdateIndexingStep};
+use crate::vector::persistent_cache::PersistentEmbeddingCache;
+use crate::vector::scalar::ScalarQuantizedStore;
 use crate::vector::settings::{
     EmbedderAction, EmbedderSource, EmbeddingSettings, NestingContext, ReindexAction,
     SubEmbeddingSettings, WriteBackToDocuments,
 };
-use crate::vector::{Embedder, EmbeddingConfig, EmbeddingConfigs};
+use crate::vector::sparse::SparseVectorStore;
+use crate::vector::{Embedder, EmbedderOptions, EmbeddingConfig, EmbeddingConfigs};
 use crate::{FieldId, FilterableAttributesRule, Index, LocalizedAttributesRule, Result};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -155,6 +161,8 @@ pub struct Settings<'a, 't, 'i> {
     index: &'i Index,
 
     indexer_config: &'a IndexerConfig,
+    /// The persistent cache read and filled by the embedders when reindexing, if any.
+    embedding_cache: Option<Arc<PersistentEmbeddingCache>>,
 
     searchable_fields: Setting<Vec<String>>,
     displayed_fields: Setting<Vec<String>>,
@@ -166,6 +174,8 @@ pub struct Settings<'a, 't, 'i> {
     separator_tokens: Setting<BTreeSet<String>>,
     dictionary: Setting<BTreeSet<String>>,
     distinct_field: Setting<String>,
+    ttl_field: Setting<String>,
+    document_schema: Setting<serde_json::Value>,
     synonyms: Setting<BTreeMap<String, Vec<String>>>,
     primary_key: Setting<String>,
     authorize_typos: Setting<bool>,
@@ -204,6 +214,8 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             distinct_field: Setting::NotSet,
+            ttl_field: Setting::NotSet,
+            document_schema: Setting::NotSet,
             synonyms: Setting::NotSet,
             primary_key: Setting::NotSet,
             authorize_typos: Setting::NotSet,
@@ -221,9 +233,15 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             prefix_search: Setting::NotSet,
             facet_search: Setting::NotSet,
             indexer_config,
+            embedding_cache: None,
         }
     }
 
+    /// Makes the embedders used to reindex the documents read and fill the given persistent cache.
+    pub fn set_embedding_cache(&mut self, cache: Arc<PersistentEmbeddingCache>) {
+        self.embedding_cache = Some(cache);
+    }
+
     pub fn reset_searchable_fields(&mut self) {
         self.searchable_fields = Setting::Reset;
     }
@@ -314,6 +332,22 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.distinct_field = Setting::Set(distinct_field);
     }
 
+    pub fn reset_ttl_field(&mut self) {
+        self.ttl_field = Setting::Reset;
+    }
+
+    pub fn set_ttl_field(&mut self, ttl_field: String) {
+        self.ttl_field = Setting::Set(ttl_field);
+    }
+
+    pub fn reset_document_schema(&mut self) {
+        self.document_schema = Setting::Reset;
+    }
+
+    pub fn set_document_schema(&mut self, document_schema: serde_json::Value) {
+        self.document_schema = Setting::Set(document_schema);
+    }
+
     pub fn reset_synonyms(&mut self) {
         self.synonyms = Setting::Reset;
     }
@@ -518,6 +552,36 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(true)
     }
 
+    fn update_ttl_field(&mut self) -> Result<bool> {
+        match self.ttl_field {
+            Setting::Set(ref attr) => {
+                self.index.put_ttl_field(self.wtxn, attr)?;
+            }
+            Setting::Reset => {
+                self.index.delete_ttl_field(self.wtxn)?;
+            }
+            Setting::NotSet => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Updates the schema the documents sent to the index must match.
+    ///
+    /// The documents already in the index are not validated against the new schema.
+    fn update_document_schema(&mut self) -> Result<bool> {
+        match self.document_schema {
+            Setting::Set(ref schema) => {
+                DocumentSchema::new(schema).map_err(UserError::InvalidDocumentSchema)?;
+                self.index.put_document_schema(self.wtxn, schema)?;
+            }
+            Setting::Reset => {
+                self.index.delete_document_schema(self.wtxn)?;
+            }
+            Setting::NotSet => return Ok(false),
+        }
+        Ok(true)
+    }
+
     /// Updates the index's searchable attributes.
     fn update_user_defined_searchable_attributes(&mut self) -> Result<bool> {
         match self.searchable_fields {
@@ -994,10 +1058,24 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changed)
     }
 
-    fn update_embedding_configs(&mut self) -> Result<BTreeMap<String, EmbedderAction>> {
+    /// Updates the embedders, returning the actions to apply on the dense embedders
+    /// and the names of the sparse embedders whose sparse embeddings must be rebuilt.
+    fn update_embedding_configs(
+        &mut self,
+    ) -> Result<(BTreeMap<String, EmbedderAction>, BTreeSet<String>)> {
         match std::mem::take(&mut self.embedder_settings) {
             Setting::Set(configs) => self.update_embedding_configs_set(configs),
             Setting::Reset => {
+                // sparse embeddings are not part of the documents, they can be dropped right away
+                for IndexEmbeddingConfig { name, .. } in
+                    self.index.sparse_embedding_configs(self.wtxn)?
+                {
+                    if let Some(store) = self.index.sparse_vector_store(self.wtxn, &name)? {
+                        store.clear(self.wtxn)?;
+                    }
+                }
+                self.index.delete_sparse_embedding_configs(self.wtxn)?;
+
                 // all vectors should be written back to documents
                 let old_configs = self.index.embedding_configs(self.wtxn)?;
                 let remove_all: Result<BTreeMap<String, EmbedderAction>> = old_configs
@@ -1024,7 +1102,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
 
                 self.index.embedder_category_id.clear(self.wtxn)?;
                 self.index.delete_embedding_configs(self.wtxn)?;
-                Ok(remove_all)
+                Ok((remove_all, BTreeSet::new()))
             }
             Setting::NotSet => Ok(Default::default()),
         }
@@ -1033,10 +1111,16 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
     fn update_embedding_configs_set(
         &mut self,
         configs: BTreeMap<String, Setting<EmbeddingSettings>>,
-    ) -> Result<BTreeMap<String, EmbedderAction>> {
+    ) -> Result<(BTreeMap<String, EmbedderAction>, BTreeSet<String>)> {
         use crate::vector::settings::SettingsDiff;
 
-        let old_configs = self.index.embedding_configs(self.wtxn)?;
+        let old_sparse_configs = self.index.sparse_embedding_configs(self.wtxn)?;
+        let old_sparse_names: BTreeSet<String> =
+            old_sparse_configs.iter().map(|config| config.name.clone()).collect();
+        let mut old_configs = self.index.embedding_configs(self.wtxn)?;
+        let old_dense_names: BTreeSet<String> =
+            old_configs.iter().map(|config| config.name.clone()).collect();
+        old_configs.extend(old_sparse_configs);
         let old_configs: BTreeMap<String, (EmbeddingSettings, RoaringBitmap)> = old_configs
             .into_iter()
             .map(|IndexEmbeddingConfig { name, config, user_provided }| {
@@ -1052,7 +1136,8 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             match joined {
                 // updated config
                 EitherOrBoth::Both((name, (old, user_provided)), (_, new)) => {
-                    let was_quantized = old.binary_quantized.set().unwrap_or_default();
+                    let was_quantized = old.binary_quantized.set().unwrap_or_default()
+                        || old.int8_rescoring.set().unwrap_or_default();
                     let settings_diff = SettingsDiff::from_settings(&name, old, new)?;
                     match settings_diff {
                         SettingsDiff::Remove => {
@@ -1167,12 +1252,55 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                 }),
             })
             .collect();
+
+        // sparse embedders are stored apart, and their embeddings are handled outside of the dense pipeline
+        let (sparse_configs, updated_configs): (Vec<_>, Vec<_>) =
+            updated_configs.into_iter().partition(|config| {
+                matches!(config.config.embedder_options, EmbedderOptions::Sparse(_))
+            });
+        for config in &sparse_configs {
+            if old_dense_names.contains(&config.name) {
+                return Err(sparse_source_change_error(&config.name));
+            }
+        }
+        for config in &updated_configs {
+            if old_sparse_names.contains(&config.name) {
+                return Err(sparse_source_change_error(&config.name));
+            }
+        }
+        let mut sparse_to_rebuild = BTreeSet::new();
+        let sparse_names: BTreeSet<String> = old_sparse_names
+            .into_iter()
+            .chain(sparse_configs.iter().map(|config| config.name.clone()))
+            .collect();
+        for name in sparse_names {
+            let Some(action) = embedder_actions.remove(&name) else { continue };
+            if let Some(WriteBackToDocuments { embedder_id, .. }) = action.write_back() {
+                SparseVectorStore::new(self.index.sparse_vectors, *embedder_id).clear(self.wtxn)?;
+            } else if action.reindex().is_some() {
+                sparse_to_rebuild.insert(name);
+            }
+        }
+
+        if sparse_configs.is_empty() {
+            self.index.delete_sparse_embedding_configs(self.wtxn)?;
+        } else {
+            self.index.put_sparse_embedding_configs(self.wtxn, sparse_configs)?;
+        }
+        // the embeddings written from now on are also stored as int8, the existing ones are copied before being quantized
+        for config in updated_configs.iter().filter(|config| config.config.int8_rescoring()) {
+            if let Some(embedder_id) =
+                self.index.embedder_category_id.get(self.wtxn, &config.name)?
+            {
+                ScalarQuantizedStore::new(self.index.vector_int8, embedder_id).enable(self.wtxn)?;
+            }
+        }
         if updated_configs.is_empty() {
             self.index.delete_embedding_configs(self.wtxn)?;
         } else {
             self.index.put_embedding_configs(self.wtxn, updated_configs)?;
         }
-        Ok(embedder_actions)
+        Ok((embedder_actions, sparse_to_rebuild))
     }
 
     fn update_search_cutoff(&mut self) -> Result<bool> {
@@ -1231,10 +1359,12 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_sort_facet_values_by()?;
         self.update_pagination_max_total_hits()?;
         self.update_search_cutoff()?;
+        self.update_document_schema()?;
 
         // could trigger re-indexing
         self.update_filterable()?;
         self.update_sortable()?;
+        self.update_ttl_field()?;
         self.update_stop_words()?;
         self.update_non_separator_tokens()?;
         self.update_separator_tokens()?;
@@ -1247,9 +1377,13 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_facet_search()?;
         self.update_localized_attributes_rules()?;
 
-        let embedding_config_updates = self.update_embedding_configs()?;
+        let (embedding_config_updates, sparse_embedders_to_rebuild) =
+            self.update_embedding_configs()?;
 
-        let mut new_inner_settings = InnerIndexSettings::from_index(self.index, self.wtxn, None)?;
+        let embedding_configs =
+            embedders(self.index.embedding_configs(self.wtxn)?, self.embedding_cache.as_ref())?;
+        let mut new_inner_settings =
+            InnerIndexSettings::from_index(self.index, self.wtxn, Some(embedding_configs))?;
         new_inner_settings.recompute_searchables(self.wtxn, self.index)?;
 
         let primary_key_id = self
@@ -1269,10 +1403,33 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             self.reindex(&progress_callback, &should_abort, inner_settings_diff)?;
         }
 
+        if !sparse_embedders_to_rebuild.is_empty() {
+            let backup_pool;
+            let pool = match self.indexer_config.thread_pool {
+                Some(ref pool) => pool,
+                None => {
+                    backup_pool = ThreadPoolNoAbortBuilder::new().build()?;
+                    &backup_pool
+                }
+            };
+            let documents_ids = self.index.documents_ids(self.wtxn)?;
+            for embedder_name in &sparse_embedders_to_rebuild {
+                rebuild_sparse_vectors(self.wtxn, self.index, embedder_name, &documents_ids, pool)?;
+            }
+        }
+
         Ok(())
     }
 }
 
+fn sparse_source_change_error(embedder_name: &str) -> crate::Error {
+    UserError::InvalidSettingsEmbedder {
+        embedder_name: embedder_name.to_owned(),
+        message: "cannot change the source of an embedder from or to `sparse`.\n  - Hint: remove the embedder first, then add it back with its new source.".to_owned(),
+    }
+    .into()
+}
+
 pub struct InnerIndexSettingsDiff {
     pub(crate) old: InnerIndexSettings,
     pub(crate) new: InnerIndexSettings,
@@ -1523,6 +1680,7 @@ pub(crate) struct InnerIndexSettings {
     pub filterable_attributes_rules: Vec<FilterableAttributesRule>,
     pub asc_desc_fields: HashSet<String>,
     pub distinct_field: Option<String>,
+    pub ttl_field: Option<String>,
     pub user_defined_searchable_attributes: Option<Vec<String>>,
     pub sortable_fields: HashSet<String>,
     pub exact_attributes: HashSet<FieldId>,
@@ -1548,7 +1706,7 @@ impl InnerIndexSettings {
         let proximity_precision = index.proximity_precision(rtxn)?.unwrap_or_default();
         let embedding_configs = match embedding_configs {
             Some(embedding_configs) => embedding_configs,
-            None => embedders(index.embedding_configs(rtxn)?)?,
+            None => embedders(index.embedding_configs(rtxn)?, None)?,
         };
         let prefix_search = index.prefix_search(rtxn)?.unwrap_or_default();
         let facet_search = index.facet_search(rtxn)?;
@@ -1569,6 +1727,7 @@ impl InnerIndexSettings {
         let sortable_fields = index.sortable_fields(rtxn)?;
         let asc_desc_fields = index.asc_desc_fields(rtxn)?;
         let distinct_field = index.distinct_field(rtxn)?.map(|f| f.to_string());
+        let ttl_field = index.ttl_field(rtxn)?.map(|f| f.to_string());
         let user_defined_searchable_attributes = index
             .user_defined_searchable_fields(rtxn)?
             .map(|fields| fields.into_iter().map(|f| f.to_string()).collect());
@@ -1584,6 +1743,7 @@ impl InnerIndexSettings {
             filterable_attributes_rules,
             asc_desc_fields,
             distinct_field,
+            ttl_field,
             user_defined_searchable_attributes,
             sortable_fields,
             exact_attributes,
@@ -1602,6 +1762,7 @@ impl InnerIndexSettings {
             &self.sortable_fields,
             &self.asc_desc_fields,
             &self.distinct_field,
+            &self.ttl_field,
         )
     }
 
@@ -1629,26 +1790,28 @@ impl InnerIndexSettings {
     }
 }
 
-fn embedders(embedding_configs: Vec<IndexEmbeddingConfig>) -> Result<EmbeddingConfigs> {
+fn embedders(
+    embedding_configs: Vec<IndexEmbeddingConfig>,
+    cache: Option<&Arc<PersistentEmbeddingCache>>,
+) -> Result<EmbeddingConfigs> {
     let res: Result<_> = embedding_configs
         .into_iter()
-        .map(
-            |IndexEmbeddingConfig {
-                 name,
-                 config: EmbeddingConfig { embedder_options, prompt, quantized },
-                 ..
-             }| {
-                let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
-
-                let embedder = Arc::new(
-                    // cache_cap: no cache needed for indexing purposes
-                    Embedder::new(embedder_options.clone(), 0)
-                        .map_err(crate::vector::Error::from)
-                        .map_err(crate::Error::from)?,
-                );
-                Ok((name, (embedder, prompt, quantized.unwrap_or_default())))
-            },
-        )
+        .map(|IndexEmbeddingConfig { name, config, .. }| {
+            let quantized = config.quantized();
+            let EmbeddingConfig { embedder_options, prompt, .. } = config;
+            let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
+
+            let embedder = Arc::new(
+                // cache_cap: no in-memory cache needed for indexing purposes
+                Embedder::new(embedder_options.clone(), 0)
+                    .map_err(crate::vector::Error::from)
+                    .map_err(crate::Error::from)?,
+            );
+            if let Some(cache) = cache {
+                embedder.set_persistent_cache(cache, &embedder_options);
+            }
+            Ok((name, (embedder, prompt, quantized)))
+        })
         .collect();
     res.map(EmbeddingConfigs::new)
 }
@@ -1706,10 +1869,29 @@ pub fn validate_embedding_settings(
         distribution,
         headers,
         binary_quantized: binary_quantize,
+        int8_rescoring,
+        chunking,
+        request_policy,
     } = settings;
 
     let document_template = validate_prompt(name, document_template, document_template_max_bytes)?;
 
+    if let Some(chunking) = chunking.as_ref().set() {
+        chunking.validate().map_err(|reason| crate::error::UserError::InvalidSettingsChunking {
+            embedder_name: name.to_owned(),
+            reason,
+        })?;
+    }
+
+    if let Some(request_policy) = request_policy.as_ref().set() {
+        request_policy.validate().map_err(|reason| {
+            crate::error::UserError::InvalidSettingsRequestPolicy {
+                embedder_name: name.to_owned(),
+                reason,
+            }
+        })?;
+    }
+
     if let Some(0) = dimensions.set() {
         return Err(crate::error::UserError::InvalidSettingsDimensions {
             embedder_name: name.to_owned(),
@@ -1717,6 +1899,14 @@ pub fn validate_embedding_settings(
         .into());
     }
 
+    if matches!(binary_quantize, Setting::Set(true)) && matches!(int8_rescoring, Setting::Set(true))
+    {
+        return Err(crate::error::UserError::InvalidInt8RescoringOfBinaryQuantized {
+            embedder_name: name.to_owned(),
+        }
+        .into());
+    }
+
     if let Some(url) = url.as_ref().set() {
         url::Url::parse(url).map_err(|error| crate::error::UserError::InvalidUrl {
             embedder_name: name.to_owned(),
@@ -1753,6 +1943,9 @@ pub fn validate_embedding_settings(
             distribution,
             headers,
             binary_quantized: binary_quantize,
+            int8_rescoring,
+            chunking,
+            request_policy,
         }));
     };
     EmbeddingSettings::check_settings(
@@ -1773,7 +1966,10 @@ pub fn validate_embedding_settings(
         &search_embedder,
         &indexing_embedder,
         &binary_quantize,
+        &int8_rescoring,
         &distribution,
+        &chunking,
+        &request_policy,
     )?;
     match inferred_source {
         EmbedderSource::OpenAi => {
@@ -1811,6 +2007,21 @@ pub fn validate_embedding_settings(
         | EmbedderSource::HuggingFace
         | EmbedderSource::UserProvided
         | EmbedderSource::Rest => {}
+        EmbedderSource::Sparse => {
+            // a remote sparse embedder needs to know how to build its requests and read its responses
+            if url.as_ref().set().is_some() {
+                for (field, setting) in [("request", &request), ("response", &response)] {
+                    if setting.as_ref().set().is_none() {
+                        return Err(crate::error::UserError::MissingFieldForSource {
+                            field,
+                            source_: inferred_source,
+                            embedder_name: name.to_owned(),
+                        }
+                        .into());
+                    }
+                }
+            }
+        }
         EmbedderSource::Composite => {
             if let Setting::Set(embedder) = &search_embedder {
                 if let Some(source) = embedder.source.set() {
@@ -1851,7 +2062,10 @@ pub fn validate_embedding_settings(
                         &search_embedder,
                         &indexing_embedder,
                         &embedder.binary_quantized,
+                        &embedder.int8_rescoring,
                         &embedder.distribution,
+                        &Setting::NotSet,
+                        &Setting::NotSet,
                     )?;
                 } else {
                     return Err(UserError::MissingSourceForNested {
@@ -1906,7 +2120,10 @@ pub fn validate_embedding_settings(
                         &search_embedder,
                         &indexing_embedder,
                         &embedder.binary_quantized,
+                        &embedder.int8_rescoring,
                         &embedder.distribution,
+                        &Setting::NotSet,
+                        &Setting::NotSet,
                     )?;
                 } else {
                     return Err(UserError::MissingSourceForNested {
@@ -1937,6 +2154,9 @@ pub fn validate_embedding_settings(
         distribution,
         headers,
         binary_quantized: binary_quantize,
+        int8_rescoring,
+        chunking,
+        request_policy,
     }))
 }
 
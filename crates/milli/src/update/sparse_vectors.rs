@@ -0,0 +1,264 @@
+//! Maintains the inverted indexes of the sparse embedders, see [`crate::vector::sparse`].
+//!
+//! Sparse embedders are not handled by the dense indexing pipelines: their user-provided embeddings stay
+//! in the `_vectors` field of the documents, and their embeddings are computed once the documents are written.
+
+use std::cell::RefCell;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use bumpalo::Bump;
+use heed::RwTxn;
+use roaring::RoaringBitmap;
+
+use crate::documents::PrimaryKey;
+use crate::error::UserError;
+use crate::index::IndexEmbeddingConfig;
+use crate::prompt::Prompt;
+use crate::update::new::document::{Document, DocumentFromDb};
+use crate::vector::sparse::{self, SparseVector};
+use crate::vector::EmbedderOptions;
+use crate::{DocumentId, GlobalFieldsIdsMap, Index, Result, ThreadPoolNoAbort};
+
+/// Number of documents read before their embeddings are computed and written.
+const DOCUMENTS_PER_BATCH: usize = 1000;
+/// Time given to the embedder to compute the embeddings of a batch of documents, retries included.
+const BATCH_EMBEDDING_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Updates the sparse embeddings of the given documents, for all the sparse embedders of the index.
+///
+/// Documents that do not exist anymore are removed from the inverted indexes.
+pub fn update_sparse_vectors(
+    wtxn: &mut RwTxn<'_>,
+    index: &Index,
+    docids: &RoaringBitmap,
+    threads: &ThreadPoolNoAbort,
+) -> Result<()> {
+    for config in index.sparse_embedding_configs(wtxn)? {
+        update_embedder(wtxn, index, config, docids, threads, false)?;
+    }
+    Ok(())
+}
+
+/// Recomputes the sparse embeddings of the given documents for a single sparse embedder,
+/// after its settings changed.
+pub fn rebuild_sparse_vectors(
+    wtxn: &mut RwTxn<'_>,
+    index: &Index,
+    embedder_name: &str,
+    docids: &RoaringBitmap,
+    threads: &ThreadPoolNoAbort,
+) -> Result<()> {
+    let Some(config) = index
+        .sparse_embedding_configs(wtxn)?
+        .into_iter()
+        .find(|config| config.name == embedder_name)
+    else {
+        return Ok(());
+    };
+    update_embedder(wtxn, index, config, docids, threads, true)
+}
+
+/// What to do with the sparse embedding of a document.
+enum Update {
+    Remove,
+    Keep,
+    Set(SparseVector),
+    Generate(String),
+}
+
+fn update_embedder(
+    wtxn: &mut RwTxn<'_>,
+    index: &Index,
+    config: IndexEmbeddingConfig,
+    docids: &RoaringBitmap,
+    threads: &ThreadPoolNoAbort,
+    rebuild: bool,
+) -> Result<()> {
+    let IndexEmbeddingConfig { name, config, user_provided: _ } = config;
+    let EmbedderOptions::Sparse(options) = config.embedder_options else {
+        return Ok(());
+    };
+    let Some(store) = index.sparse_vector_store(wtxn, &name)? else {
+        return Ok(());
+    };
+    let embedder = sparse::Embedder::new(options).map_err(crate::vector::Error::from)?;
+    let prompt: Prompt = config.prompt.try_into().map_err(crate::Error::from)?;
+
+    let db_fields_ids_map = index.fields_ids_map(wtxn)?;
+    let fields_ids_map = RwLock::new(index.fields_ids_map_with_metadata(wtxn)?);
+    let primary_key = index.primary_key(wtxn)?.map(String::from);
+
+    let docids: Vec<DocumentId> = docids.iter().collect();
+    for batch in docids.chunks(DOCUMENTS_PER_BATCH) {
+        let mut updates = Vec::with_capacity(batch.len());
+        {
+            let mut doc_alloc = Bump::new();
+            let global_fields_ids_map = RefCell::new(GlobalFieldsIdsMap::new(&fields_ids_map));
+            let primary_key = primary_key
+                .as_deref()
+                .and_then(|primary_key| PrimaryKey::new(primary_key, &db_fields_ids_map));
+            for &docid in batch {
+                doc_alloc.reset();
+                let Some(document) = DocumentFromDb::new(docid, wtxn, index, &db_fields_ids_map)?
+                else {
+                    updates.push((docid, Update::Remove));
+                    continue;
+                };
+                let external_docid = || -> Result<String> {
+                    let Some(primary_key) = &primary_key else { return Ok(String::new()) };
+                    let document = index.document(wtxn, docid)?;
+                    Ok(primary_key.document_id(document, &db_fields_ids_map)?.unwrap_or_default())
+                };
+
+                let provided = match document.vectors_field()? {
+                    Some(vectors) => {
+                        let vectors: serde_json::Map<String, serde_json::Value> =
+                            serde_json::from_str(vectors.get())
+                                .map_err(crate::InternalError::SerdeJson)?;
+                        match vectors.get(&name) {
+                            Some(value) => {
+                                Some(sparse::parse_user_provided(value).map_err(|error| {
+                                    UserError::InvalidVectorsEmbedderConf {
+                                        document_id: external_docid().unwrap_or_default(),
+                                        error: format!("`_vectors.{name}`: {error}"),
+                                    }
+                                })?)
+                            }
+                            None => None,
+                        }
+                    }
+                    None => None,
+                };
+
+                let update = match provided {
+                    // the user provided an embedding that they don't want to regenerate
+                    Some(Some((vector, false))) => Update::Set(vector),
+                    Some(Some((vector, true))) if !embedder.can_generate() => Update::Set(vector),
+                    // the user explicitly asked to keep the current embedding
+                    Some(None) if !rebuild && regenerate_is_false(&document, &name)? => {
+                        Update::Keep
+                    }
+                    _ if embedder.can_generate() => {
+                        let text = prompt.render_document(
+                            &external_docid()?,
+                            document,
+                            &global_fields_ids_map,
+                            &doc_alloc,
+                        )?;
+                        Update::Generate(text.to_owned())
+                    }
+                    _ => Update::Remove,
+                };
+                updates.push((docid, update));
+            }
+        }
+
+        let texts: Vec<&str> = updates
+            .iter()
+            .filter_map(|(_, update)| match update {
+                Update::Generate(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        let deadline = Instant::now() + BATCH_EMBEDDING_TIMEOUT;
+        let embeddings = embedder
+            .embed_index_ref(&texts, threads, Some(deadline))
+            .map_err(crate::vector::Error::from)?;
+        let mut embeddings = embeddings.into_iter();
+
+        for (docid, update) in updates {
+            match update {
+                Update::Remove => {
+                    store.del(wtxn, docid)?;
+                }
+                Update::Keep => (),
+                Update::Set(vector) => store.add(wtxn, docid, &vector)?,
+                Update::Generate(_) => {
+                    // the embedder checks that it returns as many embeddings as it received texts
+                    let vector = embeddings.next().unwrap_or_default();
+                    store.add(wtxn, docid, &vector)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the `_vectors` field of the document is `{"regenerate": false}` for this embedder.
+fn regenerate_is_false<'doc>(document: &impl Document<'doc>, embedder_name: &str) -> Result<bool> {
+    let Some(vectors) = document.vectors_field()? else { return Ok(false) };
+    let vectors: serde_json::Value =
+        serde_json::from_str(vectors.get()).map_err(crate::InternalError::SerdeJson)?;
+    Ok(vectors
+        .get(embedder_name)
+        .and_then(|value| value.get("regenerate"))
+        .is_some_and(|regenerate| regenerate == &serde_json::Value::Bool(false)))
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+
+    use crate::index::tests::TempIndex;
+    use crate::update::Setting;
+    use crate::vector::settings::{EmbedderSource, EmbeddingSettings};
+    use crate::vector::sparse::SparseVector;
+
+    #[test]
+    fn user_provided_sparse_vectors() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(
+                    [(
+                        S("splade"),
+                        Setting::Set(EmbeddingSettings {
+                            source: Setting::Set(EmbedderSource::Sparse),
+                            ..Default::default()
+                        }),
+                    )]
+                    .into(),
+                );
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "splade": { "embeddings": { "1": 1.0, "2": 0.5 }, "regenerate": false } } },
+                { "id": 1, "_vectors": { "splade": { "embeddings": { "2": 2.0 }, "regenerate": false } } },
+                { "id": 2 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let store = index.sparse_vector_store(&rtxn, "splade").unwrap().unwrap();
+        assert_eq!(store.get(&rtxn, 0).unwrap().unwrap().entries(), &[(1, 1.0), (2, 0.5)]);
+        assert_eq!(store.get(&rtxn, 1).unwrap().unwrap().entries(), &[(2, 2.0)]);
+        assert!(store.get(&rtxn, 2).unwrap().is_none());
+
+        let query = SparseVector::new([(2, 1.0)]);
+        let results = store.nns_by_vector(&rtxn, &query, 10, &(0..3).collect()).unwrap();
+        assert_eq!(results, vec![(1, 2.0), (0, 0.5)]);
+        drop(rtxn);
+
+        index.delete_documents(vec![S("1")]);
+        let rtxn = index.read_txn().unwrap();
+        let store = index.sparse_vector_store(&rtxn, "splade").unwrap().unwrap();
+        let results = store.nns_by_vector(&rtxn, &query, 10, &(0..3).collect()).unwrap();
+        assert_eq!(results, vec![(0, 0.5)]);
+        drop(rtxn);
+
+        // removing the embedder drops its inverted index
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings([(S("splade"), Setting::Reset)].into());
+            })
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.sparse_vectors.is_empty(&rtxn).unwrap());
+        assert!(index.sparse_embedding_configs(&rtxn).unwrap().is_empty());
+    }
+}
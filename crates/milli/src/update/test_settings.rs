@@ -426,6 +426,123 @@ fn set_nested_distinct_field() {
     assert_eq!(documents_ids.len(), 3);
 }
 
+#[test]
+fn set_ttl_field() {
+    let index = TempIndex::new();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "name": "kevin", "expires_at": 0 },
+            { "id": 1, "name": "kevina", "expires_at": 4102444800_u64 },
+            { "id": 2, "name": "benoit" }
+        ]))
+        .unwrap();
+
+    // The ttl field is extracted when the setting is defined after the documents.
+    index
+        .update_settings(|settings| {
+            settings.set_ttl_field(S("expires_at"));
+        })
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    let now = time::OffsetDateTime::now_utc().unix_timestamp() as f64;
+    let expired = index.expired_documents_ids(&rtxn, now).unwrap();
+    assert_eq!(expired.iter().collect::<Vec<_>>(), vec![0]);
+
+    // Expired documents are excluded from the search results.
+    let SearchResult { documents_ids, .. } = index.search(&rtxn).execute().unwrap();
+    assert_eq!(documents_ids, vec![1, 2]);
+    drop(rtxn);
+
+    index
+        .update_settings(|settings| {
+            settings.reset_ttl_field();
+        })
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    assert!(index.expired_documents_ids(&rtxn, now).unwrap().is_empty());
+    let SearchResult { documents_ids, .. } = index.search(&rtxn).execute().unwrap();
+    assert_eq!(documents_ids, vec![0, 1, 2]);
+}
+
+#[test]
+fn set_document_schema() {
+    let index = TempIndex::new();
+
+    let error = index
+        .update_settings(|settings| {
+            settings.set_document_schema(serde_json::json!({ "type": "text" }));
+        })
+        .unwrap_err();
+    assert!(matches!(error, Error::UserError(UserError::InvalidDocumentSchema(_))));
+
+    index
+        .update_settings(|settings| {
+            settings.set_document_schema(serde_json::json!({
+                "required": ["name"],
+                "properties": { "name": { "type": "string" }, "age": { "type": "integer" } }
+            }));
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "name": "kevin", "age": 23 },
+            { "id": 1, "name": "kevina" }
+        ]))
+        .unwrap();
+
+    let error = index
+        .add_documents(documents!([
+            { "id": 2, "name": "benoit", "age": "34" },
+            { "id": 3, "age": 21 },
+            { "id": 4, "name": "tamo" }
+        ]))
+        .unwrap_err();
+    snapshot!(error, @r###"
+    2 documents do not match the `documentSchema` of the index:
+      - document `2` at `/age`: expected `integer`, but found `string`
+      - document `3` at `/name`: missing required property
+    "###);
+
+    // The whole payload is rejected.
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.number_of_documents(&rtxn).unwrap(), 2);
+    drop(rtxn);
+
+    // Only the first documents are listed in the error.
+    let documents: Vec<_> = (10..22).map(|id| serde_json::json!({ "id": id })).collect();
+    let error = index.add_documents(documents!(documents)).unwrap_err();
+    snapshot!(error, @r###"
+    12 documents do not match the `documentSchema` of the index:
+      - document `10` at `/name`: missing required property
+      - document `11` at `/name`: missing required property
+      - document `12` at `/name`: missing required property
+      - document `13` at `/name`: missing required property
+      - document `14` at `/name`: missing required property
+      - document `15` at `/name`: missing required property
+      - document `16` at `/name`: missing required property
+      - document `17` at `/name`: missing required property
+      - document `18` at `/name`: missing required property
+      - document `19` at `/name`: missing required property
+      - 2 more documents
+    "###);
+
+    index
+        .update_settings(|settings| {
+            settings.reset_document_schema();
+        })
+        .unwrap();
+
+    index.add_documents(documents!([{ "id": 3, "age": "21" }])).unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+    assert_eq!(index.document_schema(&rtxn).unwrap(), None);
+    assert_eq!(index.number_of_documents(&rtxn).unwrap(), 3);
+}
+
 #[test]
 fn default_stop_words() {
     let index = TempIndex::new();
@@ -880,6 +997,8 @@ fn test_correct_settings_init() {
                 separator_tokens,
                 dictionary,
                 distinct_field,
+                ttl_field,
+                document_schema,
                 synonyms,
                 primary_key,
                 authorize_typos,
@@ -907,6 +1026,8 @@ fn test_correct_settings_init() {
             assert!(matches!(separator_tokens, Setting::NotSet));
             assert!(matches!(dictionary, Setting::NotSet));
             assert!(matches!(distinct_field, Setting::NotSet));
+            assert!(matches!(ttl_field, Setting::NotSet));
+            assert!(matches!(document_schema, Setting::NotSet));
             assert!(matches!(synonyms, Setting::NotSet));
             assert!(matches!(primary_key, Setting::NotSet));
             assert!(matches!(authorize_typos, Setting::NotSet));
@@ -0,0 +1,257 @@
+use std::cell::RefCell;
+use std::ops::Range;
+use std::sync::RwLock;
+
+use bumpalo::Bump;
+use deserr::Deserr;
+use heed::RoTxn;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ArroyWrapper;
+use crate::fields_ids_map::metadata::FieldIdMapWithMetadata;
+use crate::prompt::Prompt;
+use crate::update::new::document::DocumentFromDb;
+use crate::{DocumentId, FieldsIdsMap, GlobalFieldsIdsMap, Index, Result};
+
+/// A document cannot have more vectors than there are arroy stores for an embedder.
+pub const MAX_CHUNKS_PER_DOCUMENT: usize = u8::MAX as usize;
+
+/// Describes how the text of a document is split into overlapping chunks, each chunk being
+/// embedded as a separate vector of the document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct ChunkingSettings {
+    /// Maximum number of words in a chunk.
+    #[serde(default = "default_max_words")]
+    #[deserr(default = default_max_words())]
+    pub max_words: usize,
+    /// Number of words shared by two consecutive chunks.
+    #[serde(default = "default_overlap_words")]
+    #[deserr(default = default_overlap_words())]
+    pub overlap_words: usize,
+    /// Field whose value is chunked instead of the text rendered from the document template.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub field: Option<String>,
+}
+
+pub fn default_max_words() -> usize {
+    200
+}
+
+pub fn default_overlap_words() -> usize {
+    40
+}
+
+impl Default for ChunkingSettings {
+    fn default() -> Self {
+        Self { max_words: default_max_words(), overlap_words: default_overlap_words(), field: None }
+    }
+}
+
+impl ChunkingSettings {
+    /// Returns a description of the problem if the settings cannot be used to split a text.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.max_words == 0 {
+            return Err("`maxWords` must be greater than 0".to_string());
+        }
+        if self.overlap_words >= self.max_words {
+            return Err(format!(
+                "`overlapWords` ({}) must be lower than `maxWords` ({})",
+                self.overlap_words, self.max_words
+            ));
+        }
+        Ok(())
+    }
+
+    /// Splits `text` into the byte ranges of its chunks.
+    ///
+    /// Chunks are aligned on word boundaries and always contain at least one range, even for an empty text.
+    /// Texts too long to fit in [`MAX_CHUNKS_PER_DOCUMENT`] chunks have their tail ignored.
+    pub fn split(&self, text: &str) -> Vec<Range<usize>> {
+        let words = word_ranges(text);
+        let max_words = self.max_words.max(1);
+        if words.len() <= max_words {
+            return vec![0..text.len()];
+        }
+        let step = max_words.saturating_sub(self.overlap_words).max(1);
+
+        let mut chunks = Vec::new();
+        let mut first = 0;
+        while chunks.len() < MAX_CHUNKS_PER_DOCUMENT {
+            let last = (first + max_words).min(words.len()) - 1;
+            chunks.push(words[first].start..words[last].end);
+            if last + 1 == words.len() {
+                break;
+            }
+            first += step;
+        }
+        chunks
+    }
+}
+
+/// The chunk of a document whose embedding is the most similar to the query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MatchedChunk {
+    /// Text of the chunk.
+    pub text: String,
+    /// Byte offset of the start of the chunk in the chunked text.
+    pub start: usize,
+    /// Byte offset of the end of the chunk in the chunked text.
+    pub end: usize,
+}
+
+/// Finds the chunks of documents matching a query, for an embedder that chunks documents.
+pub struct ChunkMatcher {
+    prompt: Prompt,
+    chunking: ChunkingSettings,
+    arroy: ArroyWrapper,
+    db_fields_ids_map: FieldsIdsMap,
+    fields_ids_map: RwLock<FieldIdMapWithMetadata>,
+}
+
+impl ChunkMatcher {
+    /// Returns `None` if the embedder does not exist or does not chunk documents.
+    pub fn new(index: &Index, rtxn: &RoTxn<'_>, embedder_name: &str) -> Result<Option<Self>> {
+        let Some(config) =
+            index.embedding_configs(rtxn)?.into_iter().find(|config| config.name == embedder_name)
+        else {
+            return Ok(None);
+        };
+        let Some(chunking) = config.config.prompt.chunking.clone() else {
+            return Ok(None);
+        };
+        let Some(embedder_id) = index.embedder_category_id.get(rtxn, embedder_name)? else {
+            return Ok(None);
+        };
+        let arroy = ArroyWrapper::new(
+            index.vector_arroy,
+            index.vector_int8,
+            embedder_id,
+            config.config.quantized(),
+        );
+        let prompt = config.config.prompt.try_into().map_err(crate::Error::from)?;
+
+        Ok(Some(Self {
+            prompt,
+            chunking,
+            arroy,
+            db_fields_ids_map: index.fields_ids_map(rtxn)?,
+            fields_ids_map: RwLock::new(index.fields_ids_map_with_metadata(rtxn)?),
+        }))
+    }
+
+    /// Returns the chunk of the document whose embedding is the most similar to `query`.
+    pub fn matched_chunk(
+        &self,
+        index: &Index,
+        rtxn: &RoTxn<'_>,
+        docid: DocumentId,
+        query: &[f32],
+    ) -> Result<Option<MatchedChunk>> {
+        let vectors = self.arroy.item_vectors(rtxn, docid)?;
+        let Some((position, _)) = vectors
+            .iter()
+            .map(|vector| cosine_similarity(query, vector))
+            .enumerate()
+            .max_by(|(_, left), (_, right)| left.total_cmp(right))
+        else {
+            return Ok(None);
+        };
+
+        let Some(document) = DocumentFromDb::new(docid, rtxn, index, &self.db_fields_ids_map)?
+        else {
+            return Ok(None);
+        };
+        let doc_alloc = Bump::new();
+        let fields_ids_map = RefCell::new(GlobalFieldsIdsMap::new(&self.fields_ids_map));
+        let text = self.prompt.render_chunked_text(
+            &docid.to_string(),
+            document,
+            &fields_ids_map,
+            &doc_alloc,
+        )?;
+
+        // the texts are chunked deterministically, so the chunk has the position of its embedding
+        Ok(self.chunking.split(text).into_iter().nth(position).map(|range| MatchedChunk {
+            text: text[range.clone()].to_string(),
+            start: range.start,
+            end: range.end,
+        }))
+    }
+}
+
+fn cosine_similarity(left: &[f32], right: &[f32]) -> f32 {
+    let dot: f32 = left.iter().zip(right).map(|(l, r)| l * r).sum();
+    let norm = |vector: &[f32]| vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(left) * norm(right);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+fn word_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(word_start)) => {
+                words.push(word_start..index);
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(word_start..text.len());
+    }
+    words
+}
+
+#[cfg(test)]
+mod test {
+    use super::ChunkingSettings;
+
+    fn chunking(max_words: usize, overlap_words: usize) -> ChunkingSettings {
+        ChunkingSettings { max_words, overlap_words, field: None }
+    }
+
+    #[test]
+    fn short_text_is_a_single_chunk() {
+        let text = "  the quick brown fox ";
+        assert_eq!(chunking(4, 1).split(text), vec![0..text.len()]);
+        assert_eq!(chunking(4, 1).split(""), vec![0..0]);
+    }
+
+    #[test]
+    fn chunks_overlap() {
+        let text = "one two three four five six seven";
+        let chunks: Vec<_> =
+            chunking(3, 1).split(text).into_iter().map(|range| &text[range]).collect();
+        assert_eq!(chunks, vec!["one two three", "three four five", "five six seven"]);
+
+        let chunks: Vec<_> =
+            chunking(4, 0).split(text).into_iter().map(|range| &text[range]).collect();
+        assert_eq!(chunks, vec!["one two three four", "five six seven"]);
+    }
+
+    #[test]
+    fn chunks_are_aligned_on_char_boundaries() {
+        let text = "élan\u{a0}vital  über\nalles ça";
+        let chunks: Vec<_> =
+            chunking(2, 1).split(text).into_iter().map(|range| &text[range]).collect();
+        assert_eq!(chunks, vec!["élan\u{a0}vital", "vital  über", "über\nalles", "alles ça"]);
+    }
+
+    #[test]
+    fn invalid_settings() {
+        assert!(chunking(0, 0).validate().is_err());
+        assert!(chunking(3, 3).validate().is_err());
+        assert!(chunking(3, 2).validate().is_ok());
+    }
+}
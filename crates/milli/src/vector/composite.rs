@@ -3,6 +3,7 @@ use std::time::Instant;
 use arroy::Distance;
 
 use super::error::CompositeEmbedderContainsHuggingFace;
+use super::request_policy::RequestStats;
 use super::{
     hf, manual, ollama, openai, rest, DistributionShift, EmbedError, Embedding, EmbeddingCache,
     NewEmbedderError,
@@ -203,13 +204,14 @@ impl SubEmbedder {
         &self,
         texts: &[&str],
         threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
     ) -> std::result::Result<Vec<Embedding>, EmbedError> {
         match self {
             SubEmbedder::HuggingFace(embedder) => embedder.embed_index_ref(texts),
-            SubEmbedder::OpenAi(embedder) => embedder.embed_index_ref(texts, threads),
-            SubEmbedder::Ollama(embedder) => embedder.embed_index_ref(texts, threads),
+            SubEmbedder::OpenAi(embedder) => embedder.embed_index_ref(texts, threads, deadline),
+            SubEmbedder::Ollama(embedder) => embedder.embed_index_ref(texts, threads, deadline),
             SubEmbedder::UserProvided(embedder) => embedder.embed_index_ref(texts),
-            SubEmbedder::Rest(embedder) => embedder.embed_index_ref(texts, threads),
+            SubEmbedder::Rest(embedder) => embedder.embed_index_ref(texts, threads, deadline),
         }
     }
 
@@ -224,6 +226,15 @@ impl SubEmbedder {
         }
     }
 
+    pub fn request_stats(&self) -> Option<RequestStats> {
+        match self {
+            SubEmbedder::HuggingFace(_) | SubEmbedder::UserProvided(_) => None,
+            SubEmbedder::OpenAi(embedder) => Some(embedder.request_stats()),
+            SubEmbedder::Ollama(embedder) => Some(embedder.request_stats()),
+            SubEmbedder::Rest(embedder) => Some(embedder.request_stats()),
+        }
+    }
+
     /// Indicates the preferred number of texts in a single chunk passed to [`Self::embed`]
     pub fn prompt_count_in_chunk_hint(&self) -> usize {
         match self {
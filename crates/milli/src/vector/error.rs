@@ -59,6 +59,8 @@ pub enum EmbedErrorKind {
     ModelForward(candle_core::Error),
     #[error("attempt to embed the following text in a configuration where embeddings must be user provided:\n  - `{0}`")]
     ManualEmbed(String),
+    #[error("attempt to compute a dense embedding of the following text with a sparse embedder:\n  - `{0}`")]
+    DenseEmbedOnSparseEmbedder(String),
     #[error("model not found. Meilisearch will not automatically download models from the Ollama library, please pull the model manually{}", option_info(.0.as_deref(), "server replied with "))]
     OllamaModelNotFoundError(Option<String>),
     #[error("error deserializing the response body as JSON:\n  - {0}")]
@@ -79,6 +81,8 @@ pub enum EmbedErrorKind {
     RestUnauthorized(Option<String>, ConfigurationSource),
     #[error("sent too many requests to embedding server{}", option_info(.0.as_deref(), "server replied with "))]
     RestTooManyRequests(Option<String>),
+    #[error("could not send the request to embedding server before the deadline, as the `requestPolicy` of the embedder delays it by {}ms", .0.as_millis())]
+    RestThrottled(std::time::Duration),
     #[error("sent a bad request to embedding server{}{}",
     if ConfigurationSource::User == *.1 {
         "\n  - Hint: check that the `request` in the embedder configuration matches the remote server's API"
@@ -131,6 +135,10 @@ impl EmbedError {
         Self { kind: EmbedErrorKind::ManualEmbed(texts), fault: FaultSource::User }
     }
 
+    pub(crate) fn dense_embed_on_sparse_embedder(texts: String) -> EmbedError {
+        Self { kind: EmbedErrorKind::DenseEmbedOnSparseEmbedder(texts), fault: FaultSource::Bug }
+    }
+
     pub(crate) fn ollama_model_not_found(inner: Option<String>) -> EmbedError {
         Self { kind: EmbedErrorKind::OllamaModelNotFoundError(inner), fault: FaultSource::User }
     }
@@ -166,6 +174,10 @@ impl EmbedError {
         }
     }
 
+    pub(crate) fn rest_throttled(wait: std::time::Duration) -> EmbedError {
+        Self { kind: EmbedErrorKind::RestThrottled(wait), fault: FaultSource::Runtime }
+    }
+
     pub(crate) fn rest_bad_request(
         error_response: Option<String>,
         configuration_source: ConfigurationSource,
@@ -319,6 +331,25 @@ impl NewEmbedderError {
         Self { kind: NewEmbedderErrorKind::LoadModel(inner), fault: FaultSource::Runtime }
     }
 
+    pub fn local_model_with_revision(model: String, revision: String) -> Self {
+        Self {
+            kind: NewEmbedderErrorKind::LocalModelWithRevision { model, revision },
+            fault: FaultSource::User,
+        }
+    }
+
+    pub fn local_model_not_a_directory(path: PathBuf) -> Self {
+        Self { kind: NewEmbedderErrorKind::LocalModelNotADirectory(path), fault: FaultSource::User }
+    }
+
+    pub fn local_model_missing_file(path: PathBuf) -> Self {
+        Self { kind: NewEmbedderErrorKind::LocalModelMissingFile(path), fault: FaultSource::User }
+    }
+
+    pub fn local_model_invalid_file(path: PathBuf) -> Self {
+        Self { kind: NewEmbedderErrorKind::LocalModelInvalidFile(path), fault: FaultSource::User }
+    }
+
     pub fn could_not_determine_dimension(inner: EmbedError) -> NewEmbedderError {
         Self {
             kind: NewEmbedderErrorKind::CouldNotDetermineDimension(inner),
@@ -439,12 +470,15 @@ pub struct DeserializePoolingConfig {
     pub inner: serde_json::Error,
 }
 
+const SUPPORTED_ARCHITECTURES: &str =
+    "`\"BertModel\"`, `\"XLMRobertaModel\"`, `\"ModernBertModel\"` and `\"JinaBertModel\"`";
+
 #[derive(Debug, thiserror::Error)]
 #[error("model `{model_name}` appears to be unsupported{}\n  - inner error: {inner}",
 if architectures.is_empty() {
-    "\n  - Note: only models with architecture \"BertModel\" are supported.".to_string()
+    format!("\n  - Note: only models with architectures {SUPPORTED_ARCHITECTURES} are supported.")
 } else {
-    format!("\n  - Note: model has declared architectures `{architectures:?}`, only models with architecture `\"BertModel\"` are supported.")
+    format!("\n  - Note: model has declared architectures `{architectures:?}`, only models with architectures {SUPPORTED_ARCHITECTURES} are supported.")
 })]
 pub struct UnsupportedModel {
     pub model_name: String,
@@ -487,6 +521,14 @@ pub enum NewEmbedderErrorKind {
     CouldNotDetermineDimension(EmbedError),
     #[error("loading model failed:\n  - {0}")]
     LoadModel(candle_core::Error),
+    #[error("`revision` cannot be used with a local model.\n  - Model `{model}` designates a local directory, but got revision `{revision}`.\n  - Hint: remove `revision` from the embedder settings.")]
+    LocalModelWithRevision { model: String, revision: String },
+    #[error("could not find local model directory at {0}")]
+    LocalModelNotADirectory(PathBuf),
+    #[error("could not find file {0} in local model directory")]
+    LocalModelMissingFile(PathBuf),
+    #[error("could not load file {0} of the local model directory: the file is invalid or the model is unsupported.\n  - Note: only models with architectures {SUPPORTED_ARCHITECTURES} are supported.")]
+    LocalModelInvalidFile(PathBuf),
     #[error("{0}")]
     CouldNotParseTemplate(String),
     #[error("unsupported Ollama URL.\n  - For `ollama` sources, the URL must end with `/api/embed` or `/api/embeddings`\n  - Got `{0}`")]
@@ -1,6 +1,13 @@
-use candle_core::Tensor;
+use std::path::{Component, Path, PathBuf};
+
+use candle_core::{Module, Tensor};
 use candle_nn::VarBuilder;
 use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use candle_transformers::models::jina_bert::{
+    BertModel as JinaBertModel, Config as JinaBertConfig,
+};
+use candle_transformers::models::modernbert::{Config as ModernBertConfig, ModernBert};
+use candle_transformers::models::xlm_roberta::{Config as XlmRobertaConfig, XLMRobertaModel};
 // FIXME: currently we'll be using the hub to retrieve model, in the future we might want to embed it into Meilisearch itself
 use hf_hub::api::sync::Api;
 use hf_hub::{Repo, RepoType};
@@ -77,9 +84,150 @@ impl Default for EmbedderOptions {
     }
 }
 
+/// The encoder architectures the embedder knows how to run.
+enum Model {
+    Bert(BertModel),
+    XlmRoberta(XLMRobertaModel),
+    ModernBert(ModernBert),
+    JinaBert(JinaBertModel),
+}
+
+impl Model {
+    fn forward(&self, token_ids: &Tensor) -> candle_core::Result<Tensor> {
+        match self {
+            Model::Bert(model) => {
+                let token_type_ids = token_ids.zeros_like()?;
+                model.forward(token_ids, &token_type_ids, None)
+            }
+            Model::XlmRoberta(model) => {
+                let token_type_ids = token_ids.zeros_like()?;
+                let attention_mask = token_ids.ones_like()?;
+                model.forward(token_ids, &attention_mask, &token_type_ids, None, None, None)
+            }
+            Model::ModernBert(model) => {
+                let attention_mask = token_ids.ones_like()?;
+                model.forward(token_ids, &attention_mask)
+            }
+            Model::JinaBert(model) => model.forward(token_ids),
+        }
+    }
+}
+
+/// The architecture of a model, as declared by its `config.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Architecture {
+    Bert,
+    XlmRoberta,
+    ModernBert,
+    JinaBert,
+}
+
+impl Architecture {
+    fn from_config(config: &serde_json::Value) -> Self {
+        let architectures: Vec<&str> = config
+            .get("architectures")
+            .and_then(|architectures| architectures.as_array())
+            .map(|architectures| architectures.iter().filter_map(|a| a.as_str()).collect())
+            .unwrap_or_default();
+
+        if architectures.iter().any(|a| a.starts_with("JinaBert"))
+            || config.get("position_embedding_type").and_then(|t| t.as_str()) == Some("alibi")
+        {
+            return Self::JinaBert;
+        }
+
+        match config.get("model_type").and_then(|t| t.as_str()) {
+            Some("xlm-roberta") => Self::XlmRoberta,
+            Some("modernbert") => Self::ModernBert,
+            // anything else is loaded as BERT, and fails if the config doesn't match
+            _ => Self::Bert,
+        }
+    }
+
+    /// Maximum number of tokens the model accepts for a single text.
+    fn max_tokens(&self, config: &serde_json::Value) -> usize {
+        let max_position_embeddings = config
+            .get("max_position_embeddings")
+            .and_then(|max| max.as_u64())
+            .map(|max| max as usize);
+        match self {
+            Architecture::Bert => max_position_embeddings.unwrap_or(512).min(512),
+            // position ids start after the padding index
+            Architecture::XlmRoberta => {
+                max_position_embeddings.map(|max| max.saturating_sub(2)).unwrap_or(512).min(512)
+            }
+            Architecture::ModernBert | Architecture::JinaBert => {
+                max_position_embeddings.unwrap_or(8192).min(8192)
+            }
+        }
+    }
+}
+
+/// Where the model files come from.
+enum ModelSource {
+    Hub(Api, Repo),
+    Local(PathBuf),
+}
+
+impl ModelSource {
+    fn get(&self, filename: &str) -> Result<Option<PathBuf>, NewEmbedderError> {
+        match self {
+            ModelSource::Hub(api, repo) => match api.repo(repo.clone()).get(filename) {
+                Ok(path) => Ok(Some(path)),
+                Err(hf_hub::api::sync::ApiError::RequestError(error))
+                    if matches!(*error, ureq::Error::Status(404, _,)) =>
+                {
+                    Ok(None)
+                }
+                Err(error) => Err(NewEmbedderError::api_get(error)),
+            },
+            ModelSource::Local(directory) => {
+                let path = directory.join(filename);
+                Ok(path.is_file().then_some(path))
+            }
+        }
+    }
+
+    fn get_required(&self, filename: &str) -> Result<PathBuf, NewEmbedderError> {
+        match self {
+            ModelSource::Hub(api, repo) => {
+                api.repo(repo.clone()).get(filename).map_err(NewEmbedderError::api_get)
+            }
+            ModelSource::Local(directory) => {
+                let path = directory.join(filename);
+                if path.is_file() {
+                    Ok(path)
+                } else {
+                    Err(NewEmbedderError::local_model_missing_file(path))
+                }
+            }
+        }
+    }
+}
+
+/// Whether the `model` option designates a directory on the local filesystem rather than a model on the hub.
+pub fn is_local_model(model: &str) -> bool {
+    Path::new(model).is_absolute() || model.starts_with("./") || model.starts_with("../")
+}
+
+/// Whether the local `model` is located inside `directory`, relative paths being resolved against the current directory.
+///
+/// The check is lexical: models going up the directory tree with `..` are never inside `directory`.
+pub fn is_local_model_inside(model: &str, directory: &Path) -> bool {
+    if Path::new(model).components().any(|component| component == Component::ParentDir) {
+        return false;
+    }
+    let Ok(current_dir) = std::env::current_dir() else { return false };
+    let model = current_dir.join(model);
+    let directory = current_dir.join(directory);
+    model != directory && model.starts_with(&directory)
+}
+
 /// Perform embedding of documents and queries
 pub struct Embedder {
-    model: BertModel,
+    model: Model,
+    device: candle_core::Device,
+    max_tokens: usize,
     tokenizer: Tokenizer,
     options: EmbedderOptions,
     dimensions: usize,
@@ -161,34 +309,40 @@ impl Embedder {
                 candle_core::Device::Cpu
             }
         };
-        let repo = match options.revision.clone() {
-            Some(revision) => Repo::with_revision(options.model.clone(), RepoType::Model, revision),
-            None => Repo::model(options.model.clone()),
+        let model_source = if is_local_model(&options.model) {
+            if let Some(revision) = &options.revision {
+                return Err(NewEmbedderError::local_model_with_revision(
+                    options.model.clone(),
+                    revision.clone(),
+                ));
+            }
+            let directory = PathBuf::from(&options.model);
+            if !directory.is_dir() {
+                return Err(NewEmbedderError::local_model_not_a_directory(directory));
+            }
+            ModelSource::Local(directory)
+        } else {
+            let repo = match options.revision.clone() {
+                Some(revision) => {
+                    Repo::with_revision(options.model.clone(), RepoType::Model, revision)
+                }
+                None => Repo::model(options.model.clone()),
+            };
+            ModelSource::Hub(Api::new().map_err(NewEmbedderError::new_api_fail)?, repo)
         };
+
+        // errors about the files of local models must not reveal their content
+        let local = matches!(model_source, ModelSource::Local(_));
+
         let (config_filename, tokenizer_filename, weights_filename, weight_source, pooling) = {
-            let api = Api::new().map_err(NewEmbedderError::new_api_fail)?;
-            let api = api.repo(repo);
-            let config = api.get("config.json").map_err(NewEmbedderError::api_get)?;
-            let tokenizer = api.get("tokenizer.json").map_err(NewEmbedderError::api_get)?;
-            let (weights, source) = {
-                api.get("model.safetensors")
-                    .map(|filename| (filename, WeightSource::Safetensors))
-                    .or_else(|_| {
-                        api.get("pytorch_model.bin")
-                            .map(|filename| (filename, WeightSource::Pytorch))
-                    })
-                    .map_err(NewEmbedderError::api_get)?
-            };
-            let pooling = match api.get("1_Pooling/config.json") {
-                Ok(pooling) => Some(pooling),
-                Err(hf_hub::api::sync::ApiError::RequestError(error))
-                    if matches!(*error, ureq::Error::Status(404, _,)) =>
-                {
-                    // ignore the error if the file simply doesn't exist
-                    None
-                }
-                Err(error) => return Err(NewEmbedderError::api_get(error)),
+            let config = model_source.get_required("config.json")?;
+            let tokenizer = model_source.get_required("tokenizer.json")?;
+            let (weights, source) = match model_source.get("model.safetensors")? {
+                Some(filename) => (filename, WeightSource::Safetensors),
+                None => (model_source.get_required("pytorch_model.bin")?, WeightSource::Pytorch),
             };
+            // the pooling config is optional, ignore it if the file simply doesn't exist
+            let pooling = model_source.get("1_Pooling/config.json")?;
             let mut pooling: Pooling = match pooling {
                 Some(pooling_filename) => {
                     let pooling = std::fs::read_to_string(&pooling_filename).map_err(|inner| {
@@ -197,11 +351,15 @@ impl Embedder {
 
                     let pooling: PoolingConfig =
                         serde_json::from_str(&pooling).map_err(|inner| {
-                            NewEmbedderError::deserialize_pooling_config(
-                                options.model.clone(),
-                                pooling_filename,
-                                inner,
-                            )
+                            if local {
+                                NewEmbedderError::local_model_invalid_file(pooling_filename)
+                            } else {
+                                NewEmbedderError::deserialize_pooling_config(
+                                    options.model.clone(),
+                                    pooling_filename,
+                                    inner,
+                                )
+                            }
                         })?;
                     pooling.into()
                 }
@@ -215,16 +373,30 @@ impl Embedder {
 
         let config = std::fs::read_to_string(&config_filename)
             .map_err(|inner| NewEmbedderError::open_config(config_filename.clone(), inner))?;
-        let config: Config = serde_json::from_str(&config).map_err(|inner| {
-            NewEmbedderError::deserialize_config(
-                options.model.clone(),
-                config,
-                config_filename,
-                inner,
-            )
+        let deserialize_config = |inner: serde_json::Error| {
+            if local {
+                NewEmbedderError::local_model_invalid_file(config_filename.clone())
+            } else {
+                NewEmbedderError::deserialize_config(
+                    options.model.clone(),
+                    config.clone(),
+                    config_filename.clone(),
+                    inner,
+                )
+            }
+        };
+        let config_value: serde_json::Value =
+            serde_json::from_str(&config).map_err(deserialize_config)?;
+        let architecture = Architecture::from_config(&config_value);
+        let max_tokens = architecture.max_tokens(&config_value);
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_filename).map_err(|inner| {
+            if local {
+                NewEmbedderError::local_model_invalid_file(tokenizer_filename)
+            } else {
+                NewEmbedderError::open_tokenizer(tokenizer_filename, inner)
+            }
         })?;
-        let mut tokenizer = Tokenizer::from_file(&tokenizer_filename)
-            .map_err(|inner| NewEmbedderError::open_tokenizer(tokenizer_filename, inner))?;
 
         let vb = match weight_source {
             WeightSource::Pytorch => VarBuilder::from_pth(&weights_filename, DTYPE, &device)
@@ -235,9 +407,52 @@ impl Embedder {
             },
         };
 
-        tracing::debug!(model = options.model, weight=?weight_source, pooling=?pooling, "model config");
+        tracing::debug!(model = options.model, architecture=?architecture, weight=?weight_source, pooling=?pooling, "model config");
 
-        let model = BertModel::load(vb, &config).map_err(NewEmbedderError::load_model)?;
+        let model = match architecture {
+            Architecture::Bert => {
+                let config: Config = serde_json::from_str(&config).map_err(deserialize_config)?;
+                Model::Bert(BertModel::load(vb, &config).map_err(NewEmbedderError::load_model)?)
+            }
+            Architecture::XlmRoberta => {
+                let config: XlmRobertaConfig =
+                    serde_json::from_str(&config).map_err(deserialize_config)?;
+                // checkpoints saved from a task-specific head nest the encoder under `roberta`
+                let vb = if vb.contains_tensor("roberta.embeddings.word_embeddings.weight") {
+                    vb.pp("roberta")
+                } else {
+                    vb
+                };
+                Model::XlmRoberta(
+                    XLMRobertaModel::new(&config, vb).map_err(NewEmbedderError::load_model)?,
+                )
+            }
+            Architecture::ModernBert => {
+                let config: ModernBertConfig =
+                    serde_json::from_str(&config).map_err(deserialize_config)?;
+                // the encoder is expected under `model`, which bare encoder checkpoints omit
+                let vb = if vb.contains_tensor("model.embeddings.tok_embeddings.weight") {
+                    vb
+                } else {
+                    vb.rename_f(|name: &str| name.strip_prefix("model.").unwrap_or(name).to_owned())
+                };
+                Model::ModernBert(
+                    ModernBert::load(vb, &config).map_err(NewEmbedderError::load_model)?,
+                )
+            }
+            Architecture::JinaBert => {
+                let config: JinaBertConfig =
+                    serde_json::from_str(&config).map_err(deserialize_config)?;
+                let vb = if vb.contains_tensor("bert.embeddings.word_embeddings.weight") {
+                    vb.pp("bert")
+                } else {
+                    vb
+                };
+                Model::JinaBert(
+                    JinaBertModel::new(vb, &config).map_err(NewEmbedderError::load_model)?,
+                )
+            }
+        };
 
         if let Some(pp) = tokenizer.get_padding_mut() {
             pp.strategy = tokenizers::PaddingStrategy::BatchLongest
@@ -251,6 +466,8 @@ impl Embedder {
 
         let mut this = Self {
             model,
+            device,
+            max_tokens,
             tokenizer,
             options,
             dimensions: 0,
@@ -314,15 +531,14 @@ impl Embedder {
     pub fn embed_one(&self, text: &str) -> std::result::Result<Embedding, EmbedError> {
         let tokens = self.tokenizer.encode(text, true).map_err(EmbedError::tokenize)?;
         let token_ids = tokens.get_ids();
-        let token_ids = if token_ids.len() > 512 { &token_ids[..512] } else { token_ids };
-        let token_ids =
-            Tensor::new(token_ids, &self.model.device).map_err(EmbedError::tensor_shape)?;
+        let token_ids = if token_ids.len() > self.max_tokens {
+            &token_ids[..self.max_tokens]
+        } else {
+            token_ids
+        };
+        let token_ids = Tensor::new(token_ids, &self.device).map_err(EmbedError::tensor_shape)?;
         let token_ids = Tensor::stack(&[token_ids], 0).map_err(EmbedError::tensor_shape)?;
-        let token_type_ids = token_ids.zeros_like().map_err(EmbedError::tensor_shape)?;
-        let embeddings = self
-            .model
-            .forward(&token_ids, &token_type_ids, None)
-            .map_err(EmbedError::model_forward)?;
+        let embeddings = self.model.forward(&token_ids).map_err(EmbedError::model_forward)?;
 
         let embedding = Self::pooling(embeddings, self.pooling)?;
 
@@ -371,3 +587,47 @@ impl Embedder {
         &self.cache
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn architecture_from_config() {
+        let config = serde_json::json!({ "architectures": ["BertModel"], "model_type": "bert" });
+        assert_eq!(Architecture::from_config(&config), Architecture::Bert);
+        assert_eq!(Architecture::from_config(&config).max_tokens(&config), 512);
+
+        let config = serde_json::json!({
+            "architectures": ["XLMRobertaModel"],
+            "model_type": "xlm-roberta",
+            "max_position_embeddings": 514,
+        });
+        assert_eq!(Architecture::from_config(&config), Architecture::XlmRoberta);
+        assert_eq!(Architecture::from_config(&config).max_tokens(&config), 512);
+
+        let config = serde_json::json!({
+            "architectures": ["ModernBertModel"],
+            "model_type": "modernbert",
+            "max_position_embeddings": 8192,
+        });
+        assert_eq!(Architecture::from_config(&config), Architecture::ModernBert);
+        assert_eq!(Architecture::from_config(&config).max_tokens(&config), 8192);
+
+        let config = serde_json::json!({
+            "architectures": ["JinaBertForMaskedLM"],
+            "model_type": "bert",
+            "position_embedding_type": "alibi",
+        });
+        assert_eq!(Architecture::from_config(&config), Architecture::JinaBert);
+    }
+
+    #[test]
+    fn local_model() {
+        assert!(is_local_model("/models/multilingual-e5-base"));
+        assert!(is_local_model("./models/bge"));
+        assert!(is_local_model("../bge"));
+        assert!(!is_local_model("BAAI/bge-base-en-v1.5"));
+        assert!(!is_local_model("sentence-transformers/all-MiniLM-L6-v2"));
+    }
+}
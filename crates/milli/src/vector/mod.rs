@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Instant;
 
 use arroy::distances::{BinaryQuantizedCosine, Cosine};
 use arroy::ItemId;
 use deserr::{DeserializeError, Deserr};
+use heed::types::Bytes;
 use heed::{RoTxn, RwTxn, Unspecified};
 use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
@@ -13,10 +14,14 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use self::error::{EmbedError, NewEmbedderError};
+use self::persistent_cache::{PersistentCacheScope, PersistentEmbeddingCache};
+use self::request_policy::{RequestPolicy, RequestStats};
+use self::scalar::ScalarQuantizedStore;
 use crate::progress::Progress;
 use crate::prompt::{Prompt, PromptData};
 use crate::ThreadPoolNoAbort;
 
+pub mod chunking;
 pub mod composite;
 pub mod error;
 pub mod hf;
@@ -24,10 +29,15 @@ pub mod json_template;
 pub mod manual;
 pub mod openai;
 pub mod parsed_vectors;
+pub mod persistent_cache;
+pub mod request_policy;
 pub mod settings;
 
 pub mod ollama;
+pub mod rerank;
 pub mod rest;
+pub mod scalar;
+pub mod sparse;
 
 pub use self::error::Error;
 
@@ -40,21 +50,65 @@ pub struct ArroyWrapper {
     quantized: bool,
     embedder_index: u8,
     database: arroy::Database<Unspecified>,
+    scalar: ScalarQuantizedStore,
 }
 
 impl ArroyWrapper {
     pub fn new(
         database: arroy::Database<Unspecified>,
+        scalar_database: heed::Database<Bytes, Bytes>,
         embedder_index: u8,
         quantized: bool,
     ) -> Self {
-        Self { database, embedder_index, quantized }
+        Self {
+            database,
+            embedder_index,
+            quantized,
+            scalar: ScalarQuantizedStore::new(scalar_database, embedder_index),
+        }
     }
 
     pub fn embedder_index(&self) -> u8 {
         self.embedder_index
     }
 
+    /// The int8 embeddings of this embedder, if it has int8 rescoring enabled.
+    ///
+    /// Embedders with int8 rescoring are always binary quantized in arroy, see [`scalar`].
+    fn scalar_store(&self, rtxn: &RoTxn) -> Result<Option<ScalarQuantizedStore>, arroy::Error> {
+        if self.quantized && self.scalar.is_enabled(rtxn)? {
+            Ok(Some(self.scalar))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Copies the full-precision embeddings of the given items to the int8 store of an embedder with int8 rescoring.
+    ///
+    /// Must be called before the embedder is binary quantized, as it is the last time its embeddings are available.
+    pub fn scalar_quantize_items(
+        &self,
+        wtxn: &mut RwTxn,
+        dimension: usize,
+        items: &RoaringBitmap,
+    ) -> Result<(), arroy::Error> {
+        if self.quantized || !self.scalar.is_enabled(wtxn)? {
+            return Ok(());
+        }
+        for index in arroy_db_range_for_embedder(self.embedder_index) {
+            let writer = arroy::Writer::new(self.angular_db(), index, dimension);
+            if writer.is_empty(wtxn)? {
+                break;
+            }
+            for item_id in items {
+                if let Some(vector) = writer.item_vector(wtxn, item_id)? {
+                    self.scalar.put(wtxn, index, item_id, &vector)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn readers<'a, D: arroy::Distance>(
         &'a self,
         rtxn: &'a RoTxn<'a>,
@@ -142,12 +196,16 @@ impl ArroyWrapper {
         embeddings: &Embeddings<f32>,
     ) -> Result<(), arroy::Error> {
         let dimension = embeddings.dimension();
+        let scalar = self.scalar_store(wtxn)?;
         for (index, vector) in
             arroy_db_range_for_embedder(self.embedder_index).zip(embeddings.iter())
         {
             if self.quantized {
                 arroy::Writer::new(self.quantized_db(), index, dimension)
-                    .add_item(wtxn, item_id, vector)?
+                    .add_item(wtxn, item_id, vector)?;
+                if let Some(scalar) = scalar {
+                    scalar.put(wtxn, index, item_id, vector)?;
+                }
             } else {
                 arroy::Writer::new(self.angular_db(), index, dimension)
                     .add_item(wtxn, item_id, vector)?
@@ -164,9 +222,10 @@ impl ArroyWrapper {
         vector: &[f32],
     ) -> Result<(), arroy::Error> {
         if self.quantized {
-            self._add_item(wtxn, self.quantized_db(), item_id, vector)
+            let scalar = self.scalar_store(wtxn)?;
+            self._add_item(wtxn, self.quantized_db(), scalar, item_id, vector)
         } else {
-            self._add_item(wtxn, self.angular_db(), item_id, vector)
+            self._add_item(wtxn, self.angular_db(), None, item_id, vector)
         }
     }
 
@@ -174,6 +233,7 @@ impl ArroyWrapper {
         &self,
         wtxn: &mut RwTxn,
         db: arroy::Database<D>,
+        scalar: Option<ScalarQuantizedStore>,
         item_id: arroy::ItemId,
         vector: &[f32],
     ) -> Result<(), arroy::Error> {
@@ -183,6 +243,9 @@ impl ArroyWrapper {
             let writer = arroy::Writer::new(db, index, dimension);
             if !writer.contains_item(wtxn, item_id)? {
                 writer.add_item(wtxn, item_id, vector)?;
+                if let Some(scalar) = scalar {
+                    scalar.put(wtxn, index, item_id, vector)?;
+                }
                 break;
             }
         }
@@ -196,9 +259,13 @@ impl ArroyWrapper {
         dimension: usize,
         item_id: arroy::ItemId,
     ) -> Result<(), arroy::Error> {
+        let scalar = self.scalar_store(wtxn)?;
         for index in arroy_db_range_for_embedder(self.embedder_index) {
             if self.quantized {
                 let writer = arroy::Writer::new(self.quantized_db(), index, dimension);
+                if let Some(scalar) = scalar {
+                    scalar.del(wtxn, index, item_id)?;
+                }
                 if !writer.del_item(wtxn, item_id)? {
                     break;
                 }
@@ -221,12 +288,59 @@ impl ArroyWrapper {
         vector: &[f32],
     ) -> Result<bool, arroy::Error> {
         if self.quantized {
-            self._del_item(wtxn, self.quantized_db(), item_id, vector)
+            match self.scalar_store(wtxn)? {
+                Some(scalar) => self.del_scalar_item(wtxn, scalar, item_id, vector),
+                None => self._del_item(wtxn, self.quantized_db(), item_id, vector),
+            }
         } else {
             self._del_item(wtxn, self.angular_db(), item_id, vector)
         }
     }
 
+    /// Delete one item of an embedder with int8 rescoring.
+    ///
+    /// The binary quantized embeddings cannot be compared to `vector`, so the int8 embeddings are compared instead.
+    fn del_scalar_item(
+        &self,
+        wtxn: &mut RwTxn,
+        scalar: ScalarQuantizedStore,
+        item_id: arroy::ItemId,
+        vector: &[f32],
+    ) -> Result<bool, arroy::Error> {
+        let dimension = vector.len();
+        let db = self.quantized_db();
+        let mut deleted_index = None;
+        let mut last_index_with_a_vector = None;
+
+        for index in arroy_db_range_for_embedder(self.embedder_index) {
+            let writer = arroy::Writer::new(db, index, dimension);
+            // uses invariant: vectors are packed in the first writers.
+            if !writer.contains_item(wtxn, item_id)? {
+                break;
+            }
+            last_index_with_a_vector = Some(index);
+            if deleted_index.is_none() && scalar.contains_vector(wtxn, index, item_id, vector)? {
+                writer.del_item(wtxn, item_id)?;
+                scalar.del(wtxn, index, item_id)?;
+                deleted_index = Some(index);
+            }
+        }
+
+        // enforce invariant: vectors are packed in the first writers.
+        if let (Some(deleted_index), Some(last_index)) = (deleted_index, last_index_with_a_vector) {
+            if last_index != deleted_index {
+                let writer = arroy::Writer::new(db, last_index, dimension);
+                if let Some(vector) = writer.item_vector(wtxn, item_id)? {
+                    writer.del_item(wtxn, item_id)?;
+                    let writer = arroy::Writer::new(db, deleted_index, dimension);
+                    writer.add_item(wtxn, item_id, &vector)?;
+                }
+                scalar.move_item(wtxn, last_index, deleted_index, item_id)?;
+            }
+        }
+        Ok(deleted_index.is_some())
+    }
+
     fn _del_item<D: arroy::Distance>(
         &self,
         wtxn: &mut RwTxn,
@@ -271,7 +385,9 @@ impl ArroyWrapper {
         Ok(deleted_index.is_some())
     }
 
+    /// Removes all the embeddings of the embedder, along with its int8 embeddings if it has int8 rescoring enabled.
     pub fn clear(&self, wtxn: &mut RwTxn, dimension: usize) -> Result<(), arroy::Error> {
+        self.scalar.disable(wtxn)?;
         for index in arroy_db_range_for_embedder(self.embedder_index) {
             if self.quantized {
                 let writer = arroy::Writer::new(self.quantized_db(), index, dimension);
@@ -324,7 +440,13 @@ impl ArroyWrapper {
         limit: usize,
         filter: Option<&RoaringBitmap>,
     ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
-        if self.quantized {
+        if let Some(scalar) = self.scalar_store(rtxn)? {
+            let first_index = arroy_db_range_for_embedder(self.embedder_index).next().unwrap();
+            let Some(vector) = scalar.get(rtxn, first_index, item)? else {
+                return Ok(Vec::new());
+            };
+            self.scalar_nns_by_vector(rtxn, scalar, &vector, limit, filter)
+        } else if self.quantized {
             self._nns_by_item(rtxn, self.quantized_db(), item, limit, filter)
         } else {
             self._nns_by_item(rtxn, self.angular_db(), item, limit, filter)
@@ -365,13 +487,143 @@ impl ArroyWrapper {
         limit: usize,
         filter: Option<&RoaringBitmap>,
     ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
-        if self.quantized {
-            self._nns_by_vector(rtxn, self.quantized_db(), vector, limit, filter)
+        if let Some(scalar) = self.scalar_store(rtxn)? {
+            self.scalar_nns_by_vector(rtxn, scalar, vector, limit, filter)
+        } else if self.quantized {
+            self.binary_nns_by_vector(rtxn, vector, limit, filter)
         } else {
             self._nns_by_vector(rtxn, self.angular_db(), vector, limit, filter)
         }
     }
 
+    /// Fetches more candidates than requested from the binary quantized index,
+    /// and rescores them with their int8 embeddings.
+    fn scalar_nns_by_vector(
+        &self,
+        rtxn: &RoTxn,
+        store: ScalarQuantizedStore,
+        vector: &[f32],
+        limit: usize,
+        filter: Option<&RoaringBitmap>,
+    ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
+        let mut results = Vec::new();
+
+        for (index, reader) in arroy_db_range_for_embedder(self.embedder_index)
+            .zip(self.readers(rtxn, self.quantized_db()))
+        {
+            let reader = reader?;
+            let mut searcher = reader.nns(limit.saturating_mul(scalar::RESCORING_OVERSAMPLING));
+            if let Some(filter) = filter {
+                searcher.candidates(filter);
+            }
+
+            let mut rescored = Vec::new();
+            for (item, _) in searcher.by_vector(rtxn, vector)? {
+                if let Some(candidate) = store.get(rtxn, index, item)? {
+                    rescored.push((item, scalar::cosine_distance(vector, &candidate)));
+                }
+            }
+            rescored.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+            rescored.truncate(limit);
+            results.append(&mut rescored);
+        }
+
+        results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+
+        Ok(results)
+    }
+
+    /// Computes the distance between the vector and the embeddings of every candidate
+    /// instead of relying on the approximate search of arroy, so the nearest neighbors are always exact.
+    ///
+    /// Documents with several embeddings are ranked by their closest embedding.
+    /// The distances are the same as the ones of [`Self::nns_by_vector`].
+    pub fn exact_nns_by_vector(
+        &self,
+        rtxn: &RoTxn,
+        vector: &[f32],
+        limit: usize,
+        candidates: &RoaringBitmap,
+    ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
+        let mut distances: HashMap<ItemId, f32> = HashMap::new();
+        let mut insert = |item, embedding: &[f32]| {
+            let distance = scalar::cosine_distance(vector, embedding);
+            distances
+                .entry(item)
+                .and_modify(|closest| *closest = closest.min(distance))
+                .or_insert(distance);
+        };
+
+        if let Some(scalar) = self.scalar_store(rtxn)? {
+            for (index, reader) in arroy_db_range_for_embedder(self.embedder_index)
+                .zip(self.readers(rtxn, self.quantized_db()))
+            {
+                reader?;
+                for item in candidates {
+                    if let Some(embedding) = scalar.get(rtxn, index, item)? {
+                        insert(item, &embedding);
+                    }
+                }
+            }
+        } else if self.quantized {
+            for reader in self.readers(rtxn, self.quantized_db()) {
+                let reader = reader?;
+                for item in candidates {
+                    if let Some(embedding) = reader.item_vector(rtxn, item)? {
+                        insert(item, &embedding);
+                    }
+                }
+            }
+        } else {
+            for reader in self.readers(rtxn, self.angular_db()) {
+                let reader = reader?;
+                for item in candidates {
+                    if let Some(embedding) = reader.item_vector(rtxn, item)? {
+                        insert(item, &embedding);
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<_> = distances.into_iter().collect();
+        results.sort_unstable_by_key(|&(item, distance)| (OrderedFloat(distance), item));
+        results.truncate(limit);
+
+        Ok(results)
+    }
+
+    /// Searches the binary quantized index, and rescores the results with the cosine distance
+    /// to their quantized embeddings, like [`Self::exact_nns_by_vector`] does.
+    ///
+    /// This way, the distances don't depend on whether the candidates were scanned or searched.
+    fn binary_nns_by_vector(
+        &self,
+        rtxn: &RoTxn,
+        vector: &[f32],
+        limit: usize,
+        filter: Option<&RoaringBitmap>,
+    ) -> Result<Vec<(ItemId, f32)>, arroy::Error> {
+        let mut results = Vec::new();
+
+        for reader in self.readers(rtxn, self.quantized_db()) {
+            let reader = reader?;
+            let mut searcher = reader.nns(limit);
+            if let Some(filter) = filter {
+                searcher.candidates(filter);
+            }
+
+            for (item, _) in searcher.by_vector(rtxn, vector)? {
+                if let Some(embedding) = reader.item_vector(rtxn, item)? {
+                    results.push((item, scalar::cosine_distance(vector, &embedding)));
+                }
+            }
+        }
+
+        results.sort_unstable_by_key(|(_, distance)| OrderedFloat(*distance));
+
+        Ok(results)
+    }
+
     fn _nns_by_vector<D: arroy::Distance>(
         &self,
         rtxn: &RoTxn,
@@ -400,7 +652,18 @@ impl ArroyWrapper {
     pub fn item_vectors(&self, rtxn: &RoTxn, item_id: u32) -> Result<Vec<Vec<f32>>, arroy::Error> {
         let mut vectors = Vec::new();
 
-        if self.quantized {
+        if let Some(scalar) = self.scalar_store(rtxn)? {
+            for (index, reader) in arroy_db_range_for_embedder(self.embedder_index)
+                .zip(self.readers(rtxn, self.quantized_db()))
+            {
+                reader?;
+                if let Some(vec) = scalar.get(rtxn, index, item_id)? {
+                    vectors.push(vec);
+                } else {
+                    break;
+                }
+            }
+        } else if self.quantized {
             for reader in self.readers(rtxn, self.quantized_db()) {
                 if let Some(vec) = reader?.item_vector(rtxn, item_id)? {
                     vectors.push(vec);
@@ -555,11 +818,15 @@ pub enum Embedder {
     Rest(rest::Embedder),
     /// An embedder composed of an embedder at search time and an embedder at indexing time.
     Composite(composite::Embedder),
+    /// An embedder producing sparse embeddings, stored in an inverted index rather than in arroy.
+    Sparse(sparse::Embedder),
 }
 
 #[derive(Debug)]
 struct EmbeddingCache {
     data: Option<Mutex<lru::LruCache<String, Embedding>>>,
+    /// The persistent cache consulted when an embedding is not in memory, if any.
+    persistent: OnceLock<PersistentCacheScope>,
 }
 
 impl EmbeddingCache {
@@ -567,11 +834,30 @@ impl EmbeddingCache {
 
     pub fn new(cap: usize) -> Self {
         let data = NonZeroUsize::new(cap).map(lru::LruCache::new).map(Mutex::new);
-        Self { data }
+        Self { data, persistent: OnceLock::new() }
     }
 
     /// Get the embedding corresponding to `text`, if any is present in the cache.
     pub fn get(&self, text: &str) -> Option<Embedding> {
+        if let Some(embedding) = self.get_in_memory(text) {
+            return Some(embedding);
+        }
+        let embedding = self.persistent()?.get_many(&[text]).pop().flatten()?;
+        self.put_in_memory(text.to_owned(), embedding.clone());
+        Some(embedding)
+    }
+
+    /// Puts a new embedding for the specified `text`
+    ///
+    /// The embedding is written to the persistent cache in the background, if any.
+    pub fn put(&self, text: String, embedding: Embedding) {
+        if let Some(persistent) = self.persistent() {
+            persistent.put_deferred(&text, embedding.clone());
+        }
+        self.put_in_memory(text, embedding);
+    }
+
+    fn get_in_memory(&self, text: &str) -> Option<Embedding> {
         let data = self.data.as_ref()?;
         if text.len() > Self::MAX_TEXT_LEN {
             return None;
@@ -581,8 +867,7 @@ impl EmbeddingCache {
         cache.get(text).cloned()
     }
 
-    /// Puts a new embedding for the specified `text`
-    pub fn put(&self, text: String, embedding: Embedding) {
+    fn put_in_memory(&self, text: String, embedding: Embedding) {
         let Some(data) = self.data.as_ref() else {
             return;
         };
@@ -595,6 +880,15 @@ impl EmbeddingCache {
 
         cache.put(text, embedding);
     }
+
+    fn persistent(&self) -> Option<&PersistentCacheScope> {
+        self.persistent.get()
+    }
+
+    fn set_persistent(&self, persistent: PersistentCacheScope) {
+        // the persistent cache is set once, right after the embedder is created
+        let _ = self.persistent.set(persistent);
+    }
 }
 
 /// Configuration for an embedder.
@@ -606,12 +900,22 @@ pub struct EmbeddingConfig {
     pub prompt: PromptData,
     /// If this embedder is binary quantized
     pub quantized: Option<bool>,
+    /// If this embedder is binary quantized and rescores its candidates with int8 embeddings
+    #[serde(default)]
+    pub int8_rescoring: Option<bool>,
     // TODO: add metrics and anything needed
 }
 
 impl EmbeddingConfig {
+    /// Whether the arroy index of this embedder is binary quantized.
+    ///
+    /// This is the case for both binary quantized embedders and embedders with int8 rescoring.
     pub fn quantized(&self) -> bool {
-        self.quantized.unwrap_or_default()
+        self.quantized.unwrap_or_default() || self.int8_rescoring()
+    }
+
+    pub fn int8_rescoring(&self) -> bool {
+        self.int8_rescoring.unwrap_or_default()
     }
 }
 
@@ -636,6 +940,14 @@ impl EmbeddingConfigs {
         self.0.get(name).cloned()
     }
 
+    /// The counters of the requests sent by each embedder, see [`Embedder::request_stats`].
+    pub fn request_stats(&self) -> Vec<(String, RequestStats)> {
+        self.0
+            .iter()
+            .filter_map(|(name, (embedder, _, _))| Some((name.clone(), embedder.request_stats()?)))
+            .collect()
+    }
+
     pub fn inner_as_ref(&self) -> &HashMap<String, (Arc<Embedder>, Arc<Prompt>, bool)> {
         &self.0
     }
@@ -665,6 +977,7 @@ pub enum EmbedderOptions {
     UserProvided(manual::EmbedderOptions),
     Rest(rest::EmbedderOptions),
     Composite(composite::EmbedderOptions),
+    Sparse(sparse::EmbedderOptions),
 }
 
 impl Default for EmbedderOptions {
@@ -673,6 +986,34 @@ impl Default for EmbedderOptions {
     }
 }
 
+impl EmbedderOptions {
+    /// The policy used to send requests to the embedder, for embedders configured with a `requestPolicy`.
+    pub fn request_policy(&self) -> Option<&RequestPolicy> {
+        match self {
+            EmbedderOptions::OpenAi(options) => options.request_policy.as_ref(),
+            EmbedderOptions::Ollama(options) => options.request_policy.as_ref(),
+            EmbedderOptions::Rest(options) => options.request_policy.as_ref(),
+            EmbedderOptions::HuggingFace(_)
+            | EmbedderOptions::UserProvided(_)
+            | EmbedderOptions::Composite(_)
+            | EmbedderOptions::Sparse(_) => None,
+        }
+    }
+
+    /// Sets the policy used to send requests to the embedder, for embedders sending requests to a remote server.
+    pub(crate) fn set_request_policy(&mut self, request_policy: Option<RequestPolicy>) {
+        match self {
+            EmbedderOptions::OpenAi(options) => options.request_policy = request_policy,
+            EmbedderOptions::Ollama(options) => options.request_policy = request_policy,
+            EmbedderOptions::Rest(options) => options.request_policy = request_policy,
+            EmbedderOptions::HuggingFace(_)
+            | EmbedderOptions::UserProvided(_)
+            | EmbedderOptions::Composite(_)
+            | EmbedderOptions::Sparse(_) => {}
+        }
+    }
+}
+
 impl Embedder {
     /// Spawns a new embedder built from its options.
     pub fn new(
@@ -700,9 +1041,35 @@ impl Embedder {
             EmbedderOptions::Composite(options) => {
                 Self::Composite(composite::Embedder::new(options, cache_cap)?)
             }
+            EmbedderOptions::Sparse(options) => Self::Sparse(sparse::Embedder::new(options)?),
         })
     }
 
+    /// Makes the embedder read and fill the given persistent cache, both when indexing and when searching.
+    ///
+    /// `options` must be the options the embedder was created with: the embeddings are cached per configuration.
+    pub fn set_persistent_cache(
+        &self,
+        cache: &Arc<PersistentEmbeddingCache>,
+        options: &EmbedderOptions,
+    ) {
+        match (self, options) {
+            (Embedder::Composite(embedder), EmbedderOptions::Composite(options)) => {
+                if let Some(search_cache) = embedder.search.cache() {
+                    search_cache.set_persistent(cache.scope(&options.search));
+                }
+                if let Some(index_cache) = embedder.index.cache() {
+                    index_cache.set_persistent(cache.scope(&options.index));
+                }
+            }
+            (embedder, options) => {
+                if let Some(embedder_cache) = embedder.cache() {
+                    embedder_cache.set_persistent(cache.scope(options));
+                }
+            }
+        }
+    }
+
     /// Embed in search context
 
     #[tracing::instrument(level = "debug", skip_all, target = "search")]
@@ -717,7 +1084,73 @@ impl Embedder {
                 return Ok(embedding);
             }
         }
-        let embedding = match self {
+        let embedding = self.embed_one(text, deadline, true)?;
+
+        if let Some(cache) = self.cache() {
+            cache.put(text.to_owned(), embedding.clone());
+        }
+
+        Ok(embedding)
+    }
+
+    /// Embed multiple texts in search context, by chunks sent in parallel.
+    ///
+    /// Like [`Self::embed_search`], the embeddings are read from and stored in the embedding cache.
+    pub fn embed_search_ref(
+        &self,
+        texts: &[&str],
+        threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
+    ) -> std::result::Result<Vec<Embedding>, EmbedError> {
+        let mut embeddings: Vec<Option<Embedding>> = match self.cache() {
+            Some(cache) => texts.iter().map(|text| cache.get(text)).collect(),
+            None => vec![None; texts.len()],
+        };
+        let missing_texts: Vec<&str> = texts
+            .iter()
+            .zip(&embeddings)
+            .filter_map(|(text, embedding)| embedding.is_none().then_some(*text))
+            .collect();
+
+        if !missing_texts.is_empty() {
+            let missing_embeddings = match self {
+                Embedder::Composite(embedder) => {
+                    embedder.search.embed_index_ref(&missing_texts, threads, deadline)?
+                }
+                embedder => embedder.embed_index_ref_uncached(&missing_texts, threads, deadline)?,
+            };
+            if let Some(cache) = self.cache() {
+                for (text, embedding) in missing_texts.iter().zip(&missing_embeddings) {
+                    cache.put(text.to_string(), embedding.clone());
+                }
+            }
+            fill_missing(embeddings.iter_mut(), missing_embeddings.into_iter());
+        }
+
+        embeddings
+            .into_iter()
+            .map(|embedding| embedding.ok_or_else(EmbedError::missing_embedding))
+            .collect()
+    }
+
+    /// Embed a single document text, with the indexing embedder of composite embedders.
+    ///
+    /// Unlike [`Self::embed_search`], the embedding cache is neither read nor filled.
+    pub fn embed_document(
+        &self,
+        text: &str,
+        deadline: Option<Instant>,
+    ) -> std::result::Result<Embedding, EmbedError> {
+        self.embed_one(text, deadline, false)
+    }
+
+    fn embed_one(
+        &self,
+        text: &str,
+        deadline: Option<Instant>,
+        search: bool,
+    ) -> std::result::Result<Embedding, EmbedError> {
+        match self {
             Embedder::HuggingFace(embedder) => embedder.embed_one(text),
             Embedder::OpenAi(embedder) => {
                 embedder.embed(&[text], deadline)?.pop().ok_or_else(EmbedError::missing_embedding)
@@ -730,14 +1163,12 @@ impl Embedder {
                 .embed_ref(&[text], deadline)?
                 .pop()
                 .ok_or_else(EmbedError::missing_embedding),
-            Embedder::Composite(embedder) => embedder.search.embed_one(text, deadline),
-        }?;
-
-        if let Some(cache) = self.cache() {
-            cache.put(text.to_owned(), embedding.clone());
+            Embedder::Composite(embedder) if search => embedder.search.embed_one(text, deadline),
+            Embedder::Composite(embedder) => embedder.index.embed_one(text, deadline),
+            Embedder::Sparse(_) => {
+                Err(EmbedError::dense_embed_on_sparse_embedder(text.chars().take(250).collect()))
+            }
         }
-
-        Ok(embedding)
     }
 
     /// Embed multiple chunks of texts.
@@ -747,6 +1178,55 @@ impl Embedder {
         &self,
         text_chunks: Vec<Vec<String>>,
         threads: &ThreadPoolNoAbort,
+    ) -> std::result::Result<Vec<Vec<Embedding>>, EmbedError> {
+        let Some(cache) = self.index_persistent_cache() else {
+            return self.embed_index_uncached(text_chunks, threads);
+        };
+
+        let mut embeddings: Vec<Vec<Option<Embedding>>> = text_chunks
+            .iter()
+            .map(|chunk| cache.get_many(&chunk.iter().map(String::as_str).collect::<Vec<_>>()))
+            .collect();
+        let missing_chunks: Vec<Vec<String>> = text_chunks
+            .into_iter()
+            .zip(&embeddings)
+            .map(|(chunk, embeddings)| {
+                chunk
+                    .into_iter()
+                    .zip(embeddings)
+                    .filter_map(|(text, embedding)| embedding.is_none().then_some(text))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|chunk| !chunk.is_empty())
+            .collect();
+
+        if !missing_chunks.is_empty() {
+            let missing_embeddings = self.embed_index_uncached(missing_chunks.clone(), threads)?;
+            cache.put_many(
+                missing_chunks
+                    .iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .zip(missing_embeddings.iter().flatten()),
+            );
+            fill_missing(embeddings.iter_mut().flatten(), missing_embeddings.into_iter().flatten());
+        }
+
+        embeddings
+            .into_iter()
+            .map(|chunk| {
+                chunk
+                    .into_iter()
+                    .map(|embedding| embedding.ok_or_else(EmbedError::missing_embedding))
+                    .collect::<std::result::Result<Vec<_>, _>>()
+            })
+            .collect()
+    }
+
+    fn embed_index_uncached(
+        &self,
+        text_chunks: Vec<Vec<String>>,
+        threads: &ThreadPoolNoAbort,
     ) -> std::result::Result<Vec<Vec<Embedding>>, EmbedError> {
         match self {
             Embedder::HuggingFace(embedder) => embedder.embed_index(text_chunks),
@@ -755,22 +1235,63 @@ impl Embedder {
             Embedder::UserProvided(embedder) => embedder.embed_index(text_chunks),
             Embedder::Rest(embedder) => embedder.embed_index(text_chunks, threads),
             Embedder::Composite(embedder) => embedder.index.embed_index(text_chunks, threads),
+            Embedder::Sparse(_) => Err(EmbedError::dense_embed_on_sparse_embedder(
+                text_chunks.first().and_then(|chunk| chunk.first()).cloned().unwrap_or_default(),
+            )),
         }
     }
 
     /// Non-owning variant of [`Self::embed_index`].
+    ///
+    /// The requests sent to the embedder give up once the `deadline` is reached, if any.
     pub fn embed_index_ref(
         &self,
         texts: &[&str],
         threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
+    ) -> std::result::Result<Vec<Embedding>, EmbedError> {
+        let Some(cache) = self.index_persistent_cache() else {
+            return self.embed_index_ref_uncached(texts, threads, deadline);
+        };
+
+        let mut embeddings = cache.get_many(texts);
+        let missing_texts: Vec<&str> = texts
+            .iter()
+            .zip(&embeddings)
+            .filter_map(|(text, embedding)| embedding.is_none().then_some(*text))
+            .collect();
+
+        if !missing_texts.is_empty() {
+            let missing_embeddings =
+                self.embed_index_ref_uncached(&missing_texts, threads, deadline)?;
+            cache.put_many(missing_texts.iter().copied().zip(&missing_embeddings));
+            fill_missing(embeddings.iter_mut(), missing_embeddings.into_iter());
+        }
+
+        embeddings
+            .into_iter()
+            .map(|embedding| embedding.ok_or_else(EmbedError::missing_embedding))
+            .collect()
+    }
+
+    fn embed_index_ref_uncached(
+        &self,
+        texts: &[&str],
+        threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
     ) -> std::result::Result<Vec<Embedding>, EmbedError> {
         match self {
             Embedder::HuggingFace(embedder) => embedder.embed_index_ref(texts),
-            Embedder::OpenAi(embedder) => embedder.embed_index_ref(texts, threads),
-            Embedder::Ollama(embedder) => embedder.embed_index_ref(texts, threads),
+            Embedder::OpenAi(embedder) => embedder.embed_index_ref(texts, threads, deadline),
+            Embedder::Ollama(embedder) => embedder.embed_index_ref(texts, threads, deadline),
             Embedder::UserProvided(embedder) => embedder.embed_index_ref(texts),
-            Embedder::Rest(embedder) => embedder.embed_index_ref(texts, threads),
-            Embedder::Composite(embedder) => embedder.index.embed_index_ref(texts, threads),
+            Embedder::Rest(embedder) => embedder.embed_index_ref(texts, threads, deadline),
+            Embedder::Composite(embedder) => {
+                embedder.index.embed_index_ref(texts, threads, deadline)
+            }
+            Embedder::Sparse(_) => Err(EmbedError::dense_embed_on_sparse_embedder(
+                texts.first().map(|text| text.to_string()).unwrap_or_default(),
+            )),
         }
     }
 
@@ -783,6 +1304,21 @@ impl Embedder {
             Embedder::UserProvided(_) => 100,
             Embedder::Rest(embedder) => embedder.chunk_count_hint(),
             Embedder::Composite(embedder) => embedder.index.chunk_count_hint(),
+            Embedder::Sparse(_) => REQUEST_PARALLELISM,
+        }
+    }
+
+    /// Counters of the requests sent to the embedder that were delayed by its `requestPolicy`, or by the server.
+    ///
+    /// Returns `None` for embedders that do not send requests.
+    pub fn request_stats(&self) -> Option<RequestStats> {
+        match self {
+            Embedder::HuggingFace(_) | Embedder::UserProvided(_) => None,
+            Embedder::OpenAi(embedder) => Some(embedder.request_stats()),
+            Embedder::Ollama(embedder) => Some(embedder.request_stats()),
+            Embedder::Rest(embedder) => Some(embedder.request_stats()),
+            Embedder::Composite(embedder) => embedder.index.request_stats(),
+            Embedder::Sparse(embedder) => embedder.request_stats(),
         }
     }
 
@@ -795,10 +1331,13 @@ impl Embedder {
             Embedder::UserProvided(_) => 1,
             Embedder::Rest(embedder) => embedder.prompt_count_in_chunk_hint(),
             Embedder::Composite(embedder) => embedder.index.prompt_count_in_chunk_hint(),
+            Embedder::Sparse(embedder) => embedder.prompt_count_in_chunk_hint(),
         }
     }
 
     /// Indicates the dimensions of a single embedding produced by the embedder.
+    ///
+    /// Sparse embeddings have no fixed dimensions, so `0` is returned for sparse embedders.
     pub fn dimensions(&self) -> usize {
         match self {
             Embedder::HuggingFace(embedder) => embedder.dimensions(),
@@ -807,6 +1346,7 @@ impl Embedder {
             Embedder::UserProvided(embedder) => embedder.dimensions(),
             Embedder::Rest(embedder) => embedder.dimensions(),
             Embedder::Composite(embedder) => embedder.dimensions(),
+            Embedder::Sparse(_) => 0,
         }
     }
 
@@ -819,6 +1359,7 @@ impl Embedder {
             Embedder::UserProvided(embedder) => embedder.distribution(),
             Embedder::Rest(embedder) => embedder.distribution(),
             Embedder::Composite(embedder) => embedder.distribution(),
+            Embedder::Sparse(embedder) => embedder.distribution(),
         }
     }
 
@@ -830,6 +1371,7 @@ impl Embedder {
             | Embedder::Rest(_) => true,
             Embedder::UserProvided(_) => false,
             Embedder::Composite(embedder) => embedder.index.uses_document_template(),
+            Embedder::Sparse(embedder) => embedder.can_generate(),
         }
     }
 
@@ -841,8 +1383,28 @@ impl Embedder {
             Embedder::Ollama(embedder) => Some(embedder.cache()),
             Embedder::Rest(embedder) => Some(embedder.cache()),
             Embedder::Composite(embedder) => embedder.search.cache(),
+            Embedder::Sparse(_) => None,
         }
     }
+
+    /// The persistent cache of the embeddings of documents, if any.
+    fn index_persistent_cache(&self) -> Option<&PersistentCacheScope> {
+        let cache = match self {
+            Embedder::Composite(embedder) => embedder.index.cache(),
+            embedder => embedder.cache(),
+        };
+        cache?.persistent()
+    }
+}
+
+/// Fills the missing embeddings with the newly generated ones, in order.
+fn fill_missing<'a>(
+    embeddings: impl Iterator<Item = &'a mut Option<Embedding>>,
+    mut missing_embeddings: impl Iterator<Item = Embedding>,
+) {
+    for embedding in embeddings.filter(|embedding| embedding.is_none()) {
+        *embedding = missing_embeddings.next();
+    }
 }
 
 /// Describes the mean and sigma of distribution of embedding similarity in the embedding space.
@@ -4,6 +4,7 @@ use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use rayon::slice::ParallelSlice as _;
 
 use super::error::{EmbedError, EmbedErrorKind, NewEmbedderError, NewEmbedderErrorKind};
+use super::request_policy::{RequestPolicy, RequestStats};
 use super::rest::{Embedder as RestEmbedder, EmbedderOptions as RestEmbedderOptions};
 use super::{DistributionShift, EmbeddingCache, REQUEST_PARALLELISM};
 use crate::error::FaultSource;
@@ -22,6 +23,8 @@ pub struct EmbedderOptions {
     pub api_key: Option<String>,
     pub distribution: Option<DistributionShift>,
     pub dimensions: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_policy: Option<RequestPolicy>,
 }
 
 impl EmbedderOptions {
@@ -36,6 +39,7 @@ impl EmbedderOptions {
             url,
             distribution: None,
             dimensions,
+            request_policy: None,
         }
     }
 
@@ -70,6 +74,7 @@ impl EmbedderOptions {
             request,
             response,
             headers: Default::default(),
+            request_policy: self.request_policy,
         })
     }
 }
@@ -139,13 +144,14 @@ impl Embedder {
         &self,
         texts: &[&str],
         threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
     ) -> Result<Vec<Vec<f32>>, EmbedError> {
         // This condition helps reduce the number of active rayon jobs
         // so that we avoid consuming all the LMDB rtxns and avoid stack overflows.
         if threads.active_operations() >= REQUEST_PARALLELISM {
             let embeddings: Result<Vec<Vec<Embedding>>, _> = texts
                 .chunks(self.prompt_count_in_chunk_hint())
-                .map(move |chunk| self.embed(chunk, None))
+                .map(move |chunk| self.embed(chunk, deadline))
                 .collect();
 
             let embeddings = embeddings?;
@@ -155,7 +161,7 @@ impl Embedder {
                 .install(move || {
                     let embeddings: Result<Vec<Vec<Embedding>>, _> = texts
                         .par_chunks(self.prompt_count_in_chunk_hint())
-                        .map(move |chunk| self.embed(chunk, None))
+                        .map(move |chunk| self.embed(chunk, deadline))
                         .collect();
 
                     let embeddings = embeddings?;
@@ -176,6 +182,10 @@ impl Embedder {
         self.rest_embedder.prompt_count_in_chunk_hint()
     }
 
+    pub fn request_stats(&self) -> RequestStats {
+        self.rest_embedder.request_stats()
+    }
+
     pub fn dimensions(&self) -> usize {
         self.rest_embedder.dimensions()
     }
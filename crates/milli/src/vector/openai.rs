@@ -6,6 +6,7 @@ use rayon::iter::{IntoParallelIterator, ParallelIterator as _};
 use rayon::slice::ParallelSlice as _;
 
 use super::error::{EmbedError, NewEmbedderError};
+use super::request_policy::{RequestPolicy, RequestStats};
 use super::rest::{Embedder as RestEmbedder, EmbedderOptions as RestEmbedderOptions};
 use super::{DistributionShift, EmbeddingCache, REQUEST_PARALLELISM};
 use crate::error::FaultSource;
@@ -20,6 +21,8 @@ pub struct EmbedderOptions {
     pub embedding_model: EmbeddingModel,
     pub dimensions: Option<usize>,
     pub distribution: Option<DistributionShift>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_policy: Option<RequestPolicy>,
 }
 
 impl EmbedderOptions {
@@ -159,6 +162,7 @@ impl EmbedderOptions {
             dimensions: None,
             distribution: None,
             url: None,
+            request_policy: None,
         }
     }
 }
@@ -200,6 +204,7 @@ impl Embedder {
                     ]
                 }),
                 headers: Default::default(),
+                request_policy: options.request_policy.clone(),
             },
             cache_cap,
             super::rest::ConfigurationSource::OpenAi,
@@ -276,13 +281,14 @@ impl Embedder {
         &self,
         texts: &[&str],
         threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
     ) -> Result<Vec<Vec<f32>>, EmbedError> {
         // This condition helps reduce the number of active rayon jobs
         // so that we avoid consuming all the LMDB rtxns and avoid stack overflows.
         if threads.active_operations() >= REQUEST_PARALLELISM {
             let embeddings: Result<Vec<Vec<Embedding>>, _> = texts
                 .chunks(self.prompt_count_in_chunk_hint())
-                .map(move |chunk| self.embed(chunk, None))
+                .map(move |chunk| self.embed(chunk, deadline))
                 .collect();
             let embeddings = embeddings?;
             Ok(embeddings.into_iter().flatten().collect())
@@ -291,7 +297,7 @@ impl Embedder {
                 .install(move || {
                     let embeddings: Result<Vec<Vec<Embedding>>, _> = texts
                         .par_chunks(self.prompt_count_in_chunk_hint())
-                        .map(move |chunk| self.embed(chunk, None))
+                        .map(move |chunk| self.embed(chunk, deadline))
                         .collect();
 
                     let embeddings = embeddings?;
@@ -312,6 +318,10 @@ impl Embedder {
         self.rest_embedder.prompt_count_in_chunk_hint()
     }
 
+    pub fn request_stats(&self) -> RequestStats {
+        self.rest_embedder.request_stats()
+    }
+
     pub fn dimensions(&self) -> usize {
         self.options.dimensions()
     }
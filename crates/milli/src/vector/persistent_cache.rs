@@ -0,0 +1,334 @@
+//! A persistent, content-addressed cache of embeddings.
+//!
+//! Embeddings are stored in a dedicated LMDB environment under the SHA-256 of the configuration of the embedder
+//! that generated them followed by the embedded text, so that they survive restarts and can be shared by all the
+//! indexes using the same embedder configuration, both when indexing documents and when embedding search queries.
+//!
+//! The cache is bounded in size: when the embeddings it contains exceed its maximum size, the oldest entries are
+//! evicted first.
+//!
+//! The embeddings of search queries are written by a background thread, in batches, so that searches never wait
+//! for a write transaction.
+
+use std::path::Path;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use heed::types::{Bytes, Str};
+use heed::{Database, Env, EnvOpenOptions, RwTxn, WithoutTls};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::Embedding;
+use crate::BEU64;
+
+const EMBEDDINGS_DB_NAME: &str = "embeddings";
+const INSERTION_ORDER_DB_NAME: &str = "insertion-order";
+const MAIN_DB_NAME: &str = "main";
+
+const TOTAL_SIZE_KEY: &str = "total-size";
+
+type Key = [u8; 32];
+
+/// Size of the sequence number stored before each embedding.
+const SEQUENCE_SIZE: usize = std::mem::size_of::<u64>();
+
+/// When the cache is full, entries are evicted until it is back to this fraction of its maximum size,
+/// so that evictions happen in batches rather than on every insertion.
+const EVICTION_TARGET_RATIO: f64 = 0.75;
+
+/// Maximum number of deferred embeddings waiting to be written, further embeddings are not cached.
+const DEFERRED_QUEUE_CAPACITY: usize = 1024;
+
+/// Maximum number of deferred embeddings written in a single write transaction.
+const DEFERRED_BATCH_SIZE: usize = 256;
+
+pub struct PersistentEmbeddingCache {
+    store: Arc<Store>,
+    /// Sends the deferred embeddings to the writer thread, `None` once the cache is dropped.
+    deferred: Option<SyncSender<(Key, Embedding)>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+struct Store {
+    env: Env<WithoutTls>,
+    /// Maps the key of a text to the sequence number of its insertion, followed by its embedding.
+    embeddings: Database<Bytes, Bytes>,
+    /// Maps the sequence number of an insertion to the key of the inserted text.
+    insertion_order: Database<BEU64, Bytes>,
+    /// Stores the total size of the entries.
+    main: Database<Str, BEU64>,
+    max_size: u64,
+}
+
+impl PersistentEmbeddingCache {
+    /// Opens or creates the cache in the given folder, with the given maximum size in bytes.
+    pub fn new(path: &Path, max_size: usize) -> heed::Result<Self> {
+        std::fs::create_dir_all(path).map_err(heed::Error::Io)?;
+
+        // leave room for the overhead of LMDB pages over the size of the entries
+        let map_size = max_size.saturating_mul(2).max(1).next_multiple_of(1024 * 1024);
+
+        let options = EnvOpenOptions::new();
+        let mut options = options.read_txn_without_tls();
+        options.map_size(map_size);
+        options.max_dbs(3);
+        let env = unsafe { options.open(path) }?;
+
+        let mut wtxn = env.write_txn()?;
+        let embeddings = env.create_database(&mut wtxn, Some(EMBEDDINGS_DB_NAME))?;
+        let insertion_order = env.create_database(&mut wtxn, Some(INSERTION_ORDER_DB_NAME))?;
+        let main = env.create_database(&mut wtxn, Some(MAIN_DB_NAME))?;
+        wtxn.commit()?;
+
+        let store =
+            Arc::new(Store { env, embeddings, insertion_order, main, max_size: max_size as u64 });
+        let (sender, receiver) = sync_channel(DEFERRED_QUEUE_CAPACITY);
+        let writer = std::thread::Builder::new()
+            .name("embedding-cache-writer".to_string())
+            .spawn({
+                let store = store.clone();
+                move || store.write_deferred(receiver)
+            })
+            .map_err(heed::Error::Io)?;
+
+        Ok(Self { store, deferred: Some(sender), writer: Some(writer) })
+    }
+
+    /// Returns a view of the cache restricted to the embeddings generated with the given configuration.
+    pub fn scope(self: &Arc<Self>, config: &impl Serialize) -> PersistentCacheScope {
+        let config = serde_json::to_vec(config).unwrap();
+        PersistentCacheScope { cache: self.clone(), config_hash: Sha256::digest(config).into() }
+    }
+
+    /// Queues an embedding to be written by the writer thread, dropping it if the queue is full.
+    fn put_deferred(&self, key: Key, embedding: Embedding) {
+        let Some(sender) = &self.deferred else { return };
+        match sender.try_send((key, embedding)) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => {
+                tracing::debug!("embedding cache write queue is full, the embedding is not cached")
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                tracing::warn!("embedding cache writer has stopped, the embedding is not cached")
+            }
+        }
+    }
+}
+
+impl Drop for PersistentEmbeddingCache {
+    fn drop(&mut self) {
+        // closing the channel stops the writer thread once it wrote the remaining embeddings
+        drop(self.deferred.take());
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                tracing::error!("embedding cache writer panicked");
+            }
+        }
+    }
+}
+
+impl Store {
+    /// Writes the deferred embeddings in batches, until the channel is closed.
+    fn write_deferred(&self, receiver: Receiver<(Key, Embedding)>) {
+        while let Ok(entry) = receiver.recv() {
+            let mut batch = vec![entry];
+            batch.extend(receiver.try_iter().take(DEFERRED_BATCH_SIZE - 1));
+            let entries = batch.iter().map(|(key, embedding)| (*key, embedding));
+            if let Err(error) = self.put_many(entries) {
+                tracing::warn!(%error, "could not write to the embedding cache");
+            }
+        }
+    }
+
+    fn get_many(&self, keys: &[Key]) -> heed::Result<Vec<Option<Embedding>>> {
+        let rtxn = self.env.read_txn()?;
+        keys.iter()
+            .map(|key| {
+                Ok(self
+                    .embeddings
+                    .get(&rtxn, key)?
+                    .map(|bytes| bytemuck::pod_collect_to_vec(&bytes[SEQUENCE_SIZE..])))
+            })
+            .collect()
+    }
+
+    fn put_many<'a>(
+        &self,
+        entries: impl IntoIterator<Item = (Key, &'a Embedding)>,
+    ) -> heed::Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        let mut total_size = self.main.get(&wtxn, TOTAL_SIZE_KEY)?.unwrap_or_default();
+        let mut sequence = match self.insertion_order.last(&wtxn)? {
+            Some((sequence, _)) => sequence + 1,
+            None => 0,
+        };
+
+        for (key, embedding) in entries {
+            total_size = total_size.saturating_sub(self.delete(&mut wtxn, &key)?);
+
+            let mut value = Vec::with_capacity(SEQUENCE_SIZE + std::mem::size_of_val(&**embedding));
+            value.extend_from_slice(&sequence.to_be_bytes());
+            value.extend_from_slice(bytemuck::cast_slice(embedding));
+            self.embeddings.put(&mut wtxn, &key, &value)?;
+            self.insertion_order.put(&mut wtxn, &sequence, &key)?;
+            total_size += entry_size(value.len());
+            sequence += 1;
+        }
+
+        if total_size > self.max_size {
+            let target = (self.max_size as f64 * EVICTION_TARGET_RATIO) as u64;
+            while total_size > target {
+                let Some((_, key)) = self.insertion_order.first(&wtxn)? else { break };
+                let key: Key = key.try_into().unwrap();
+                total_size = total_size.saturating_sub(self.delete(&mut wtxn, &key)?);
+            }
+        }
+
+        self.main.put(&mut wtxn, TOTAL_SIZE_KEY, &total_size)?;
+        wtxn.commit()
+    }
+
+    /// Deletes the entry of the given key if any, returning its size.
+    fn delete(&self, wtxn: &mut RwTxn, key: &Key) -> heed::Result<u64> {
+        let Some(value) = self.embeddings.get(wtxn, key)? else { return Ok(0) };
+        let size = entry_size(value.len());
+        let sequence = u64::from_be_bytes(value[..SEQUENCE_SIZE].try_into().unwrap());
+        self.insertion_order.delete(wtxn, &sequence)?;
+        self.embeddings.delete(wtxn, key)?;
+        Ok(size)
+    }
+}
+
+/// The size of an entry in both databases, given the size of its value in the embeddings database.
+///
+/// The key is stored in both databases, the sequence number is the key of the insertion order database.
+fn entry_size(value_size: usize) -> u64 {
+    (2 * std::mem::size_of::<Key>() + SEQUENCE_SIZE + value_size) as u64
+}
+
+/// The part of a [`PersistentEmbeddingCache`] storing the embeddings of a single embedder configuration.
+#[derive(Clone)]
+pub struct PersistentCacheScope {
+    cache: Arc<PersistentEmbeddingCache>,
+    config_hash: Key,
+}
+
+impl PersistentCacheScope {
+    fn key(&self, text: &str) -> Key {
+        let mut hasher = Sha256::new();
+        hasher.update(self.config_hash);
+        hasher.update(text.as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Returns the cached embedding of each text, if any.
+    ///
+    /// Errors of the cache are logged and treated as cache misses.
+    pub fn get_many(&self, texts: &[&str]) -> Vec<Option<Embedding>> {
+        let keys: Vec<_> = texts.iter().map(|text| self.key(text)).collect();
+        self.cache.store.get_many(&keys).unwrap_or_else(|error| {
+            tracing::warn!(%error, "could not read from the embedding cache");
+            vec![None; texts.len()]
+        })
+    }
+
+    /// Stores the embedding of each text, evicting the oldest entries if the cache becomes too large.
+    ///
+    /// Errors of the cache are logged and otherwise ignored.
+    pub fn put_many<'a>(&self, entries: impl IntoIterator<Item = (&'a str, &'a Embedding)>) {
+        let entries = entries.into_iter().map(|(text, embedding)| (self.key(text), embedding));
+        if let Err(error) = self.cache.store.put_many(entries) {
+            tracing::warn!(%error, "could not write to the embedding cache");
+        }
+    }
+
+    /// Stores the embedding of a text from a background thread, batching it with the other deferred embeddings.
+    ///
+    /// Used on the search path, where waiting for a write transaction would delay the search.
+    /// The embedding may not be stored if too many embeddings are already waiting to be written.
+    pub fn put_deferred(&self, text: &str, embedding: Embedding) {
+        self.cache.put_deferred(self.key(text), embedding);
+    }
+}
+
+impl std::fmt::Debug for PersistentCacheScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistentCacheScope").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeddings_are_persisted_per_configuration() {
+        let dir = tempfile::tempdir().unwrap();
+        let kefir = vec![0.0, 1.0, 2.0];
+        let intel = vec![3.0, 4.0, 5.0];
+
+        {
+            let cache = Arc::new(PersistentEmbeddingCache::new(dir.path(), 1024 * 1024).unwrap());
+            let scope = cache.scope(&"rest");
+            scope.put_many([("kefir", &kefir), ("intel", &intel)]);
+            assert_eq!(
+                scope.get_many(&["kefir", "echo", "intel"]),
+                vec![Some(kefir.clone()), None, Some(intel.clone())]
+            );
+
+            // another configuration doesn't see the embeddings
+            assert_eq!(cache.scope(&"openai").get_many(&["kefir"]), vec![None]);
+        }
+
+        // the embeddings survive reopening the cache
+        let cache = Arc::new(PersistentEmbeddingCache::new(dir.path(), 1024 * 1024).unwrap());
+        assert_eq!(cache.scope(&"rest").get_many(&["kefir"]), vec![Some(kefir)]);
+    }
+
+    #[test]
+    fn deferred_embeddings_are_written_by_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let texts: Vec<String> = (0..300).map(|i| format!("text {i}")).collect();
+
+        {
+            let cache = Arc::new(PersistentEmbeddingCache::new(dir.path(), 1024 * 1024).unwrap());
+            let scope = cache.scope(&"rest");
+            for (i, text) in texts.iter().enumerate() {
+                scope.put_deferred(text, vec![i as f32; 3]);
+            }
+            // dropping the cache waits for the deferred embeddings to be written
+        }
+
+        let cache = Arc::new(PersistentEmbeddingCache::new(dir.path(), 1024 * 1024).unwrap());
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let cached = cache.scope(&"rest").get_many(&texts);
+        assert_eq!(cached[0], Some(vec![0.0; 3]));
+        assert_eq!(cached[299], Some(vec![299.0; 3]));
+        assert!(cached.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn oldest_embeddings_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedding = vec![0.5; 256];
+        let size = entry_size(SEQUENCE_SIZE + 256 * std::mem::size_of::<f32>());
+        // room for 10 embeddings
+        let cache =
+            Arc::new(PersistentEmbeddingCache::new(dir.path(), 10 * size as usize).unwrap());
+        let scope = cache.scope(&"rest");
+
+        let texts: Vec<String> = (0..11).map(|i| format!("text {i}")).collect();
+        for text in &texts[..10] {
+            scope.put_many([(text.as_str(), &embedding)]);
+        }
+        assert!(scope.get_many(&["text 0"])[0].is_some());
+
+        // the 11th embedding evicts the oldest ones, down to 3/4 of the maximum size
+        scope.put_many([(texts[10].as_str(), &embedding)]);
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let cached: Vec<bool> = scope.get_many(&texts).iter().map(Option::is_some).collect();
+        assert_eq!(cached, [false, false, false, false, true, true, true, true, true, true, true]);
+    }
+}
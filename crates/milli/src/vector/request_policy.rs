@@ -0,0 +1,402 @@
+//! Limits on the requests sent to remote embedders.
+//!
+//! The [`RequestPolicy`] of an embedder bounds the number of requests in flight, the rate at which requests and
+//! tokens are sent and how failed requests are retried. It is enforced by a [`Throttle`] shared by all the
+//! threads sending requests with the same embedder.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::error::EmbedError;
+use crate::progress::Progress;
+
+/// Number of attempts after which a failing request is given up, if not overridden by [`RequestPolicy::max_retries`].
+pub const DEFAULT_MAX_RETRIES: u32 = 10;
+
+/// Maximum time waited before retrying a request, if not overridden by [`RequestPolicy::max_retry_delay_ms`].
+pub const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Describes how requests are sent to a remote embedder.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Deserr, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct RequestPolicy {
+    /// Maximum number of requests sent to the embedder at the same time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// Maximum number of texts sent in a single request, for embedders accepting several texts per request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub batch_size: Option<usize>,
+    /// Maximum number of requests sent per minute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub requests_per_minute: Option<u64>,
+    /// Maximum number of tokens sent per minute, estimated as one token every 4 bytes of request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub tokens_per_minute: Option<u64>,
+    /// Maximum number of times a failed request is retried.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub max_retries: Option<u32>,
+    /// Maximum time waited before retrying a failed request, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[deserr(default)]
+    pub max_retry_delay_ms: Option<u64>,
+}
+
+impl RequestPolicy {
+    /// Returns a description of the problem if the policy would prevent any request from being sent.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let limits = [
+            ("maxConcurrentRequests", self.max_concurrent_requests.map(|n| n as u64)),
+            ("batchSize", self.batch_size.map(|n| n as u64)),
+            ("requestsPerMinute", self.requests_per_minute),
+            ("tokensPerMinute", self.tokens_per_minute),
+        ];
+        for (name, limit) in limits {
+            if limit == Some(0) {
+                return Err(format!("`{name}` must be greater than 0"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(DEFAULT_MAX_RETRIES)
+    }
+
+    pub fn max_retry_delay(&self) -> Duration {
+        self.max_retry_delay_ms.map_or(DEFAULT_MAX_RETRY_DELAY, Duration::from_millis)
+    }
+}
+
+/// Counters of the requests delayed to respect the policy of an embedder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    /// Requests that waited before being sent.
+    pub throttled_requests: u64,
+    /// Total time spent waiting by the throttled requests.
+    pub throttled_time: Duration,
+    /// Requests sent again after a failure.
+    pub retries: u64,
+    /// Responses indicating that the embedder rate limited the requests.
+    pub rate_limited_responses: u64,
+}
+
+impl RequestStats {
+    /// The requests counted since `earlier` was taken.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            throttled_requests: self.throttled_requests.saturating_sub(earlier.throttled_requests),
+            throttled_time: self.throttled_time.saturating_sub(earlier.throttled_time),
+            retries: self.retries.saturating_sub(earlier.retries),
+            rate_limited_responses: self
+                .rate_limited_responses
+                .saturating_sub(earlier.rate_limited_responses),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reports the counters in the trace of the progress, unless they are all zero.
+    pub fn record(&self, embedder_name: &str, progress: &Progress) {
+        if self.is_empty() {
+            return;
+        }
+        let Self { throttled_requests, throttled_time, retries, rate_limited_responses } = self;
+        let prefix = format!("embedder `{embedder_name}` requests");
+        progress.record_metric(format!("{prefix} > throttled"), throttled_requests.to_string());
+        progress
+            .record_metric(format!("{prefix} > throttled time"), format!("{throttled_time:.2?}"));
+        progress.record_metric(format!("{prefix} > retries"), retries.to_string());
+        progress.record_metric(
+            format!("{prefix} > rate limited responses"),
+            rate_limited_responses.to_string(),
+        );
+    }
+}
+
+/// Enforces a [`RequestPolicy`] across the threads sending requests.
+#[derive(Debug)]
+pub(super) struct Throttle {
+    policy: RequestPolicy,
+    in_flight: Option<Semaphore>,
+    requests: Option<TokenBucket>,
+    tokens: Option<TokenBucket>,
+    /// Set when the embedder asks to wait before sending more requests.
+    paused_until: Mutex<Option<Instant>>,
+    throttled_requests: AtomicU64,
+    throttled_nanos: AtomicU64,
+    retries: AtomicU64,
+    rate_limited_responses: AtomicU64,
+}
+
+impl Throttle {
+    pub fn new(policy: RequestPolicy) -> Self {
+        Self {
+            in_flight: policy.max_concurrent_requests.map(Semaphore::new),
+            requests: policy.requests_per_minute.map(TokenBucket::new),
+            tokens: policy.tokens_per_minute.map(TokenBucket::new),
+            policy,
+            paused_until: Mutex::new(None),
+            throttled_requests: AtomicU64::new(0),
+            throttled_nanos: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            rate_limited_responses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn policy(&self) -> &RequestPolicy {
+        &self.policy
+    }
+
+    /// Waits until a request of `request_size` bytes can be sent, and returns a permit to hold while it is in flight.
+    ///
+    /// Fails without waiting if the request cannot be sent before the deadline, or once the deadline is reached
+    /// while waiting for another request to complete. The reserved units are then given back.
+    pub fn acquire(
+        &self,
+        request_size: usize,
+        deadline: Option<Instant>,
+    ) -> Result<Permit<'_>, EmbedError> {
+        let started_at = Instant::now();
+        let request_tokens = request_size.div_ceil(4) as f64;
+
+        let paused_until = *self.paused_until.lock().unwrap();
+        let mut wait = paused_until
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(started_at));
+        if let Some(requests) = &self.requests {
+            wait = wait.max(requests.reserve(1.0, started_at));
+        }
+        if let Some(tokens) = &self.tokens {
+            wait = wait.max(tokens.reserve(request_tokens, started_at));
+        }
+
+        if let Some(deadline) = deadline {
+            if started_at + wait > deadline {
+                self.cancel_reservation(request_tokens);
+                return Err(EmbedError::rest_throttled(wait));
+            }
+        }
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+
+        let waited_for_permit = match &self.in_flight {
+            Some(in_flight) => match in_flight.acquire(deadline) {
+                Some(waited) => waited,
+                None => {
+                    self.cancel_reservation(request_tokens);
+                    return Err(EmbedError::rest_throttled(started_at.elapsed()));
+                }
+            },
+            None => false,
+        };
+        let permit = Permit { semaphore: self.in_flight.as_ref() };
+
+        if !wait.is_zero() || waited_for_permit {
+            self.throttled_requests.fetch_add(1, Ordering::Relaxed);
+            self.throttled_nanos
+                .fetch_add(started_at.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        }
+        Ok(permit)
+    }
+
+    /// Gives back the units reserved for a request that will not be sent.
+    fn cancel_reservation(&self, request_tokens: f64) {
+        if let Some(requests) = &self.requests {
+            requests.cancel(1.0);
+        }
+        if let Some(tokens) = &self.tokens {
+            tokens.cancel(request_tokens);
+        }
+    }
+
+    /// Delays all the requests to the embedder until `duration` elapsed.
+    pub fn pause_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut paused_until = self.paused_until.lock().unwrap();
+        if !paused_until.is_some_and(|paused_until| paused_until >= until) {
+            *paused_until = Some(until);
+        }
+    }
+
+    pub fn record_retry(&self, rate_limited: bool) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+        if rate_limited {
+            self.rate_limited_responses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn stats(&self) -> RequestStats {
+        RequestStats {
+            throttled_requests: self.throttled_requests.load(Ordering::Relaxed),
+            throttled_time: Duration::from_nanos(self.throttled_nanos.load(Ordering::Relaxed)),
+            retries: self.retries.load(Ordering::Relaxed),
+            rate_limited_responses: self.rate_limited_responses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Held while a request is in flight.
+pub(super) struct Permit<'a> {
+    semaphore: Option<&'a Semaphore>,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if let Some(semaphore) = self.semaphore {
+            semaphore.release();
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Semaphore {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Self { available: Mutex::new(permits), released: Condvar::new() }
+    }
+
+    /// Takes a permit, returning whether it had to wait for one, or `None` if none was released before the deadline.
+    fn acquire(&self, deadline: Option<Instant>) -> Option<bool> {
+        let mut available = self.available.lock().unwrap();
+        let waited = *available == 0;
+        while *available == 0 {
+            available = match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    if timeout.is_zero() {
+                        return None;
+                    }
+                    self.released.wait_timeout(available, timeout).unwrap().0
+                }
+                None => self.released.wait(available).unwrap(),
+            };
+        }
+        *available -= 1;
+        Some(waited)
+    }
+
+    fn release(&self) {
+        *self.available.lock().unwrap() += 1;
+        self.released.notify_one();
+    }
+}
+
+/// A bucket refilled continuously with `per_minute` units per minute, up to `per_minute` units.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    /// Units available, negative when units are reserved ahead of their refill, and the time they were computed.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u64) -> Self {
+        let capacity = per_minute as f64;
+        Self {
+            capacity,
+            per_second: capacity / 60.0,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Takes `amount` units from the bucket, returning how long to wait until they are refilled.
+    fn reserve(&self, amount: f64, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let (available, last_refill) = &mut *state;
+        let elapsed = now.saturating_duration_since(*last_refill).as_secs_f64();
+        *available = (*available + elapsed * self.per_second).min(self.capacity);
+        *last_refill = now.max(*last_refill);
+
+        // a request larger than the bucket can still be sent once the bucket is full
+        *available -= amount.min(self.capacity);
+        if *available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-*available / self.per_second)
+        }
+    }
+
+    /// Gives back `amount` units taken by [`Self::reserve`].
+    fn cancel(&self, amount: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.0 = (state.0 + amount.min(self.capacity)).min(self.capacity);
+    }
+}
+
+/// Parses the value of a `Retry-After` header given in seconds.
+pub(super) fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_delays_requests_over_the_rate() {
+        let now = Instant::now();
+        let bucket = TokenBucket::new(60);
+        bucket.state.lock().unwrap().1 = now;
+
+        for _ in 0..60 {
+            assert_eq!(bucket.reserve(1.0, now), Duration::ZERO);
+        }
+        // one unit is refilled every second
+        assert_eq!(bucket.reserve(1.0, now), Duration::from_secs(1));
+        assert_eq!(bucket.reserve(1.0, now), Duration::from_secs(2));
+        assert_eq!(bucket.reserve(1.0, now + Duration::from_secs(3)), Duration::ZERO);
+        // larger requests wait for a full bucket at most
+        assert_eq!(bucket.reserve(1000.0, now + Duration::from_secs(3)), Duration::from_secs(60));
+        // cancelled reservations are given back
+        bucket.cancel(1000.0);
+        assert_eq!(bucket.reserve(1.0, now + Duration::from_secs(3)), Duration::ZERO);
+    }
+
+    #[test]
+    fn throttle_gives_up_at_the_deadline() {
+        let throttle = Throttle::new(RequestPolicy {
+            max_concurrent_requests: Some(1),
+            requests_per_minute: Some(60),
+            ..Default::default()
+        });
+        let permit = throttle.acquire(0, None).unwrap();
+        // waiting for the request in flight is bounded by the deadline
+        let deadline = Instant::now() + Duration::from_millis(50);
+        throttle.acquire(0, Some(deadline)).unwrap_err();
+        assert!(Instant::now() >= deadline);
+        drop(permit);
+
+        // the requests that gave up didn't consume the rate limit
+        let permits = throttle.requests.as_ref().unwrap().state.lock().unwrap().0;
+        assert!(permits >= 59.0, "{permits}");
+        throttle.acquire(0, Some(Instant::now() + Duration::from_secs(1))).unwrap();
+    }
+
+    #[test]
+    fn invalid_policies() {
+        let policy = RequestPolicy { batch_size: Some(0), ..Default::default() };
+        assert_eq!(policy.validate().unwrap_err(), "`batchSize` must be greater than 0");
+        let policy = RequestPolicy { max_retries: Some(0), ..Default::default() };
+        policy.validate().unwrap();
+        assert_eq!(parse_retry_after(" 12 "), Some(Duration::from_secs(12)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Second-stage reranking of search results with a cross-encoder served over HTTP.
+//!
+//! The reranker follows the request and response format shared by most hosted rerank APIs
+//! (Cohere, Jina, Voyage, Hugging Face text-embeddings-inference):
+//! it sends the query along with the text of each document, and receives one relevance score
+//! per document.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// The reranker runs at search time, we don't want to hang a search request for too long on it.
+const RERANK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RerankerOptions {
+    pub url: String,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Reranker {
+    client: ureq::Agent,
+    bearer: Option<String>,
+    url: String,
+    model: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RerankError {
+    #[error("could not reach reranking server:\n  - {0}")]
+    Network(Box<ureq::Transport>),
+    #[error("received unexpected HTTP {0} from reranking server{}", .1.as_deref().map(|reply| format!(", server replied with `{reply}`")).unwrap_or_default())]
+    Status(u16, Option<String>),
+    #[error("error deserializing the response body of the reranking server:\n  - {0}")]
+    Deserialization(std::io::Error),
+    #[error("the reranking server returned a score for document #{index}, but only {count} documents were sent")]
+    UnknownDocument { index: usize, count: usize },
+    #[error("the reranking server returned {got} scores for {expected} documents")]
+    MissingScores { expected: usize, got: usize },
+}
+
+#[derive(Serialize)]
+struct RerankRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    query: &'a str,
+    documents: &'a [String],
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RerankResponse {
+    /// Cohere and Jina return the scores under `results`, Voyage under `data`.
+    Wrapped {
+        #[serde(alias = "data")]
+        results: Vec<RerankResult>,
+    },
+    /// text-embeddings-inference returns the scores directly.
+    List(Vec<RerankResult>),
+}
+
+#[derive(Deserialize)]
+struct RerankResult {
+    index: usize,
+    #[serde(alias = "score")]
+    relevance_score: f32,
+}
+
+impl Reranker {
+    pub fn new(options: RerankerOptions) -> Self {
+        let client = ureq::AgentBuilder::new().timeout(RERANK_TIMEOUT).build();
+        let bearer = options.api_key.as_deref().map(|api_key| format!("Bearer {api_key}"));
+        Self { client, bearer, url: options.url, model: options.model }
+    }
+
+    /// Scores each document against the query.
+    ///
+    /// The returned scores are in the same order as the documents.
+    pub fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>, RerankError> {
+        let request = self.client.post(&self.url).set("Content-Type", "application/json");
+        let request = match &self.bearer {
+            Some(bearer) => request.set("Authorization", bearer),
+            None => request,
+        };
+
+        let body = RerankRequest { model: self.model.as_deref(), query, documents };
+        let response = match request.send_json(&body) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                return Err(RerankError::Status(code, response.into_string().ok()))
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(RerankError::Network(Box::new(transport)))
+            }
+        };
+
+        let results = match response.into_json().map_err(RerankError::Deserialization)? {
+            RerankResponse::Wrapped { results } => results,
+            RerankResponse::List(results) => results,
+        };
+
+        let mut scores = vec![None; documents.len()];
+        for RerankResult { index, relevance_score } in results {
+            let score = scores
+                .get_mut(index)
+                .ok_or(RerankError::UnknownDocument { index, count: documents.len() })?;
+            *score = Some(relevance_score);
+        }
+
+        let got = scores.iter().flatten().count();
+        scores
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(RerankError::MissingScores { expected: documents.len(), got })
+    }
+}
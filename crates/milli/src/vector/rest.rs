@@ -1,5 +1,5 @@
 use std::collections::BTreeMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use deserr::Deserr;
 use rand::Rng;
@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 
 use super::error::EmbedErrorKind;
 use super::json_template::ValueTemplate;
+use super::request_policy::{parse_retry_after, RequestPolicy, RequestStats, Throttle};
+use super::sparse::SparseVector;
 use super::{
     DistributionShift, EmbedError, Embedding, EmbeddingCache, NewEmbedderError, REQUEST_PARALLELISM,
 };
@@ -19,6 +21,8 @@ use crate::ThreadPoolNoAbort;
 pub struct Retry {
     pub error: EmbedError,
     strategy: RetryStrategy,
+    /// Delay requested by the server through the `Retry-After` header.
+    retry_after: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,32 +41,40 @@ pub enum RetryStrategy {
 
 impl Retry {
     pub fn give_up(error: EmbedError) -> Self {
-        Self { error, strategy: RetryStrategy::GiveUp }
+        Self { error, strategy: RetryStrategy::GiveUp, retry_after: None }
     }
 
     pub fn retry_later(error: EmbedError) -> Self {
-        Self { error, strategy: RetryStrategy::Retry }
+        Self { error, strategy: RetryStrategy::Retry, retry_after: None }
     }
 
     pub fn retry_tokenized(error: EmbedError) -> Self {
-        Self { error, strategy: RetryStrategy::RetryTokenized }
+        Self { error, strategy: RetryStrategy::RetryTokenized, retry_after: None }
     }
 
     pub fn rate_limited(error: EmbedError) -> Self {
-        Self { error, strategy: RetryStrategy::RetryAfterRateLimit }
+        Self { error, strategy: RetryStrategy::RetryAfterRateLimit, retry_after: None }
     }
 
-    pub fn into_duration(self, attempt: u32) -> Result<std::time::Duration, EmbedError> {
+    pub fn with_retry_after(self, retry_after: Option<Duration>) -> Self {
+        Self { retry_after, ..self }
+    }
+
+    pub fn into_duration(self, attempt: u32) -> Result<Duration, EmbedError> {
         match self.strategy {
             RetryStrategy::GiveUp => Err(self.error),
-            RetryStrategy::Retry => Ok(std::time::Duration::from_millis((10u64).pow(attempt))),
-            RetryStrategy::RetryTokenized => Ok(std::time::Duration::from_millis(1)),
+            RetryStrategy::Retry => Ok(Duration::from_millis(10u64.saturating_pow(attempt))),
+            RetryStrategy::RetryTokenized => Ok(Duration::from_millis(1)),
             RetryStrategy::RetryAfterRateLimit => {
-                Ok(std::time::Duration::from_millis(100 + 10u64.pow(attempt)))
+                Ok(Duration::from_millis(100u64.saturating_add(10u64.saturating_pow(attempt))))
             }
         }
     }
 
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self.strategy, RetryStrategy::RetryAfterRateLimit)
+    }
+
     pub fn must_tokenize(&self) -> bool {
         matches!(self.strategy, RetryStrategy::RetryTokenized)
     }
@@ -82,7 +94,7 @@ pub struct Embedder {
 
 /// All data needed to perform requests and parse responses
 #[derive(Debug)]
-struct EmbedderData {
+pub(super) struct EmbedderData {
     client: ureq::Agent,
     bearer: Option<String>,
     headers: BTreeMap<String, String>,
@@ -90,6 +102,7 @@ struct EmbedderData {
     request: Request,
     response: Response,
     configuration_source: ConfigurationSource,
+    throttle: Throttle,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -101,6 +114,8 @@ pub struct EmbedderOptions {
     pub request: serde_json::Value,
     pub response: serde_json::Value,
     pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_policy: Option<RequestPolicy>,
 }
 
 impl std::hash::Hash for EmbedderOptions {
@@ -129,26 +144,15 @@ impl Embedder {
         cache_cap: usize,
         configuration_source: ConfigurationSource,
     ) -> Result<Self, NewEmbedderError> {
-        let bearer = options.api_key.as_deref().map(|api_key| format!("Bearer {api_key}"));
-
-        let client = ureq::AgentBuilder::new()
-            .max_idle_connections(REQUEST_PARALLELISM * 2)
-            .max_idle_connections_per_host(REQUEST_PARALLELISM * 2)
-            .timeout(std::time::Duration::from_secs(30))
-            .build();
-
-        let request = Request::new(options.request)?;
-        let response = Response::new(options.response, &request)?;
-
-        let data = EmbedderData {
-            client,
-            bearer,
-            url: options.url,
-            request,
-            response,
+        let data = EmbedderData::new(
+            options.url,
+            options.api_key.as_deref(),
+            options.request,
+            options.response,
+            options.headers,
+            options.request_policy.unwrap_or_default(),
             configuration_source,
-            headers: options.headers,
-        };
+        )?;
 
         let dimensions = if let Some(dimensions) = options.dimensions {
             dimensions
@@ -218,13 +222,14 @@ impl Embedder {
         &self,
         texts: &[&str],
         threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
     ) -> Result<Vec<Embedding>, EmbedError> {
         // This condition helps reduce the number of active rayon jobs
         // so that we avoid consuming all the LMDB rtxns and avoid stack overflows.
         if threads.active_operations() >= REQUEST_PARALLELISM {
             let embeddings: Result<Vec<Vec<Embedding>>, _> = texts
                 .chunks(self.prompt_count_in_chunk_hint())
-                .map(move |chunk| self.embed_ref(chunk, None))
+                .map(move |chunk| self.embed_ref(chunk, deadline))
                 .collect();
 
             let embeddings = embeddings?;
@@ -234,7 +239,7 @@ impl Embedder {
                 .install(move || {
                     let embeddings: Result<Vec<Vec<Embedding>>, _> = texts
                         .par_chunks(self.prompt_count_in_chunk_hint())
-                        .map(move |chunk| self.embed_ref(chunk, None))
+                        .map(move |chunk| self.embed_ref(chunk, deadline))
                         .collect();
 
                     let embeddings = embeddings?;
@@ -248,14 +253,15 @@ impl Embedder {
     }
 
     pub fn chunk_count_hint(&self) -> usize {
-        super::REQUEST_PARALLELISM
+        self.data.chunk_count_hint()
     }
 
     pub fn prompt_count_in_chunk_hint(&self) -> usize {
-        match self.data.request.input_type() {
-            InputType::Text => 1,
-            InputType::TextArray => 10,
-        }
+        self.data.prompt_count_in_chunk_hint()
+    }
+
+    pub fn request_stats(&self) -> RequestStats {
+        self.data.throttle.stats()
     }
 
     pub fn dimensions(&self) -> usize {
@@ -271,6 +277,56 @@ impl Embedder {
     }
 }
 
+impl EmbedderData {
+    pub(super) fn new(
+        url: String,
+        api_key: Option<&str>,
+        request: serde_json::Value,
+        response: serde_json::Value,
+        headers: BTreeMap<String, String>,
+        request_policy: RequestPolicy,
+        configuration_source: ConfigurationSource,
+    ) -> Result<Self, NewEmbedderError> {
+        let bearer = api_key.map(|api_key| format!("Bearer {api_key}"));
+
+        let client = ureq::AgentBuilder::new()
+            .max_idle_connections(REQUEST_PARALLELISM * 2)
+            .max_idle_connections_per_host(REQUEST_PARALLELISM * 2)
+            .timeout(std::time::Duration::from_secs(30))
+            .build();
+
+        let request = Request::new(request)?;
+        let response = Response::new(response, &request)?;
+
+        Ok(Self {
+            client,
+            bearer,
+            headers,
+            url,
+            request,
+            response,
+            configuration_source,
+            throttle: Throttle::new(request_policy),
+        })
+    }
+
+    pub(super) fn chunk_count_hint(&self) -> usize {
+        let max_concurrent_requests = self.throttle.policy().max_concurrent_requests;
+        max_concurrent_requests.map_or(REQUEST_PARALLELISM, |max| max.min(REQUEST_PARALLELISM))
+    }
+
+    pub(super) fn prompt_count_in_chunk_hint(&self) -> usize {
+        match self.request.input_type() {
+            InputType::Text => 1,
+            InputType::TextArray => self.throttle.policy().batch_size.unwrap_or(10),
+        }
+    }
+
+    pub(super) fn request_stats(&self) -> RequestStats {
+        self.throttle.stats()
+    }
+}
+
 fn infer_dimensions(data: &EmbedderData) -> Result<usize, NewEmbedderError> {
     let v = embed(data, ["test"].as_slice(), 1, None, None)
         .map_err(NewEmbedderError::could_not_determine_dimension)?;
@@ -285,6 +341,37 @@ fn embed<S>(
     expected_dimension: Option<usize>,
     deadline: Option<Instant>,
 ) -> Result<Vec<Embedding>, EmbedError>
+where
+    S: Serialize,
+{
+    send(data, inputs, deadline, |response| {
+        response_to_embedding(response, data, expected_count, expected_dimension)
+    })
+}
+
+/// Embeds `inputs` with an embedding server returning sparse embeddings.
+pub(super) fn embed_sparse<S>(
+    data: &EmbedderData,
+    inputs: &[S],
+    deadline: Option<Instant>,
+) -> Result<Vec<SparseVector>, EmbedError>
+where
+    S: Serialize,
+{
+    send(data, inputs, deadline, |response| {
+        response_to_sparse_embedding(response, data, inputs.len())
+    })
+}
+
+/// Sends `inputs` to the embedding server, retrying on failures, and parses the response with `parse`.
+///
+/// Requests are delayed as needed to respect the [`RequestPolicy`] of the embedder.
+fn send<S, T>(
+    data: &EmbedderData,
+    inputs: &[S],
+    deadline: Option<Instant>,
+    parse: impl Fn(ureq::Response) -> Result<T, Retry>,
+) -> Result<T, EmbedError>
 where
     S: Serialize,
 {
@@ -300,48 +387,66 @@ where
     }
 
     let body = data.request.inject_texts(inputs);
+    let body_size = body.to_string().len();
+
+    let throttle = &data.throttle;
+    let policy = throttle.policy();
+    let max_retry_delay = policy.max_retry_delay();
 
-    for attempt in 0..10 {
+    for attempt in 0..policy.max_retries() {
+        let permit = throttle.acquire(body_size, deadline)?;
         let response = request.clone().send_json(&body);
-        let result = check_response(response, data.configuration_source).and_then(|response| {
-            response_to_embedding(response, data, expected_count, expected_dimension)
-        });
+        drop(permit);
+        let result = check_response(response, data.configuration_source).and_then(&parse);
 
-        let retry_duration = match result {
+        let retry = match result {
             Ok(response) => return Ok(response),
-            Err(retry) => {
-                tracing::warn!("Failed: {}", retry.error);
-                if let Some(deadline) = deadline {
-                    let now = std::time::Instant::now();
-                    if now > deadline {
-                        tracing::warn!("Could not embed due to deadline");
-                        return Err(retry.into_error());
-                    }
-
-                    let duration_to_deadline = deadline - now;
-                    retry.into_duration(attempt).map(|duration| duration.min(duration_to_deadline))
-                } else {
-                    retry.into_duration(attempt)
-                }
+            Err(retry) => retry,
+        };
+        tracing::warn!("Failed: {}", retry.error);
+
+        let rate_limited = retry.is_rate_limited();
+        let retry_after = retry.retry_after.map(|retry_after| retry_after.min(max_retry_delay));
+
+        let retry_duration = if let Some(deadline) = deadline {
+            let now = std::time::Instant::now();
+            if now > deadline {
+                tracing::warn!("Could not embed due to deadline");
+                return Err(retry.into_error());
             }
+
+            let duration_to_deadline = deadline - now;
+            retry.into_duration(attempt).map(|duration| duration.min(duration_to_deadline))
+        } else {
+            retry.into_duration(attempt)
         }?;
 
-        let retry_duration = retry_duration.min(std::time::Duration::from_secs(60)); // don't wait more than a minute
+        throttle.record_retry(rate_limited);
 
-        // randomly up to double the retry duration
-        let retry_duration = retry_duration
-            + rand::thread_rng().gen_range(std::time::Duration::ZERO..retry_duration);
+        let retry_duration = if let Some(retry_after) = retry_after {
+            // the server asks to wait, so all the requests to this embedder wait when acquiring their permit
+            throttle.pause_for(retry_after);
+            Duration::ZERO
+        } else {
+            let retry_duration = retry_duration.min(max_retry_delay); // don't wait more than the configured delay
+
+            // randomly up to double the retry duration
+            retry_duration + rand::thread_rng().gen_range(Duration::ZERO..=retry_duration)
+        };
 
-        tracing::warn!("Attempt #{}, retrying after {}ms.", attempt, retry_duration.as_millis());
+        tracing::warn!(
+            "Attempt #{}, retrying after {}ms.",
+            attempt,
+            retry_after.unwrap_or(retry_duration).as_millis()
+        );
         std::thread::sleep(retry_duration);
     }
 
+    let permit = throttle.acquire(body_size, deadline)?;
     let response = request.send_json(&body);
+    drop(permit);
     let result = check_response(response, data.configuration_source);
-    result.map_err(Retry::into_error).and_then(|response| {
-        response_to_embedding(response, data, expected_count, expected_dimension)
-            .map_err(Retry::into_error)
-    })
+    result.and_then(parse).map_err(Retry::into_error)
 }
 
 fn check_response(
@@ -351,8 +456,9 @@ fn check_response(
     match response {
         Ok(response) => Ok(response),
         Err(ureq::Error::Status(code, response)) => {
+            let retry_after = response.header("Retry-After").and_then(parse_retry_after);
             let error_response: Option<String> = response.into_string().ok();
-            Err(match code {
+            let retry = match code {
                 401 => Retry::give_up(EmbedError::rest_unauthorized(
                     error_response,
                     configuration_source,
@@ -369,7 +475,8 @@ fn check_response(
                     Retry::give_up(EmbedError::rest_other_status_code(code, error_response))
                 }
                 _ => Retry::retry_later(EmbedError::rest_other_status_code(code, error_response)),
-            })
+            };
+            Err(retry.with_retry_after(retry_after))
         }
         Err(ureq::Error::Transport(transport)) => {
             Err(Retry::retry_later(EmbedError::rest_network(transport)))
@@ -411,6 +518,28 @@ fn response_to_embedding(
     Ok(embeddings)
 }
 
+fn response_to_sparse_embedding(
+    response: ureq::Response,
+    data: &EmbedderData,
+    expected_count: usize,
+) -> Result<Vec<SparseVector>, Retry> {
+    let response: serde_json::Value = response
+        .into_json()
+        .map_err(EmbedError::rest_response_deserialization)
+        .map_err(Retry::retry_later)?;
+
+    let embeddings = data.response.extract_sparse_embeddings(response).map_err(Retry::give_up)?;
+
+    if embeddings.len() != expected_count {
+        return Err(Retry::give_up(EmbedError::rest_response_embedding_count(
+            expected_count,
+            embeddings.len(),
+        )));
+    }
+
+    Ok(embeddings)
+}
+
 pub(super) const REQUEST_PLACEHOLDER: &str = "{{text}}";
 pub(super) const RESPONSE_PLACEHOLDER: &str = "{{embedding}}";
 pub(super) const REPEAT_PLACEHOLDER: &str = "{{..}}";
@@ -490,4 +619,18 @@ impl Response {
 
         Ok(embeddings)
     }
+
+    pub fn extract_sparse_embeddings(
+        &self,
+        response: serde_json::Value,
+    ) -> Result<Vec<SparseVector>, EmbedError> {
+        self.template.extract(response).map_err(|error| {
+            let error_message = error.error_message(
+                "response",
+                "{{embedding}}",
+                "an object mapping token ids to weights",
+            );
+            EmbedError::rest_extraction_error(error_message)
+        })
+    }
 }
@@ -0,0 +1,357 @@
+//! Int8 rescoring of the embeddings of dense embedders.
+//!
+//! Embedders with `int8Rescoring` enabled keep a binary quantized arroy index, only used to find candidates,
+//! and store each embedding a second time with one byte per dimension. The candidates returned by arroy are then
+//! rescored by comparing these int8 embeddings to the full-precision query, which recovers most of the recall lost
+//! by the binary quantization while keeping the embeddings about 4 times smaller than their `f32` counterpart.
+//! The full-precision embeddings are not kept, so the rescoring is only as precise as the int8 embeddings.
+//!
+//! Each int8 embedding is stored at the same arroy index as its binary quantized counterpart, under the key
+//! `[arroy index (u16 BE), item id (u32 BE)]`. The key `[embedder index]` alone marks the embedders that have
+//! int8 rescoring enabled.
+
+use heed::types::Bytes;
+use heed::{Database, RoTxn, RwTxn};
+
+/// How many more candidates than requested are fetched from the binary quantized index before rescoring.
+pub const RESCORING_OVERSAMPLING: usize = 4;
+
+/// Size in bytes of the header of an int8 embedding, storing its offset and its step as `f32`.
+const HEADER_SIZE: usize = 2 * std::mem::size_of::<f32>();
+
+/// The int8 embeddings of a single embedder.
+#[derive(Debug, Clone, Copy)]
+pub struct ScalarQuantizedStore {
+    database: Database<Bytes, Bytes>,
+    embedder_index: u8,
+}
+
+impl ScalarQuantizedStore {
+    pub fn new(database: Database<Bytes, Bytes>, embedder_index: u8) -> Self {
+        Self { database, embedder_index }
+    }
+
+    /// Whether the embedder is marked as having int8 rescoring enabled.
+    pub fn is_enabled(&self, rtxn: &RoTxn) -> heed::Result<bool> {
+        Ok(self.database.get(rtxn, &[self.embedder_index])?.is_some())
+    }
+
+    /// Marks the embedder as having int8 rescoring enabled, embeddings written from now on are also stored as int8.
+    pub fn enable(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        self.database.put(wtxn, &[self.embedder_index], &[])
+    }
+
+    /// Removes the int8 embeddings of the embedder along with its mark, to be called when the embedder is removed.
+    pub fn disable(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        let mut iter = self.database.prefix_iter_mut(wtxn, &[self.embedder_index])?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the database while deleting
+            unsafe { iter.del_current()? };
+        }
+        Ok(())
+    }
+
+    /// Returns the dequantized embedding stored at the given arroy index for this item.
+    pub fn get(
+        &self,
+        rtxn: &RoTxn,
+        arroy_index: u16,
+        item_id: arroy::ItemId,
+    ) -> heed::Result<Option<Vec<f32>>> {
+        let key = key(arroy_index, item_id);
+        Ok(self.database.get(rtxn, &key)?.map(dequantize))
+    }
+
+    /// Whether the embedding stored at the given arroy index for this item is the quantized version of `vector`.
+    pub fn contains_vector(
+        &self,
+        rtxn: &RoTxn,
+        arroy_index: u16,
+        item_id: arroy::ItemId,
+        vector: &[f32],
+    ) -> heed::Result<bool> {
+        let key = key(arroy_index, item_id);
+        Ok(self.database.get(rtxn, &key)?.is_some_and(|bytes| bytes == quantize(vector)))
+    }
+
+    pub fn put(
+        &self,
+        wtxn: &mut RwTxn,
+        arroy_index: u16,
+        item_id: arroy::ItemId,
+        vector: &[f32],
+    ) -> heed::Result<()> {
+        let key = key(arroy_index, item_id);
+        self.database.put(wtxn, &key, &quantize(vector))
+    }
+
+    /// Moves the embedding of the item from an arroy index to another one.
+    pub fn move_item(
+        &self,
+        wtxn: &mut RwTxn,
+        from_arroy_index: u16,
+        to_arroy_index: u16,
+        item_id: arroy::ItemId,
+    ) -> heed::Result<()> {
+        let from = key(from_arroy_index, item_id);
+        let Some(bytes) = self.database.get(wtxn, &from)?.map(<[u8]>::to_vec) else {
+            return Ok(());
+        };
+        self.database.delete(wtxn, &from)?;
+        self.database.put(wtxn, &key(to_arroy_index, item_id), &bytes)
+    }
+
+    pub fn del(
+        &self,
+        wtxn: &mut RwTxn,
+        arroy_index: u16,
+        item_id: arroy::ItemId,
+    ) -> heed::Result<bool> {
+        let key = key(arroy_index, item_id);
+        self.database.delete(wtxn, &key)
+    }
+}
+
+/// Removes the int8 embeddings of all the embedders, but keeps their int8 rescoring enabled.
+pub fn clear_all_embeddings(
+    wtxn: &mut RwTxn,
+    database: Database<Bytes, Bytes>,
+) -> heed::Result<()> {
+    let mut iter = database.iter_mut(wtxn)?;
+    while let Some((key, _)) = iter.next().transpose()? {
+        if key.len() == 1 {
+            continue;
+        }
+        // safety: we don't keep references from inside the database while deleting
+        unsafe { iter.del_current()? };
+    }
+    Ok(())
+}
+
+fn key(arroy_index: u16, item_id: arroy::ItemId) -> [u8; 6] {
+    let mut key = [0; 6];
+    key[..2].copy_from_slice(&arroy_index.to_be_bytes());
+    key[2..].copy_from_slice(&item_id.to_be_bytes());
+    key
+}
+
+/// Quantizes each component of the vector to one of 256 evenly spaced values between its minimum and its maximum.
+pub fn quantize(vector: &[f32]) -> Vec<u8> {
+    let min = vector.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = vector.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let (min, step) = if min.is_finite() && max > min {
+        (min, (max - min) / u8::MAX as f32)
+    } else {
+        // empty or constant vector
+        (if min.is_finite() { min } else { 0.0 }, 0.0)
+    };
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + vector.len());
+    bytes.extend_from_slice(&min.to_le_bytes());
+    bytes.extend_from_slice(&step.to_le_bytes());
+    bytes.extend(vector.iter().map(|value| {
+        if step == 0.0 {
+            0
+        } else {
+            ((value - min) / step).round().clamp(0.0, u8::MAX as f32) as u8
+        }
+    }));
+    bytes
+}
+
+pub fn dequantize(bytes: &[u8]) -> Vec<f32> {
+    let (header, codes) = bytes.split_at(HEADER_SIZE);
+    let min = f32::from_le_bytes(header[..4].try_into().unwrap());
+    let step = f32::from_le_bytes(header[4..].try_into().unwrap());
+    codes.iter().map(|&code| min + code as f32 * step).collect()
+}
+
+/// The cosine distance between two vectors, in the same `[0, 1]` range as the one returned by arroy.
+pub fn cosine_distance(left: &[f32], right: &[f32]) -> f32 {
+    let (mut dot, mut left_norm, mut right_norm) = (0.0, 0.0, 0.0);
+    for (l, r) in left.iter().zip(right) {
+        dot += l * r;
+        left_norm += l * l;
+        right_norm += r * r;
+    }
+    let norms = left_norm.sqrt() * right_norm.sqrt();
+    if norms <= f32::EPSILON {
+        return 0.0;
+    }
+    let cos = (dot / norms).clamp(-1.0, 1.0);
+    (1.0 - cos) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::btreemap;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+    use crate::update::Setting;
+    use crate::vector::settings::{EmbedderSource, EmbeddingSettings};
+    use crate::vector::ArroyWrapper;
+    use crate::{Error, UserError};
+
+    #[test]
+    fn quantization_roundtrip() {
+        let vector = [-1.0, -0.5, 0.0, 0.25, 1.0];
+        let bytes = quantize(&vector);
+        assert_eq!(bytes.len(), HEADER_SIZE + vector.len());
+        let dequantized = dequantize(&bytes);
+        let step = 2.0 / 255.0;
+        for (original, dequantized) in vector.iter().zip(&dequantized) {
+            assert!((original - dequantized).abs() <= step / 2.0 + f32::EPSILON);
+        }
+        // the bounds are exact
+        assert_eq!(dequantized[0], -1.0);
+        assert!((dequantized[4] - 1.0).abs() < 1e-6);
+
+        // constant vectors don't divide by zero
+        assert_eq!(dequantize(&quantize(&[0.5, 0.5])), vec![0.5, 0.5]);
+        assert!(dequantize(&quantize(&[])).is_empty());
+    }
+
+    #[test]
+    fn rescoring_distance() {
+        assert_eq!(cosine_distance(&[1.0, 0.0], &[2.0, 0.0]), 0.0);
+        assert_eq!(cosine_distance(&[1.0, 0.0], &[0.0, 1.0]), 0.5);
+        assert_eq!(cosine_distance(&[1.0, 0.0], &[-1.0, 0.0]), 1.0);
+        assert_eq!(cosine_distance(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+
+        let vector = [0.1, -0.3, 0.7, 0.2];
+        let distance = cosine_distance(&vector, &dequantize(&quantize(&vector)));
+        assert!(distance < 1e-4, "{distance}");
+    }
+
+    #[test]
+    fn int8_rescoring_of_existing_embeddings() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => Setting::Set(EmbeddingSettings {
+                        source: Setting::Set(EmbedderSource::UserProvided),
+                        dimensions: Setting::Set(3),
+                        ..Default::default()
+                    }),
+                });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "manual": [1.0, 0.0, 0.0] } },
+                { "id": 1, "_vectors": { "manual": [0.0, 1.0, 0.0] } },
+                { "id": 2, "_vectors": { "manual": [0.7, 0.7, 0.0] } },
+            ]))
+            .unwrap();
+
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => Setting::Set(EmbeddingSettings {
+                        int8_rescoring: Setting::Set(true),
+                        ..Default::default()
+                    }),
+                });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let configs = index.embedding_configs(&rtxn).unwrap();
+        assert!(configs[0].config.quantized());
+        let embedder_id = index.embedder_category_id.get(&rtxn, "manual").unwrap().unwrap();
+        let reader = ArroyWrapper::new(index.vector_arroy, index.vector_int8, embedder_id, true);
+        let results = reader.nns_by_vector(&rtxn, &[1.0, 0.1, 0.0], 3, None).unwrap();
+        let docids: Vec<_> = results.iter().map(|(docid, _)| *docid).collect();
+        assert_eq!(docids, vec![0, 2, 1]);
+        // the embeddings are retrieved from their int8 copy rather than from the binary quantized index
+        let vectors = reader.item_vectors(&rtxn, 2).unwrap();
+        assert!(cosine_distance(&vectors[0], &[0.7, 0.7, 0.0]) < 1e-4);
+        drop(rtxn);
+
+        // documents added after the quantization also get an int8 copy
+        index
+            .add_documents(documents!([{ "id": 3, "_vectors": { "manual": [0.0, 0.0, 1.0] } }]))
+            .unwrap();
+        index.delete_documents(vec![S("0")]);
+        let rtxn = index.read_txn().unwrap();
+        let results = reader.nns_by_vector(&rtxn, &[0.1, 0.0, 1.0], 3, None).unwrap();
+        let docids: Vec<_> = results.iter().map(|(docid, _)| *docid).collect();
+        assert_eq!(docids, vec![3, 2, 1]);
+        drop(rtxn);
+
+        let error = index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => Setting::Set(EmbeddingSettings {
+                        int8_rescoring: Setting::Set(false),
+                        ..Default::default()
+                    }),
+                });
+            })
+            .unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::InvalidDisableInt8Rescoring { .. })));
+    }
+
+    #[test]
+    fn binary_quantized_exact_search_distances() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => Setting::Set(EmbeddingSettings {
+                        source: Setting::Set(EmbedderSource::UserProvided),
+                        dimensions: Setting::Set(3),
+                        binary_quantized: Setting::Set(true),
+                        ..Default::default()
+                    }),
+                });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "manual": [1.0, 0.0, 0.0] } },
+                { "id": 1, "_vectors": { "manual": [0.0, 1.0, 0.0] } },
+                { "id": 2, "_vectors": { "manual": [0.7, 0.7, 0.0] } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let embedder_id = index.embedder_category_id.get(&rtxn, "manual").unwrap().unwrap();
+        let reader = ArroyWrapper::new(index.vector_arroy, index.vector_int8, embedder_id, true);
+        let query = [1.0, 0.1, 0.0];
+        // scanning the candidates and searching them give the same distances
+        let searched = reader.nns_by_vector(&rtxn, &query, 3, None).unwrap();
+        let scanned = reader.exact_nns_by_vector(&rtxn, &query, 3, &(0..3).collect()).unwrap();
+        assert_eq!(searched, scanned);
+        let docids: Vec<_> = scanned.iter().map(|(docid, _)| *docid).collect();
+        assert_eq!(docids, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn cannot_enable_int8_rescoring_of_binary_quantized_embedder() {
+        let index = TempIndex::new();
+
+        let error = index
+            .update_settings(|settings| {
+                settings.set_embedder_settings(btreemap! {
+                    S("manual") => Setting::Set(EmbeddingSettings {
+                        source: Setting::Set(EmbedderSource::UserProvided),
+                        dimensions: Setting::Set(3),
+                        binary_quantized: Setting::Set(true),
+                        int8_rescoring: Setting::Set(true),
+                        ..Default::default()
+                    }),
+                });
+            })
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::UserError(UserError::InvalidInt8RescoringOfBinaryQuantized { .. })
+        ));
+    }
+}
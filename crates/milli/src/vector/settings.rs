@@ -6,8 +6,10 @@ use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::chunking::ChunkingSettings;
 use super::composite::SubEmbedderOptions;
 use super::hf::OverridePooling;
+use super::request_policy::RequestPolicy;
 use super::{ollama, openai, DistributionShift, EmbedderOptions};
 use crate::prompt::{default_max_bytes, PromptData};
 use crate::update::Setting;
@@ -38,6 +40,10 @@ pub struct EmbeddingSettings {
     #[schema(value_type = Option<String>)]
     /// The name of the model to use.
     ///
+    /// For source `huggingFace`, this can also be the path to a local directory containing
+    /// `config.json`, `tokenizer.json` and the model weights, starting with `/`, `./` or `../`.
+    /// The directory must be inside the one passed to `--experimental-local-models-directory`.
+    ///
     /// # Mandatory
     ///
     /// - This parameter is mandatory for source `ollama`
@@ -103,7 +109,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `ollama`, `rest`
+    /// - This parameter is available for source `openAi`, `ollama`, `rest`, `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -170,6 +176,34 @@ pub struct EmbeddingSettings {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
     #[schema(value_type = Option<bool>)]
+    /// Whether to binary quantize the embeddings of this embedder and rescore the candidates with int8 embeddings.
+    ///
+    /// The embeddings are binary quantized, like with `binaryQuantized`, and a second copy of each embedding is
+    /// stored with one byte per dimension (int8), about 4 times smaller than the full-precision embedding.
+    /// Candidates are retrieved from the binary quantized index, then rescored against the int8 embeddings,
+    /// so that the loss in relevancy is much smaller than with `binaryQuantized` alone.
+    /// The full-precision embeddings are not kept: the rescoring is not as precise as a full-precision search.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for all embedders, except `sparse` embedders
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ When set to `true`, embeddings are not regenerated, but they are quantized, which takes time.
+    ///
+    /// # Defaults
+    ///
+    /// - Defaults to `false`
+    ///
+    /// # Note
+    ///
+    /// As both quantizations are destructive operations, it is not possible to disable again this setting after
+    /// first enabling it. It cannot be enabled for an embedder that is already binary quantized.
+    pub int8_rescoring: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<bool>)]
     /// A liquid template used to render documents to a text that can be embedded.
     ///
     /// Meillisearch interpolates the template for each document and sends the resulting text to the embedder.
@@ -177,7 +211,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama` and `rest
+    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama`, `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -190,7 +224,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama` and `rest`
+    /// - This parameter is available for source `openAi`, `huggingFace`, `ollama`, `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -212,7 +246,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `openAi`, `ollama` and `rest`
+    /// - This parameter is available for source `openAi`, `ollama`, `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -230,7 +264,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `rest`
+    /// - This parameter is available for sources `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -247,7 +281,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `rest`
+    /// - This parameter is available for sources `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -260,7 +294,7 @@ pub struct EmbeddingSettings {
     ///
     /// # Availability
     ///
-    /// - This parameter is available for source `rest`
+    /// - This parameter is available for sources `rest` and `sparse`
     ///
     /// # 🔄 Reindexing
     ///
@@ -290,6 +324,48 @@ pub struct EmbeddingSettings {
     ///
     /// - 🌱 Changing the value of this parameter never regenerates embeddings
     pub distribution: Setting<DistributionShift>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<ChunkingSettings>)]
+    /// Split the text to embed into overlapping chunks of words, each chunk being embedded as a separate vector.
+    ///
+    /// The chunked text is the rendered document template, or the value of `field` when it is specified.
+    /// When chunking, the rendered text is not truncated to `documentTemplateMaxBytes`.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for sources `openAi`, `huggingFace`, `ollama`, `rest` and `composite`
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🏗️ Changing the value of this parameter always regenerates embeddings
+    ///
+    /// # Defaults
+    ///
+    /// - Chunking is disabled by default
+    /// - When enabled, `maxWords` defaults to 200 and `overlapWords` defaults to 40
+    pub chunking: Setting<ChunkingSettings>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    #[schema(value_type = Option<RequestPolicy>)]
+    /// Limits on the requests sent to the remote embedder, and how failed requests are retried.
+    ///
+    /// `Retry-After` headers sent by the embedder are always honored, up to `maxRetryDelayMs`.
+    ///
+    /// # Availability
+    ///
+    /// - This parameter is available for sources `openAi`, `ollama` and `rest`
+    ///
+    /// # 🔄 Reindexing
+    ///
+    /// - 🌱 Changing the value of this parameter never regenerates embeddings
+    ///
+    /// # Defaults
+    ///
+    /// - Requests are neither rate limited nor limited in concurrency beyond the internal limit of 40 concurrent requests
+    /// - `batchSize` defaults to 10 for embedders accepting several texts per request
+    /// - `maxRetries` defaults to 10 and `maxRetryDelayMs` defaults to 60000
+    pub request_policy: Setting<RequestPolicy>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr, ToSchema)]
@@ -316,6 +392,10 @@ pub struct SubEmbeddingSettings {
     #[schema(value_type = Option<String>)]
     /// The name of the model to use.
     ///
+    /// For source `huggingFace`, this can also be the path to a local directory containing
+    /// `config.json`, `tokenizer.json` and the model weights, starting with `/`, `./` or `../`.
+    /// The directory must be inside the one passed to `--experimental-local-models-directory`.
+    ///
     /// # Mandatory
     ///
     /// - This parameter is mandatory for source `ollama`
@@ -531,6 +611,11 @@ pub struct SubEmbeddingSettings {
     #[schema(ignore)]
     pub binary_quantized: Setting<bool>,
 
+    #[serde(default, skip_serializing)]
+    #[deserr(default)]
+    #[schema(ignore)]
+    pub int8_rescoring: Setting<bool>,
+
     #[serde(default, skip_serializing)]
     #[deserr(default)]
     #[schema(ignore)]
@@ -630,6 +715,9 @@ impl SettingsDiff {
                     mut headers,
                     mut document_template_max_bytes,
                     binary_quantized: mut binary_quantize,
+                    mut int8_rescoring,
+                    mut chunking,
+                    mut request_policy,
                 } = old;
 
                 let EmbeddingSettings {
@@ -649,6 +737,9 @@ impl SettingsDiff {
                     headers: new_headers,
                     document_template_max_bytes: new_document_template_max_bytes,
                     binary_quantized: new_binary_quantize,
+                    int8_rescoring: new_int8_rescoring,
+                    chunking: new_chunking,
+                    request_policy: new_request_policy,
                 } = new;
 
                 if matches!(binary_quantize, Setting::Set(true))
@@ -658,6 +749,13 @@ impl SettingsDiff {
                         embedder_name: embedder_name.to_string(),
                     });
                 }
+                if matches!(int8_rescoring, Setting::Set(true))
+                    && matches!(new_int8_rescoring, Setting::Set(false))
+                {
+                    return Err(UserError::InvalidDisableInt8Rescoring {
+                        embedder_name: embedder_name.to_string(),
+                    });
+                }
 
                 let mut reindex_action = None;
 
@@ -690,6 +788,9 @@ impl SettingsDiff {
                 );
 
                 let binary_quantize_changed = binary_quantize.apply(new_binary_quantize);
+                let int8_rescoring_changed = int8_rescoring.apply(new_int8_rescoring);
+                // both quantizations start by binary quantizing the arroy index
+                let quantize_changed = binary_quantize_changed || int8_rescoring_changed;
 
                 // changes to the *search* embedder never triggers any reindexing
                 search_embedder.apply(new_search_embedder);
@@ -701,6 +802,14 @@ impl SettingsDiff {
 
                 distribution.apply(new_distribution);
 
+                // the splitting of the texts changes, so their embeddings must all be regenerated
+                if chunking.apply(new_chunking) {
+                    ReindexAction::push_action(&mut reindex_action, ReindexAction::FullReindex);
+                }
+
+                // the policy only changes how the requests are sent, not the embeddings
+                request_policy.apply(new_request_policy);
+
                 let updated_settings = EmbeddingSettings {
                     source,
                     model,
@@ -718,18 +827,18 @@ impl SettingsDiff {
                     headers,
                     document_template_max_bytes,
                     binary_quantized: binary_quantize,
+                    int8_rescoring,
+                    chunking,
+                    request_policy,
                 };
 
                 match reindex_action {
-                    Some(action) => Self::Reindex {
-                        action,
-                        updated_settings,
-                        quantize: binary_quantize_changed,
-                    },
-                    None => Self::UpdateWithoutReindex {
-                        updated_settings,
-                        quantize: binary_quantize_changed,
-                    },
+                    Some(action) => {
+                        Self::Reindex { action, updated_settings, quantize: quantize_changed }
+                    }
+                    None => {
+                        Self::UpdateWithoutReindex { updated_settings, quantize: quantize_changed }
+                    }
                 }
             }
             Setting::Reset => Self::Remove,
@@ -763,6 +872,7 @@ impl SettingsDiff {
                     // phony settings
                     mut distribution,
                     mut binary_quantized,
+                    mut int8_rescoring,
                     mut search_embedder,
                     mut indexing_embedder,
                 }) = sub_embedder
@@ -787,6 +897,7 @@ impl SettingsDiff {
                     headers: new_headers,
                     distribution: new_distribution,
                     binary_quantized: new_binary_quantized,
+                    int8_rescoring: new_int8_rescoring,
                     search_embedder: new_search_embedder,
                     indexing_embedder: new_indexing_embedder,
                 } = new_sub_embedder;
@@ -822,6 +933,7 @@ impl SettingsDiff {
                 // update phony settings, it is always an error to have them set.
                 distribution.apply(new_distribution);
                 binary_quantized.apply(new_binary_quantized);
+                int8_rescoring.apply(new_int8_rescoring);
                 search_embedder.apply(new_search_embedder);
                 indexing_embedder.apply(new_indexing_embedder);
 
@@ -840,6 +952,7 @@ impl SettingsDiff {
                     document_template_max_bytes,
                     distribution,
                     binary_quantized,
+                    int8_rescoring,
                     search_embedder,
                     indexing_embedder,
                 };
@@ -1061,6 +1174,18 @@ fn apply_default_for_source(
             *search_embedder = Setting::Reset;
             *indexing_embedder = Setting::Reset;
         }
+        Setting::Set(EmbedderSource::Sparse) => {
+            *model = Setting::NotSet;
+            *revision = Setting::NotSet;
+            *pooling = Setting::NotSet;
+            *dimensions = Setting::NotSet;
+            *url = Setting::Reset;
+            *request = Setting::Reset;
+            *response = Setting::Reset;
+            *headers = Setting::Reset;
+            *search_embedder = Setting::NotSet;
+            *indexing_embedder = Setting::NotSet;
+        }
         Setting::NotSet => {}
     }
 }
@@ -1126,6 +1251,9 @@ pub enum MetaEmbeddingSetting {
     IndexingEmbedder,
     Distribution,
     BinaryQuantized,
+    Int8Rescoring,
+    Chunking,
+    RequestPolicy,
 }
 
 impl MetaEmbeddingSetting {
@@ -1148,6 +1276,9 @@ impl MetaEmbeddingSetting {
             IndexingEmbedder => "indexingEmbedder",
             Distribution => "distribution",
             BinaryQuantized => "binaryQuantized",
+            Int8Rescoring => "int8Rescoring",
+            Chunking => "chunking",
+            RequestPolicy => "requestPolicy",
         }
     }
 }
@@ -1172,7 +1303,10 @@ impl EmbeddingSettings {
         search_embedder: &Setting<SubEmbeddingSettings>,
         indexing_embedder: &Setting<SubEmbeddingSettings>,
         binary_quantized: &Setting<bool>,
+        int8_rescoring: &Setting<bool>,
         distribution: &Setting<DistributionShift>,
+        chunking: &Setting<ChunkingSettings>,
+        request_policy: &Setting<RequestPolicy>,
     ) -> Result<(), UserError> {
         Self::check_setting(embedder_name, source, MetaEmbeddingSetting::Model, context, model)?;
         Self::check_setting(
@@ -1254,12 +1388,33 @@ impl EmbeddingSettings {
             context,
             binary_quantized,
         )?;
+        Self::check_setting(
+            embedder_name,
+            source,
+            MetaEmbeddingSetting::Int8Rescoring,
+            context,
+            int8_rescoring,
+        )?;
         Self::check_setting(
             embedder_name,
             source,
             MetaEmbeddingSetting::Distribution,
             context,
             distribution,
+        )?;
+        Self::check_setting(
+            embedder_name,
+            source,
+            MetaEmbeddingSetting::Chunking,
+            context,
+            chunking,
+        )?;
+        Self::check_setting(
+            embedder_name,
+            source,
+            MetaEmbeddingSetting::RequestPolicy,
+            context,
+            request_policy,
         )
     }
 
@@ -1320,8 +1475,15 @@ impl EmbeddingSettings {
         use MetaEmbeddingSetting::*;
         use NestingContext::*;
         match (source, field, context) {
-            (_, Distribution | BinaryQuantized, NotNested) => FieldStatus::Allowed,
-            (_, Distribution | BinaryQuantized, _) => FieldStatus::Disallowed,
+            // sparse embeddings are neither quantized nor chunked
+            (Sparse, BinaryQuantized | Int8Rescoring | Chunking, _) => FieldStatus::Disallowed,
+            (_, Distribution | BinaryQuantized | Int8Rescoring, NotNested) => FieldStatus::Allowed,
+            (_, Distribution | BinaryQuantized | Int8Rescoring, _) => FieldStatus::Disallowed,
+            (UserProvided, Chunking, _) => FieldStatus::Disallowed,
+            (_, Chunking, NotNested) => FieldStatus::Allowed,
+            (_, Chunking, _) => FieldStatus::Disallowed,
+            (OpenAi | Ollama | Rest, RequestPolicy, NotNested) => FieldStatus::Allowed,
+            (_, RequestPolicy, _) => FieldStatus::Disallowed,
             (_, DocumentTemplate | DocumentTemplateMaxBytes, Search) => FieldStatus::Disallowed,
             (
                 OpenAi,
@@ -1412,6 +1574,23 @@ impl EmbeddingSettings {
                 | Headers,
                 _,
             ) => FieldStatus::Disallowed,
+            (
+                Sparse,
+                Source
+                | Url
+                | Request
+                | Response
+                | ApiKey
+                | Headers
+                | DocumentTemplate
+                | DocumentTemplateMaxBytes,
+                _,
+            ) => FieldStatus::Allowed,
+            (
+                Sparse,
+                Model | Revision | Pooling | Dimensions | SearchEmbedder | IndexingEmbedder,
+                _,
+            ) => FieldStatus::Disallowed,
         }
     }
 
@@ -1445,7 +1624,7 @@ impl EmbeddingSettings {
             (NestingContext::NotNested, _) => Ok(()),
             (
                 NestingContext::Search | NestingContext::Indexing,
-                EmbedderSource::Composite | EmbedderSource::UserProvided,
+                EmbedderSource::Composite | EmbedderSource::UserProvided | EmbedderSource::Sparse,
             ) => Err(UserError::InvalidSourceForNested {
                 embedder_name: context.embedder_name_with_context(embedder_name),
                 source_: source,
@@ -1484,6 +1663,7 @@ pub enum EmbedderSource {
     UserProvided,
     Rest,
     Composite,
+    Sparse,
 }
 
 impl std::fmt::Display for EmbedderSource {
@@ -1495,6 +1675,7 @@ impl std::fmt::Display for EmbedderSource {
             EmbedderSource::Ollama => "ollama",
             EmbedderSource::Rest => "rest",
             EmbedderSource::Composite => "composite",
+            EmbedderSource::Sparse => "sparse",
         };
         f.write_str(s)
     }
@@ -1529,6 +1710,9 @@ impl EmbeddingSettings {
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            int8_rescoring: Setting::NotSet,
+            chunking: Setting::NotSet,
+            request_policy: Setting::NotSet,
         }
     }
 
@@ -1539,6 +1723,8 @@ impl EmbeddingSettings {
             embedding_model,
             dimensions,
             distribution,
+            // retrieved by the EmbeddingConfig
+            request_policy: _,
         }: super::openai::EmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
@@ -1561,16 +1747,21 @@ impl EmbeddingSettings {
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            int8_rescoring: Setting::NotSet,
+            chunking: Setting::NotSet,
+            request_policy: Setting::NotSet,
         }
     }
 
     fn from_ollama(
         super::ollama::EmbedderOptions {
-          embedding_model,
-          url,
-          api_key,
-          distribution,
-          dimensions,
+            embedding_model,
+            url,
+            api_key,
+            distribution,
+            dimensions,
+            // retrieved by the EmbeddingConfig
+            request_policy: _,
         }: super::ollama::EmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
@@ -1593,6 +1784,9 @@ impl EmbeddingSettings {
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            int8_rescoring: Setting::NotSet,
+            chunking: Setting::NotSet,
+            request_policy: Setting::NotSet,
         }
     }
 
@@ -1617,6 +1811,9 @@ impl EmbeddingSettings {
             indexing_embedder: Setting::NotSet,
             distribution: Setting::some_or_not_set(distribution),
             binary_quantized: Setting::some_or_not_set(quantized),
+            int8_rescoring: Setting::NotSet,
+            chunking: Setting::NotSet,
+            request_policy: Setting::NotSet,
         }
     }
 
@@ -1629,6 +1826,8 @@ impl EmbeddingSettings {
             response,
             distribution,
             headers,
+            // retrieved by the EmbeddingConfig
+            request_policy: _,
         }: super::rest::EmbedderOptions,
         document_template: Setting<String>,
         document_template_max_bytes: Setting<usize>,
@@ -1651,16 +1850,56 @@ impl EmbeddingSettings {
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
             binary_quantized: Setting::some_or_not_set(quantized),
+            int8_rescoring: Setting::NotSet,
+            chunking: Setting::NotSet,
+            request_policy: Setting::NotSet,
+        }
+    }
+
+    fn from_sparse(
+        super::sparse::EmbedderOptions {
+            url,
+            api_key,
+            request,
+            response,
+            headers,
+            distribution,
+        }: super::sparse::EmbedderOptions,
+        document_template: Setting<String>,
+        document_template_max_bytes: Setting<usize>,
+    ) -> Self {
+        Self {
+            source: Setting::Set(EmbedderSource::Sparse),
+            model: Setting::NotSet,
+            revision: Setting::NotSet,
+            pooling: Setting::NotSet,
+            api_key: Setting::some_or_not_set(api_key),
+            dimensions: Setting::NotSet,
+            document_template,
+            document_template_max_bytes,
+            url: Setting::some_or_not_set(url),
+            request: Setting::some_or_not_set(request),
+            response: Setting::some_or_not_set(response),
+            distribution: Setting::some_or_not_set(distribution),
+            headers: Setting::Set(headers),
+            search_embedder: Setting::NotSet,
+            indexing_embedder: Setting::NotSet,
+            binary_quantized: Setting::NotSet,
+            int8_rescoring: Setting::NotSet,
+            chunking: Setting::NotSet,
+            request_policy: Setting::NotSet,
         }
     }
 }
 
 impl From<EmbeddingConfig> for EmbeddingSettings {
     fn from(value: EmbeddingConfig) -> Self {
-        let EmbeddingConfig { embedder_options, prompt, quantized } = value;
+        let EmbeddingConfig { embedder_options, prompt, quantized, int8_rescoring } = value;
         let document_template_max_bytes =
             Setting::Set(prompt.max_bytes.unwrap_or(default_max_bytes()).get());
-        match embedder_options {
+        let chunking = Setting::some_or_not_set(prompt.chunking);
+        let request_policy = Setting::some_or_not_set(embedder_options.request_policy().cloned());
+        let settings = match embedder_options {
             super::EmbedderOptions::HuggingFace(options) => Self::from_hugging_face(
                 options,
                 Setting::Set(prompt.template),
@@ -1688,6 +1927,11 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 document_template_max_bytes,
                 quantized,
             ),
+            super::EmbedderOptions::Sparse(options) => Self::from_sparse(
+                options,
+                Setting::Set(prompt.template),
+                document_template_max_bytes,
+            ),
             super::EmbedderOptions::Composite(super::composite::EmbedderOptions {
                 search,
                 index,
@@ -1699,6 +1943,7 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 api_key: Setting::NotSet,
                 dimensions: Setting::NotSet,
                 binary_quantized: Setting::some_or_not_set(quantized),
+                int8_rescoring: Setting::NotSet,
                 document_template: Setting::NotSet,
                 document_template_max_bytes: Setting::NotSet,
                 url: Setting::NotSet,
@@ -1716,8 +1961,12 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                     Setting::Set(prompt.template),
                     document_template_max_bytes,
                 )),
+                chunking: Setting::NotSet,
+                request_policy: Setting::NotSet,
             },
-        }
+        };
+        let int8_rescoring = Setting::some_or_not_set(int8_rescoring);
+        Self { chunking, int8_rescoring, request_policy, ..settings }
     }
 }
 
@@ -1778,9 +2027,12 @@ impl From<EmbeddingSettings> for SubEmbeddingSettings {
             response,
             headers,
             binary_quantized: _,
+            int8_rescoring: _,
             search_embedder: _,
             indexing_embedder: _,
             distribution: _,
+            chunking: _,
+            request_policy: _,
         } = value;
         Self {
             source,
@@ -1797,6 +2049,7 @@ impl From<EmbeddingSettings> for SubEmbeddingSettings {
             headers,
             distribution: Setting::NotSet,
             binary_quantized: Setting::NotSet,
+            int8_rescoring: Setting::NotSet,
             search_embedder: Setting::NotSet,
             indexing_embedder: Setting::NotSet,
         }
@@ -1821,11 +2074,15 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
             distribution,
             headers,
             binary_quantized,
+            int8_rescoring,
             search_embedder,
             mut indexing_embedder,
+            chunking,
+            request_policy,
         } = value;
 
         this.quantized = binary_quantized.set();
+        this.int8_rescoring = int8_rescoring.set();
         if let Some((template, document_template_max_bytes)) =
             match (document_template, &mut indexing_embedder) {
                 (Setting::Set(template), _) => Some((template, document_template_max_bytes)),
@@ -1846,8 +2103,9 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                 .and_then(NonZeroUsize::new)
                 .unwrap_or(default_max_bytes());
 
-            this.prompt = PromptData { template, max_bytes: Some(max_bytes) }
+            this.prompt = PromptData { template, max_bytes: Some(max_bytes), chunking: None }
         }
+        this.prompt.chunking = chunking.set();
 
         if let Some(source) = source.set() {
             this.embedder_options = match source {
@@ -1887,8 +2145,19 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                         ),
                     })
                 }
+                EmbedderSource::Sparse => {
+                    super::EmbedderOptions::Sparse(super::sparse::EmbedderOptions {
+                        url: url.set(),
+                        api_key: api_key.set(),
+                        request: request.set(),
+                        response: response.set(),
+                        headers: headers.set().unwrap_or_default(),
+                        distribution: distribution.set(),
+                    })
+                }
             };
         }
+        this.embedder_options.set_request_policy(request_policy.set());
 
         this
     }
@@ -1916,6 +2185,7 @@ impl SubEmbedderOptions {
             // phony parameters
             distribution: _,
             binary_quantized: _,
+            int8_rescoring: _,
             search_embedder: _,
             indexing_embedder: _,
         } = settings;
@@ -1939,6 +2209,7 @@ impl SubEmbedderOptions {
                 distribution,
             ),
             EmbedderSource::Composite => panic!("nested composite embedders"),
+            EmbedderSource::Sparse => panic!("nested sparse embedders"),
         }
     }
 
@@ -2015,6 +2286,7 @@ impl SubEmbedderOptions {
             response,
             distribution: distribution.set(),
             headers: headers.set().unwrap_or_default(),
+            request_policy: None,
         })
     }
     fn ollama(
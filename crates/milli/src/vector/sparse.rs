@@ -0,0 +1,448 @@
+//! Sparse embedders and their inverted index.
+//!
+//! A sparse embedding maps a small number of token ids to a weight, as produced by learned sparse models
+//! (SPLADE) or BM25-style term weighting. Instead of an ANN graph, sparse embeddings are stored in an
+//! inverted index mapping each token id to the list of documents containing it along with their weight.
+//!
+//! The similarity between a query and a document is the dot product of their sparse embeddings, computed
+//! by walking the postings of the query tokens only.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+
+use heed::types::Bytes;
+use heed::{Database, RoTxn, RwTxn};
+use rayon::iter::ParallelIterator as _;
+use rayon::slice::ParallelSlice as _;
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+
+use super::error::{EmbedError, EmbedErrorKind, NewEmbedderError};
+use super::request_policy::{RequestPolicy, RequestStats};
+use super::rest::{self, ConfigurationSource, EmbedderData};
+use super::{DistributionShift, REQUEST_PARALLELISM};
+use crate::error::FaultSource;
+use crate::{DocumentId, ThreadPoolNoAbort};
+
+/// Size in bytes of an encoded `(u32, f32)` pair.
+const PAIR_SIZE: usize = std::mem::size_of::<u32>() + std::mem::size_of::<f32>();
+
+/// Key tag of the entries storing the sparse embedding of a document.
+const DOCUMENT_KEY: u8 = 0;
+/// Key tag of the entries storing the postings of a token.
+const POSTINGS_KEY: u8 = 1;
+
+/// A sparse embedding, as a list of `(token id, weight)` sorted by token id.
+///
+/// Serialized as a JSON object mapping the token ids to their weight, e.g. `{"1012": 0.4, "2451": 1.3}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "BTreeMap<String, f32>", into = "BTreeMap<String, f32>")]
+pub struct SparseVector {
+    entries: Vec<(u32, f32)>,
+}
+
+impl SparseVector {
+    /// Builds a sparse vector from `(token id, weight)` pairs.
+    ///
+    /// Pairs with a null weight are dropped, and the last weight wins for duplicated token ids.
+    pub fn new(entries: impl IntoIterator<Item = (u32, f32)>) -> Self {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        // stable sort so that the last duplicate remains last
+        entries.sort_by_key(|(token, _)| *token);
+        let mut deduped: Vec<(u32, f32)> = Vec::with_capacity(entries.len());
+        for (token, weight) in entries {
+            match deduped.last_mut() {
+                Some((last, last_weight)) if *last == token => *last_weight = weight,
+                _ => deduped.push((token, weight)),
+            }
+        }
+        deduped.retain(|(_, weight)| *weight != 0.0);
+        Self { entries: deduped }
+    }
+
+    pub fn entries(&self) -> &[(u32, f32)] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Dot product between two sparse vectors.
+    pub fn dot(&self, other: &SparseVector) -> f32 {
+        let mut left = self.entries.iter().peekable();
+        let mut right = other.entries.iter().peekable();
+        let mut sum = 0.0;
+        while let (Some((ltoken, lweight)), Some((rtoken, rweight))) = (left.peek(), right.peek()) {
+            match ltoken.cmp(rtoken) {
+                std::cmp::Ordering::Less => {
+                    left.next();
+                }
+                std::cmp::Ordering::Greater => {
+                    right.next();
+                }
+                std::cmp::Ordering::Equal => {
+                    sum += lweight * rweight;
+                    left.next();
+                    right.next();
+                }
+            }
+        }
+        sum
+    }
+}
+
+impl TryFrom<BTreeMap<String, f32>> for SparseVector {
+    type Error = String;
+
+    fn try_from(map: BTreeMap<String, f32>) -> Result<Self, Self::Error> {
+        let entries: Result<Vec<_>, _> = map
+            .into_iter()
+            .map(|(token, weight)| match token.parse::<u32>() {
+                Ok(token) if weight.is_finite() => Ok((token, weight)),
+                Ok(token) => Err(format!("the weight of token `{token}` is not a finite number")),
+                Err(_) => Err(format!(
+                    "expected token ids to be integers between 0 and {}, found `{token}`",
+                    u32::MAX
+                )),
+            })
+            .collect();
+        entries.map(Self::new)
+    }
+}
+
+impl From<SparseVector> for BTreeMap<String, f32> {
+    fn from(vector: SparseVector) -> Self {
+        vector.entries.into_iter().map(|(token, weight)| (token.to_string(), weight)).collect()
+    }
+}
+
+/// Extracts the sparse embedding provided by the user in the `_vectors.<embedder>` field of a document.
+///
+/// Sparse embeddings are objects, so they must be provided in the explicit form
+/// `{"embeddings": <sparse embedding>, "regenerate": <bool>}`.
+/// Returns the sparse embedding along with whether it should be regenerated, or `None` if no embedding was provided.
+pub fn parse_user_provided(
+    value: &serde_json::Value,
+) -> Result<Option<(SparseVector, bool)>, serde_json::Error> {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Explicit {
+        #[serde(default)]
+        embeddings: Option<SparseVector>,
+        regenerate: bool,
+    }
+
+    if value.is_null() {
+        return Ok(None);
+    }
+    let Explicit { embeddings, regenerate } = Explicit::deserialize(value)?;
+    Ok(embeddings.map(|embeddings| (embeddings, regenerate)))
+}
+
+fn encode_pairs(pairs: impl IntoIterator<Item = (u32, f32)>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (key, weight) in pairs {
+        bytes.extend_from_slice(&key.to_be_bytes());
+        bytes.extend_from_slice(&weight.to_be_bytes());
+    }
+    bytes
+}
+
+fn decode_pairs(bytes: &[u8]) -> impl Iterator<Item = (u32, f32)> + '_ {
+    bytes.chunks_exact(PAIR_SIZE).map(|chunk| {
+        let (key, weight) = chunk.split_at(std::mem::size_of::<u32>());
+        (
+            u32::from_be_bytes(key.try_into().unwrap()),
+            f32::from_be_bytes(weight.try_into().unwrap()),
+        )
+    })
+}
+
+/// The inverted index of the sparse embeddings of a single embedder.
+///
+/// All the sparse embedders share the same database, each entry being prefixed by the embedder id:
+/// - `[embedder id, 0, docid]` stores the sparse embedding of a document,
+/// - `[embedder id, 1, token id, docid]` stores the weight of a token in a document,
+///   so that the postings of a token are the entries prefixed by `[embedder id, 1, token id]`.
+pub struct SparseVectorStore {
+    database: Database<Bytes, Bytes>,
+    embedder_index: u8,
+}
+
+impl SparseVectorStore {
+    pub fn new(database: Database<Bytes, Bytes>, embedder_index: u8) -> Self {
+        Self { database, embedder_index }
+    }
+
+    pub fn embedder_index(&self) -> u8 {
+        self.embedder_index
+    }
+
+    fn key(&self, tag: u8, id: u32) -> [u8; 6] {
+        let mut key = [0; 6];
+        key[0] = self.embedder_index;
+        key[1] = tag;
+        key[2..].copy_from_slice(&id.to_be_bytes());
+        key
+    }
+
+    fn posting_key(&self, token: u32, docid: DocumentId) -> [u8; 10] {
+        let mut key = [0; 10];
+        key[..6].copy_from_slice(&self.key(POSTINGS_KEY, token));
+        key[6..].copy_from_slice(&docid.to_be_bytes());
+        key
+    }
+
+    /// The sparse embedding of a document, if any.
+    pub fn get(&self, rtxn: &RoTxn, docid: DocumentId) -> heed::Result<Option<SparseVector>> {
+        let key = self.key(DOCUMENT_KEY, docid);
+        Ok(self
+            .database
+            .get(rtxn, &key)?
+            .map(|bytes| SparseVector { entries: decode_pairs(bytes).collect() }))
+    }
+
+    /// Sets the sparse embedding of a document, replacing any previous one.
+    pub fn add(
+        &self,
+        wtxn: &mut RwTxn,
+        docid: DocumentId,
+        vector: &SparseVector,
+    ) -> heed::Result<()> {
+        self.del(wtxn, docid)?;
+        if vector.is_empty() {
+            return Ok(());
+        }
+
+        for &(token, weight) in vector.entries() {
+            self.database.put(wtxn, &self.posting_key(token, docid), &weight.to_be_bytes())?;
+        }
+
+        let key = self.key(DOCUMENT_KEY, docid);
+        self.database.put(wtxn, &key, &encode_pairs(vector.entries().iter().copied()))
+    }
+
+    /// Removes the sparse embedding of a document.
+    ///
+    /// Returns `true` if the document had a sparse embedding.
+    pub fn del(&self, wtxn: &mut RwTxn, docid: DocumentId) -> heed::Result<bool> {
+        let Some(vector) = self.get(wtxn, docid)? else {
+            return Ok(false);
+        };
+
+        for &(token, _) in vector.entries() {
+            self.database.delete(wtxn, &self.posting_key(token, docid))?;
+        }
+
+        self.database.delete(wtxn, &self.key(DOCUMENT_KEY, docid))?;
+        Ok(true)
+    }
+
+    /// Removes all the sparse embeddings of this embedder.
+    pub fn clear(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        let mut iter = self.database.prefix_iter_mut(wtxn, &[self.embedder_index])?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references from inside the database while deleting
+            unsafe { iter.del_current()? };
+        }
+        Ok(())
+    }
+
+    /// Returns the documents of `candidates` that share at least one token with the `query`,
+    /// along with their dot product with the query, sorted by decreasing similarity.
+    ///
+    /// At most `limit` documents are returned.
+    pub fn nns_by_vector(
+        &self,
+        rtxn: &RoTxn,
+        query: &SparseVector,
+        limit: usize,
+        candidates: &RoaringBitmap,
+    ) -> heed::Result<Vec<(DocumentId, f32)>> {
+        let mut scores: HashMap<DocumentId, f32> = HashMap::new();
+        for &(token, query_weight) in query.entries() {
+            let prefix = self.key(POSTINGS_KEY, token);
+            for result in self.database.prefix_iter(rtxn, &prefix)? {
+                let (key, weight) = result?;
+                let docid = DocumentId::from_be_bytes(key[prefix.len()..].try_into().unwrap());
+                if candidates.contains(docid) {
+                    let weight = f32::from_be_bytes(weight.try_into().unwrap());
+                    *scores.entry(docid).or_default() += query_weight * weight;
+                }
+            }
+        }
+
+        let mut scores: Vec<_> = scores.into_iter().collect();
+        scores.sort_unstable_by(|(left_id, left), (right_id, right)| {
+            right.total_cmp(left).then(left_id.cmp(right_id))
+        });
+        scores.truncate(limit);
+        Ok(scores)
+    }
+}
+
+/// Turns the dot product of two sparse embeddings into a similarity between 0 and 1.
+///
+/// Dot products of sparse embeddings are unbounded, so they are squashed with `x / (1 + x)`.
+pub fn similarity(dot: f32) -> f32 {
+    let dot = dot.max(0.0);
+    dot / (1.0 + dot)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EmbedderOptions {
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    pub request: Option<serde_json::Value>,
+    pub response: Option<serde_json::Value>,
+    pub headers: BTreeMap<String, String>,
+    pub distribution: Option<DistributionShift>,
+}
+
+impl std::hash::Hash for EmbedderOptions {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.url.hash(state);
+        self.api_key.hash(state);
+        self.distribution.hash(state);
+        // skip hashing the request and response, like for REST embedders
+    }
+}
+
+/// An embedder producing sparse embeddings, either provided by the user or fetched from a REST server.
+#[derive(Debug)]
+pub struct Embedder {
+    data: Option<EmbedderData>,
+    distribution: Option<DistributionShift>,
+}
+
+impl Embedder {
+    pub fn new(options: EmbedderOptions) -> Result<Self, NewEmbedderError> {
+        let data = match options.url {
+            Some(url) => Some(EmbedderData::new(
+                url,
+                options.api_key.as_deref(),
+                options.request.unwrap_or_default(),
+                options.response.unwrap_or_default(),
+                options.headers,
+                RequestPolicy::default(),
+                ConfigurationSource::User,
+            )?),
+            None => None,
+        };
+        Ok(Self { data, distribution: options.distribution })
+    }
+
+    /// Whether this embedder can compute embeddings, or relies on the user to provide them.
+    pub fn can_generate(&self) -> bool {
+        self.data.is_some()
+    }
+
+    pub fn embed<S>(
+        &self,
+        texts: &[S],
+        deadline: Option<Instant>,
+    ) -> Result<Vec<SparseVector>, EmbedError>
+    where
+        S: AsRef<str> + Serialize,
+    {
+        match &self.data {
+            Some(data) => rest::embed_sparse(data, texts, deadline),
+            None => Err(EmbedError::embed_on_manual_embedder(
+                texts
+                    .first()
+                    .map(|text| text.as_ref().chars().take(250).collect())
+                    .unwrap_or_default(),
+            )),
+        }
+    }
+
+    pub fn embed_one(
+        &self,
+        text: &str,
+        deadline: Option<Instant>,
+    ) -> Result<SparseVector, EmbedError> {
+        self.embed(&[text], deadline)?.pop().ok_or_else(EmbedError::missing_embedding)
+    }
+
+    /// Embeds the texts by chunks sent in parallel, the embeddings being in the same order as the texts.
+    pub fn embed_index_ref(
+        &self,
+        texts: &[&str],
+        threads: &ThreadPoolNoAbort,
+        deadline: Option<Instant>,
+    ) -> Result<Vec<SparseVector>, EmbedError> {
+        let chunk_size = self.prompt_count_in_chunk_hint();
+        // This condition helps reduce the number of active rayon jobs
+        // so that we avoid consuming all the LMDB rtxns and avoid stack overflows.
+        let embeddings: Result<Vec<Vec<SparseVector>>, _> = if threads.active_operations()
+            >= REQUEST_PARALLELISM
+        {
+            texts.chunks(chunk_size).map(|chunk| self.embed(chunk, deadline)).collect()
+        } else {
+            threads
+                .install(|| {
+                    texts.par_chunks(chunk_size).map(|chunk| self.embed(chunk, deadline)).collect()
+                })
+                .map_err(|error| EmbedError {
+                    kind: EmbedErrorKind::PanicInThreadPool(error),
+                    fault: FaultSource::Bug,
+                })?
+        };
+        let embeddings = embeddings?;
+        Ok(embeddings.into_iter().flatten().collect())
+    }
+
+    pub fn request_stats(&self) -> Option<RequestStats> {
+        self.data.as_ref().map(EmbedderData::request_stats)
+    }
+
+    pub fn prompt_count_in_chunk_hint(&self) -> usize {
+        self.data.as_ref().map_or(1, EmbedderData::prompt_count_in_chunk_hint)
+    }
+
+    pub fn distribution(&self) -> Option<DistributionShift> {
+        self.distribution
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sparse_vector_normalization_and_dot() {
+        let left = SparseVector::new([(3, 1.0), (1, 2.0), (3, 0.5), (7, 0.0)]);
+        assert_eq!(left.entries(), &[(1, 2.0), (3, 0.5)]);
+
+        let right = SparseVector::new([(1, 1.5), (2, 4.0), (3, 2.0)]);
+        assert_eq!(left.dot(&right), 4.0);
+        assert_eq!(right.dot(&left), 4.0);
+        assert_eq!(left.dot(&SparseVector::default()), 0.0);
+    }
+
+    #[test]
+    fn parse_sparse_vectors() {
+        let explicit =
+            serde_json::json!({ "embeddings": { "12": 0.5, "3": 1.0 }, "regenerate": false });
+        let (vector, regenerate) = parse_user_provided(&explicit).unwrap().unwrap();
+        assert_eq!(vector.entries(), &[(3, 1.0), (12, 0.5)]);
+        assert!(!regenerate);
+
+        let explicit = serde_json::json!({ "embeddings": { "3": 1.0 }, "regenerate": true });
+        let (vector, regenerate) = parse_user_provided(&explicit).unwrap().unwrap();
+        assert_eq!(vector.entries(), &[(3, 1.0)]);
+        assert!(regenerate);
+
+        let implicit = serde_json::json!({ "3": 1.0 });
+        assert!(parse_user_provided(&implicit).is_err());
+
+        let regenerate_only = serde_json::json!({ "regenerate": true });
+        assert!(parse_user_provided(&regenerate_only).unwrap().is_none());
+        assert!(parse_user_provided(&serde_json::Value::Null).unwrap().is_none());
+
+        let invalid = serde_json::json!({ "embeddings": { "token": 0.5 }, "regenerate": false });
+        let error = parse_user_provided(&invalid).unwrap_err();
+        assert!(error.to_string().contains("expected token ids to be integers"), "{error}");
+    }
+}